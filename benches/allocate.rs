@@ -0,0 +1,102 @@
+#![feature(allocator_api)]
+
+use core::alloc::{Allocator, Layout};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dodgems::{BumpCar, DownBumpCar};
+
+fn box_new_in(c: &mut Criterion) {
+    let mut bumpcar = BumpCar::new(1024 * 1024).unwrap();
+
+    c.bench_function("Box::new_in (u64 x 1000)", |b| {
+        b.iter(|| {
+            for i in 0..1000u64 {
+                black_box(Box::new_in(black_box(i), &bumpcar));
+            }
+            bumpcar.reset();
+        });
+    });
+}
+
+fn box_new_in_dyn(c: &mut Criterion) {
+    let mut bumpcar = BumpCar::new(1024 * 1024).unwrap();
+
+    c.bench_function("Box::new_in &dyn Allocator (u64 x 1000)", |b| {
+        b.iter(|| {
+            let allocator = black_box(bumpcar.as_dyn());
+            for i in 0..1000u64 {
+                black_box(Box::new_in(black_box(i), allocator));
+            }
+            bumpcar.reset();
+        });
+    });
+}
+
+fn box_new_in_down(c: &mut Criterion) {
+    let mut bumpcar = DownBumpCar::new(1024 * 1024).unwrap();
+
+    c.bench_function("Box::new_in downward (u64 x 1000)", |b| {
+        b.iter(|| {
+            for i in 0..1000u64 {
+                black_box(Box::new_in(black_box(i), &bumpcar));
+            }
+            bumpcar.reset();
+        });
+    });
+}
+
+fn allocate_mixed_alignments(c: &mut Criterion) {
+    let mut bumpcar = BumpCar::new(1024 * 1024).unwrap();
+    let layouts = [
+        Layout::new::<u8>(),
+        Layout::new::<u64>(),
+        Layout::new::<[u16; 3]>(),
+        Layout::new::<u32>(),
+    ];
+
+    c.bench_function("allocate mixed alignments (x 1000)", |b| {
+        b.iter(|| {
+            for layout in layouts.iter().cycle().take(1000) {
+                black_box(bumpcar.allocate(black_box(*layout)).unwrap());
+            }
+            bumpcar.reset();
+        });
+    });
+}
+
+fn allocate_until_full(c: &mut Criterion) {
+    let mut bumpcar = BumpCar::new(1000 * 8).unwrap();
+
+    c.bench_function("allocate until full (u64 x 1000 + 1 failure)", |b| {
+        b.iter(|| {
+            while black_box(bumpcar.allocate(Layout::new::<u64>())).is_ok() {}
+            bumpcar.reset();
+        });
+    });
+}
+
+fn vec_push(c: &mut Criterion) {
+    let mut bumpcar = BumpCar::new(1024 * 1024).unwrap();
+
+    c.bench_function("Vec::push growing in place (u32 x 10000)", |b| {
+        b.iter(|| {
+            let mut v = Vec::new_in(&bumpcar);
+            for i in 0..10000u32 {
+                v.push(black_box(i));
+            }
+            black_box(v);
+            bumpcar.reset();
+        });
+    });
+}
+
+criterion_group!(
+    benches,
+    box_new_in,
+    box_new_in_dyn,
+    box_new_in_down,
+    allocate_mixed_alignments,
+    allocate_until_full,
+    vec_push
+);
+criterion_main!(benches);
@@ -0,0 +1,222 @@
+//! Baseline of the [`BumpCar`] against `bumpalo` and the global allocator.
+
+#![feature(allocator_api)]
+
+use core::alloc::{Allocator, Layout};
+use std::alloc::Global;
+use std::thread;
+
+use bumpalo::Bump;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dodgems::{BumpCar, SyncBumpCar};
+
+const CAPACITY: usize = 1024 * 1024;
+const ALLOCATIONS: usize = 1000;
+const THREADS: usize = 4;
+
+fn small_allocations(c: &mut Criterion) {
+    let mut group = c.benchmark_group("small allocations (u64 x 1000)");
+
+    let mut bumpcar = BumpCar::new(CAPACITY).unwrap();
+    group.bench_function("dodgems", |b| {
+        b.iter(|| {
+            for i in 0..ALLOCATIONS as u64 {
+                black_box(Box::new_in(black_box(i), &bumpcar));
+            }
+            bumpcar.reset();
+        });
+    });
+
+    let mut bump = Bump::with_capacity(CAPACITY);
+    group.bench_function("bumpalo", |b| {
+        b.iter(|| {
+            for i in 0..ALLOCATIONS as u64 {
+                black_box(bump.alloc(black_box(i)));
+            }
+            bump.reset();
+        });
+    });
+
+    group.bench_function("Global", |b| {
+        b.iter(|| {
+            for i in 0..ALLOCATIONS as u64 {
+                black_box(Box::new_in(black_box(i), Global));
+            }
+        });
+    });
+
+    group.finish();
+}
+
+fn vec_growth(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Vec growth (push u32 x 1000)");
+
+    let mut bumpcar = BumpCar::new(CAPACITY).unwrap();
+    group.bench_function("dodgems", |b| {
+        b.iter(|| {
+            let mut v = Vec::new_in(&bumpcar);
+            for i in 0..ALLOCATIONS as u32 {
+                v.push(black_box(i));
+            }
+            black_box(v);
+            bumpcar.reset();
+        });
+    });
+
+    let mut bump = Bump::with_capacity(CAPACITY);
+    group.bench_function("bumpalo", |b| {
+        b.iter(|| {
+            let mut v = bumpalo::collections::Vec::new_in(&bump);
+            for i in 0..ALLOCATIONS as u32 {
+                v.push(black_box(i));
+            }
+            black_box(v);
+            bump.reset();
+        });
+    });
+
+    group.bench_function("Global", |b| {
+        b.iter(|| {
+            let mut v = Vec::new();
+            for i in 0..ALLOCATIONS as u32 {
+                v.push(black_box(i));
+            }
+            black_box(v);
+        });
+    });
+
+    group.finish();
+}
+
+fn varied_alignments(c: &mut Criterion) {
+    let mut group = c.benchmark_group("varied alignments (x 1000)");
+    let layouts = [
+        Layout::new::<u8>(),
+        Layout::new::<u64>(),
+        Layout::new::<[u16; 3]>(),
+        Layout::from_size_align(32, 32).unwrap(),
+        Layout::new::<u32>(),
+    ];
+
+    let mut bumpcar = BumpCar::new(CAPACITY).unwrap();
+    group.bench_function("dodgems", |b| {
+        b.iter(|| {
+            for layout in layouts.iter().cycle().take(ALLOCATIONS) {
+                black_box(bumpcar.allocate(black_box(*layout)).unwrap());
+            }
+            bumpcar.reset();
+        });
+    });
+
+    let mut bump = Bump::with_capacity(CAPACITY);
+    group.bench_function("bumpalo", |b| {
+        b.iter(|| {
+            for layout in layouts.iter().cycle().take(ALLOCATIONS) {
+                black_box(bump.alloc_layout(black_box(*layout)));
+            }
+            bump.reset();
+        });
+    });
+
+    group.bench_function("Global", |b| {
+        b.iter(|| {
+            for layout in layouts.iter().cycle().take(ALLOCATIONS) {
+                let ptr = Global.allocate(black_box(*layout)).unwrap();
+                unsafe { Global.deallocate(black_box(ptr).cast(), *layout) };
+            }
+        });
+    });
+
+    group.finish();
+}
+
+fn reset(c: &mut Criterion) {
+    let mut group = c.benchmark_group("reset after 1000 u64");
+
+    let mut bumpcar = BumpCar::new(CAPACITY).unwrap();
+    group.bench_function("dodgems", |b| {
+        b.iter(|| {
+            for _ in 0..ALLOCATIONS {
+                black_box(bumpcar.allocate(Layout::new::<u64>()).unwrap());
+            }
+            black_box(&mut bumpcar).reset();
+        });
+    });
+
+    let mut bump = Bump::with_capacity(CAPACITY);
+    group.bench_function("bumpalo", |b| {
+        b.iter(|| {
+            for _ in 0..ALLOCATIONS {
+                black_box(bump.alloc_layout(Layout::new::<u64>()));
+            }
+            black_box(&mut bump).reset();
+        });
+    });
+
+    group.finish();
+}
+
+/// `bumpalo` has no thread-safe arena: it is measured with one arena per thread.
+fn concurrent(c: &mut Criterion) {
+    let mut group = c.benchmark_group("concurrent (4 threads x 1000 u64)");
+
+    let mut bumpcar = SyncBumpCar::new(THREADS * ALLOCATIONS * 8).unwrap();
+    group.bench_function("dodgems", |b| {
+        b.iter(|| {
+            thread::scope(|s| {
+                for _ in 0..THREADS {
+                    s.spawn(|| {
+                        for _ in 0..ALLOCATIONS {
+                            black_box(bumpcar.allocate(Layout::new::<u64>()).unwrap());
+                        }
+                    });
+                }
+            });
+            bumpcar.reset();
+        });
+    });
+
+    let mut bumps: Vec<Bump> = (0..THREADS)
+        .map(|_| Bump::with_capacity(ALLOCATIONS * 8))
+        .collect();
+    group.bench_function("bumpalo", |b| {
+        b.iter(|| {
+            thread::scope(|s| {
+                for bump in &mut bumps {
+                    s.spawn(move || {
+                        for i in 0..ALLOCATIONS as u64 {
+                            black_box(bump.alloc(i));
+                        }
+                        bump.reset();
+                    });
+                }
+            });
+        });
+    });
+
+    group.bench_function("Global", |b| {
+        b.iter(|| {
+            thread::scope(|s| {
+                for _ in 0..THREADS {
+                    s.spawn(|| {
+                        for i in 0..ALLOCATIONS as u64 {
+                            black_box(Box::new(i));
+                        }
+                    });
+                }
+            });
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    small_allocations,
+    vec_growth,
+    varied_alignments,
+    reset,
+    concurrent
+);
+criterion_main!(benches);
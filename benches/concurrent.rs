@@ -0,0 +1,96 @@
+#![feature(allocator_api)]
+
+use core::alloc::{Allocator, Layout};
+use std::alloc::Global;
+use std::thread;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dodgems::{CursorPadding, ShardedBumpCar, SyncBumpCar};
+
+const THREADS: usize = 4;
+const ALLOCATIONS: usize = 10_000;
+
+fn shared_cursor(c: &mut Criterion) {
+    let mut bumpcar = SyncBumpCar::new(THREADS * ALLOCATIONS * 8).unwrap();
+
+    c.bench_function("SyncBumpCar shared cursor (4 threads x 10000 u64)", |b| {
+        b.iter(|| {
+            thread::scope(|s| {
+                for _ in 0..THREADS {
+                    s.spawn(|| {
+                        for _ in 0..ALLOCATIONS {
+                            black_box(bumpcar.allocate(Layout::new::<u64>()).unwrap());
+                        }
+                    });
+                }
+            });
+            bumpcar.reset();
+        });
+    });
+}
+
+/// Allocates from one arena per thread, the arenas being stored next to each other.
+fn adjacent_arenas<P: CursorPadding>(
+    c: &mut Criterion,
+    name: &str,
+    new: impl Fn(usize) -> SyncBumpCar<Global, P>,
+) {
+    let mut arenas: Vec<_> = (0..THREADS).map(|_| new(ALLOCATIONS * 8)).collect();
+
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            thread::scope(|s| {
+                for bumpcar in &arenas {
+                    s.spawn(move || {
+                        for _ in 0..ALLOCATIONS {
+                            black_box(bumpcar.allocate(Layout::new::<u64>()).unwrap());
+                        }
+                    });
+                }
+            });
+            arenas.iter_mut().for_each(SyncBumpCar::reset);
+        });
+    });
+}
+
+/// Without the cache line padding of their cursors, every allocation invalidates the cursors
+/// of the neighbouring arenas.
+fn adjacent_cursors(c: &mut Criterion) {
+    adjacent_arenas(
+        c,
+        "SyncBumpCar adjacent padded cursors (4 threads x 10000 u64)",
+        |capacity| SyncBumpCar::new(capacity).unwrap(),
+    );
+    adjacent_arenas(
+        c,
+        "SyncBumpCar adjacent unpadded cursors (4 threads x 10000 u64)",
+        |capacity| SyncBumpCar::new_unpadded(capacity).unwrap(),
+    );
+}
+
+fn adjacent_shards(c: &mut Criterion) {
+    let mut bumpcar = ShardedBumpCar::new(THREADS * (ALLOCATIONS * 8 + 4096), 4096).unwrap();
+
+    c.bench_function(
+        "ShardedBumpCar adjacent shards (4 threads x 10000 u64)",
+        |b| {
+            b.iter(|| {
+                let mut shards: Vec<_> = (0..THREADS).map(|_| bumpcar.shard()).collect();
+                thread::scope(|s| {
+                    for shard in &mut shards {
+                        s.spawn(move || {
+                            for _ in 0..ALLOCATIONS {
+                                black_box((&*shard).allocate(Layout::new::<u64>()).unwrap());
+                            }
+                        });
+                    }
+                });
+                drop(shards);
+                bumpcar.reset();
+            });
+        },
+    );
+}
+
+criterion_group!(benches, shared_cursor, adjacent_cursors, adjacent_shards);
+criterion_main!(benches);
@@ -0,0 +1,29 @@
+//! Firmware-style usage of a [`StaticBumpCar`]: the arena is reserved statically,
+//! used without any initialization, and reset at the end of every iteration of the main loop.
+//!
+//! On an embedded target, the static can be placed in a specific memory region
+//! with the `#[link_section]` attribute.
+#![feature(allocator_api)]
+
+use dodgems::StaticBumpCar;
+
+static SCRATCH: StaticBumpCar<4096> = StaticBumpCar::new();
+
+/// Decodes a fake sensor frame in the scratch arena.
+fn process_frame(frame: u32) -> u32 {
+    let mut samples = Vec::with_capacity_in(64, &SCRATCH);
+    samples.extend((0..64).map(|i| frame.wrapping_mul(31).wrapping_add(i)));
+    samples.iter().fold(0, |acc, x| acc ^ x)
+}
+
+fn main() {
+    for frame in 0..10 {
+        let checksum = process_frame(frame);
+        println!(
+            "frame {frame}: checksum {checksum:#010x}, {} bytes left",
+            SCRATCH.remaining_capacity()
+        );
+        // SAFETY: every allocation made in process_frame has been dropped.
+        unsafe { SCRATCH.reset() };
+    }
+}
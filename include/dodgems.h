@@ -0,0 +1,43 @@
+/*
+ * C interface to the dodgems bump allocator.
+ *
+ * Build the crate with the `ffi` feature to export these functions. An arena
+ * must not be used from several threads at once.
+ */
+
+#ifndef DODGEMS_H
+#define DODGEMS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Opaque handle to an arena allocated with the global allocator. */
+typedef struct DodgemsArena DodgemsArena;
+
+/* Creates a new arena with the given capacity, or returns NULL on failure.
+ * The arena must be freed with dodgems_free. */
+DodgemsArena *dodgems_new(size_t capacity);
+
+/* Allocates `size` bytes aligned to `align` in the arena. Returns NULL if the
+ * arena is NULL, `size` is zero, `align` is not a power of two, or if the
+ * arena is exhausted. The memory is valid until the arena is reset or freed. */
+uint8_t *dodgems_alloc(const DodgemsArena *arena, size_t size, size_t align);
+
+/* Returns the remaining capacity of the arena, or zero if it is NULL. */
+size_t dodgems_remaining(const DodgemsArena *arena);
+
+/* Resets the arena, invalidating every pointer it returned. */
+void dodgems_reset(DodgemsArena *arena);
+
+/* Frees the arena and all of its allocations. */
+void dodgems_free(DodgemsArena *arena);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* DODGEMS_H */
@@ -0,0 +1,106 @@
+use core::alloc::Allocator;
+
+use crate::BumpCar;
+
+/// Sliding window of the peak usages of an arena over its last `N` resets, suggesting a
+/// capacity to right-size the next arenas.
+///
+/// The suggested capacity is the 99th percentile of the recorded peaks, plus a headroom
+/// (10% by default), so that a rare spike does not inflate every arena.
+///
+/// # Example
+/// ```rust
+/// use dodgems::{BumpCar, CapacityAdvisor};
+///
+/// let mut advisor = CapacityAdvisor::<16>::new();
+/// let mut bumpcar = BumpCar::new(4096).unwrap();
+/// for request in 0..16 {
+///     bumpcar.alloc_slice_fill_with(100 + request, |_| 0u8);
+///     advisor.record_reset(&mut bumpcar);
+/// }
+/// assert_eq!(advisor.suggested_capacity(), Some(115 + 12));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CapacityAdvisor<const N: usize = 64> {
+    peaks: [usize; N],
+    len: usize,
+    next: usize,
+    headroom: usize,
+}
+
+impl<const N: usize> CapacityAdvisor<N> {
+    /// Creates an empty [`CapacityAdvisor`], with a headroom of 10%.
+    pub const fn new() -> Self {
+        Self {
+            peaks: [0; N],
+            len: 0,
+            next: 0,
+            headroom: 10,
+        }
+    }
+
+    /// Sets the headroom added to the suggested capacity, in percent of the 99th percentile of
+    /// the peaks.
+    pub const fn with_headroom(mut self, percent: usize) -> Self {
+        self.headroom = percent;
+        self
+    }
+
+    /// Returns the headroom added to the suggested capacity, in percent.
+    pub const fn headroom(&self) -> usize {
+        self.headroom
+    }
+
+    /// Returns the number of peaks in the window.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no peak was recorded.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Records a peak usage, evicting the oldest one if the window is full.
+    pub fn record(&mut self, peak: usize) {
+        if N == 0 {
+            return;
+        }
+        self.peaks[self.next] = peak;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    /// Records the [peak usage](BumpCar::peak_usage) of `bumpcar`, resets it, and clears its
+    /// peak usage for the next round.
+    pub fn record_reset<A: Allocator>(&mut self, bumpcar: &mut BumpCar<A>) {
+        self.record(bumpcar.peak_usage());
+        bumpcar.reset();
+        bumpcar.reset_peak();
+    }
+
+    /// Returns the 99th percentile of the recorded peaks plus the headroom, or `None` if no
+    /// peak was recorded.
+    pub fn suggested_capacity(&self) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+        let mut peaks = self.peaks;
+        let peaks = &mut peaks[..self.len];
+        peaks.sort_unstable();
+        let p99 = peaks[(self.len * 99).div_ceil(100) - 1];
+        Some(p99.saturating_add(p99.saturating_mul(self.headroom).div_ceil(100)))
+    }
+
+    /// Forgets the recorded peaks.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.next = 0;
+    }
+}
+
+impl<const N: usize> Default for CapacityAdvisor<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
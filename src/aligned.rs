@@ -0,0 +1,193 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{next_multiple, BumpCar, BumpError};
+
+/// [`BumpCar`] keeping its cursor aligned to `MIN_ALIGN`.
+///
+/// Allocation sizes are rounded up to a multiple of `MIN_ALIGN`, so that allocations with an
+/// alignment smaller than or equal to `MIN_ALIGN` skip the alignment computation entirely.
+/// This trades a little memory for a shorter allocation path, when most allocations share
+/// the same alignment.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::AlignedBumpCar;
+///
+/// let bumpcar = AlignedBumpCar::<8>::new(256).unwrap();
+/// let a = Box::new_in(1u8, &bumpcar);
+/// let b = Box::new_in(2u64, &bumpcar);
+/// assert_eq!(bumpcar.remaining_capacity(), 240);
+/// ```
+#[derive(Debug)]
+pub struct AlignedBumpCar<
+    const MIN_ALIGN: usize,
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    bumpcar: BumpCar<A>,
+}
+
+impl<const MIN_ALIGN: usize, A: Allocator> AlignedBumpCar<MIN_ALIGN, A> {
+    /// Allocates a new [`AlignedBumpCar`] in the given allocator.
+    ///
+    /// `MIN_ALIGN` must be a power of two, or this fails to compile.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        const {
+            assert!(
+                MIN_ALIGN.is_power_of_two(),
+                "MIN_ALIGN must be a power of two"
+            )
+        };
+        let this = Self {
+            bumpcar: BumpCar::new_in(capacity, allocator)?,
+        };
+        this.align_cursor();
+        Ok(this)
+    }
+
+    /// Moves the cursor up to the next multiple of `MIN_ALIGN`, or to the end of the buffer.
+    fn align_cursor(&self) {
+        let current = self.bumpcar.current.get();
+        let end = self.bumpcar.end.addr().get();
+        // SAFETY: MIN_ALIGN is a power of two.
+        let aligned = unsafe { next_multiple(current.addr().get(), MIN_ALIGN) }.min(end);
+        // SAFETY: current <= aligned <= end, so the pointer stays in bounds of the buffer.
+        self.bumpcar
+            .current
+            .set(unsafe { current.byte_add(aligned - current.addr().get()) });
+    }
+
+    /// Returns the capacity of the [`AlignedBumpCar`].
+    pub fn capacity(&self) -> usize {
+        self.bumpcar.capacity()
+    }
+
+    /// Returns the remaining capacity of the [`AlignedBumpCar`].
+    pub fn remaining_capacity(&self) -> usize {
+        self.bumpcar.remaining_capacity()
+    }
+
+    /// Resets the [`AlignedBumpCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    pub fn reset(&mut self) {
+        self.bumpcar.reset();
+        self.align_cursor();
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const MIN_ALIGN: usize> AlignedBumpCar<MIN_ALIGN> {
+    /// Allocates an [`AlignedBumpCar`] with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, Global)
+    }
+}
+
+/// Rounds `size` up to a multiple of `MIN_ALIGN`.
+fn round<const MIN_ALIGN: usize>(size: usize) -> Option<usize> {
+    Some(size.checked_add(MIN_ALIGN - 1)? & !(MIN_ALIGN - 1))
+}
+
+unsafe impl<const MIN_ALIGN: usize, A: Allocator> Allocator for AlignedBumpCar<MIN_ALIGN, A> {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let size = round::<MIN_ALIGN>(layout.size()).ok_or(AllocError)?;
+        if layout.align() <= MIN_ALIGN {
+            let current = self.bumpcar.current.get();
+            if size > self.bumpcar.end.addr().get() - current.addr().get() {
+                return Err(AllocError);
+            }
+            // SAFETY: the cursor is aligned to MIN_ALIGN, and size fits before the end.
+            self.bumpcar.current.set(unsafe { current.add(size) });
+            #[cfg(feature = "valgrind")]
+            crate::valgrind::alloc(self.bumpcar.start, current, layout.size());
+            #[cfg(all(feature = "asan", sanitize = "address"))]
+            crate::asan::alloc(current, layout.size());
+            return Ok(NonNull::slice_from_raw_parts(current, layout.size()));
+        }
+
+        let ptr = self
+            .bumpcar
+            .allocate(Layout::from_size_align(size, layout.align()).map_err(|_| AllocError)?)?;
+        Ok(NonNull::slice_from_raw_parts(ptr.cast(), layout.size()))
+    }
+
+    /// Deallocates an allocated region.
+    ///
+    /// If it is the last allocation made, its space is reclaimed.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if let Some(size) = round::<MIN_ALIGN>(layout.size()) {
+            self.bumpcar.resize_in_place(ptr, size, 0);
+        }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if let (Some(old_size), Some(new_size)) = (
+            round::<MIN_ALIGN>(old_layout.size()),
+            round::<MIN_ALIGN>(new_layout.size()),
+        ) {
+            if ptr.addr().get() & (new_layout.align() - 1) == 0
+                && self.bumpcar.resize_in_place(ptr, old_size, new_size)
+            {
+                return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+            }
+        }
+
+        let new_ptr = self.allocate(new_layout)?;
+        // SAFETY: the new allocation is valid for new_layout.size() >= old_layout.size() bytes,
+        // and cannot overlap the old allocation.
+        unsafe {
+            new_ptr
+                .cast::<u8>()
+                .copy_from_nonoverlapping(ptr, old_layout.size());
+        }
+        Ok(new_ptr)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if ptr.addr().get() & (new_layout.align() - 1) == 0 {
+            if let (Some(old_size), Some(new_size)) = (
+                round::<MIN_ALIGN>(old_layout.size()),
+                round::<MIN_ALIGN>(new_layout.size()),
+            ) {
+                self.bumpcar.resize_in_place(ptr, old_size, new_size);
+            }
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+
+        let new_ptr = self.allocate(new_layout)?;
+        // SAFETY: the new allocation is valid for new_layout.size() bytes,
+        // and cannot overlap the old allocation.
+        unsafe {
+            new_ptr
+                .cast::<u8>()
+                .copy_from_nonoverlapping(ptr, new_layout.size());
+        }
+        Ok(new_ptr)
+    }
+}
@@ -0,0 +1,139 @@
+//! Thread-local "current" [`BumpCar`], for code that cannot take the arena as a parameter.
+//!
+//! A scope started with [`with_bump!`](crate::with_bump) or [`with_bumpcar`] installs an arena
+//! for the current thread, and the free functions of this module allocate in it. Scopes can be
+//! nested: the previous arena is restored when a scope ends, even if it panics.
+//!
+//! The arena is shared through an [`Rc`], that is an allocator as well: values allocated in it
+//! keep it alive, so they can safely outlive the scope, and its buffer is freed when the last
+//! of them is dropped.
+//!
+//! # Example
+//! ```rust
+//! use dodgems::{ambient, with_bump};
+//!
+//! struct Matrix(Vec<f64>);
+//!
+//! impl core::ops::Mul<f64> for &Matrix {
+//!     type Output = f64;
+//!
+//!     fn mul(self, k: f64) -> f64 {
+//!         // no way to pass an arena to an operator: use the ambient one
+//!         let mut scaled = ambient::alloc_slice_copy(&self.0);
+//!         scaled.iter_mut().for_each(|x| *x *= k);
+//!         scaled.iter().sum()
+//!     }
+//! }
+//!
+//! let m = Matrix(vec![1.0, 2.0, 3.0]);
+//! let sum = with_bump!(1024, { &m * 2.0 });
+//! assert_eq!(sum, 12.0);
+//! ```
+
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use core::alloc::{AllocError, Layout};
+use core::cell::Cell;
+
+use crate::{BumpCar, BumpError, BumpErrorKind};
+
+std::thread_local! {
+    static CURRENT: Cell<Option<Rc<BumpCar>>> = const { Cell::new(None) };
+}
+
+/// Restores the previous arena of the thread when a scope ends.
+struct Restore(Option<Rc<BumpCar>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.set(self.0.take());
+    }
+}
+
+/// Runs `f` with `bumpcar` as the current arena of the thread.
+pub fn with_bumpcar<R>(bumpcar: Rc<BumpCar>, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(CURRENT.replace(Some(bumpcar)));
+    f()
+}
+
+/// Runs `f` with a new arena of `capacity` bytes as the current arena of the thread.
+///
+/// # Panics
+/// This function panics if the arena could not be allocated.
+#[track_caller]
+pub fn with_capacity<R>(capacity: usize, f: impl FnOnce() -> R) -> R {
+    match BumpCar::new(capacity) {
+        Ok(bumpcar) => with_bumpcar(Rc::new(bumpcar), f),
+        Err(err) => panic!("{err}"),
+    }
+}
+
+/// Returns the current arena of the thread, if any.
+pub fn current() -> Option<Rc<BumpCar>> {
+    let bumpcar = CURRENT.take();
+    CURRENT.set(bumpcar.clone());
+    bumpcar
+}
+
+/// Moves `value` into the current arena of the thread.
+///
+/// # Errors
+/// This function returns an error if there is no current arena, or if it does not have enough
+/// capacity left.
+pub fn try_alloc<T>(value: T) -> Result<Box<T, Rc<BumpCar>>, AllocError> {
+    Box::try_new_in(value, current().ok_or(AllocError)?)
+}
+
+/// Moves `value` into the current arena of the thread.
+///
+/// # Panics
+/// This function panics if there is no current arena, or if it does not have enough capacity
+/// left.
+#[track_caller]
+pub fn alloc<T>(value: T) -> Box<T, Rc<BumpCar>> {
+    let bumpcar = expect_current();
+    match Box::try_new_in(value, Rc::clone(&bumpcar)) {
+        Ok(b) => b,
+        Err(_) => capacity_exceeded(Layout::new::<T>(), &bumpcar),
+    }
+}
+
+/// Copies `values` into the current arena of the thread.
+///
+/// # Panics
+/// This function panics if there is no current arena, or if it does not have enough capacity
+/// left.
+#[track_caller]
+pub fn alloc_slice_copy<T: Copy>(values: &[T]) -> Box<[T], Rc<BumpCar>> {
+    let bumpcar = expect_current();
+    match Box::try_new_uninit_slice_in(values.len(), Rc::clone(&bumpcar)) {
+        Ok(mut b) => {
+            b.write_copy_of_slice(values);
+            // SAFETY: every element was initialized.
+            unsafe { b.assume_init() }
+        }
+        Err(_) => capacity_exceeded(Layout::for_value(values), &bumpcar),
+    }
+}
+
+/// Returns the current arena of the thread.
+///
+/// # Panics
+/// This function panics if there is no current arena.
+#[track_caller]
+fn expect_current() -> Rc<BumpCar> {
+    match current() {
+        Some(bumpcar) => bumpcar,
+        None => panic!("no ambient BumpCar: allocate inside of `with_bump!`"),
+    }
+}
+
+#[track_caller]
+fn capacity_exceeded(layout: Layout, bumpcar: &BumpCar) -> ! {
+    let err = BumpError::new(
+        BumpErrorKind::CapacityExceeded,
+        Some(layout),
+        bumpcar.remaining_capacity(),
+    );
+    panic!("{err}")
+}
@@ -0,0 +1,102 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::ptr::NonNull;
+
+use allocator_api2::alloc as api2;
+
+use crate::{BumpCar, FlexBumpCar};
+
+/// Adapter to use an [`allocator_api2`] allocator as the backing allocator of a [`BumpCar`].
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use allocator_api2::alloc::Global;
+/// use dodgems::{BumpCar, Compat};
+///
+/// let bumpcar = BumpCar::new_in(256, Compat(Global)).unwrap();
+/// let v = allocator_api2::vec::Vec::<u8, _>::with_capacity_in(16, &bumpcar);
+/// assert_eq!(bumpcar.remaining_capacity(), 240);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Compat<A>(pub A);
+
+unsafe impl<A: api2::Allocator> Allocator for Compat<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.0.allocate(layout).map_err(|_| AllocError)
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.0.allocate_zeroed(layout).map_err(|_| AllocError)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: the caller upholds the contract of Allocator::deallocate.
+        unsafe { self.0.deallocate(ptr, layout) }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: the caller upholds the contract of Allocator::grow.
+        unsafe { self.0.grow(ptr, old_layout, new_layout) }.map_err(|_| AllocError)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: the caller upholds the contract of Allocator::shrink.
+        unsafe { self.0.shrink(ptr, old_layout, new_layout) }.map_err(|_| AllocError)
+    }
+}
+
+/// Implements [`allocator_api2::alloc::Allocator`] for a reference to an arena,
+/// by forwarding to its [`Allocator`] implementation.
+macro_rules! impl_api2 {
+    ($arena:ident) => {
+        unsafe impl<A: Allocator> api2::Allocator for &$arena<A> {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, api2::AllocError> {
+                Allocator::allocate(self, layout).map_err(|_| api2::AllocError)
+            }
+
+            fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, api2::AllocError> {
+                Allocator::allocate_zeroed(self, layout).map_err(|_| api2::AllocError)
+            }
+
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                // SAFETY: the caller upholds the contract of Allocator::deallocate.
+                unsafe { Allocator::deallocate(self, ptr, layout) }
+            }
+
+            unsafe fn grow(
+                &self,
+                ptr: NonNull<u8>,
+                old_layout: Layout,
+                new_layout: Layout,
+            ) -> Result<NonNull<[u8]>, api2::AllocError> {
+                // SAFETY: the caller upholds the contract of Allocator::grow.
+                unsafe { Allocator::grow(self, ptr, old_layout, new_layout) }
+                    .map_err(|_| api2::AllocError)
+            }
+
+            unsafe fn shrink(
+                &self,
+                ptr: NonNull<u8>,
+                old_layout: Layout,
+                new_layout: Layout,
+            ) -> Result<NonNull<[u8]>, api2::AllocError> {
+                // SAFETY: the caller upholds the contract of Allocator::shrink.
+                unsafe { Allocator::shrink(self, ptr, old_layout, new_layout) }
+                    .map_err(|_| api2::AllocError)
+            }
+        }
+    };
+}
+
+impl_api2!(BumpCar);
+impl_api2!(FlexBumpCar);
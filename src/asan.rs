@@ -0,0 +1,55 @@
+//! AddressSanitizer manual poisoning of the unused part of a [`BumpCar`](crate::BumpCar).
+//!
+//! The whole buffer is poisoned when the arena is created, and bytes are unpoisoned as they are
+//! handed out, then poisoned again when they are reclaimed.
+
+use core::ptr::NonNull;
+
+extern "C" {
+    fn __asan_poison_memory_region(addr: *const u8, size: usize);
+    fn __asan_unpoison_memory_region(addr: *const u8, size: usize);
+}
+
+/// Marks the `size` bytes at `ptr` as unaddressable.
+fn poison(ptr: NonNull<u8>, size: usize) {
+    // SAFETY: the region is part of the buffer of an arena, poisoning it has no other effect
+    // than reporting its accesses.
+    unsafe { __asan_poison_memory_region(ptr.as_ptr(), size) };
+}
+
+/// Marks the `size` bytes at `ptr` as addressable.
+fn unpoison(ptr: NonNull<u8>, size: usize) {
+    // SAFETY: the region is part of the buffer of an arena.
+    unsafe { __asan_unpoison_memory_region(ptr.as_ptr(), size) };
+}
+
+/// Poisons the whole buffer of `len` bytes at `start`.
+pub(crate) fn create(start: NonNull<u8>, len: usize) {
+    poison(start, len);
+}
+
+/// Unpoisons the whole buffer of `len` bytes at `start`, before it is returned.
+pub(crate) fn destroy(start: NonNull<u8>, len: usize) {
+    unpoison(start, len);
+}
+
+/// Unpoisons the allocation of `size` bytes at `ptr`.
+pub(crate) fn alloc(ptr: NonNull<u8>, size: usize) {
+    unpoison(ptr, size);
+}
+
+/// Poisons or unpoisons the difference between the old and new sizes of an allocation.
+pub(crate) fn resize(ptr: NonNull<u8>, old_size: usize, new_size: usize) {
+    // SAFETY: both sizes are in bounds of the buffer.
+    if new_size < old_size {
+        poison(unsafe { ptr.add(new_size) }, old_size - new_size);
+    } else {
+        unpoison(unsafe { ptr.add(old_size) }, new_size - old_size);
+    }
+}
+
+/// Poisons the buffer of `len` bytes at `start` past its first `position` bytes.
+pub(crate) fn trim(start: NonNull<u8>, position: usize, len: usize) {
+    // SAFETY: position <= len, so the pointer is in bounds of the buffer.
+    poison(unsafe { start.add(position) }, len - position);
+}
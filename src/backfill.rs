@@ -0,0 +1,168 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{bump, BumpCar, BumpError};
+
+/// [`BumpCar`] reusing the padding skipped by aligned allocations.
+///
+/// When an allocation skips bytes to align its start, the skipped gap is remembered, and later
+/// allocations that fit in it are placed there instead of advancing the cursor. Workloads mixing
+/// small, weakly aligned values with strongly aligned ones (such as SIMD buffers or cache line
+/// aligned structures) waste much less of the arena to padding.
+///
+/// Only the most recent gap is remembered, and allocations placed in it are never reclaimed
+/// before the next reset, since they are never the last allocation of the arena.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::BackfillBumpCar;
+///
+/// #[repr(align(64))]
+/// struct Line([u8; 64]);
+///
+/// let bumpcar = BackfillBumpCar::new(512).unwrap();
+/// let flag = Box::new_in(true, &bumpcar);
+/// let line = Box::new_in(Line([0; 64]), &bumpcar);
+/// let remaining = bumpcar.remaining_capacity();
+///
+/// // This fits in the bytes skipped to align the line, after the flag.
+/// let count = Box::new_in(0u32, &bumpcar);
+/// assert!(core::ptr::from_ref(&*count).addr() < core::ptr::from_ref(&*line).addr());
+/// assert_eq!(bumpcar.remaining_capacity(), remaining);
+/// ```
+#[derive(Debug)]
+pub struct BackfillBumpCar<
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    bumpcar: BumpCar<A>,
+    gap: Cell<(NonNull<u8>, NonNull<u8>)>,
+}
+
+impl<A: Allocator> BackfillBumpCar<A> {
+    /// Allocates a new [`BackfillBumpCar`] in the given allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        let bumpcar = BumpCar::new_in(capacity, allocator)?;
+        let gap = Cell::new((bumpcar.start, bumpcar.start));
+        Ok(Self { bumpcar, gap })
+    }
+
+    /// Returns the capacity of the [`BackfillBumpCar`].
+    pub fn capacity(&self) -> usize {
+        self.bumpcar.capacity()
+    }
+
+    /// Returns the remaining capacity of the [`BackfillBumpCar`], after its cursor.
+    ///
+    /// The bytes left in the remembered gap are not included.
+    pub fn remaining_capacity(&self) -> usize {
+        self.bumpcar.remaining_capacity()
+    }
+
+    /// Returns the number of bytes left in the remembered gap.
+    pub fn gap_len(&self) -> usize {
+        let (start, end) = self.gap.get();
+        end.addr().get() - start.addr().get()
+    }
+
+    /// Resets the [`BackfillBumpCar`]'s remaining capacity to its initial capacity, and forgets
+    /// the remembered gap.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    pub fn reset(&mut self) {
+        self.bumpcar.reset();
+        let current = self.bumpcar.current.get();
+        self.gap.set((current, current));
+    }
+
+    /// Places an allocation in the remembered gap, if it fits.
+    #[inline]
+    fn backfill(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let (start, end) = self.gap.get();
+        // The gap is always below the cursor, unless the allocation that filled it up to the
+        // cursor was reclaimed.
+        if end > self.bumpcar.current.get() {
+            return None;
+        }
+        // SAFETY: start <= end are in bounds of the buffer.
+        let (ptr, new_start) = unsafe { bump(start, end, layout) }?;
+        self.gap.set((new_start, end));
+        #[cfg(feature = "valgrind")]
+        crate::valgrind::alloc(self.bumpcar.start, ptr, layout.size());
+        #[cfg(all(feature = "asan", sanitize = "address"))]
+        crate::asan::alloc(ptr, layout.size());
+        Some(ptr)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl BackfillBumpCar {
+    /// Allocates a [`BackfillBumpCar`] with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, Global)
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for BackfillBumpCar<A> {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if let Some(ptr) = self.backfill(layout) {
+            return Ok(NonNull::slice_from_raw_parts(ptr, layout.size()));
+        }
+        let current = self.bumpcar.current.get();
+        let ptr = self.bumpcar.allocate(layout)?;
+        if ptr.cast::<u8>() > current {
+            self.gap.set((current, ptr.cast()));
+        }
+        Ok(ptr)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.deallocate(ptr, layout) }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.grow(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.grow_zeroed(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.shrink(ptr, old_layout, new_layout) }
+    }
+}
@@ -0,0 +1,271 @@
+use core::alloc::Allocator;
+use core::borrow::{Borrow, BorrowMut};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::{PhantomData, Unsize};
+use core::mem::ManuallyDrop;
+use core::ops::{CoerceUnsized, Deref, DerefMut};
+#[cfg(feature = "alloc")]
+use core::pin::Pin;
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::typed::expect_capacity;
+use crate::{BumpCar, BumpError};
+
+/// Owning pointer to a value allocated in a [`BumpCar`], that drops the value when it goes out
+/// of scope.
+///
+/// Unlike `Box::new_in`, it does not rely on the allocator parameter of `Box`: dropping a
+/// [`BumpBox`] runs the destructor of its value, but its memory is only reclaimed when the
+/// [`BumpCar`] is reset.
+///
+/// # Example
+/// ```rust
+/// use dodgems::{BumpBox, BumpCar};
+///
+/// let bumpcar = BumpCar::new(1024).unwrap();
+/// let mut names = bumpcar.alloc_box(vec![String::from("alice")]);
+/// names.push(String::from("bob"));
+/// assert_eq!(names.len(), 2);
+///
+/// drop(names); // the vector and its strings are freed
+///
+/// let answer = bumpcar.alloc_box(41);
+/// assert_eq!(BumpBox::into_inner(answer) + 1, 42);
+/// ```
+pub struct BumpBox<'a, T: ?Sized> {
+    ptr: NonNull<T>,
+    /// Count of the live pinned boxes of the [`BumpCar`], if the box is pinned.
+    pinned: Option<&'a AtomicUsize>,
+    _marker: PhantomData<(&'a T, T)>,
+}
+
+// SAFETY: a BumpBox owns its value, like a Box.
+unsafe impl<T: ?Sized + Send> Send for BumpBox<'_, T> {}
+// SAFETY: a BumpBox only gives shared access to its value through a shared reference.
+unsafe impl<T: ?Sized + Sync> Sync for BumpBox<'_, T> {}
+
+// A box can be coerced to a box of a trait object or a slice, as `Box` is.
+impl<'a, T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<BumpBox<'a, U>> for BumpBox<'a, T> {}
+
+// The value is never moved by the box itself, so the box can be moved freely.
+impl<T: ?Sized> Unpin for BumpBox<'_, T> {}
+
+impl<'a, T: ?Sized> BumpBox<'a, T> {
+    /// Takes ownership of the value behind `value`, that will be dropped with the box.
+    ///
+    /// # Safety
+    /// The value must not be used, or dropped, once `value` expires.
+    pub unsafe fn from_mut(value: &'a mut T) -> Self {
+        Self {
+            ptr: NonNull::from(value),
+            pinned: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Consumes the box without dropping its value, and returns a mutable reference to it.
+    pub fn leak(b: Self) -> &'a mut T {
+        let b = ManuallyDrop::new(b);
+        b.unpin();
+        // SAFETY: the value is valid for 'a, and the box is not dropped.
+        unsafe { &mut *b.ptr.as_ptr() }
+    }
+}
+
+impl<T: ?Sized> BumpBox<'_, T> {
+    /// Unregisters a pinned box, once its value can no longer be accessed through it.
+    fn unpin(&self) {
+        if let Some(pinned) = self.pinned {
+            pinned.fetch_sub(1, Ordering::Release);
+        }
+    }
+}
+
+impl<T> BumpBox<'_, T> {
+    /// Moves the value out of the box.
+    pub fn into_inner(b: Self) -> T {
+        let b = ManuallyDrop::new(b);
+        b.unpin();
+        // SAFETY: the value is valid, and is not dropped by the box.
+        unsafe { b.ptr.read() }
+    }
+}
+
+impl<T: ?Sized> Drop for BumpBox<'_, T> {
+    fn drop(&mut self) {
+        // SAFETY: the box owns a valid value, that has not been dropped yet.
+        unsafe { core::ptr::drop_in_place(self.ptr.as_ptr()) };
+        self.unpin();
+    }
+}
+
+impl<T: ?Sized> Deref for BumpBox<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the box owns a valid value.
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for BumpBox<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: the box owns a valid value, and is borrowed mutably.
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T: ?Sized> AsRef<T> for BumpBox<'_, T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized> AsMut<T> for BumpBox<'_, T> {
+    fn as_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T: ?Sized> Borrow<T> for BumpBox<'_, T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized> BorrowMut<T> for BumpBox<'_, T> {
+    fn borrow_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for BumpBox<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: ?Sized + fmt::Display> fmt::Display for BumpBox<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: ?Sized + PartialEq> PartialEq for BumpBox<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: ?Sized + Eq> Eq for BumpBox<'_, T> {}
+
+impl<T: ?Sized + Hash> Hash for BumpBox<'_, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
+}
+
+impl<A: Allocator> BumpCar<A> {
+    /// Moves `value` into the [`BumpCar`], and returns a [`BumpBox`] that drops it.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_box<T>(&self, value: T) -> Result<BumpBox<'_, T>, BumpError> {
+        // SAFETY: the value is only reachable through the box.
+        self.try_alloc(value)
+            .map(|value| unsafe { BumpBox::from_mut(value) })
+    }
+
+    /// Moves `value` into the [`BumpCar`], and returns a [`BumpBox`] that drops it.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    #[track_caller]
+    pub fn alloc_box<T>(&self, value: T) -> BumpBox<'_, T> {
+        expect_capacity(self.try_alloc_box(value))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl BumpCar {
+    /// Moves `value` into the [`BumpCar`], and returns a pinned [`BumpBox`] that drops it, such
+    /// as a future polled by an executor, or the node of an intrusive list.
+    ///
+    /// Pinning requires the value to be dropped before its memory is reused. If the box is
+    /// leaked instead (for example with [`core::mem::forget`]), the resets of the [`BumpCar`]
+    /// keep its value, its buffer can no longer be resized, and it is leaked when the
+    /// [`BumpCar`] is dropped. This is why pinned boxes are only available with the global
+    /// allocator, that never reclaims a leaked buffer.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use core::future::Future;
+    /// use core::pin::pin;
+    /// use core::task::{Context, Poll, Waker};
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(1024).unwrap();
+    /// let mut future = bumpcar.alloc_pinned(async { 42 });
+    ///
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(42));
+    /// ```
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_pinned<T>(&self, value: T) -> Result<Pin<BumpBox<'_, T>>, BumpError> {
+        self.try_alloc_pinned_with(|| value)
+    }
+
+    /// Moves `value` into the [`BumpCar`], and returns a pinned [`BumpBox`] that drops it.
+    ///
+    /// See [`BumpCar::try_alloc_pinned`].
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    #[track_caller]
+    pub fn alloc_pinned<T>(&self, value: T) -> Pin<BumpBox<'_, T>> {
+        expect_capacity(self.try_alloc_pinned(value))
+    }
+
+    /// Builds a value in place in the [`BumpCar`] with `f`, and returns a pinned [`BumpBox`]
+    /// that drops it.
+    ///
+    /// See [`BumpCar::try_alloc_pinned`].
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    /// `f` is then not called.
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_pinned_with<T>(
+        &self,
+        f: impl FnOnce() -> T,
+    ) -> Result<Pin<BumpBox<'_, T>>, BumpError> {
+        let value = self.try_alloc_with(f)?;
+        let b = BumpBox {
+            ptr: NonNull::from(value),
+            pinned: Some(self.register_pinned()),
+            _marker: PhantomData,
+        };
+        // SAFETY: the value is only reachable through the box, and its memory is not reused
+        // before it is dropped: the cursor of the BumpCar never moves back below it while the
+        // box is registered, and the buffer is leaked if the box is.
+        Ok(unsafe { Pin::new_unchecked(b) })
+    }
+
+    /// Builds a value in place in the [`BumpCar`] with `f`, and returns a pinned [`BumpBox`]
+    /// that drops it.
+    ///
+    /// See [`BumpCar::try_alloc_pinned`].
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    #[track_caller]
+    pub fn alloc_pinned_with<T>(&self, f: impl FnOnce() -> T) -> Pin<BumpBox<'_, T>> {
+        expect_capacity(self.try_alloc_pinned_with(f))
+    }
+}
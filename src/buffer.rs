@@ -0,0 +1,80 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ptr::NonNull;
+
+use crate::BumpCar;
+
+/// Placeholder backing allocator of a [`BumpCar`] built on a borrowed buffer,
+/// with [`BumpCar::from_buffer`].
+///
+/// It never allocates, and deallocation is a no-op: the buffer is simply released
+/// when the [`BumpCar`] is dropped.
+#[derive(Debug)]
+pub struct BorrowedBuffer<'buf> {
+    _marker: PhantomData<&'buf mut [MaybeUninit<u8>]>,
+}
+
+unsafe impl Allocator for BorrowedBuffer<'_> {
+    /// A [`BorrowedBuffer`] cannot allocate.
+    fn allocate(&self, _: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        Err(AllocError)
+    }
+
+    /// The buffer is owned by the caller of [`BumpCar::from_buffer`], so this does nothing.
+    unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {}
+}
+
+/// [`BumpCar`] allocating in a borrowed buffer, created with [`BumpCar::from_buffer`].
+pub type BufferBumpCar<'buf> = BumpCar<BorrowedBuffer<'buf>>;
+
+impl<'buf> BumpCar<BorrowedBuffer<'buf>> {
+    /// Creates a [`BumpCar`] that allocates inside `buffer`, without any backing allocator.
+    ///
+    /// The buffer is borrowed for as long as the [`BumpCar`] lives, and is not deallocated
+    /// when it is dropped.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use core::mem::MaybeUninit;
+    /// use dodgems::BumpCar;
+    ///
+    /// let mut buffer = [MaybeUninit::uninit(); 64];
+    /// let bumpcar = BumpCar::from_buffer(&mut buffer);
+    ///
+    /// let v = Box::new_in([1u32, 2, 3, 4], &bumpcar);
+    /// assert!(bumpcar.owns(core::ptr::NonNull::from(&*v).cast()));
+    /// assert!(bumpcar.remaining_capacity() <= 48);
+    /// ```
+    pub fn from_buffer(buffer: &'buf mut [MaybeUninit<u8>]) -> Self {
+        let len = buffer.len();
+        let start = NonNull::from(buffer).cast::<u8>();
+        #[cfg(feature = "valgrind")]
+        crate::valgrind::create(start, len);
+        #[cfg(all(feature = "asan", sanitize = "address"))]
+        crate::asan::create(start, len);
+        Self {
+            start,
+            // SAFETY: the buffer is len bytes long.
+            end: unsafe { start.add(len) },
+            current: Cell::new(start),
+            floor: 0,
+            peak: Cell::new(0),
+            pristine: Cell::new(usize::MAX),
+            pinned: core::sync::atomic::AtomicUsize::new(0),
+            pinned_end: Cell::new(0),
+            align: 1,
+            #[cfg(debug_assertions)]
+            generation: Cell::new(0),
+            #[cfg(feature = "stats")]
+            stats: Cell::new(crate::Stats::default()),
+            #[cfg(feature = "profiling")]
+            profile: core::cell::RefCell::new(crate::Profile::new()),
+            allocator: BorrowedBuffer {
+                _marker: PhantomData,
+            },
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use core::alloc::{Allocator, Layout};
+use core::mem::size_of;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{BumpCar, BumpError, BumpErrorKind};
+
+/// Sums the layouts of the values to be allocated in a [`BumpCar`], to compute the capacity
+/// they need, alignment padding included.
+///
+/// Every layout is counted with the padding that it may need in the worst case, so that the
+/// values fit whatever the order in which they are allocated.
+///
+/// # Example
+/// ```rust
+/// use dodgems::CapacityBuilder;
+///
+/// struct Header {
+///     len: u64,
+/// }
+///
+/// let bumpcar = CapacityBuilder::new()
+///     .value::<Header>()
+///     .array::<u16>(100)
+///     .value::<u8>()
+///     .build()
+///     .unwrap();
+///
+/// bumpcar.alloc(1u8);
+/// bumpcar.alloc(Header { len: 100 });
+/// bumpcar.alloc_slice_fill_default::<u16>(100);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct CapacityBuilder {
+    size: Option<usize>,
+    align: usize,
+}
+
+impl CapacityBuilder {
+    /// Creates a [`CapacityBuilder`] for an empty arena.
+    pub const fn new() -> Self {
+        Self {
+            size: Some(0),
+            align: size_of::<usize>(),
+        }
+    }
+
+    /// Adds a block described by `layout`.
+    #[must_use]
+    pub const fn layout(self, layout: Layout) -> Self {
+        let size = match self.size {
+            Some(size) => match size.checked_add(layout.size()) {
+                Some(size) => size.checked_add(layout.align() - 1),
+                None => None,
+            },
+            None => None,
+        };
+        let align = if layout.align() > self.align {
+            layout.align()
+        } else {
+            self.align
+        };
+        Self { size, align }
+    }
+
+    /// Adds a value of type `T`.
+    #[must_use]
+    pub const fn value<T>(self) -> Self {
+        self.layout(Layout::new::<T>())
+    }
+
+    /// Adds an array of `n` values of type `T`, allocated at once.
+    #[must_use]
+    pub const fn array<T>(self, n: usize) -> Self {
+        match Layout::array::<T>(n) {
+            Ok(layout) => self.layout(layout),
+            Err(_) => Self {
+                size: None,
+                align: self.align,
+            },
+        }
+    }
+
+    /// Returns the capacity needed by the blocks added so far.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity overflows.
+    pub const fn capacity(&self) -> Result<usize, BumpError> {
+        match self.size {
+            Some(size) => Ok(size),
+            None => Err(BumpError::new(BumpErrorKind::Overflow, None, 0)),
+        }
+    }
+
+    /// Allocates a [`BumpCar`] with the capacity needed by the blocks added so far, in the given
+    /// allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity overflows or is greater than
+    /// [`isize::MAX`], or if the underlying allocator returns an error.
+    pub fn build_in<A: Allocator>(&self, allocator: A) -> Result<BumpCar<A>, BumpError> {
+        BumpCar::new_in_with_align(self.capacity()?, self.align, allocator)
+    }
+
+    /// Allocates a [`BumpCar`] with the capacity needed by the blocks added so far, with the
+    /// Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity overflows or is greater than
+    /// [`isize::MAX`], or if the global allocator returns an error.
+    #[cfg(feature = "alloc")]
+    pub fn build(&self) -> Result<BumpCar, BumpError> {
+        self.build_in(Global)
+    }
+}
+
+impl Default for CapacityBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Allocator> BumpCar<A> {
+    /// Allocates a new [`BumpCar`] in the given allocator, that can hold exactly `n` values of
+    /// type `T`, one at a time or as a slice.
+    ///
+    /// The buffer is aligned for `T`, so that no padding is needed between the values.
+    ///
+    /// # Errors
+    /// This function returns an error if the size of `n` values overflows or is greater than
+    /// [`isize::MAX`], or if the underlying allocator returns an error.
+    pub fn with_capacity_for_in<T>(n: usize, allocator: A) -> Result<Self, BumpError> {
+        let layout =
+            Layout::array::<T>(n).map_err(|_| BumpError::new(BumpErrorKind::Overflow, None, 0))?;
+        Self::new_in_with_align(layout.size(), layout.align(), allocator)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl BumpCar {
+    /// Allocates a [`BumpCar`] with the Global allocator, that can hold exactly `n` values of
+    /// type `T` (see [`BumpCar::with_capacity_for_in`]).
+    ///
+    /// # Errors
+    /// This function returns an error if the size of `n` values overflows or is greater than
+    /// [`isize::MAX`], or if the global allocator returns an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// #[repr(align(16))]
+    /// struct Particle([f32; 3]);
+    ///
+    /// let bumpcar = BumpCar::with_capacity_for::<Particle>(100).unwrap();
+    /// assert_eq!(bumpcar.capacity(), 1600);
+    /// for _ in 0..100 {
+    ///     bumpcar.alloc(Particle([0.0; 3]));
+    /// }
+    /// assert_eq!(bumpcar.remaining_capacity(), 0);
+    /// ```
+    pub fn with_capacity_for<T>(n: usize) -> Result<Self, BumpError> {
+        Self::with_capacity_for_in::<T>(n, Global)
+    }
+}
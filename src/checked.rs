@@ -0,0 +1,269 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::mem::{align_of, size_of};
+use core::ptr::NonNull;
+
+/// Value of [`Header::magic`] for a live allocation.
+const LIVE: u64 = 0xD0D6_E4A5_0A11_0C8D;
+/// Value of [`Header::magic`] once the allocation has been deallocated or moved.
+const FREED: u64 = 0xD0D6_E4A5_F4EE_D00D;
+
+/// Header written right before every allocation of a [`CheckedCar`].
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Header {
+    size: usize,
+    align: usize,
+    sequence: u64,
+    magic: u64,
+}
+
+/// Allocator wrapper validating that every request matches the allocation it refers to.
+///
+/// Each allocation is preceded by a small header recording its size, alignment and sequence
+/// number (the number of allocations made before it). [`Allocator::deallocate`],
+/// [`Allocator::grow`] and [`Allocator::shrink`] check that the pointer they are given is a live
+/// allocation of the wrapper, and that the layout is the one it was allocated with, and panic
+/// otherwise. Violations of the allocator contract that would silently corrupt an arena, such as
+/// a double free or a mismatched layout, are then reported where they happen.
+///
+/// The headers take some capacity from the inner allocator, so this is meant for tests and debug
+/// builds. It is only available with the `debug-headers` feature.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use std::alloc::{Allocator, Layout};
+/// use std::panic::{catch_unwind, AssertUnwindSafe};
+/// use dodgems::{BumpCar, CheckedCar};
+///
+/// let bumpcar = BumpCar::new(1024).unwrap();
+/// let checked = CheckedCar::new(&bumpcar);
+/// let mut v = Vec::new_in(&checked);
+/// v.extend(0..100u32);
+/// drop(v);
+///
+/// let ptr = checked.allocate(Layout::new::<u64>()).unwrap().cast::<u8>();
+/// // Deallocating with the wrong layout panics.
+/// let result = catch_unwind(AssertUnwindSafe(|| unsafe {
+///     checked.deallocate(ptr, Layout::new::<u32>());
+/// }));
+/// assert!(result.is_err());
+/// ```
+#[derive(Debug)]
+pub struct CheckedCar<A> {
+    inner: A,
+    sequence: Cell<u64>,
+}
+
+impl<A: Allocator> CheckedCar<A> {
+    /// Wraps an allocator, checking the requests made through the wrapper.
+    pub const fn new(inner: A) -> Self {
+        Self {
+            inner,
+            sequence: Cell::new(0),
+        }
+    }
+
+    /// Returns the number of allocations made through the wrapper, including the moves of
+    /// regions that could not be resized in place. It is the sequence number of the next
+    /// allocation.
+    pub fn allocations(&self) -> u64 {
+        self.sequence.get()
+    }
+
+    /// Returns a reference to the inner allocator.
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Unwraps the inner allocator.
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+
+    /// Returns the layout requested from the inner allocator for an allocation of `layout`, and
+    /// the offset of the allocation in it.
+    fn outer(layout: Layout) -> Result<(Layout, usize), AllocError> {
+        let align = layout.align().max(align_of::<Header>());
+        let offset = size_of::<Header>().next_multiple_of(align);
+        let size = offset.checked_add(layout.size()).ok_or(AllocError)?;
+        Ok((
+            Layout::from_size_align(size, align).map_err(|_| AllocError)?,
+            offset,
+        ))
+    }
+
+    /// Writes the header of an allocation, and returns the allocation.
+    fn init(base: NonNull<[u8]>, offset: usize, layout: Layout, sequence: u64) -> NonNull<[u8]> {
+        // SAFETY: the inner allocation is valid for offset + layout.size() bytes, and
+        // offset >= size_of::<Header>(), so both the header and the allocation are in bounds.
+        unsafe {
+            let ptr = base.cast::<u8>().add(offset);
+            ptr.cast::<Header>().sub(1).write(Header {
+                size: layout.size(),
+                align: layout.align(),
+                sequence,
+                magic: LIVE,
+            });
+            NonNull::slice_from_raw_parts(ptr, layout.size())
+        }
+    }
+
+    /// Checks that `ptr` is a live allocation of `layout`, and returns the start of its inner
+    /// allocation and its sequence number.
+    ///
+    /// # Safety
+    /// The [`size_of::<Header>()`] bytes before `ptr` must be readable.
+    #[track_caller]
+    unsafe fn check(what: &str, ptr: NonNull<u8>, layout: Layout) -> (NonNull<u8>, u64) {
+        // SAFETY: guaranteed by the caller. The read is unaligned, in case ptr is not one of
+        // the allocations of the wrapper.
+        let header = unsafe { ptr.cast::<Header>().sub(1).read_unaligned() };
+        match header.magic {
+            LIVE => {}
+            FREED => panic!(
+                "{what} of {ptr:p}, allocation #{} that was already deallocated",
+                header.sequence
+            ),
+            _ => panic!("{what} of {ptr:p}, that was not allocated by this CheckedCar"),
+        }
+        assert!(
+            header.size == layout.size() && header.align == layout.align(),
+            "{what} of {ptr:p} with a size of {} bytes aligned to {}, but allocation #{} has a \
+             size of {} bytes aligned to {}",
+            layout.size(),
+            layout.align(),
+            header.sequence,
+            header.size,
+            header.align,
+        );
+        let offset = size_of::<Header>().next_multiple_of(layout.align().max(align_of::<Header>()));
+        // SAFETY: the allocation starts offset bytes after its inner allocation.
+        (unsafe { ptr.sub(offset) }, header.sequence)
+    }
+
+    /// Marks the live allocation at `ptr` as freed.
+    ///
+    /// # Safety
+    /// `ptr` must have been checked with [`CheckedCar::check`].
+    unsafe fn free(ptr: NonNull<u8>) {
+        // SAFETY: the header precedes the allocation, and was checked to be live.
+        unsafe { (&raw mut (*ptr.cast::<Header>().as_ptr().sub(1)).magic).write(FREED) };
+    }
+
+    /// Resizes an allocation, in place in the inner allocator if the offset of the allocation in
+    /// it is unchanged, or by moving it otherwise.
+    ///
+    /// # Safety
+    /// Same as [`Allocator::grow`] or [`Allocator::shrink`], depending on `grow`.
+    #[track_caller]
+    unsafe fn resize(
+        &self,
+        grow: bool,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let what = if grow { "grow" } else { "shrink" };
+        // SAFETY: the caller guarantees that ptr was allocated by the wrapper, so the header
+        // before it is readable.
+        let (base, sequence) = unsafe { Self::check(what, ptr, old_layout) };
+        let (old_outer, old_offset) = Self::outer(old_layout)?;
+        let (new_outer, new_offset) = Self::outer(new_layout)?;
+        if old_offset == new_offset {
+            // SAFETY: base was allocated by the inner allocator with old_outer, and the sizes
+            // of the layouts are ordered like the ones of the caller.
+            let new_base = unsafe {
+                if grow {
+                    self.inner.grow(base, old_outer, new_outer)
+                } else {
+                    self.inner.shrink(base, old_outer, new_outer)
+                }
+            }?;
+            return Ok(Self::init(new_base, new_offset, new_layout, sequence));
+        }
+
+        let new_ptr = self.allocate(new_layout)?;
+        // SAFETY: both regions are valid for the smallest of the two sizes, and distinct.
+        unsafe {
+            new_ptr
+                .cast::<u8>()
+                .copy_from_nonoverlapping(ptr, old_layout.size().min(new_layout.size()));
+            Self::free(ptr);
+            self.inner.deallocate(base, old_outer);
+        }
+        Ok(new_ptr)
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for CheckedCar<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let (outer, offset) = Self::outer(layout)?;
+        let base = self.inner.allocate(outer)?;
+        let sequence = self.sequence.get();
+        self.sequence.set(sequence + 1);
+        Ok(Self::init(base, offset, layout, sequence))
+    }
+
+    /// Deallocates an allocated region.
+    ///
+    /// # Panics
+    /// This function panics if `ptr` is not a live allocation of the wrapper, or if `layout` is
+    /// not the layout it was allocated with.
+    #[track_caller]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: the caller guarantees that ptr was allocated by the wrapper, so the header
+        // before it is readable.
+        let (base, _) = unsafe { Self::check("deallocate", ptr, layout) };
+        // SAFETY: the allocation is live, and base was allocated by the inner allocator with
+        // the outer layout, that was valid then.
+        unsafe {
+            Self::free(ptr);
+            self.inner
+                .deallocate(base, Self::outer(layout).unwrap_unchecked().0);
+        }
+    }
+
+    /// Grows an allocated region.
+    ///
+    /// # Panics
+    /// This function panics if `ptr` is not a live allocation of the wrapper, or if
+    /// `old_layout` is not the layout it was allocated with.
+    #[track_caller]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        assert!(
+            new_layout.size() >= old_layout.size(),
+            "grow of {ptr:p} to a smaller size of {} bytes",
+            new_layout.size()
+        );
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.resize(true, ptr, old_layout, new_layout) }
+    }
+
+    /// Shrinks an allocated region.
+    ///
+    /// # Panics
+    /// This function panics if `ptr` is not a live allocation of the wrapper, or if
+    /// `old_layout` is not the layout it was allocated with.
+    #[track_caller]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        assert!(
+            new_layout.size() <= old_layout.size(),
+            "shrink of {ptr:p} to a greater size of {} bytes",
+            new_layout.size()
+        );
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.resize(false, ptr, old_layout, new_layout) }
+    }
+}
@@ -0,0 +1,141 @@
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::alloc::{AllocError, Allocator, Layout};
+use core::ptr::NonNull;
+
+use crate::BumpCar;
+
+/// Deep-cloning of a value into a [`BumpCar`].
+///
+/// The [`Cloned`](CloneIn::Cloned) value only references memory allocated in the arena
+/// (or no memory at all), so the original value can be dropped.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::{BumpCar, CloneIn};
+///
+/// let bumpcar = BumpCar::new(256).unwrap();
+/// let words = vec![String::from("hello"), String::from("world")];
+///
+/// let cloned = words.clone_in(&bumpcar);
+/// drop(words);
+/// assert_eq!(cloned, ["hello", "world"]);
+/// ```
+pub trait CloneIn<'bump, A: Allocator + 'bump> {
+    /// The type of the value cloned into the arena.
+    type Cloned: 'bump;
+
+    /// Clones the value into the arena.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left.
+    fn try_clone_in(&self, bumpcar: &'bump BumpCar<A>) -> Result<Self::Cloned, AllocError>;
+
+    /// Clones the value into the arena.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    fn clone_in(&self, bumpcar: &'bump BumpCar<A>) -> Self::Cloned {
+        match self.try_clone_in(bumpcar) {
+            Ok(cloned) => cloned,
+            Err(AllocError) => panic!("BumpCar capacity exceeded"),
+        }
+    }
+}
+
+macro_rules! impl_clone_in_copy {
+    ($($t:ty),*) => {$(
+        impl<'bump, A: Allocator + 'bump> CloneIn<'bump, A> for $t {
+            type Cloned = $t;
+
+            fn try_clone_in(&self, _: &'bump BumpCar<A>) -> Result<$t, AllocError> {
+                Ok(*self)
+            }
+        }
+    )*};
+}
+
+impl_clone_in_copy!(
+    (), bool, char, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64
+);
+
+impl<'bump, A: Allocator + 'bump> CloneIn<'bump, A> for str {
+    type Cloned = &'bump str;
+
+    fn try_clone_in(&self, bumpcar: &'bump BumpCar<A>) -> Result<&'bump str, AllocError> {
+        let ptr = Allocator::allocate(&bumpcar, Layout::for_value(self))?.cast::<u8>();
+        // SAFETY: the allocation is valid for self.len() bytes, and cannot overlap self.
+        unsafe {
+            ptr.copy_from_nonoverlapping(NonNull::from(self.as_bytes()).cast(), self.len());
+            Ok(core::str::from_utf8_unchecked(core::slice::from_raw_parts(
+                ptr.as_ptr(),
+                self.len(),
+            )))
+        }
+    }
+}
+
+impl<'bump, A: Allocator + 'bump, T: CloneIn<'bump, A>> CloneIn<'bump, A> for [T] {
+    type Cloned = &'bump [T::Cloned];
+
+    fn try_clone_in(&self, bumpcar: &'bump BumpCar<A>) -> Result<&'bump [T::Cloned], AllocError> {
+        let layout = Layout::array::<T::Cloned>(self.len()).map_err(|_| AllocError)?;
+        let ptr = Allocator::allocate(&bumpcar, layout)?.cast::<T::Cloned>();
+        for (i, x) in self.iter().enumerate() {
+            // SAFETY: the allocation is valid for self.len() elements.
+            // If cloning fails, the already cloned elements are leaked in the arena.
+            unsafe { ptr.add(i).write(x.try_clone_in(bumpcar)?) };
+        }
+        // SAFETY: every element has been initialized.
+        Ok(unsafe { core::slice::from_raw_parts(ptr.as_ptr(), self.len()) })
+    }
+}
+
+impl<'bump, A: Allocator + 'bump, T: CloneIn<'bump, A> + ?Sized> CloneIn<'bump, A> for &T {
+    type Cloned = T::Cloned;
+
+    fn try_clone_in(&self, bumpcar: &'bump BumpCar<A>) -> Result<T::Cloned, AllocError> {
+        (**self).try_clone_in(bumpcar)
+    }
+}
+
+impl<'bump, A: Allocator + 'bump, T: CloneIn<'bump, A>> CloneIn<'bump, A> for Option<T> {
+    type Cloned = Option<T::Cloned>;
+
+    fn try_clone_in(&self, bumpcar: &'bump BumpCar<A>) -> Result<Option<T::Cloned>, AllocError> {
+        self.as_ref().map(|x| x.try_clone_in(bumpcar)).transpose()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'bump, A: Allocator + 'bump> CloneIn<'bump, A> for String {
+    type Cloned = &'bump str;
+
+    fn try_clone_in(&self, bumpcar: &'bump BumpCar<A>) -> Result<&'bump str, AllocError> {
+        self.as_str().try_clone_in(bumpcar)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'bump, A: Allocator + 'bump, T: CloneIn<'bump, A>> CloneIn<'bump, A> for Box<[T]> {
+    type Cloned = &'bump [T::Cloned];
+
+    fn try_clone_in(&self, bumpcar: &'bump BumpCar<A>) -> Result<&'bump [T::Cloned], AllocError> {
+        (**self).try_clone_in(bumpcar)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'bump, A: Allocator + 'bump, T: CloneIn<'bump, A>> CloneIn<'bump, A> for Vec<T> {
+    type Cloned = Vec<T::Cloned, &'bump BumpCar<A>>;
+
+    fn try_clone_in(&self, bumpcar: &'bump BumpCar<A>) -> Result<Self::Cloned, AllocError> {
+        let mut v = Vec::new_in(bumpcar);
+        v.try_reserve_exact(self.len()).map_err(|_| AllocError)?;
+        for x in self {
+            v.push(x.try_clone_in(bumpcar)?);
+        }
+        Ok(v)
+    }
+}
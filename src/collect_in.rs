@@ -0,0 +1,140 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::alloc::{AllocError, Allocator};
+
+use crate::{BumpCar, BumpString, BumpVec};
+
+/// Construction of a collection from an iterator, in a [`BumpCar`].
+///
+/// This is the arena counterpart of [`FromIterator`], and is usually called through
+/// [`CollectIn::collect_in`].
+pub trait FromIteratorIn<'bump, T>: Sized {
+    /// The backing allocator of the arena the collection is built in.
+    type Allocator: Allocator + 'bump;
+
+    /// Creates a collection in `bumpcar` from the elements of `iter`.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left.
+    fn try_from_iter_in<I: IntoIterator<Item = T>>(
+        iter: I,
+        bumpcar: &'bump BumpCar<Self::Allocator>,
+    ) -> Result<Self, AllocError>;
+
+    /// Creates a collection in `bumpcar` from the elements of `iter`.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    fn from_iter_in<I: IntoIterator<Item = T>>(
+        iter: I,
+        bumpcar: &'bump BumpCar<Self::Allocator>,
+    ) -> Self {
+        match Self::try_from_iter_in(iter, bumpcar) {
+            Ok(collection) => collection,
+            Err(AllocError) => panic!("BumpCar capacity exceeded"),
+        }
+    }
+}
+
+/// Extension trait to collect an iterator into a [`BumpCar`].
+///
+/// It is implemented for every [`Iterator`].
+///
+/// # Example
+/// ```rust
+/// use dodgems::{BumpCar, BumpVec, CollectIn};
+///
+/// let bumpcar = BumpCar::new(256).unwrap();
+/// let squares = (1..=4).map(|x| x * x).collect_in::<BumpVec<u32>>(&bumpcar);
+/// assert_eq!(squares, [1, 4, 9, 16]);
+/// ```
+pub trait CollectIn: Iterator + Sized {
+    /// Collects the iterator into a collection allocated in `bumpcar`.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left.
+    fn try_collect_in<'bump, C: FromIteratorIn<'bump, Self::Item>>(
+        self,
+        bumpcar: &'bump BumpCar<C::Allocator>,
+    ) -> Result<C, AllocError> {
+        C::try_from_iter_in(self, bumpcar)
+    }
+
+    /// Collects the iterator into a collection allocated in `bumpcar`.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    fn collect_in<'bump, C: FromIteratorIn<'bump, Self::Item>>(
+        self,
+        bumpcar: &'bump BumpCar<C::Allocator>,
+    ) -> C {
+        C::from_iter_in(self, bumpcar)
+    }
+}
+
+impl<I: Iterator> CollectIn for I {}
+
+impl<'bump, T, A: Allocator + 'bump> FromIteratorIn<'bump, T> for BumpVec<'bump, T, A> {
+    type Allocator = A;
+
+    fn try_from_iter_in<I: IntoIterator<Item = T>>(
+        iter: I,
+        bumpcar: &'bump BumpCar<A>,
+    ) -> Result<Self, AllocError> {
+        let iter = iter.into_iter();
+        let mut v = BumpVec::try_with_capacity_in(iter.size_hint().0, bumpcar)?;
+        for x in iter {
+            v.try_push(x).map_err(|_| AllocError)?;
+        }
+        Ok(v)
+    }
+}
+
+impl<'bump, A: Allocator + 'bump> FromIteratorIn<'bump, char> for BumpString<'bump, A> {
+    type Allocator = A;
+
+    fn try_from_iter_in<I: IntoIterator<Item = char>>(
+        iter: I,
+        bumpcar: &'bump BumpCar<A>,
+    ) -> Result<Self, AllocError> {
+        let mut s = BumpString::new_in(bumpcar);
+        for c in iter {
+            s.try_push_str(c.encode_utf8(&mut [0; 4]))?;
+        }
+        Ok(s)
+    }
+}
+
+impl<'bump, 's, A: Allocator + 'bump> FromIteratorIn<'bump, &'s str> for BumpString<'bump, A> {
+    type Allocator = A;
+
+    fn try_from_iter_in<I: IntoIterator<Item = &'s str>>(
+        iter: I,
+        bumpcar: &'bump BumpCar<A>,
+    ) -> Result<Self, AllocError> {
+        let mut s = BumpString::new_in(bumpcar);
+        for part in iter {
+            s.try_push_str(part)?;
+        }
+        Ok(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'bump, T, A: Allocator + 'bump> FromIteratorIn<'bump, T> for Vec<T, &'bump BumpCar<A>> {
+    type Allocator = A;
+
+    fn try_from_iter_in<I: IntoIterator<Item = T>>(
+        iter: I,
+        bumpcar: &'bump BumpCar<A>,
+    ) -> Result<Self, AllocError> {
+        let iter = iter.into_iter();
+        let mut v = Vec::new_in(bumpcar);
+        v.try_reserve(iter.size_hint().0).map_err(|_| AllocError)?;
+        for x in iter {
+            v.try_reserve(1).map_err(|_| AllocError)?;
+            v.push(x);
+        }
+        Ok(v)
+    }
+}
@@ -0,0 +1,229 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ptr::NonNull;
+
+use crate::{BumpError, BumpErrorKind};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Unsigned integer type of the cursor and capacity of a [`CompactBumpCar`]: [`u16`], [`u32`]
+/// or [`usize`].
+///
+/// This trait is sealed, and cannot be implemented outside of this crate.
+pub trait CompactIndex: Copy + fmt::Debug + private::Sealed {
+    /// The index 0.
+    const ZERO: Self;
+
+    /// Converts `value` to the index type, if it fits.
+    fn from_usize(value: usize) -> Option<Self>;
+
+    /// Converts the index to a `usize`.
+    ///
+    /// Indices are only built from buffer offsets, so this never truncates.
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! compact_index {
+    ($($ty:ty),*) => {$(
+        impl private::Sealed for $ty {}
+
+        impl CompactIndex for $ty {
+            const ZERO: Self = 0;
+
+            #[inline]
+            fn from_usize(value: usize) -> Option<Self> {
+                Self::try_from(value).ok()
+            }
+
+            #[inline]
+            fn to_usize(self) -> usize {
+                self as usize
+            }
+        }
+    )*};
+}
+
+compact_index!(u16, u32, usize);
+
+/// Bump allocator in a borrowed buffer, with a cursor and capacity stored as a small integer.
+///
+/// A [`BumpCar`](crate::BumpCar) keeps several pointers and counters, that are overkill for the
+/// few kilobytes of scratch memory of a microcontroller. A `CompactBumpCar` only holds the start
+/// of its buffer, and its capacity and cursor as offsets of type `I`: with [`u16`] indices, it
+/// takes two words on 32-bit targets, so that the arena header itself fits in scarce SRAM, and
+/// its cursor can stay in a register.
+///
+/// The buffer must not be longer than the largest value of `I`. Only the last allocation can be
+/// deallocated or resized in place.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use core::mem::MaybeUninit;
+/// use dodgems::CompactBumpCar;
+///
+/// let mut buffer = [MaybeUninit::uninit(); 256];
+/// let mut bumpcar = CompactBumpCar::<u16>::from_buffer(&mut buffer).unwrap();
+/// assert!(size_of_val(&bumpcar) <= 2 * size_of::<usize>());
+///
+/// let mut v = Vec::new_in(&bumpcar);
+/// v.extend_from_slice(&[1u32, 2, 3, 4]);
+/// v.push(5);
+/// assert_eq!(v, [1, 2, 3, 4, 5]);
+/// drop(v);
+///
+/// bumpcar.reset();
+/// assert_eq!(bumpcar.remaining_capacity(), 256);
+/// ```
+pub struct CompactBumpCar<'buf, I: CompactIndex = u32> {
+    start: NonNull<u8>,
+    capacity: I,
+    position: Cell<I>,
+    _marker: PhantomData<&'buf mut [MaybeUninit<u8>]>,
+}
+
+impl<'buf, I: CompactIndex> CompactBumpCar<'buf, I> {
+    /// Creates a [`CompactBumpCar`] that allocates inside `buffer`.
+    ///
+    /// # Errors
+    /// This function returns an error if the length of the buffer does not fit in `I`.
+    pub fn from_buffer(buffer: &'buf mut [MaybeUninit<u8>]) -> Result<Self, BumpError> {
+        let capacity =
+            I::from_usize(buffer.len()).ok_or(BumpError::new(BumpErrorKind::Overflow, None, 0))?;
+        Ok(Self {
+            start: NonNull::from(buffer).cast(),
+            capacity,
+            position: Cell::new(I::ZERO),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the capacity of the [`CompactBumpCar`].
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity.to_usize()
+    }
+
+    /// Returns the remaining capacity of the [`CompactBumpCar`].
+    ///
+    /// As for [`BumpCar::remaining_capacity`](crate::BumpCar::remaining_capacity), this does not
+    /// account for alignment.
+    #[inline]
+    pub fn remaining_capacity(&self) -> usize {
+        self.capacity() - self.position.get().to_usize()
+    }
+
+    /// Resets the [`CompactBumpCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    pub fn reset(&mut self) {
+        self.position.set(I::ZERO);
+    }
+
+    /// Returns the offset of `ptr` in the buffer.
+    fn offset(&self, ptr: NonNull<u8>) -> usize {
+        ptr.addr().get() - self.start.addr().get()
+    }
+
+    /// Moves the cursor to `position`, if it is in bounds of the buffer.
+    fn set_position(&self, position: usize) -> bool {
+        if position > self.capacity() {
+            return false;
+        }
+        // SAFETY: the position is at most the capacity, that fits in I.
+        self.position
+            .set(unsafe { I::from_usize(position).unwrap_unchecked() });
+        true
+    }
+}
+
+unsafe impl<I: CompactIndex> Allocator for CompactBumpCar<'_, I> {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let position = self.position.get().to_usize();
+        let addr = self.start.addr().get() + position;
+        let start = position + (addr.wrapping_neg() & (layout.align() - 1));
+        let end = start.checked_add(layout.size()).ok_or(AllocError)?;
+        if !self.set_position(end) {
+            return Err(AllocError);
+        }
+        // SAFETY: start <= end <= capacity, so the pointer is in bounds of the buffer.
+        let ptr = unsafe { self.start.add(start) };
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    /// Deallocates an allocated region.
+    ///
+    /// If it is the last allocation, its space is reclaimed.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        let start = self.offset(ptr);
+        if start + layout.size() == self.position.get().to_usize() {
+            self.set_position(start);
+        }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let start = self.offset(ptr);
+        if start + old_layout.size() == self.position.get().to_usize()
+            && ptr.addr().get().is_multiple_of(new_layout.align())
+            && self.set_position(start + new_layout.size())
+        {
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+        let new_ptr = self.allocate(new_layout)?;
+        // SAFETY: the old region is valid for old_layout.size() bytes, and distinct from the new
+        // one, that is at least as large.
+        unsafe {
+            new_ptr
+                .cast::<u8>()
+                .copy_from_nonoverlapping(ptr, old_layout.size());
+            self.deallocate(ptr, old_layout);
+        }
+        Ok(new_ptr)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if !ptr.addr().get().is_multiple_of(new_layout.align()) {
+            let new_ptr = self.allocate(new_layout)?;
+            // SAFETY: the old region is valid for more than new_layout.size() bytes, and
+            // distinct from the new one.
+            unsafe {
+                new_ptr
+                    .cast::<u8>()
+                    .copy_from_nonoverlapping(ptr, new_layout.size());
+                self.deallocate(ptr, old_layout);
+            }
+            return Ok(new_ptr);
+        }
+        let start = self.offset(ptr);
+        if start + old_layout.size() == self.position.get().to_usize() {
+            self.set_position(start + new_layout.size());
+        }
+        Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()))
+    }
+}
+
+impl<I: CompactIndex> fmt::Debug for CompactBumpCar<'_, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompactBumpCar")
+            .field("capacity", &self.capacity)
+            .field("position", &self.position.get())
+            .finish_non_exhaustive()
+    }
+}
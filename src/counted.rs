@@ -0,0 +1,209 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::fmt;
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{BumpCar, BumpError};
+
+/// Error returned by [`CountedBumpCar::try_reset`] when allocations are still live.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResetError {
+    live: usize,
+}
+
+impl ResetError {
+    /// Returns the number of live allocations that prevented the reset.
+    pub fn live_allocations(&self) -> usize {
+        self.live
+    }
+}
+
+impl fmt::Display for ResetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot reset BumpCar with {} live allocations",
+            self.live
+        )
+    }
+}
+
+impl core::error::Error for ResetError {}
+
+/// [`BumpCar`] counting its live allocations, so that it can be reset through a shared
+/// reference.
+///
+/// Each allocation increments the counter, and each deallocation decrements it. This is
+/// useful when the arena is shared behind an `Rc`, where [`BumpCar::reset`] cannot be called.
+///
+/// With the `leak-check` feature, [`CountedBumpCar::reset`] and [`Drop`] panic in debug builds
+/// if some allocations are still live, as the memory of a container that is still referenced
+/// somewhere (through unsafe code, or because it was leaked) is about to be reclaimed.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use std::rc::Rc;
+/// use dodgems::CountedBumpCar;
+///
+/// let bumpcar = Rc::new(CountedBumpCar::new(256).unwrap());
+/// let v = Box::new_in(42u64, bumpcar.clone());
+/// assert!(bumpcar.try_reset().is_err());
+///
+/// drop(v);
+/// assert!(bumpcar.try_reset().is_ok());
+/// assert_eq!(bumpcar.remaining_capacity(), 256);
+/// ```
+#[derive(Debug)]
+pub struct CountedBumpCar<
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    bumpcar: BumpCar<A>,
+    live: Cell<usize>,
+}
+
+impl<A: Allocator> CountedBumpCar<A> {
+    /// Allocates a new [`CountedBumpCar`] in the given allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        Ok(Self {
+            bumpcar: BumpCar::new_in(capacity, allocator)?,
+            live: Cell::new(0),
+        })
+    }
+
+    /// Returns the number of live allocations.
+    pub fn live_allocations(&self) -> usize {
+        self.live.get()
+    }
+
+    /// Returns the capacity of the [`CountedBumpCar`].
+    pub fn capacity(&self) -> usize {
+        self.bumpcar.capacity()
+    }
+
+    /// Returns the remaining capacity of the [`CountedBumpCar`].
+    pub fn remaining_capacity(&self) -> usize {
+        self.bumpcar.remaining_capacity()
+    }
+
+    /// Resets the [`CountedBumpCar`]'s remaining capacity to its initial capacity, if there
+    /// are no live allocations.
+    ///
+    /// # Errors
+    /// This function returns an error if some allocations have not been deallocated yet.
+    pub fn try_reset(&self) -> Result<(), ResetError> {
+        match self.live.get() {
+            0 => {
+                #[cfg(feature = "zeroize")]
+                self.bumpcar
+                    .wipe(self.bumpcar.floor, self.bumpcar.position());
+                self.bumpcar.set_position(self.bumpcar.floor);
+                self.bumpcar.next_generation();
+                Ok(())
+            }
+            live => Err(ResetError { live }),
+        }
+    }
+
+    /// Resets the [`CountedBumpCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// # Panics
+    /// With the `leak-check` feature, this function panics in debug builds if some allocations
+    /// are still live.
+    pub fn reset(&mut self) {
+        #[cfg(all(feature = "leak-check", debug_assertions))]
+        self.check_leaks();
+        self.live.set(0);
+        self.bumpcar.reset();
+    }
+
+    /// Panics if some allocations are still live.
+    ///
+    /// The counter is cleared first, so that the destructor does not panic again while
+    /// unwinding.
+    #[cfg(all(feature = "leak-check", debug_assertions))]
+    #[track_caller]
+    fn check_leaks(&self) {
+        let live = self.live.replace(0);
+        assert!(live == 0, "{}", ResetError { live });
+    }
+}
+
+#[cfg(all(feature = "leak-check", debug_assertions))]
+impl<A: Allocator> Drop for CountedBumpCar<A> {
+    /// Panics if some allocations are still live.
+    fn drop(&mut self) {
+        self.check_leaks();
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl CountedBumpCar {
+    /// Allocates a [`CountedBumpCar`] with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, Global)
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for CountedBumpCar<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = self.bumpcar.allocate(layout)?;
+        self.live.set(self.live.get() + 1);
+        Ok(ptr)
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = self.bumpcar.allocate_zeroed(layout)?;
+        self.live.set(self.live.get() + 1);
+        Ok(ptr)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.set(self.live.get() - 1);
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.deallocate(ptr, layout) }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.grow(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.grow_zeroed(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.shrink(ptr, old_layout, new_layout) }
+    }
+}
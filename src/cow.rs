@@ -0,0 +1,169 @@
+use core::alloc::Allocator;
+use core::fmt;
+use core::ops::Deref;
+
+use crate::{BumpCar, BumpError};
+
+/// Types that can be copied into a [`BumpCar`], to be mutated there.
+///
+/// It is implemented for every [`Clone`] type, for `str` and for slices of [`Clone`] elements.
+pub trait ToBump {
+    /// Copies `self` into `bumpcar`, and returns a mutable reference to the copy.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    #[allow(clippy::mut_from_ref)]
+    fn try_to_bump<'a, A: Allocator>(
+        &self,
+        bumpcar: &'a BumpCar<A>,
+    ) -> Result<&'a mut Self, BumpError>;
+}
+
+impl<T: Clone> ToBump for T {
+    fn try_to_bump<'a, A: Allocator>(
+        &self,
+        bumpcar: &'a BumpCar<A>,
+    ) -> Result<&'a mut T, BumpError> {
+        bumpcar.try_alloc_with(|| self.clone())
+    }
+}
+
+impl ToBump for str {
+    fn try_to_bump<'a, A: Allocator>(
+        &self,
+        bumpcar: &'a BumpCar<A>,
+    ) -> Result<&'a mut str, BumpError> {
+        bumpcar.try_alloc_str(self)
+    }
+}
+
+impl<T: Clone> ToBump for [T] {
+    fn try_to_bump<'a, A: Allocator>(
+        &self,
+        bumpcar: &'a BumpCar<A>,
+    ) -> Result<&'a mut [T], BumpError> {
+        bumpcar.try_alloc_slice_clone(self)
+    }
+}
+
+/// Clone-on-write reference, either borrowed or copied into a [`BumpCar`].
+///
+/// Like `Cow`, it starts by borrowing a value, and copies it on the first mutation, but the copy
+/// is made in an arena instead of the heap. This suits parsers that mostly borrow their input,
+/// but sometimes need to rewrite it (for example to unescape a string).
+///
+/// # Example
+/// ```rust
+/// use dodgems::{BumpCar, BumpCow};
+///
+/// let bumpcar = BumpCar::new(256).unwrap();
+/// let input = "plain, ESCAPED";
+///
+/// let words: Vec<BumpCow<str>> = input
+///     .split(", ")
+///     .map(|word| {
+///         let mut word = BumpCow::Borrowed(word);
+///         if word.starts_with("ESC") {
+///             word.to_mut(&bumpcar).make_ascii_lowercase();
+///         }
+///         word
+///     })
+///     .collect();
+///
+/// assert!(words[0].is_borrowed());
+/// assert_eq!(&*words[1], "escaped");
+/// assert!(words[1].is_owned());
+/// ```
+pub enum BumpCow<'a, T: ?Sized> {
+    /// Reference to a value outside of the arena.
+    Borrowed(&'a T),
+    /// Mutable copy of the value, in the arena.
+    Owned(&'a mut T),
+}
+
+impl<'a, T: ?Sized + ToBump> BumpCow<'a, T> {
+    /// Returns a mutable reference to the value, copying it into `bumpcar` first if it is
+    /// borrowed.
+    ///
+    /// # Errors
+    /// This function returns an error if the value must be copied, and the [`BumpCar`] does not
+    /// have enough capacity left. The reference is then left borrowed.
+    pub fn try_to_mut<A: Allocator>(
+        &mut self,
+        bumpcar: &'a BumpCar<A>,
+    ) -> Result<&mut T, BumpError> {
+        if let BumpCow::Borrowed(value) = *self {
+            *self = BumpCow::Owned(value.try_to_bump(bumpcar)?);
+        }
+        match self {
+            BumpCow::Owned(value) => Ok(value),
+            BumpCow::Borrowed(_) => unreachable!(),
+        }
+    }
+
+    /// Returns a mutable reference to the value, copying it into `bumpcar` first if it is
+    /// borrowed.
+    ///
+    /// # Panics
+    /// This function panics if the value must be copied, and the [`BumpCar`] does not have
+    /// enough capacity left.
+    #[track_caller]
+    pub fn to_mut<A: Allocator>(&mut self, bumpcar: &'a BumpCar<A>) -> &mut T {
+        crate::typed::expect_capacity(self.try_to_mut(bumpcar))
+    }
+}
+
+impl<'a, T: ?Sized> BumpCow<'a, T> {
+    /// Returns `true` if the value is borrowed from outside of the arena.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, BumpCow::Borrowed(_))
+    }
+
+    /// Returns `true` if the value was copied into the arena.
+    pub fn is_owned(&self) -> bool {
+        matches!(self, BumpCow::Owned(_))
+    }
+
+    /// Converts the [`BumpCow`] into a shared reference with its full lifetime.
+    pub fn into_ref(self) -> &'a T {
+        match self {
+            BumpCow::Borrowed(value) => value,
+            BumpCow::Owned(value) => value,
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for BumpCow<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self {
+            BumpCow::Borrowed(value) => value,
+            BumpCow::Owned(value) => value,
+        }
+    }
+}
+
+impl<'a, T: ?Sized> From<&'a T> for BumpCow<'a, T> {
+    fn from(value: &'a T) -> Self {
+        BumpCow::Borrowed(value)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for BumpCow<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: ?Sized + fmt::Display> fmt::Display for BumpCow<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: ?Sized + PartialEq<U>, U: ?Sized> PartialEq<BumpCow<'_, U>> for BumpCow<'_, T> {
+    fn eq(&self, other: &BumpCow<'_, U>) -> bool {
+        **self == **other
+    }
+}
@@ -0,0 +1,183 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use alloc::alloc::Global;
+
+use crate::sharded::refill;
+use crate::sync::CachePadded;
+use crate::{bump, BumpError, BumpErrorKind, SyncBumpCar};
+
+/// Chunk of a [`CpuLocalBumpCar`], used by the threads running on one CPU.
+struct CpuChunk {
+    /// Set while a thread allocates in the chunk.
+    locked: AtomicBool,
+    current: Cell<NonNull<u8>>,
+    end: Cell<NonNull<u8>>,
+}
+
+impl CpuChunk {
+    fn new() -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            current: Cell::new(NonNull::dangling()),
+            end: Cell::new(NonNull::dangling()),
+        }
+    }
+}
+
+/// Returns the index of the CPU the calling thread is running on.
+fn current_cpu() -> usize {
+    // SAFETY: sched_getcpu has no preconditions.
+    usize::try_from(unsafe { libc::sched_getcpu() }).unwrap_or(0)
+}
+
+/// Concurrent bump allocator, handing out a private chunk to every CPU.
+///
+/// Like a [`ShardedBumpCar`](crate::ShardedBumpCar), it takes chunks from a shared reserve, but
+/// the chunk used by an allocation is picked with the CPU the thread runs on (given by
+/// `sched_getcpu`), rather than with a [`Shard`](crate::Shard) created by each thread. A thread
+/// pool with many more threads than cores then uses as many chunks as there are cores, instead of
+/// one per thread.
+///
+/// Threads are usually alone on their CPU, but they can be preempted or migrated while
+/// allocating: each chunk is guarded by a flag, and an allocation finding the chunk of its CPU
+/// in use is made directly in the shared reserve instead of waiting.
+///
+/// It is only available on Linux, with the `cpu-local` feature.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::CpuLocalBumpCar;
+///
+/// let mut bumpcar = CpuLocalBumpCar::new(1 << 20, 1024).unwrap();
+/// std::thread::scope(|s| {
+///     for i in 0..64u64 {
+///         let bumpcar = &bumpcar;
+///         s.spawn(move || assert_eq!(*Box::new_in(i, bumpcar), i));
+///     }
+/// });
+/// assert!(bumpcar.capacity() - bumpcar.remaining_capacity() <= bumpcar.cpus() * 1024 + 64 * 8);
+///
+/// bumpcar.reset();
+/// assert_eq!(bumpcar.remaining_capacity(), 1 << 20);
+/// ```
+pub struct CpuLocalBumpCar<A: Allocator = Global> {
+    reserve: SyncBumpCar<A>,
+    chunk_size: usize,
+    chunks: Box<[CachePadded<CpuChunk>]>,
+}
+
+// SAFETY: the chunks are private to the CpuLocalBumpCar, and only accessed while their flag is
+// held.
+unsafe impl<A: Allocator + Send> Send for CpuLocalBumpCar<A> {}
+// SAFETY: the cursor and end of a chunk are only accessed by the thread that set its flag, and
+// the reserve is Sync.
+unsafe impl<A: Allocator + Sync> Sync for CpuLocalBumpCar<A> {}
+
+impl<A: Allocator> CpuLocalBumpCar<A> {
+    /// Allocates a new [`CpuLocalBumpCar`] in the given allocator, with a shared reserve of
+    /// `capacity` bytes, handed out to the CPUs in chunks of `chunk_size` bytes.
+    ///
+    /// There is one chunk for every CPU configured on the system.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, chunk_size: usize, allocator: A) -> Result<Self, BumpError> {
+        // SAFETY: sysconf has no preconditions.
+        let cpus = unsafe { libc::sysconf(libc::_SC_NPROCESSORS_CONF) };
+        let cpus = usize::try_from(cpus).unwrap_or(1).max(1);
+        let mut chunks = Vec::new();
+        chunks
+            .try_reserve_exact(cpus)
+            .map_err(|_| BumpError::new(BumpErrorKind::AllocatorFailed, None, 0))?;
+        chunks.extend((0..cpus).map(|_| CachePadded::new(CpuChunk::new())));
+        Ok(Self {
+            reserve: SyncBumpCar::new_in(capacity, allocator)?,
+            chunk_size,
+            chunks: chunks.into_boxed_slice(),
+        })
+    }
+
+    /// Returns the capacity of the [`CpuLocalBumpCar`].
+    pub fn capacity(&self) -> usize {
+        self.reserve.capacity()
+    }
+
+    /// Returns the remaining capacity of the shared reserve.
+    ///
+    /// The unused space in the current chunk of every CPU is not included.
+    pub fn remaining_capacity(&self) -> usize {
+        self.reserve.remaining_capacity()
+    }
+
+    /// Returns the size of the chunks handed out to the CPUs.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Returns the number of CPUs, that is the maximum number of chunks in use at once.
+    pub fn cpus(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Resets the [`CpuLocalBumpCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    pub fn reset(&mut self) {
+        self.reserve.reset();
+        for chunk in &mut self.chunks {
+            chunk.current.set(NonNull::dangling());
+            chunk.end.set(NonNull::dangling());
+        }
+    }
+}
+
+impl CpuLocalBumpCar {
+    /// Allocates a [`CpuLocalBumpCar`] with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize, chunk_size: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, chunk_size, Global)
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for CpuLocalBumpCar<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let chunk = &self.chunks[current_cpu() % self.chunks.len()];
+        if chunk
+            .locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return self.reserve.allocate(layout);
+        }
+        // SAFETY: current and end are in bounds of the current chunk, and current <= end.
+        let result = match unsafe { bump(chunk.current.get(), chunk.end.get(), layout) } {
+            Some((ptr, new_current)) => {
+                chunk.current.set(new_current);
+                Ok(ptr)
+            }
+            None => refill(
+                &self.reserve,
+                self.chunk_size,
+                &chunk.current,
+                &chunk.end,
+                layout,
+            ),
+        };
+        chunk.locked.store(false, Ordering::Release);
+        Ok(NonNull::slice_from_raw_parts(result?, layout.size()))
+    }
+
+    /// The [`CpuLocalBumpCar`] does not perform deallocation, the memory is reclaimed when it is
+    /// reset.
+    unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {}
+}
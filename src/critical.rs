@@ -0,0 +1,129 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::{Cell, UnsafeCell};
+use core::mem::MaybeUninit;
+use core::ptr::NonNull;
+
+use critical_section::Mutex;
+
+use crate::bump;
+
+/// Bump allocator with inline storage, whose cursor is only updated inside a critical section.
+///
+/// Like a [`StaticBumpCar`](crate::StaticBumpCar), it can be placed in a `static`, but it does
+/// not require atomic instructions: on single-core microcontrollers, it can be shared between
+/// thread mode and interrupt handlers. The critical section implementation is provided by the
+/// [`critical_section`] crate.
+///
+/// Since the cursor is locked, the last allocation can be deallocated.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::CriticalBumpCar;
+///
+/// static ARENA: CriticalBumpCar<1024> = CriticalBumpCar::new();
+///
+/// let v = Box::new_in([1u32, 2, 3], &ARENA);
+/// assert_eq!(ARENA.remaining_capacity(), 1012);
+/// drop(v);
+/// assert_eq!(ARENA.remaining_capacity(), 1024);
+/// ```
+pub struct CriticalBumpCar<const N: usize> {
+    buffer: UnsafeCell<[MaybeUninit<u8>; N]>,
+    position: Mutex<Cell<usize>>,
+}
+
+// SAFETY: the buffer is only accessed through disjoint allocations,
+// handed out by updates of the cursor inside critical sections.
+unsafe impl<const N: usize> Sync for CriticalBumpCar<N> {}
+
+impl<const N: usize> CriticalBumpCar<N> {
+    /// Creates a new [`CriticalBumpCar`] with a capacity of `N` bytes.
+    pub const fn new() -> Self {
+        Self {
+            buffer: UnsafeCell::new([MaybeUninit::uninit(); N]),
+            position: Mutex::new(Cell::new(0)),
+        }
+    }
+
+    /// Returns the capacity of the [`CriticalBumpCar`].
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the remaining capacity of the [`CriticalBumpCar`].
+    ///
+    /// As for [`BumpCar::remaining_capacity`](crate::BumpCar::remaining_capacity), this does not
+    /// account for alignment.
+    pub fn remaining_capacity(&self) -> usize {
+        N - critical_section::with(|cs| self.position.borrow(cs).get())
+    }
+
+    /// Returns a pointer to the start of the buffer.
+    fn start(&self) -> NonNull<u8> {
+        // SAFETY: the pointer to the buffer is non null.
+        unsafe { NonNull::new_unchecked(self.buffer.get().cast::<u8>()) }
+    }
+
+    /// Resets the [`CriticalBumpCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// # Safety
+    /// No allocation made with this [`CriticalBumpCar`] may be used after the reset.
+    ///
+    /// With the `zeroize` feature, the used memory is overwritten with zeros.
+    pub unsafe fn reset(&self) {
+        critical_section::with(|cs| {
+            let position = self.position.borrow(cs);
+            #[cfg(feature = "zeroize")]
+            // SAFETY: the used part of the buffer is no longer in use, as guaranteed by the
+            // caller.
+            unsafe {
+                crate::wipe_bytes(self.start(), position.get());
+            }
+            position.set(0);
+        });
+    }
+
+    /// Resets the [`CriticalBumpCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// This is the safe version of [`CriticalBumpCar::reset`], for non-static instances.
+    pub fn reset_mut(&mut self) {
+        // SAFETY: the mutable borrow guarantees that no allocation is still in use.
+        unsafe { self.reset() };
+    }
+}
+
+impl<const N: usize> Default for CriticalBumpCar<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<const N: usize> Allocator for &CriticalBumpCar<N> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let start = self.start();
+        critical_section::with(|cs| {
+            let position = self.position.borrow(cs);
+            // SAFETY: the buffer is valid for N bytes, and the cursor never exceeds it.
+            let (ptr, new_current) =
+                unsafe { bump(start.add(position.get()), start.add(N), layout) }
+                    .ok_or(AllocError)?;
+            position.set(new_current.addr().get() - start.addr().get());
+            Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+        })
+    }
+
+    /// Deallocates an allocated region.
+    ///
+    /// If it is the last allocation made, its space is reclaimed. Otherwise, the
+    /// [`CriticalBumpCar`] does not perform deallocation unless it's reset.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        let offset = ptr.addr().get() - self.start().addr().get();
+        critical_section::with(|cs| {
+            let position = self.position.borrow(cs);
+            if offset + layout.size() == position.get() {
+                position.set(offset);
+            }
+        });
+    }
+}
@@ -0,0 +1,187 @@
+use core::alloc::Allocator;
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, Deserializer, Error, SeqAccess, Visitor};
+use serde::Deserialize;
+
+use crate::{BumpCar, BumpString, BumpVec};
+
+/// Types that can be deserialized into a [`BumpCar`].
+///
+/// It is implemented for every type implementing [`Deserialize`], and for the arena-backed
+/// [`BumpVec`] and [`BumpString`], so that they can be nested.
+pub trait DeserializeIn<'de, 'bump, A: Allocator>: Sized {
+    /// Deserializes a value from `deserializer`, allocating its contents in `bumpcar`.
+    ///
+    /// # Errors
+    /// This function returns the errors of the deserializer, and a custom error if the arena
+    /// does not have enough capacity left.
+    fn deserialize_in<D: Deserializer<'de>>(
+        deserializer: D,
+        bumpcar: &'bump BumpCar<A>,
+    ) -> Result<Self, D::Error>;
+}
+
+impl<'de, 'bump, A: Allocator, T: Deserialize<'de>> DeserializeIn<'de, 'bump, A> for T {
+    fn deserialize_in<D: Deserializer<'de>>(
+        deserializer: D,
+        _: &'bump BumpCar<A>,
+    ) -> Result<Self, D::Error> {
+        T::deserialize(deserializer)
+    }
+}
+
+/// [`DeserializeSeed`] deserializing a `T` into a [`BumpCar`], to be used with the seeded
+/// methods of the deserializers (or through [`BumpCar::deserialize_in`]).
+///
+/// # Example
+/// ```rust
+/// use dodgems::{BumpCar, BumpString, BumpVec, DeserializeInSeed};
+/// use serde::de::DeserializeSeed;
+///
+/// let bumpcar = BumpCar::new(1024).unwrap();
+/// let mut deserializer = serde_json::Deserializer::from_str(r#"[["a", "b"], ["c"]]"#);
+/// let groups = DeserializeInSeed::<BumpVec<BumpVec<BumpString>>>::new(&bumpcar)
+///     .deserialize(&mut deserializer)
+///     .unwrap();
+/// assert_eq!(groups.len(), 2);
+/// assert_eq!(groups[0][1], "b");
+/// ```
+pub struct DeserializeInSeed<
+    'bump,
+    T,
+    #[cfg(feature = "alloc")] A: Allocator = alloc::alloc::Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    bumpcar: &'bump BumpCar<A>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'bump, T, A: Allocator> DeserializeInSeed<'bump, T, A> {
+    /// Creates a seed deserializing a `T` into `bumpcar`.
+    pub fn new(bumpcar: &'bump BumpCar<A>) -> Self {
+        Self {
+            bumpcar,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'bump, T, A: Allocator> Clone for DeserializeInSeed<'bump, T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'bump, T, A: Allocator> Copy for DeserializeInSeed<'bump, T, A> {}
+
+impl<'de, 'bump, T: DeserializeIn<'de, 'bump, A>, A: Allocator> DeserializeSeed<'de>
+    for DeserializeInSeed<'bump, T, A>
+{
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        T::deserialize_in(deserializer, self.bumpcar)
+    }
+}
+
+impl<A: Allocator> BumpCar<A> {
+    /// Deserializes a `T` from `deserializer`, allocating its contents in the [`BumpCar`].
+    ///
+    /// # Errors
+    /// This function returns the errors of the deserializer, and a custom error if the
+    /// [`BumpCar`] does not have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::{BumpCar, BumpString, BumpVec};
+    ///
+    /// let bumpcar = BumpCar::new(1024).unwrap();
+    /// let mut deserializer = serde_json::Deserializer::from_str(r#"["GET", "/index.html"]"#);
+    /// let request: BumpVec<BumpString> = bumpcar.deserialize_in(&mut deserializer).unwrap();
+    /// assert_eq!(request, ["GET", "/index.html"]);
+    /// ```
+    pub fn deserialize_in<'de, 'bump, T, D>(&'bump self, deserializer: D) -> Result<T, D::Error>
+    where
+        T: DeserializeIn<'de, 'bump, A>,
+        D: Deserializer<'de>,
+    {
+        T::deserialize_in(deserializer, self)
+    }
+}
+
+/// Error message of the deserialization failures due to the arena.
+const CAPACITY_EXCEEDED: &str = "BumpCar capacity exceeded";
+
+/// Visitor of a sequence, collecting its elements into a [`BumpVec`].
+struct BumpVecVisitor<'bump, T, A: Allocator> {
+    bumpcar: &'bump BumpCar<A>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'de, 'bump, T: DeserializeIn<'de, 'bump, A>, A: Allocator> Visitor<'de>
+    for BumpVecVisitor<'bump, T, A>
+{
+    type Value = BumpVec<'bump, T, A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Self::Value, S::Error> {
+        let mut vec = BumpVec::new_in(self.bumpcar);
+        if let Some(len) = seq.size_hint() {
+            vec.try_reserve(len)
+                .map_err(|_| S::Error::custom(CAPACITY_EXCEEDED))?;
+        }
+        while let Some(value) = seq.next_element_seed(DeserializeInSeed::new(self.bumpcar))? {
+            vec.try_push(value)
+                .map_err(|_| S::Error::custom(CAPACITY_EXCEEDED))?;
+        }
+        Ok(vec)
+    }
+}
+
+impl<'de, 'bump, T: DeserializeIn<'de, 'bump, A>, A: Allocator> DeserializeIn<'de, 'bump, A>
+    for BumpVec<'bump, T, A>
+{
+    fn deserialize_in<D: Deserializer<'de>>(
+        deserializer: D,
+        bumpcar: &'bump BumpCar<A>,
+    ) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(BumpVecVisitor {
+            bumpcar,
+            marker: PhantomData,
+        })
+    }
+}
+
+/// Visitor of a string, copying it into a [`BumpString`].
+struct BumpStringVisitor<'bump, A: Allocator> {
+    bumpcar: &'bump BumpCar<A>,
+}
+
+impl<'de, 'bump, A: Allocator> Visitor<'de> for BumpStringVisitor<'bump, A> {
+    type Value = BumpString<'bump, A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        let mut s = BumpString::try_with_capacity_in(v.len(), self.bumpcar)
+            .map_err(|_| E::custom(CAPACITY_EXCEEDED))?;
+        s.push_str(v);
+        Ok(s)
+    }
+}
+
+impl<'de, 'bump, A: Allocator> DeserializeIn<'de, 'bump, A> for BumpString<'bump, A> {
+    fn deserialize_in<D: Deserializer<'de>>(
+        deserializer: D,
+        bumpcar: &'bump BumpCar<A>,
+    ) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(BumpStringVisitor { bumpcar })
+    }
+}
@@ -0,0 +1,320 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::mem::size_of;
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::typed::expect_capacity;
+use crate::{bump, BumpError, BumpErrorKind};
+
+/// Double-ended bump allocator, with two cursors moving toward each other.
+///
+/// The front cursor bumps upward from the start of the buffer, and the back cursor bumps
+/// downward from its end. Both ends share the same capacity, and can be reset independently:
+/// for example, long-lived results can be allocated at the front while per-iteration scratch
+/// data is allocated and reset at the back. A scratch value worth keeping is copied to the
+/// front with [`FrontCar::promote`].
+///
+/// Allocating requires splitting the arena into its [`FrontCar`] and [`BackCar`] with
+/// [`DoubleBumpCar::split`].
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::DoubleBumpCar;
+///
+/// let mut bumpcar = DoubleBumpCar::new(256).unwrap();
+/// let (front, mut back) = bumpcar.split();
+///
+/// let mut results = Vec::new_in(&front);
+/// for i in 0..4u64 {
+///     let scratch = Box::new_in([i; 4], &back);
+///     results.push(scratch.iter().sum::<u64>());
+///     drop(scratch);
+///     back.reset();
+/// }
+/// assert_eq!(results, [0, 4, 8, 12]);
+/// ```
+pub struct DoubleBumpCar<
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    start: NonNull<u8>,
+    end: NonNull<u8>,
+    front: Cell<NonNull<u8>>,
+    back: Cell<NonNull<u8>>,
+    allocator: A,
+}
+
+impl<A: Allocator> DoubleBumpCar<A> {
+    /// Allocates a new [`DoubleBumpCar`] in the given allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        let layout = Layout::from_size_align(capacity, size_of::<usize>())
+            .map_err(|_| BumpError::new(BumpErrorKind::Overflow, None, 0))?;
+        let start = allocator
+            .allocate(layout)
+            .map_err(|_| BumpError::new(BumpErrorKind::AllocatorFailed, Some(layout), 0))?
+            .cast::<u8>();
+        // SAFETY: the buffer is capacity bytes long.
+        let end = unsafe { start.add(capacity) };
+
+        Ok(Self {
+            start,
+            end,
+            front: Cell::new(start),
+            back: Cell::new(end),
+            allocator,
+        })
+    }
+
+    /// Returns the capacity of the [`DoubleBumpCar`].
+    pub fn capacity(&self) -> usize {
+        self.end.addr().get() - self.start.addr().get()
+    }
+
+    /// Returns the capacity left between the two cursors of the [`DoubleBumpCar`].
+    ///
+    /// As for [`BumpCar::remaining_capacity`](crate::BumpCar::remaining_capacity), this does not
+    /// account for alignment.
+    pub fn remaining_capacity(&self) -> usize {
+        self.back.get().addr().get() - self.front.get().addr().get()
+    }
+
+    /// Splits the [`DoubleBumpCar`] into its front and back allocators.
+    ///
+    /// Each end can then be reset on its own, without invalidating the allocations made
+    /// from the other one.
+    pub fn split(&mut self) -> (FrontCar<'_, A>, BackCar<'_, A>) {
+        (FrontCar { arena: self }, BackCar { arena: self })
+    }
+
+    /// Resets both ends of the [`DoubleBumpCar`].
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    pub fn reset(&mut self) {
+        self.front.set(self.start);
+        self.back.set(self.end);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl DoubleBumpCar {
+    /// Allocates a [`DoubleBumpCar`] with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, Global)
+    }
+}
+
+impl<A: Allocator> Drop for DoubleBumpCar<A> {
+    /// Deallocates the [`DoubleBumpCar`]'s buffer.
+    fn drop(&mut self) {
+        // SAFETY: start was allocated with self.allocator, with this layout.
+        unsafe {
+            self.allocator.deallocate(
+                self.start,
+                Layout::from_size_align_unchecked(self.capacity(), size_of::<usize>()),
+            );
+        }
+    }
+}
+
+/// Front end of a [`DoubleBumpCar`], bumping upward.
+pub struct FrontCar<
+    'a,
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    arena: &'a DoubleBumpCar<A>,
+}
+
+impl<A: Allocator> FrontCar<'_, A> {
+    /// Returns the number of bytes used at the front of the arena.
+    pub fn used(&self) -> usize {
+        self.arena.front.get().addr().get() - self.arena.start.addr().get()
+    }
+
+    /// Resets the front of the arena, leaving the back untouched.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    pub fn reset(&mut self) {
+        self.arena.front.set(self.arena.start);
+    }
+
+    /// Allocates a region for `layout` at the front of the arena, reporting the remaining
+    /// capacity on failure.
+    fn allocate_typed(&self, layout: Layout) -> Result<NonNull<[u8]>, BumpError> {
+        self.allocate(layout).map_err(|_| {
+            BumpError::new(
+                BumpErrorKind::CapacityExceeded,
+                Some(layout),
+                self.arena.remaining_capacity(),
+            )
+        })
+    }
+
+    /// Copies `value` to the front of the arena, and returns a reference to the copy.
+    ///
+    /// This keeps the few results of a scratch computation made at the back of the arena: the
+    /// copy survives the reset of the back, until the front itself is reset.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left.
+    #[allow(clippy::mut_from_ref)]
+    pub fn try_promote<T: Copy>(&self, value: &T) -> Result<&mut T, BumpError> {
+        let ptr = self.allocate_typed(Layout::new::<T>())?.cast::<T>();
+        // SAFETY: the allocation is valid for a T, and is not handed out again
+        // while self is borrowed.
+        unsafe {
+            ptr.write(*value);
+            Ok(&mut *ptr.as_ptr())
+        }
+    }
+
+    /// Copies `value` to the front of the arena, and returns a reference to the copy.
+    ///
+    /// The copy survives the reset of the back, until the front itself is reset.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::DoubleBumpCar;
+    ///
+    /// let mut bumpcar = DoubleBumpCar::new(1024).unwrap();
+    /// let (front, mut back) = bumpcar.split();
+    /// let mut best = None;
+    /// for round in 0..10u64 {
+    ///     let mut scratch = Vec::with_capacity_in(16, &back);
+    ///     scratch.extend((0..16).map(|i| (i * 7 + round) % 13));
+    ///     let max = scratch.iter().max().unwrap();
+    ///     if round == 3 {
+    ///         best = Some(front.promote(max));
+    ///     }
+    ///     drop(scratch);
+    ///     back.reset();
+    /// }
+    /// assert_eq!(best, Some(&mut 12));
+    /// assert_eq!(front.used(), 8);
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn promote<T: Copy>(&self, value: &T) -> &mut T {
+        expect_capacity(self.try_promote(value))
+    }
+
+    /// Copies `values` to the front of the arena, and returns a reference to the copy.
+    ///
+    /// The copy survives the reset of the back, until the front itself is reset.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left.
+    #[allow(clippy::mut_from_ref)]
+    pub fn try_promote_slice<T: Copy>(&self, values: &[T]) -> Result<&mut [T], BumpError> {
+        let ptr = self.allocate_typed(Layout::for_value(values))?.cast::<T>();
+        // SAFETY: the allocation is valid for values.len() elements, distinct from values, and
+        // is not handed out again while self is borrowed.
+        unsafe {
+            ptr.copy_from_nonoverlapping(NonNull::from(values).cast(), values.len());
+            Ok(NonNull::slice_from_raw_parts(ptr, values.len()).as_mut())
+        }
+    }
+
+    /// Copies `values` to the front of the arena, and returns a reference to the copy.
+    ///
+    /// The copy survives the reset of the back, until the front itself is reset.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn promote_slice<T: Copy>(&self, values: &[T]) -> &mut [T] {
+        expect_capacity(self.try_promote_slice(values))
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for FrontCar<'_, A> {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: the front cursor never goes past the back cursor.
+        let (ptr, new_front) =
+            unsafe { bump(self.arena.front.get(), self.arena.back.get(), layout) }
+                .ok_or(AllocError)?;
+        self.arena.front.set(new_front);
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    /// Deallocates an allocated region.
+    ///
+    /// If it is the last allocation made at the front, its space is reclaimed.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: the allocation is layout.size() bytes long, in bounds of the buffer.
+        if unsafe { ptr.add(layout.size()) } == self.arena.front.get() {
+            self.arena.front.set(ptr);
+        }
+    }
+}
+
+/// Back end of a [`DoubleBumpCar`], bumping downward.
+pub struct BackCar<
+    'a,
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    arena: &'a DoubleBumpCar<A>,
+}
+
+impl<A: Allocator> BackCar<'_, A> {
+    /// Returns the number of bytes used at the back of the arena.
+    pub fn used(&self) -> usize {
+        self.arena.end.addr().get() - self.arena.back.get().addr().get()
+    }
+
+    /// Resets the back of the arena, leaving the front untouched.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    pub fn reset(&mut self) {
+        self.arena.back.set(self.arena.end);
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for BackCar<'_, A> {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let back = self.arena.back.get();
+        let addr = back.addr().get();
+        let new_addr = addr.checked_sub(layout.size()).ok_or(AllocError)? & !(layout.align() - 1);
+        if new_addr < self.arena.front.get().addr().get() {
+            return Err(AllocError);
+        }
+
+        // SAFETY: front <= new_addr <= addr, so the pointer stays in bounds of the buffer.
+        let ptr = unsafe { back.byte_sub(addr - new_addr) };
+        self.arena.back.set(ptr);
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    /// Deallocates an allocated region.
+    ///
+    /// If it is the last allocation made at the back, its space is reclaimed.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if ptr == self.arena.back.get() {
+            // SAFETY: the allocation is layout.size() bytes long, in bounds of the buffer.
+            self.arena.back.set(unsafe { ptr.add(layout.size()) });
+        }
+    }
+}
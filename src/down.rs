@@ -0,0 +1,151 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::fmt;
+use core::mem::size_of;
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{BumpError, BumpErrorKind};
+
+/// Bump allocator whose cursor moves downward, from the end of its buffer to its start.
+///
+/// Bumping downward aligns the cursor with a single mask, and saves an addition on the
+/// allocation path compared to [`BumpCar`](crate::BumpCar). On the other hand, the last
+/// allocation can not be grown in place.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::DownBumpCar;
+///
+/// let mut bumpcar = DownBumpCar::new(256).unwrap();
+/// let a = Box::new_in(1u64, &bumpcar);
+/// let b = Box::new_in(2u64, &bumpcar);
+/// assert!(core::ptr::from_ref(&*b) < core::ptr::from_ref(&*a));
+/// assert_eq!(bumpcar.remaining_capacity(), 240);
+///
+/// drop((a, b));
+/// bumpcar.reset();
+/// assert_eq!(bumpcar.remaining_capacity(), 256);
+/// ```
+pub struct DownBumpCar<
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    start: NonNull<u8>,
+    end: NonNull<u8>,
+    current: Cell<NonNull<u8>>,
+    allocator: A,
+}
+
+impl<A: Allocator> DownBumpCar<A> {
+    /// Allocates a new [`DownBumpCar`] in the given allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        let layout = Layout::from_size_align(capacity, size_of::<usize>())
+            .map_err(|_| BumpError::new(BumpErrorKind::Overflow, None, 0))?;
+        let start = allocator
+            .allocate(layout)
+            .map_err(|_| BumpError::new(BumpErrorKind::AllocatorFailed, Some(layout), 0))?
+            .cast::<u8>();
+        // SAFETY: the buffer is capacity bytes long.
+        let end = unsafe { start.add(capacity) };
+
+        Ok(Self {
+            start,
+            end,
+            current: Cell::new(end),
+            allocator,
+        })
+    }
+
+    /// Returns the capacity of the [`DownBumpCar`].
+    pub fn capacity(&self) -> usize {
+        self.end.addr().get() - self.start.addr().get()
+    }
+
+    /// Returns the remaining capacity of the [`DownBumpCar`].
+    ///
+    /// As for [`BumpCar::remaining_capacity`](crate::BumpCar::remaining_capacity), this does not
+    /// account for alignment.
+    pub fn remaining_capacity(&self) -> usize {
+        self.current.get().addr().get() - self.start.addr().get()
+    }
+
+    /// Resets the [`DownBumpCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    pub fn reset(&mut self) {
+        self.current.set(self.end);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl DownBumpCar {
+    /// Allocates a [`DownBumpCar`] with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, Global)
+    }
+}
+
+impl<A: Allocator> Drop for DownBumpCar<A> {
+    /// Deallocates the [`DownBumpCar`]'s buffer.
+    fn drop(&mut self) {
+        // SAFETY: start was allocated with self.allocator, with this layout.
+        unsafe {
+            self.allocator.deallocate(
+                self.start,
+                Layout::from_size_align_unchecked(self.capacity(), size_of::<usize>()),
+            );
+        }
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for DownBumpCar<A> {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let current = self.current.get();
+        let addr = current.addr().get();
+        let new_addr = addr.checked_sub(layout.size()).ok_or(AllocError)? & !(layout.align() - 1);
+        if new_addr < self.start.addr().get() {
+            return Err(AllocError);
+        }
+
+        // SAFETY: start <= new_addr <= addr, so the pointer stays in bounds of the buffer.
+        let ptr = unsafe { current.byte_sub(addr - new_addr) };
+        self.current.set(ptr);
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    /// Deallocates an allocated region.
+    ///
+    /// If it is the last allocation made, its space is reclaimed. Otherwise, the [`DownBumpCar`]
+    /// does not perform deallocation unless it's reset or dropped.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if ptr == self.current.get() {
+            // SAFETY: the allocation is layout.size() bytes long, in bounds of the buffer.
+            self.current.set(unsafe { ptr.add(layout.size()) });
+        }
+    }
+}
+
+impl<A: Allocator + fmt::Debug> fmt::Debug for DownBumpCar<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DownBumpCar")
+            .field("capacity", &self.capacity())
+            .field("used", &(self.capacity() - self.remaining_capacity()))
+            .field("remaining", &self.remaining_capacity())
+            .field("allocator", &self.allocator)
+            .finish()
+    }
+}
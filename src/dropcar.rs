@@ -0,0 +1,192 @@
+use core::alloc::{Allocator, Layout};
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::mem::needs_drop;
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::typed::expect_capacity;
+use crate::{BumpCar, BumpError};
+
+/// Record of a value that needs to be dropped, stored in the arena right before it.
+struct DropHeader {
+    prev: Option<NonNull<DropHeader>>,
+    drop: unsafe fn(NonNull<DropHeader>),
+}
+
+#[repr(C)]
+struct DropNode<T> {
+    header: DropHeader,
+    value: T,
+}
+
+/// Drops the value following `header`.
+///
+/// # Safety
+/// `header` must be the header of a valid `DropNode<T>`, whose value has not been dropped yet.
+unsafe fn drop_node<T>(header: NonNull<DropHeader>) {
+    let node = header.cast::<DropNode<T>>().as_ptr();
+    // SAFETY: guaranteed by the caller.
+    unsafe { core::ptr::drop_in_place(&raw mut (*node).value) };
+}
+
+/// Typed arena that runs the destructors of its values when it is reset or dropped.
+///
+/// Values that need to be dropped are allocated with a small header, linking them in a list
+/// that is walked in reverse allocation order. Values that do not need to be dropped are
+/// allocated as in a [`BumpCar`].
+///
+/// # Example
+/// ```rust
+/// use dodgems::DropCar;
+///
+/// let mut dropcar = DropCar::new(1024).unwrap();
+/// let s = dropcar.alloc(String::from("hello"));
+/// s.push_str(" world");
+/// assert_eq!(s, "hello world");
+///
+/// dropcar.reset(); // the string is dropped, and its heap buffer is freed
+/// ```
+///
+/// Values may borrow data, for at least the lifetime `'d` of the [`DropCar`], since they are
+/// dropped with it. Borrowing data that does not outlive the arena does not compile:
+/// ```rust,compile_fail,E0597
+/// use dodgems::DropCar;
+///
+/// struct Noisy<'a>(&'a String);
+///
+/// impl Drop for Noisy<'_> {
+///     fn drop(&mut self) {
+///         println!("{}", self.0);
+///     }
+/// }
+///
+/// let dropcar = DropCar::new(256).unwrap();
+/// {
+///     let s = String::from("freed");
+///     dropcar.alloc(Noisy(&s));
+/// }
+/// ```
+pub struct DropCar<
+    'd,
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    bumpcar: BumpCar<A>,
+    last: Cell<Option<NonNull<DropHeader>>>,
+    /// Invariant in `'d`, so that the values outlive the arena, whose destructor drops them.
+    _marker: PhantomData<fn(&'d ()) -> &'d ()>,
+}
+
+impl<'d, A: Allocator> DropCar<'d, A> {
+    /// Allocates a new [`DropCar`] in the given allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        Ok(Self {
+            bumpcar: BumpCar::new_in(capacity, allocator)?,
+            last: Cell::new(None),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the underlying [`BumpCar`].
+    ///
+    /// Values allocated directly in it are never dropped.
+    pub fn bumpcar(&self) -> &BumpCar<A> {
+        &self.bumpcar
+    }
+
+    /// Moves `value` into the [`DropCar`], and returns a mutable reference to it.
+    ///
+    /// The value is dropped when the [`DropCar`] is reset or dropped.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`DropCar`] does not have enough capacity left.
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc<T: 'd>(&self, value: T) -> Result<&mut T, BumpError> {
+        if !needs_drop::<T>() {
+            return self.bumpcar.try_alloc(value);
+        }
+
+        let node = self
+            .bumpcar
+            .allocate_typed(Layout::new::<DropNode<T>>())?
+            .cast::<DropNode<T>>();
+        // SAFETY: the allocation is valid for a DropNode<T>, and is not handed out again
+        // while self is borrowed.
+        unsafe {
+            node.write(DropNode {
+                header: DropHeader {
+                    prev: self.last.get(),
+                    drop: drop_node::<T>,
+                },
+                value,
+            });
+            self.last.set(Some(node.cast()));
+            Ok(&mut (*node.as_ptr()).value)
+        }
+    }
+
+    /// Moves `value` into the [`DropCar`], and returns a mutable reference to it.
+    ///
+    /// The value is dropped when the [`DropCar`] is reset or dropped.
+    ///
+    /// # Panics
+    /// This function panics if the [`DropCar`] does not have enough capacity left.
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn alloc<T: 'd>(&self, value: T) -> &mut T {
+        expect_capacity(self.try_alloc(value))
+    }
+
+    /// Returns the remaining capacity of the [`DropCar`].
+    pub fn remaining_capacity(&self) -> usize {
+        self.bumpcar.remaining_capacity()
+    }
+
+    /// Drops every value, in reverse allocation order.
+    ///
+    /// If a destructor panics, the values allocated before it are leaked.
+    fn drop_values(&mut self) {
+        while let Some(header) = self.last.get() {
+            // SAFETY: the headers in the list are valid, and their values have not been
+            // dropped yet. The header is unlinked before its value is dropped.
+            unsafe {
+                self.last.set(header.as_ref().prev);
+                (header.as_ref().drop)(header);
+            }
+        }
+    }
+
+    /// Drops every value, and resets the [`DropCar`]'s remaining capacity to its
+    /// initial capacity.
+    pub fn reset(&mut self) {
+        self.drop_values();
+        self.bumpcar.reset();
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl DropCar<'_> {
+    /// Allocates a [`DropCar`] with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, Global)
+    }
+}
+
+impl<A: Allocator> Drop for DropCar<'_, A> {
+    /// Drops every value, before the buffer is deallocated.
+    fn drop(&mut self) {
+        self.drop_values();
+    }
+}
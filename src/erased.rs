@@ -0,0 +1,142 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::fmt;
+use core::ptr::NonNull;
+
+use crate::BumpCar;
+
+/// Object safe view of a [`BumpCar`], whatever its backing allocator.
+trait ErasedBumpCar: Allocator {
+    fn capacity(&self) -> usize;
+    fn remaining_capacity(&self) -> usize;
+    fn owns(&self, ptr: NonNull<u8>) -> bool;
+}
+
+impl<A: Allocator> ErasedBumpCar for BumpCar<A> {
+    fn capacity(&self) -> usize {
+        BumpCar::capacity(self)
+    }
+
+    fn remaining_capacity(&self) -> usize {
+        BumpCar::remaining_capacity(self)
+    }
+
+    fn owns(&self, ptr: NonNull<u8>) -> bool {
+        BumpCar::owns(self, ptr)
+    }
+}
+
+/// Copyable handle to a [`BumpCar`], that hides the type of its backing allocator.
+///
+/// Collections and functions can store or take a `Bump<'a>` instead of a `&'a BumpCar<A>`,
+/// without becoming generic over `A`. Allocations go through dynamic dispatch, like with
+/// [`BumpCar::as_dyn`].
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::{Bump, BumpCar};
+///
+/// struct Ast<'a> {
+///     nodes: Vec<u32, Bump<'a>>,
+///     names: Vec<&'a str, Bump<'a>>,
+/// }
+///
+/// let bumpcar = BumpCar::new(256).unwrap();
+/// let bump = bumpcar.as_bump();
+/// let mut ast = Ast {
+///     nodes: Vec::new_in(bump),
+///     names: Vec::new_in(bump),
+/// };
+/// ast.nodes.push(1);
+/// ast.names.push("x");
+/// assert!(bump.remaining_capacity() < 256);
+/// ```
+#[derive(Clone, Copy)]
+pub struct Bump<'a> {
+    bumpcar: &'a dyn ErasedBumpCar,
+}
+
+impl<'a> Bump<'a> {
+    /// Returns the capacity of the underlying [`BumpCar`].
+    pub fn capacity(self) -> usize {
+        self.bumpcar.capacity()
+    }
+
+    /// Returns the remaining capacity of the underlying [`BumpCar`].
+    pub fn remaining_capacity(self) -> usize {
+        self.bumpcar.remaining_capacity()
+    }
+
+    /// Returns `true` if `ptr` points into the buffer of the underlying [`BumpCar`].
+    pub fn owns(self, ptr: NonNull<u8>) -> bool {
+        self.bumpcar.owns(ptr)
+    }
+}
+
+impl<A: Allocator> BumpCar<A> {
+    /// Returns a copyable [`Bump`] handle to the [`BumpCar`], that does not depend on its
+    /// backing allocator type.
+    pub fn as_bump(&self) -> Bump<'_> {
+        Bump { bumpcar: self }
+    }
+}
+
+impl<'a, A: Allocator> From<&'a BumpCar<A>> for Bump<'a> {
+    fn from(bumpcar: &'a BumpCar<A>) -> Self {
+        bumpcar.as_bump()
+    }
+}
+
+impl fmt::Debug for Bump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bump")
+            .field("capacity", &self.capacity())
+            .field("remaining_capacity", &self.remaining_capacity())
+            .finish()
+    }
+}
+
+unsafe impl Allocator for Bump<'_> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.bumpcar.allocate(layout)
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.bumpcar.allocate_zeroed(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.deallocate(ptr, layout) }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.grow(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.grow_zeroed(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.shrink(ptr, old_layout, new_layout) }
+    }
+}
@@ -0,0 +1,112 @@
+use core::alloc::{AllocError, Layout};
+use core::fmt;
+
+/// Reason why a [`BumpError`] was returned.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BumpErrorKind {
+    /// The arena does not have enough capacity left for the request.
+    CapacityExceeded,
+    /// The size of the request overflows [`isize::MAX`], so that no arena could fit it.
+    Overflow,
+    /// The requested alignment is not a power of two.
+    InvalidAlignment,
+    /// The backing allocator returned an error.
+    AllocatorFailed,
+    /// A formatting trait implementation returned an error.
+    Format,
+    /// The bytes of a C string contain a NUL byte before its terminator.
+    InteriorNul,
+}
+
+/// Error returned by the constructors and the typed allocation methods of a
+/// [`BumpCar`](crate::BumpCar).
+///
+/// Unlike [`AllocError`], it describes the failed request. It converts to [`AllocError`], so
+/// that it can be propagated with `?` in functions returning an [`AllocError`], and the
+/// [`Allocator`](core::alloc::Allocator) implementations still return an [`AllocError`].
+///
+/// # Example
+/// ```rust
+/// use core::alloc::Layout;
+/// use dodgems::{BumpCar, BumpErrorKind};
+///
+/// let bumpcar = BumpCar::new(16).unwrap();
+/// let err = bumpcar.try_alloc([0u64; 4]).unwrap_err();
+/// assert_eq!(err.kind(), BumpErrorKind::CapacityExceeded);
+/// assert_eq!(err.layout(), Some(Layout::new::<[u64; 4]>()));
+/// assert_eq!(err.remaining_capacity(), 16);
+/// assert_eq!(
+///     err.to_string(),
+///     "BumpCar capacity exceeded: 32 bytes aligned to 8 requested, 16 bytes remaining",
+/// );
+/// ```
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BumpError {
+    kind: BumpErrorKind,
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    layout: Option<Layout>,
+    remaining: usize,
+}
+
+impl BumpError {
+    /// Creates a new error of the given kind.
+    pub(crate) const fn new(kind: BumpErrorKind, layout: Option<Layout>, remaining: usize) -> Self {
+        Self {
+            kind,
+            layout,
+            remaining,
+        }
+    }
+
+    /// Returns the reason of the failure.
+    pub fn kind(&self) -> BumpErrorKind {
+        self.kind
+    }
+
+    /// Returns the layout of the failed request, if it could be computed.
+    pub fn layout(&self) -> Option<Layout> {
+        self.layout
+    }
+
+    /// Returns the remaining capacity of the arena when the request failed, or zero if the
+    /// arena was being created.
+    pub fn remaining_capacity(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl fmt::Display for BumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self.kind {
+            BumpErrorKind::CapacityExceeded => "BumpCar capacity exceeded",
+            BumpErrorKind::Overflow => "BumpCar capacity exceeded: the requested size overflows",
+            BumpErrorKind::InvalidAlignment => "BumpCar alignment is not a power of two",
+            BumpErrorKind::AllocatorFailed => "BumpCar backing allocator failed",
+            BumpErrorKind::Format => "BumpCar formatting failed",
+            BumpErrorKind::InteriorNul => "BumpCar C string contains an interior NUL byte",
+        })?;
+        if let Some(layout) = self.layout {
+            write!(
+                f,
+                ": {} bytes aligned to {} requested",
+                layout.size(),
+                layout.align()
+            )?;
+            if self.kind == BumpErrorKind::CapacityExceeded {
+                write!(f, ", {} bytes remaining", self.remaining)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for BumpError {}
+
+impl From<BumpError> for AllocError {
+    fn from(_: BumpError) -> Self {
+        AllocError
+    }
+}
@@ -0,0 +1,136 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::ptr::NonNull;
+
+/// Allocator wrapper failing allocations on a deterministic schedule, to test out of memory
+/// handling.
+///
+/// Every allocation attempt (including the grows) is numbered from 0, and fails without
+/// reaching the inner allocator if the schedule returns `true` for its number. Deallocations and
+/// shrinks are always forwarded.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::{BumpCar, FailCar};
+///
+/// let bumpcar = BumpCar::new(1024).unwrap();
+/// let failing = FailCar::fail_nth(&bumpcar, 1);
+///
+/// let a = Box::try_new_in(1u32, &failing);
+/// let b = Box::try_new_in(2u32, &failing);
+/// let c = Box::try_new_in(3u32, &failing);
+/// assert!(a.is_ok() && b.is_err() && c.is_ok());
+/// assert_eq!((failing.attempts(), failing.failures()), (3, 1));
+/// ```
+#[derive(Debug)]
+pub struct FailCar<A, F = fn(usize) -> bool> {
+    inner: A,
+    schedule: F,
+    attempts: Cell<usize>,
+    failures: Cell<usize>,
+}
+
+impl<A: Allocator, F: Fn(usize) -> bool> FailCar<A, F> {
+    /// Wraps `inner`, failing the allocation attempts for which `schedule` returns `true`.
+    pub const fn new(inner: A, schedule: F) -> Self {
+        Self {
+            inner,
+            schedule,
+            attempts: Cell::new(0),
+            failures: Cell::new(0),
+        }
+    }
+
+    /// Returns the number of allocation attempts made through the wrapper.
+    pub fn attempts(&self) -> usize {
+        self.attempts.get()
+    }
+
+    /// Returns the number of allocation attempts failed by the schedule.
+    pub fn failures(&self) -> usize {
+        self.failures.get()
+    }
+
+    /// Returns a reference to the inner allocator.
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Unwraps the inner allocator.
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+
+    /// Numbers an allocation attempt, and fails it if the schedule says so.
+    fn attempt(&self) -> Result<(), AllocError> {
+        let attempt = self.attempts.get();
+        self.attempts.set(attempt + 1);
+        if (self.schedule)(attempt) {
+            self.failures.set(self.failures.get() + 1);
+            return Err(AllocError);
+        }
+        Ok(())
+    }
+}
+
+impl<A: Allocator> FailCar<A> {
+    /// Wraps `inner`, failing only the `n`th allocation attempt (counting from 0).
+    pub fn fail_nth(inner: A, n: usize) -> FailCar<A, impl Fn(usize) -> bool> {
+        FailCar::new(inner, move |attempt| attempt == n)
+    }
+
+    /// Wraps `inner`, failing every allocation attempt after the first `n` ones.
+    pub fn fail_after(inner: A, n: usize) -> FailCar<A, impl Fn(usize) -> bool> {
+        FailCar::new(inner, move |attempt| attempt >= n)
+    }
+}
+
+unsafe impl<A: Allocator, F: Fn(usize) -> bool> Allocator for FailCar<A, F> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.attempt()?;
+        self.inner.allocate(layout)
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.attempt()?;
+        self.inner.allocate_zeroed(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.inner.deallocate(ptr, layout) }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.attempt()?;
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.inner.grow(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.attempt()?;
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.inner.grow_zeroed(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.inner.shrink(ptr, old_layout, new_layout) }
+    }
+}
@@ -0,0 +1,128 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::ptr::NonNull;
+
+use crate::BumpCar;
+
+/// Allocator able to tell wether it owns an allocation.
+///
+/// # Safety
+/// [`Owns::owns`] must return `true` for every live allocation made by the allocator, and
+/// `false` for every allocation made by another allocator.
+pub unsafe trait Owns: Allocator {
+    /// Checks wether the allocation described by `ptr` and `layout` was made by this allocator.
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool;
+}
+
+// SAFETY: the buffer is exclusively owned by the BumpCar, so no other allocator can hand out
+// memory inside of it.
+unsafe impl<A: Allocator> Owns for BumpCar<A> {
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        self.owns_allocation(ptr, layout)
+    }
+}
+
+// SAFETY: forwarded to the owned allocator.
+unsafe impl<O: Owns + ?Sized> Owns for &O {
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        (**self).owns(ptr, layout)
+    }
+}
+
+/// Allocator trying a primary allocator first, and falling back to a secondary one when it fails.
+///
+/// The primary allocator must implement [`Owns`], so that every allocation is deallocated and
+/// resized by the allocator that made it.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use std::alloc::Global;
+/// use dodgems::{BumpCar, Fallback};
+///
+/// let bumpcar = BumpCar::new(64).unwrap();
+/// let alloc = Fallback(&bumpcar, Global);
+///
+/// let mut v = Vec::new_in(&alloc);
+/// v.extend(0..100u32);
+/// assert!(!bumpcar.owns(core::ptr::NonNull::from(&v[0]).cast()));
+/// assert_eq!(v.iter().sum::<u32>(), 4950);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fallback<A, B>(pub A, pub B);
+
+unsafe impl<A: Owns, B: Allocator> Allocator for Fallback<A, B> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.0.allocate(layout).or_else(|_| self.1.allocate(layout))
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.0
+            .allocate_zeroed(layout)
+            .or_else(|_| self.1.allocate_zeroed(layout))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: the allocation is deallocated by the allocator that made it.
+        unsafe {
+            if self.0.owns(ptr, layout) {
+                self.0.deallocate(ptr, layout);
+            } else {
+                self.1.deallocate(ptr, layout);
+            }
+        }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if !self.0.owns(ptr, old_layout) {
+            // SAFETY: the allocation was made by the secondary allocator.
+            return unsafe { self.1.grow(ptr, old_layout, new_layout) };
+        }
+        // SAFETY: the allocation was made by the primary allocator.
+        if let Ok(new_ptr) = unsafe { self.0.grow(ptr, old_layout, new_layout) } {
+            return Ok(new_ptr);
+        }
+
+        let new_ptr = self.1.allocate(new_layout)?;
+        // SAFETY: the new allocation is valid for new_layout.size() >= old_layout.size() bytes,
+        // and cannot overlap the old allocation, that is deallocated afterwards.
+        unsafe {
+            new_ptr
+                .cast::<u8>()
+                .copy_from_nonoverlapping(ptr, old_layout.size());
+            self.0.deallocate(ptr, old_layout);
+        }
+        Ok(new_ptr)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if !self.0.owns(ptr, old_layout) {
+            // SAFETY: the allocation was made by the secondary allocator.
+            return unsafe { self.1.shrink(ptr, old_layout, new_layout) };
+        }
+        // SAFETY: the allocation was made by the primary allocator.
+        if let Ok(new_ptr) = unsafe { self.0.shrink(ptr, old_layout, new_layout) } {
+            return Ok(new_ptr);
+        }
+
+        let new_ptr = self.1.allocate(new_layout)?;
+        // SAFETY: the new allocation is valid for new_layout.size() bytes, and cannot overlap
+        // the old allocation, that is deallocated afterwards.
+        unsafe {
+            new_ptr
+                .cast::<u8>()
+                .copy_from_nonoverlapping(ptr, new_layout.size());
+            self.0.deallocate(ptr, old_layout);
+        }
+        Ok(new_ptr)
+    }
+}
@@ -0,0 +1,96 @@
+//! C bindings for the [`BumpCar`] allocator.
+//!
+//! The arena is exposed as an opaque [`DodgemsArena`] pointer, backed by the global allocator.
+//! All functions check their inputs and return a null pointer (or do nothing) instead of
+//! invoking undefined behaviour on null arenas, zero-sized or misaligned requests.
+//!
+//! The matching C declarations are in `include/dodgems.h`, at the root of the repository.
+//!
+//! **The arena is single-threaded**: an arena must not be used from several threads at once.
+
+use alloc::boxed::Box;
+use core::alloc::{Allocator, Layout};
+use core::ptr;
+
+use crate::BumpCar;
+
+/// Opaque handle to a [`BumpCar`] allocated with the global allocator.
+pub struct DodgemsArena(BumpCar);
+
+/// Creates a new arena with the given capacity.
+///
+/// Returns a null pointer if the arena could not be allocated.
+/// The arena must be freed with [`dodgems_free`].
+#[no_mangle]
+pub extern "C" fn dodgems_new(capacity: usize) -> *mut DodgemsArena {
+    match BumpCar::new(capacity) {
+        Ok(bumpcar) => Box::into_raw(Box::new(DodgemsArena(bumpcar))),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Allocates `size` bytes aligned to `align` in the arena.
+///
+/// Returns a null pointer if `arena` is null, `size` is zero, `align` is not a power of two,
+/// or if the arena is exhausted. The memory is valid until the arena is reset or freed.
+///
+/// # Safety
+/// `arena` must be null or a pointer returned by [`dodgems_new`] that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn dodgems_alloc(
+    arena: *const DodgemsArena,
+    size: usize,
+    align: usize,
+) -> *mut u8 {
+    // SAFETY: arena is either null or a valid arena pointer.
+    let Some(arena) = (unsafe { arena.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    if size == 0 {
+        return ptr::null_mut();
+    }
+    let Ok(layout) = Layout::from_size_align(size, align) else {
+        return ptr::null_mut();
+    };
+
+    match arena.0.allocate(layout) {
+        Ok(ptr) => ptr.cast::<u8>().as_ptr(),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Returns the remaining capacity of the arena, or zero if `arena` is null.
+///
+/// # Safety
+/// `arena` must be null or a pointer returned by [`dodgems_new`] that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn dodgems_remaining(arena: *const DodgemsArena) -> usize {
+    // SAFETY: arena is either null or a valid arena pointer.
+    unsafe { arena.as_ref() }.map_or(0, |arena| arena.0.remaining_capacity())
+}
+
+/// Resets the arena, making its whole capacity available again. Does nothing if `arena` is null.
+///
+/// # Safety
+/// `arena` must be null or a pointer returned by [`dodgems_new`] that has not been freed.
+/// Every pointer previously returned by [`dodgems_alloc`] for this arena becomes dangling.
+#[no_mangle]
+pub unsafe extern "C" fn dodgems_reset(arena: *mut DodgemsArena) {
+    // SAFETY: arena is either null or a valid arena pointer, not used concurrently.
+    if let Some(arena) = unsafe { arena.as_mut() } {
+        arena.0.reset();
+    }
+}
+
+/// Frees the arena and all of its allocations. Does nothing if `arena` is null.
+///
+/// # Safety
+/// `arena` must be null or a pointer returned by [`dodgems_new`] that has not been freed.
+/// Every pointer previously returned by [`dodgems_alloc`] for this arena becomes dangling.
+#[no_mangle]
+pub unsafe extern "C" fn dodgems_free(arena: *mut DodgemsArena) {
+    if !arena.is_null() {
+        // SAFETY: arena was allocated by dodgems_new with Box::into_raw.
+        drop(unsafe { Box::from_raw(arena) });
+    }
+}
@@ -0,0 +1,239 @@
+use core::alloc::{Allocator, Layout};
+use core::fmt;
+use core::mem::{size_of, ManuallyDrop};
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{BumpCar, BumpError, BumpErrorKind};
+
+/// Array of a fixed capacity allocated in a [`BumpCar`], that never reallocates.
+///
+/// Unlike a [`BumpVec`](crate::BumpVec), its buffer is allocated once with an exact capacity, and
+/// pushing past it fails instead of moving the elements: their addresses are stable for the
+/// lifetime of the vector, so that other objects of the arena can point into it.
+///
+/// # Example
+/// ```rust
+/// use dodgems::{BumpCar, FixedBumpVec};
+///
+/// let bumpcar = BumpCar::new(256).unwrap();
+/// let mut nodes = FixedBumpVec::with_capacity_in(3, &bumpcar);
+/// let first = nodes.push_within_capacity(1u32).unwrap() as *const u32;
+/// nodes.push_within_capacity(2).unwrap();
+/// nodes.push_within_capacity(3).unwrap();
+///
+/// assert_eq!(nodes.push_within_capacity(4), Err(4));
+/// assert_eq!(nodes, [1, 2, 3]);
+/// assert_eq!(&nodes[0] as *const u32, first);
+/// ```
+pub struct FixedBumpVec<
+    'bump,
+    T,
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    bumpcar: &'bump BumpCar<A>,
+    ptr: NonNull<T>,
+    len: usize,
+    capacity: usize,
+}
+
+impl<'bump, T, A: Allocator> FixedBumpVec<'bump, T, A> {
+    /// Allocates a new, empty [`FixedBumpVec`] in `bumpcar`, with room for exactly `capacity`
+    /// elements.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left, or if
+    /// the size of the buffer overflows.
+    pub fn try_with_capacity_in(
+        capacity: usize,
+        bumpcar: &'bump BumpCar<A>,
+    ) -> Result<Self, BumpError> {
+        let layout = Layout::array::<T>(capacity)
+            .map_err(|_| BumpError::new(BumpErrorKind::Overflow, None, 0))?;
+        let ptr = if size_of::<T>() == 0 {
+            NonNull::dangling()
+        } else {
+            bumpcar.allocate_typed(layout)?.cast()
+        };
+        Ok(Self {
+            bumpcar,
+            ptr,
+            len: 0,
+            capacity,
+        })
+    }
+
+    /// Allocates a new, empty [`FixedBumpVec`] in `bumpcar`, with room for exactly `capacity`
+    /// elements.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left, or if the size of
+    /// the buffer overflows.
+    #[track_caller]
+    pub fn with_capacity_in(capacity: usize, bumpcar: &'bump BumpCar<A>) -> Self {
+        crate::typed::expect_capacity(Self::try_with_capacity_in(capacity, bumpcar))
+    }
+
+    /// Returns the number of elements in the [`FixedBumpVec`].
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the [`FixedBumpVec`] contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the [`FixedBumpVec`] can hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns `true` if the [`FixedBumpVec`] cannot hold any more elements.
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity
+    }
+
+    /// Returns the arena of the [`FixedBumpVec`].
+    pub fn bumpcar(&self) -> &'bump BumpCar<A> {
+        self.bumpcar
+    }
+
+    /// Appends `value` to the end of the [`FixedBumpVec`], and returns a reference to it.
+    ///
+    /// # Errors
+    /// This function returns `value` back if the [`FixedBumpVec`] is full.
+    pub fn push_within_capacity(&mut self, value: T) -> Result<&mut T, T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        // SAFETY: len < capacity, so the slot is in bounds of the buffer.
+        let slot = unsafe { self.ptr.add(self.len) };
+        self.len += 1;
+        // SAFETY: the slot is not initialized yet, and is borrowed along with self.
+        unsafe {
+            slot.write(value);
+            Ok(&mut *slot.as_ptr())
+        }
+    }
+
+    /// Removes the last element of the [`FixedBumpVec`] and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: the element at index len was initialized, and is now out of bounds.
+        Some(unsafe { self.ptr.add(self.len).read() })
+    }
+
+    /// Shortens the [`FixedBumpVec`] to `len` elements, dropping the rest.
+    ///
+    /// Does nothing if `len` is greater than the current length.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail = NonNull::slice_from_raw_parts(
+            // SAFETY: len < self.len, so the pointer is in bounds of the buffer.
+            unsafe { self.ptr.add(len) },
+            self.len - len,
+        );
+        self.len = len;
+        // SAFETY: the tail elements were initialized, and are now out of bounds.
+        unsafe { tail.drop_in_place() };
+    }
+
+    /// Removes every element of the [`FixedBumpVec`].
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Returns a pointer to the buffer of the [`FixedBumpVec`], which never changes.
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+
+    /// Returns the elements of the [`FixedBumpVec`] as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the first len elements are initialized.
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Returns the elements of the [`FixedBumpVec`] as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the first len elements are initialized.
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Converts the [`FixedBumpVec`] into a slice with the lifetime of the arena.
+    ///
+    /// The unused capacity is returned to the arena if possible. The elements are never dropped.
+    pub fn into_slice(self) -> &'bump mut [T] {
+        let this = ManuallyDrop::new(self);
+        if size_of::<T>() != 0 {
+            this.bumpcar.resize_in_place(
+                this.ptr.cast(),
+                this.capacity * size_of::<T>(),
+                this.len * size_of::<T>(),
+            );
+        }
+        // SAFETY: the first len elements are initialized, and the buffer lives as long as
+        // the arena borrow.
+        unsafe { core::slice::from_raw_parts_mut(this.ptr.as_ptr(), this.len) }
+    }
+}
+
+impl<T, A: Allocator> Drop for FixedBumpVec<'_, T, A> {
+    /// Drops the elements, and returns the buffer to the arena if it is the last allocation.
+    fn drop(&mut self) {
+        self.clear();
+        if size_of::<T>() != 0 && self.capacity != 0 {
+            // SAFETY: the buffer was allocated in the arena with this layout.
+            unsafe {
+                self.bumpcar.deallocate(
+                    self.ptr.cast(),
+                    Layout::array::<T>(self.capacity).unwrap_unchecked(),
+                );
+            }
+        }
+    }
+}
+
+impl<T, A: Allocator> Deref for FixedBumpVec<'_, T, A> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, A: Allocator> DerefMut for FixedBumpVec<'_, T, A> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for FixedBumpVec<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator> PartialEq<[U]> for FixedBumpVec<'_, T, A> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator, const N: usize> PartialEq<[U; N]>
+    for FixedBumpVec<'_, T, A>
+{
+    fn eq(&self, other: &[U; N]) -> bool {
+        self.as_slice() == other
+    }
+}
@@ -0,0 +1,450 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::mem::{align_of, size_of};
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{bump, BumpError, BumpErrorKind};
+
+/// Header placed at the start of every chunk of a [`FlexBumpCar`].
+struct ChunkHeader {
+    /// Previously filled chunk, if any.
+    prev: Option<NonNull<ChunkHeader>>,
+    /// Layout the chunk was allocated with, header included.
+    layout: Layout,
+    /// Number of bytes used when the chunk stopped being the current chunk.
+    used: usize,
+}
+
+const HEADER_SIZE: usize = size_of::<ChunkHeader>();
+const CHUNK_ALIGN: usize = if align_of::<ChunkHeader>() > size_of::<usize>() {
+    align_of::<ChunkHeader>()
+} else {
+    size_of::<usize>()
+};
+
+/// Returns the bounds of the usable part of `chunk`.
+///
+/// # Safety
+/// `chunk` must be live and start with an initialized header.
+unsafe fn bounds(chunk: NonNull<ChunkHeader>) -> (NonNull<u8>, NonNull<u8>) {
+    // SAFETY: the chunk is layout.size() bytes long, and layout.size() >= HEADER_SIZE.
+    unsafe {
+        let size = chunk.as_ref().layout.size();
+        let start = chunk.cast::<u8>();
+        (start.add(HEADER_SIZE), start.add(size))
+    }
+}
+
+/// Policy used by a [`FlexBumpCar`] to size its new chunks.
+///
+/// Whatever the policy, a new chunk is always big enough for the allocation that triggered it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GrowthStrategy {
+    /// Every new chunk is twice as big as the previous one.
+    ///
+    /// The number of chunks stays logarithmic in the total size, which suits workloads with
+    /// unpredictable sizes, such as compilers.
+    #[default]
+    Double,
+    /// Every new chunk has the given capacity, in bytes.
+    ///
+    /// Memory usage grows linearly, which suits steady workloads, such as loggers.
+    Fixed(usize),
+    /// Every new chunk is twice as big as the previous one, up to the given capacity, in bytes.
+    CappedDouble(usize),
+}
+
+impl GrowthStrategy {
+    /// Returns the capacity of the chunk following a chunk of `last` bytes.
+    fn next_capacity(self, last: usize) -> usize {
+        match self {
+            Self::Double => last.saturating_mul(2),
+            Self::Fixed(capacity) => capacity,
+            Self::CappedDouble(max) => last.saturating_mul(2).min(max),
+        }
+    }
+}
+
+/// Policy applied by [`FlexBumpCar::reset`] to the chunks of a [`FlexBumpCar`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ResetPolicy {
+    /// Every chunk but the current one is released.
+    #[default]
+    KeepCurrent,
+    /// If the memory used before the reset does not fit in the current chunk, every chunk is
+    /// released, and replaced by a single chunk big enough for it.
+    ///
+    /// After a few resets, workloads that use about the same amount of memory between resets
+    /// (such as frames) stop allocating new chunks.
+    Coalesce,
+}
+
+/// Description of a chunk of a [`FlexBumpCar`], returned by [`FlexBumpCar::chunks`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChunkInfo {
+    /// Start of the usable part of the chunk.
+    pub start: NonNull<u8>,
+    /// Number of usable bytes in the chunk.
+    pub capacity: usize,
+    /// Number of bytes used, alignment padding included.
+    ///
+    /// The used bytes are at the start of the chunk, but the padding is not initialized.
+    pub used: usize,
+}
+
+/// Iterator over the chunks of a [`FlexBumpCar`], from the current one to the oldest one.
+pub struct Chunks<'a> {
+    chunk: Option<NonNull<ChunkHeader>>,
+    used: usize,
+    marker: PhantomData<&'a ChunkHeader>,
+}
+
+impl Iterator for Chunks<'_> {
+    type Item = ChunkInfo;
+
+    fn next(&mut self) -> Option<ChunkInfo> {
+        let chunk = self.chunk?;
+        // SAFETY: the chunks are live as long as the FlexBumpCar is borrowed.
+        let (start, end) = unsafe { bounds(chunk) };
+        // SAFETY: same as above.
+        let header = unsafe { chunk.as_ref() };
+        let info = ChunkInfo {
+            start,
+            capacity: end.addr().get() - start.addr().get(),
+            used: self.used,
+        };
+        self.chunk = header.prev;
+        if let Some(prev) = header.prev {
+            // SAFETY: same as above.
+            self.used = unsafe { prev.as_ref() }.used;
+        }
+        Some(info)
+    }
+}
+
+/// Growable bump allocator.
+///
+/// Unlike [`BumpCar`](crate::BumpCar), it does not fail when its buffer is exhausted: a new chunk,
+/// at least twice as big as the previous one, is allocated in the backing allocator instead.
+/// The size of the new chunks can be changed with [`FlexBumpCar::with_growth`].
+/// Every chunk but the last one is released on [`FlexBumpCar::reset`], unless the
+/// [`ResetPolicy`] says otherwise.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::FlexBumpCar;
+///
+/// let mut bumpcar = FlexBumpCar::new(64).unwrap();
+/// let v = Vec::<u8, _>::with_capacity_in(1000, &bumpcar);
+/// assert!(bumpcar.capacity() >= 1064);
+///
+/// drop(v);
+/// bumpcar.reset();
+/// assert!(bumpcar.remaining_capacity() >= 1000);
+/// ```
+pub struct FlexBumpCar<
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    chunk: Cell<NonNull<ChunkHeader>>,
+    end: Cell<NonNull<u8>>,
+    current: Cell<NonNull<u8>>,
+    growth: GrowthStrategy,
+    reset_policy: ResetPolicy,
+    allocator: A,
+}
+
+impl<A: Allocator> FlexBumpCar<A> {
+    /// Allocates a new [`FlexBumpCar`] with a first chunk of `capacity` bytes in the given allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the size of the chunk overflows [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        let chunk = Self::allocate_chunk(&allocator, capacity, None)?;
+        // SAFETY: the chunk was just allocated.
+        let (start, end) = unsafe { bounds(chunk) };
+        Ok(Self {
+            chunk: Cell::new(chunk),
+            end: Cell::new(end),
+            current: Cell::new(start),
+            growth: GrowthStrategy::Double,
+            reset_policy: ResetPolicy::KeepCurrent,
+            allocator,
+        })
+    }
+
+    /// Sets the policy used to size the new chunks of the [`FlexBumpCar`].
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::{FlexBumpCar, GrowthStrategy};
+    ///
+    /// let bumpcar = FlexBumpCar::new(64)
+    ///     .unwrap()
+    ///     .with_growth(GrowthStrategy::Fixed(256));
+    /// for i in 0..100u64 {
+    ///     Box::leak(Box::new_in(i, &bumpcar));
+    /// }
+    /// assert_eq!(bumpcar.capacity(), 64 + 3 * 256);
+    /// ```
+    pub fn with_growth(mut self, growth: GrowthStrategy) -> Self {
+        self.growth = growth;
+        self
+    }
+
+    /// Returns the policy used to size the new chunks of the [`FlexBumpCar`].
+    pub fn growth(&self) -> GrowthStrategy {
+        self.growth
+    }
+
+    /// Sets the policy applied to the chunks of the [`FlexBumpCar`] when it is reset.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::{FlexBumpCar, ResetPolicy};
+    ///
+    /// let mut bumpcar = FlexBumpCar::new(64)
+    ///     .unwrap()
+    ///     .with_reset_policy(ResetPolicy::Coalesce);
+    /// for _ in 0..10 {
+    ///     for i in 0..100u64 {
+    ///         Box::leak(Box::new_in(i, &bumpcar));
+    ///     }
+    ///     bumpcar.reset();
+    /// }
+    /// assert_eq!(bumpcar.chunk_count(), 1);
+    /// assert!(bumpcar.capacity() >= 800);
+    /// ```
+    pub fn with_reset_policy(mut self, reset_policy: ResetPolicy) -> Self {
+        self.reset_policy = reset_policy;
+        self
+    }
+
+    /// Returns the policy applied to the chunks of the [`FlexBumpCar`] when it is reset.
+    pub fn reset_policy(&self) -> ResetPolicy {
+        self.reset_policy
+    }
+
+    /// Allocates a chunk of `capacity` usable bytes, and writes its header.
+    ///
+    fn allocate_chunk(
+        allocator: &A,
+        capacity: usize,
+        prev: Option<NonNull<ChunkHeader>>,
+    ) -> Result<NonNull<ChunkHeader>, BumpError> {
+        let overflow = BumpError::new(BumpErrorKind::Overflow, None, 0);
+        let size = capacity.checked_add(HEADER_SIZE).ok_or(overflow)?;
+        let layout = Layout::from_size_align(size, CHUNK_ALIGN).map_err(|_| overflow)?;
+        let chunk = allocator
+            .allocate(layout)
+            .map_err(|_| BumpError::new(BumpErrorKind::AllocatorFailed, Some(layout), 0))?
+            .cast::<ChunkHeader>();
+
+        // SAFETY: the chunk is valid for layout.size() bytes, and aligned for a ChunkHeader.
+        unsafe {
+            chunk.write(ChunkHeader {
+                prev,
+                layout,
+                used: 0,
+            })
+        };
+        Ok(chunk)
+    }
+
+    /// Returns the total capacity of the [`FlexBumpCar`], across all of its chunks.
+    pub fn capacity(&self) -> usize {
+        let mut capacity = 0;
+        let mut chunk = Some(self.chunk.get());
+        while let Some(header) = chunk {
+            // SAFETY: every chunk in the list is live and starts with an initialized header.
+            let header = unsafe { header.as_ref() };
+            capacity += header.layout.size() - HEADER_SIZE;
+            chunk = header.prev;
+        }
+        capacity
+    }
+
+    /// Returns the number of bytes used across all chunks, alignment padding included.
+    ///
+    /// The unused ends of the previous chunks are not counted.
+    pub fn used(&self) -> usize {
+        self.chunks().map(|chunk| chunk.used).sum()
+    }
+
+    /// Returns the number of chunks of the [`FlexBumpCar`].
+    pub fn chunk_count(&self) -> usize {
+        self.chunks().count()
+    }
+
+    /// Returns an iterator over the chunks of the [`FlexBumpCar`], from the current one to the
+    /// oldest one.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::FlexBumpCar;
+    ///
+    /// let bumpcar = FlexBumpCar::new(64).unwrap();
+    /// let a = Box::new_in([0u8; 48], &bumpcar);
+    /// let b = Box::new_in([0u8; 32], &bumpcar);
+    ///
+    /// let chunks: Vec<_> = bumpcar.chunks().map(|c| (c.capacity, c.used)).collect();
+    /// assert_eq!(chunks, [(128, 32), (64, 48)]);
+    /// assert_eq!(bumpcar.used(), 80);
+    /// ```
+    pub fn chunks(&self) -> Chunks<'_> {
+        Chunks {
+            chunk: Some(self.chunk.get()),
+            used: self.current_used(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of bytes used in the current chunk.
+    fn current_used(&self) -> usize {
+        // SAFETY: the current chunk is live.
+        let start = unsafe { bounds(self.chunk.get()) }.0;
+        self.current.get().addr().get() - start.addr().get()
+    }
+
+    /// Returns the remaining capacity of the current chunk.
+    ///
+    /// An allocation bigger than this does not fail, but allocates a new chunk.
+    pub fn remaining_capacity(&self) -> usize {
+        self.end.get().addr().get() - self.current.get().addr().get()
+    }
+
+    /// Resets the [`FlexBumpCar`], releasing every chunk but the current one.
+    ///
+    /// With [`ResetPolicy::Coalesce`], if the memory used does not fit in the current chunk,
+    /// it is also replaced by a chunk big enough for it. If that chunk cannot be allocated, the
+    /// current chunk is kept.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    ///
+    /// With the `zeroize` feature, the used memory is overwritten with zeros.
+    pub fn reset(&mut self) {
+        if self.reset_policy == ResetPolicy::Coalesce {
+            let used = self.used();
+            // SAFETY: the current chunk is live.
+            let (start, end) = unsafe { bounds(self.chunk.get()) };
+            if used > end.addr().get() - start.addr().get() {
+                if let Ok(chunk) = Self::allocate_chunk(&self.allocator, used, None) {
+                    // SAFETY: the old chunks are no longer reachable from self.
+                    unsafe { self.deallocate_chunks(Some(self.chunk.replace(chunk))) };
+                    // SAFETY: the chunk was just allocated.
+                    let (start, end) = unsafe { bounds(chunk) };
+                    self.end.set(end);
+                    self.current.set(start);
+                    return;
+                }
+            }
+        }
+
+        // SAFETY: the current chunk is live and starts with an initialized header.
+        let prev = unsafe { (*self.chunk.get().as_ptr()).prev.take() };
+        // SAFETY: the previous chunks are no longer reachable from self.
+        unsafe { self.deallocate_chunks(prev) };
+        // SAFETY: the current chunk is live.
+        let start = unsafe { bounds(self.chunk.get()) }.0;
+        #[cfg(feature = "zeroize")]
+        // SAFETY: the used part of the current chunk is between start and current.
+        unsafe {
+            crate::wipe_bytes(start, self.current.get().addr().get() - start.addr().get());
+        }
+        self.current.set(start);
+    }
+
+    /// Deallocates `chunk` and every chunk before it.
+    ///
+    /// With the `zeroize` feature, the chunks are overwritten with zeros first.
+    ///
+    /// # Safety
+    /// The chunks must have been allocated by `self.allocator`, and must not be used afterwards.
+    unsafe fn deallocate_chunks(&self, mut chunk: Option<NonNull<ChunkHeader>>) {
+        while let Some(header) = chunk {
+            // SAFETY: the chunk is live, and was allocated with the layout of its header.
+            unsafe {
+                #[cfg(feature = "zeroize")]
+                {
+                    let (start, end) = bounds(header);
+                    crate::wipe_bytes(start, end.addr().get() - start.addr().get());
+                }
+                let ChunkHeader { prev, layout, .. } = header.read();
+                self.allocator.deallocate(header.cast(), layout);
+                chunk = prev;
+            }
+        }
+    }
+
+    /// Allocates a new chunk big enough for `layout`, and makes it the current chunk.
+    #[cold]
+    fn new_chunk(&self, layout: Layout) -> Result<(), AllocError> {
+        let needed = layout
+            .size()
+            .checked_add(layout.align() - 1)
+            .ok_or(AllocError)?;
+        // SAFETY: the current chunk is live and starts with an initialized header.
+        let last = unsafe { self.chunk.get().as_ref() }.layout.size() - HEADER_SIZE;
+        let capacity = needed.max(self.growth.next_capacity(last));
+
+        let chunk = Self::allocate_chunk(&self.allocator, capacity, Some(self.chunk.get()))?;
+        // SAFETY: the current chunk is live and starts with an initialized header, that is not
+        // borrowed.
+        unsafe { (*self.chunk.get().as_ptr()).used = self.current_used() };
+        // SAFETY: the chunk was just allocated.
+        let (start, end) = unsafe { bounds(chunk) };
+        self.chunk.set(chunk);
+        self.end.set(end);
+        self.current.set(start);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FlexBumpCar {
+    /// Allocates a [`FlexBumpCar`] with a first chunk of `capacity` bytes in the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the size of the chunk overflows [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, Global)
+    }
+}
+
+impl<A: Allocator> Drop for FlexBumpCar<A> {
+    /// Deallocates every chunk of the [`FlexBumpCar`].
+    fn drop(&mut self) {
+        // SAFETY: the chunks are owned by self, which is being dropped.
+        unsafe { self.deallocate_chunks(Some(self.chunk.get())) };
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for &FlexBumpCar<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: current and end are in bounds of the current chunk, and current <= end.
+        let (ptr, new_current) = match unsafe { bump(self.current.get(), self.end.get(), layout) } {
+            Some(placement) => placement,
+            None => {
+                self.new_chunk(layout)?;
+                // SAFETY: the new chunk is big enough for layout, whatever its alignment.
+                unsafe { bump(self.current.get(), self.end.get(), layout) }.ok_or(AllocError)?
+            }
+        };
+        self.current.set(new_current);
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    /// The [`FlexBumpCar`] does not perform deallocation unless it's reset or dropped.
+    unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {}
+}
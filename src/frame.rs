@@ -0,0 +1,110 @@
+use core::alloc::Allocator;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{BumpCar, BumpError};
+
+/// Double-buffered frame arena.
+///
+/// A [`FrameCar`] owns two [`BumpCar`]s. Every frame allocates in the current one, while the data
+/// of the previous frame stays readable in the other one, for exactly one more frame:
+/// [`FrameCar::begin_frame`] swaps them, and resets the new current arena.
+///
+/// References do not outlive [`FrameCar::begin_frame`], which requires a mutable reference.
+/// To carry data over to the next frame, allocate it with [handles](BumpCar::alloc_handle),
+/// and resolve them in [`FrameCar::previous`].
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::FrameCar;
+///
+/// let mut frames = FrameCar::new(256).unwrap();
+/// let mut position = frames.current().alloc_handle([0.0f32; 2]).unwrap();
+///
+/// for _ in 0..10 {
+///     frames.begin_frame();
+///     // SAFETY: the handle was allocated in the previous frame's arena.
+///     let [x, y] = unsafe { *frames.previous().get(position) };
+///     position = frames.current().alloc_handle([x + 1.0, y + 0.5]).unwrap();
+/// }
+///
+/// // SAFETY: the handle was allocated in the current frame's arena.
+/// assert_eq!(unsafe { *frames.current().get(position) }, [10.0, 5.0]);
+/// ```
+pub struct FrameCar<
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    frames: [BumpCar<A>; 2],
+    current: usize,
+    frame: u64,
+}
+
+impl<A: Allocator> FrameCar<A> {
+    /// Allocates a new [`FrameCar`] with two buffers of `capacity` bytes in the given allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, BumpError>
+    where
+        A: Clone,
+    {
+        Ok(Self {
+            frames: [
+                BumpCar::new_in(capacity, allocator.clone())?,
+                BumpCar::new_in(capacity, allocator)?,
+            ],
+            current: 0,
+            frame: 0,
+        })
+    }
+
+    /// Returns the arena of the current frame.
+    pub fn current(&self) -> &BumpCar<A> {
+        &self.frames[self.current]
+    }
+
+    /// Returns the arena of the previous frame.
+    ///
+    /// It holds the data allocated during the previous frame, until the next call to
+    /// [`FrameCar::begin_frame`].
+    pub fn previous(&self) -> &BumpCar<A> {
+        &self.frames[self.current ^ 1]
+    }
+
+    /// Returns the number of frames begun since the [`FrameCar`] was created.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Begins a new frame.
+    ///
+    /// The current arena becomes the previous one, and the arena of the frame before it is reset
+    /// to become the current one.
+    pub fn begin_frame(&mut self) {
+        self.current ^= 1;
+        self.frames[self.current].reset();
+        self.frame += 1;
+    }
+
+    /// Resets both arenas of the [`FrameCar`].
+    pub fn reset(&mut self) {
+        self.frames[0].reset();
+        self.frames[1].reset();
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FrameCar {
+    /// Allocates a [`FrameCar`] with two buffers of `capacity` bytes with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, Global)
+    }
+}
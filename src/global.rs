@@ -0,0 +1,67 @@
+use core::alloc::{Allocator, GlobalAlloc, Layout};
+use core::ptr::null_mut;
+
+use crate::StaticBumpCar;
+
+/// Global allocator over a [`StaticBumpCar`].
+///
+/// It can be registered with `#[global_allocator]` on small targets that only allocate during
+/// their initialization, without any other allocator. Like the [`StaticBumpCar`], its cursor is
+/// updated atomically, so it can be used from multiple threads. Memory is never reclaimed.
+///
+/// # Example
+/// ```rust
+/// use dodgems::BumpGlobal;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: BumpGlobal<{ 1024 * 1024 }> = BumpGlobal::new();
+///
+/// let before = ALLOCATOR.remaining_capacity();
+/// let v = Box::new([0u8; 100]);
+/// assert!(ALLOCATOR.remaining_capacity() <= before - 100);
+/// ```
+pub struct BumpGlobal<const N: usize> {
+    arena: StaticBumpCar<N>,
+}
+
+impl<const N: usize> BumpGlobal<N> {
+    /// Creates a new [`BumpGlobal`] with a capacity of `N` bytes.
+    pub const fn new() -> Self {
+        Self {
+            arena: StaticBumpCar::new(),
+        }
+    }
+
+    /// Returns the capacity of the [`BumpGlobal`].
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the remaining capacity of the [`BumpGlobal`].
+    pub fn remaining_capacity(&self) -> usize {
+        self.arena.remaining_capacity()
+    }
+
+    /// Returns the underlying [`StaticBumpCar`].
+    pub const fn arena(&self) -> &StaticBumpCar<N> {
+        &self.arena
+    }
+}
+
+impl<const N: usize> Default for BumpGlobal<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<const N: usize> GlobalAlloc for BumpGlobal<N> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        match (&self.arena).allocate(layout) {
+            Ok(ptr) => ptr.cast::<u8>().as_ptr(),
+            Err(_) => null_mut(),
+        }
+    }
+
+    /// The [`BumpGlobal`] never deallocates.
+    unsafe fn dealloc(&self, _: *mut u8, _: Layout) {}
+}
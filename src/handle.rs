@@ -0,0 +1,360 @@
+use core::alloc::{Allocator, Layout};
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+use crate::{BumpCar, BumpError, BumpErrorKind};
+
+/// Offset of a `T` allocated in a [`BumpCar`], relative to the start of its buffer.
+///
+/// Unlike a reference, a handle stays meaningful if the buffer is copied elsewhere (e.g. written
+/// to disk and read back), and can be stored in other arena allocations.
+///
+/// A handle is resolved to a reference with [`BumpCar::get`] or [`BumpCar::get_mut`].
+#[repr(transparent)]
+pub struct Handle<T> {
+    offset: u32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// Offset and length of a `[T]` allocated in a [`BumpCar`], relative to the start of its buffer.
+///
+/// A slice handle is resolved to a slice with [`BumpCar::get_slice`]
+/// or [`BumpCar::get_slice_mut`].
+pub struct SliceHandle<T> {
+    offset: u32,
+    len: u32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// [`Handle`] tagged with the generation of its [`BumpCar`], to detect its use after a reset.
+///
+/// In debug builds, the [`BumpCar`] counts its resets, and [`BumpCar::get_tagged`] panics if the
+/// handle was allocated before the last one (unless it lies in the [frozen](BumpCar::freeze)
+/// prefix). In release builds, it is a plain [`Handle`].
+pub struct TaggedHandle<T> {
+    handle: Handle<T>,
+    #[cfg(debug_assertions)]
+    generation: u32,
+}
+
+impl<T> TaggedHandle<T> {
+    /// Returns the untagged handle.
+    pub const fn handle(self) -> Handle<T> {
+        self.handle
+    }
+}
+
+impl<T> Clone for TaggedHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TaggedHandle<T> {}
+
+impl<T> core::fmt::Debug for TaggedHandle<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut s = f.debug_struct("TaggedHandle");
+        s.field("offset", &self.handle.offset);
+        #[cfg(debug_assertions)]
+        s.field("generation", &self.generation);
+        s.finish()
+    }
+}
+
+impl<T> Handle<T> {
+    /// Creates a handle from a raw offset.
+    pub const fn from_offset(offset: u32) -> Self {
+        Self {
+            offset,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the raw offset of the handle.
+    pub const fn offset(self) -> u32 {
+        self.offset
+    }
+}
+
+impl<T> SliceHandle<T> {
+    /// Creates a slice handle from a raw offset and a length.
+    pub const fn from_raw_parts(offset: u32, len: u32) -> Self {
+        Self {
+            offset,
+            len,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the raw offset of the slice handle.
+    pub const fn offset(self) -> u32 {
+        self.offset
+    }
+
+    /// Returns the number of elements in the slice.
+    #[allow(clippy::len_without_is_empty)]
+    pub const fn len(self) -> u32 {
+        self.len
+    }
+}
+
+macro_rules! impl_handle_traits {
+    ($name:ident, $($field:ident),*) => {
+        impl<T> Clone for $name<T> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<T> Copy for $name<T> {}
+
+        impl<T> PartialEq for $name<T> {
+            fn eq(&self, other: &Self) -> bool {
+                ($(self.$field),*) == ($(other.$field),*)
+            }
+        }
+
+        impl<T> Eq for $name<T> {}
+
+        impl<T> PartialOrd for $name<T> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<T> Ord for $name<T> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                ($(self.$field),*).cmp(&($(other.$field),*))
+            }
+        }
+
+        impl<T> Hash for $name<T> {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                ($(self.$field),*).hash(state);
+            }
+        }
+
+        impl<T> core::fmt::Debug for $name<T> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    $(.field(stringify!($field), &self.$field))*
+                    .finish()
+            }
+        }
+    };
+}
+
+impl_handle_traits!(Handle, offset);
+impl_handle_traits!(SliceHandle, offset, len);
+
+impl<A: Allocator> BumpCar<A> {
+    /// Returns the offset of `ptr` relative to the start of the buffer, if it fits in a `u32`.
+    ///
+    /// `layout` is the layout of the allocation at `ptr`, reported in the error.
+    fn offset_of(&self, ptr: NonNull<u8>, layout: Layout) -> Result<u32, BumpError> {
+        u32::try_from(ptr.addr().get() - self.start.addr().get())
+            .map_err(|_| BumpError::new(BumpErrorKind::Overflow, Some(layout), 0))
+    }
+
+    /// Returns a pointer to the region starting at `offset` described by `layout`.
+    ///
+    /// # Panics
+    /// This function panics if the region is out of bounds or misaligned.
+    fn resolve(&self, offset: u32, layout: Layout) -> NonNull<u8> {
+        let offset = offset as usize;
+        assert!(
+            offset
+                .checked_add(layout.size())
+                .is_some_and(|end| end <= self.capacity()),
+            "handle out of bounds"
+        );
+        // SAFETY: offset <= capacity
+        let ptr = unsafe { self.start.add(offset) };
+        assert!(
+            ptr.addr().get() & (layout.align() - 1) == 0,
+            "misaligned handle"
+        );
+        ptr
+    }
+
+    /// Allocates `value` in the [`BumpCar`], and returns a handle to it.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left,
+    /// or if the offset of the allocation does not fit in a `u32`.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let handle = bumpcar.alloc_handle(42u64).unwrap();
+    /// // SAFETY: the handle was allocated by this BumpCar, which has not been reset.
+    /// assert_eq!(unsafe { *bumpcar.get(handle) }, 42);
+    /// ```
+    pub fn alloc_handle<T>(&self, value: T) -> Result<Handle<T>, BumpError> {
+        let layout = Layout::new::<T>();
+        let ptr = self.allocate_typed(layout)?.cast::<u8>();
+        let offset = self.offset_of(ptr, layout)?;
+        // SAFETY: the allocation is valid for a T.
+        unsafe { ptr.cast::<T>().write(value) };
+        Ok(Handle::from_offset(offset))
+    }
+
+    /// Copies `values` in the [`BumpCar`], and returns a handle to the slice.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left,
+    /// or if the offset or the length of the slice does not fit in a `u32`.
+    pub fn alloc_slice_handle<T: Copy>(&self, values: &[T]) -> Result<SliceHandle<T>, BumpError> {
+        let layout = Layout::for_value(values);
+        let len = u32::try_from(values.len())
+            .map_err(|_| BumpError::new(BumpErrorKind::Overflow, Some(layout), 0))?;
+        let ptr = self.allocate_typed(layout)?.cast::<u8>();
+        let offset = self.offset_of(ptr, layout)?;
+        // SAFETY: the allocation is valid for values.len() elements, and cannot overlap values.
+        unsafe {
+            ptr.cast::<T>()
+                .copy_from_nonoverlapping(NonNull::from(values).cast(), values.len());
+        }
+        Ok(SliceHandle::from_raw_parts(offset, len))
+    }
+
+    /// Resolves a handle to a reference.
+    ///
+    /// Only the bounds and alignment of the handle are checked, not wether the value is still
+    /// live: a handle must not be resolved after the [`BumpCar`] has been reset, unless it
+    /// lies in the [frozen](BumpCar::freeze) prefix.
+    ///
+    /// # Safety
+    /// `handle` must have been returned by [`BumpCar::alloc_handle`] on this [`BumpCar`]
+    /// (or one with the same contents), and the [`BumpCar`] must not have been reset since
+    /// (or the handle must lie in the frozen prefix).
+    ///
+    /// # Panics
+    /// This function panics if the handle is out of bounds or misaligned.
+    pub unsafe fn get<T>(&self, handle: Handle<T>) -> &T {
+        let ptr = self.resolve(handle.offset, Layout::new::<T>());
+        // SAFETY: the handle points to a live and initialized T.
+        unsafe { ptr.cast::<T>().as_ref() }
+    }
+
+    /// Resolves a handle to a mutable reference.
+    ///
+    /// # Safety
+    /// Same as [`BumpCar::get`].
+    ///
+    /// # Panics
+    /// This function panics if the handle is out of bounds or misaligned.
+    pub unsafe fn get_mut<T>(&mut self, handle: Handle<T>) -> &mut T {
+        let ptr = self.resolve(handle.offset, Layout::new::<T>());
+        // SAFETY: the handle points to a live and initialized T,
+        // and no other reference into the buffer can exist.
+        unsafe { ptr.cast::<T>().as_mut() }
+    }
+
+    /// Resolves a slice handle to a slice.
+    ///
+    /// # Safety
+    /// `handle` must have been returned by [`BumpCar::alloc_slice_handle`] on this [`BumpCar`]
+    /// (or one with the same contents), and the [`BumpCar`] must not have been reset since
+    /// (or the handle must lie in the frozen prefix).
+    ///
+    /// # Panics
+    /// This function panics if the handle is out of bounds or misaligned.
+    pub unsafe fn get_slice<T>(&self, handle: SliceHandle<T>) -> &[T] {
+        let layout = Layout::array::<T>(handle.len as usize).expect("invalid slice handle");
+        let ptr = self.resolve(handle.offset, layout);
+        // SAFETY: the handle points to live and initialized elements.
+        unsafe { core::slice::from_raw_parts(ptr.cast::<T>().as_ptr(), handle.len as usize) }
+    }
+
+    /// Resolves a slice handle to a mutable slice.
+    ///
+    /// # Safety
+    /// Same as [`BumpCar::get_slice`].
+    ///
+    /// # Panics
+    /// This function panics if the handle is out of bounds or misaligned.
+    pub unsafe fn get_slice_mut<T>(&mut self, handle: SliceHandle<T>) -> &mut [T] {
+        let layout = Layout::array::<T>(handle.len as usize).expect("invalid slice handle");
+        let ptr = self.resolve(handle.offset, layout);
+        // SAFETY: the handle points to live and initialized elements,
+        // and no other reference into the buffer can exist.
+        unsafe { core::slice::from_raw_parts_mut(ptr.cast::<T>().as_ptr(), handle.len as usize) }
+    }
+
+    /// Allocates `value` in the [`BumpCar`], and returns a handle to it, tagged with the
+    /// current generation.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left,
+    /// or if the offset of the allocation does not fit in a `u32`.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let handle = bumpcar.alloc_tagged(42u64).unwrap();
+    /// // SAFETY: the handle was allocated by this BumpCar. Using it after a reset would
+    /// // panic in debug builds.
+    /// assert_eq!(unsafe { *bumpcar.get_tagged(handle) }, 42);
+    /// ```
+    pub fn alloc_tagged<T>(&self, value: T) -> Result<TaggedHandle<T>, BumpError> {
+        Ok(TaggedHandle {
+            handle: self.alloc_handle(value)?,
+            #[cfg(debug_assertions)]
+            generation: self.generation.get(),
+        })
+    }
+
+    /// Checks that a tagged handle of a `T` was allocated in the current generation,
+    /// or lies in the frozen prefix.
+    ///
+    /// # Panics
+    /// This function panics in debug builds if the check fails.
+    #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+    fn check_generation<T>(&self, handle: TaggedHandle<T>) {
+        #[cfg(debug_assertions)]
+        assert!(
+            handle.generation == self.generation.get()
+                || handle.handle.offset as usize + size_of::<T>() <= self.floor,
+            "use of handle after BumpCar reset"
+        );
+    }
+
+    /// Resolves a tagged handle to a reference.
+    ///
+    /// # Safety
+    /// Same as [`BumpCar::get`]. In debug builds, a use after reset is detected and panics
+    /// instead of causing undefined behavior.
+    ///
+    /// # Panics
+    /// This function panics if the handle is out of bounds or misaligned, or in debug builds
+    /// if the [`BumpCar`] has been reset since the handle was allocated.
+    pub unsafe fn get_tagged<T>(&self, handle: TaggedHandle<T>) -> &T {
+        self.check_generation(handle);
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.get(handle.handle) }
+    }
+
+    /// Resolves a tagged handle to a mutable reference.
+    ///
+    /// # Safety
+    /// Same as [`BumpCar::get_tagged`].
+    ///
+    /// # Panics
+    /// This function panics if the handle is out of bounds or misaligned, or in debug builds
+    /// if the [`BumpCar`] has been reset since the handle was allocated.
+    pub unsafe fn get_tagged_mut<T>(&mut self, handle: TaggedHandle<T>) -> &mut T {
+        self.check_generation(handle);
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.get_mut(handle.handle) }
+    }
+}
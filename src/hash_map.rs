@@ -0,0 +1,677 @@
+use core::alloc::{Allocator, Layout};
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::typed::expect_capacity;
+use crate::{BumpCar, BumpError, BumpErrorKind};
+
+/// Fast, non-cryptographic hasher used by default by [`BumpHashMap`] and [`BumpHashSet`].
+///
+/// It mixes the hashed words with a rotation and a multiplication, like the hasher of the Rust
+/// compiler. It is not resistant to collision attacks: maps with keys chosen by an adversary
+/// should use a keyed hasher instead, such as the `RandomState` of the standard library.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BumpHasher {
+    hash: u64,
+}
+
+impl BumpHasher {
+    /// Mixes a word into the hash.
+    #[inline]
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+}
+
+impl Hasher for BumpHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            // SAFETY: the chunk is exactly 8 bytes long.
+            self.add(u64::from_le_bytes(unsafe {
+                chunk.try_into().unwrap_unchecked()
+            }));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(u64::from(i));
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add(u64::from(i));
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(u64::from(i));
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Builder of the default hasher of [`BumpHashMap`] and [`BumpHashSet`].
+pub type BumpBuildHasher = BuildHasherDefault<BumpHasher>;
+
+/// Hash map with open addressing, whose table is allocated in a [`BumpCar`].
+///
+/// Lookups probe the table linearly from the slot given by the hash of the key. When the table
+/// is three quarters full, a table twice as large is allocated in the arena, and the old one is
+/// returned to the arena if it was its last allocation, or abandoned until the arena is reset.
+/// Symbol tables and other per-frame lookup structures then never touch the heap.
+///
+/// # Example
+/// ```rust
+/// use dodgems::{BumpCar, BumpHashMap};
+///
+/// let bumpcar = BumpCar::new(4096).unwrap();
+/// let mut scopes = BumpHashMap::new_in(&bumpcar);
+/// scopes.insert("x", 1);
+/// scopes.insert("y", 2);
+/// *scopes.get_mut("x").unwrap() += 10;
+///
+/// assert_eq!(scopes.get("x"), Some(&11));
+/// assert_eq!(scopes.remove("y"), Some(2));
+/// assert_eq!(scopes.len(), 1);
+/// ```
+pub struct BumpHashMap<
+    'bump,
+    K,
+    V,
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+    S = BumpBuildHasher,
+> {
+    bumpcar: &'bump BumpCar<A>,
+    slots: NonNull<Option<(K, V)>>,
+    /// Number of slots of the table, a power of two or 0.
+    buckets: usize,
+    len: usize,
+    hasher: S,
+    _marker: PhantomData<(K, V)>,
+}
+
+impl<'bump, K, V, A: Allocator> BumpHashMap<'bump, K, V, A> {
+    /// Creates a new, empty [`BumpHashMap`] in `bumpcar`, without allocating.
+    pub fn new_in(bumpcar: &'bump BumpCar<A>) -> Self {
+        Self::with_hasher_in(BumpBuildHasher::default(), bumpcar)
+    }
+}
+
+impl<'bump, K: Hash + Eq, V, A: Allocator> BumpHashMap<'bump, K, V, A> {
+    /// Creates a new, empty [`BumpHashMap`] in `bumpcar`, with room for `capacity` entries.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left.
+    pub fn try_with_capacity_in(
+        capacity: usize,
+        bumpcar: &'bump BumpCar<A>,
+    ) -> Result<Self, BumpError> {
+        let mut map = Self::new_in(bumpcar);
+        map.try_reserve(capacity)?;
+        Ok(map)
+    }
+
+    /// Creates a new, empty [`BumpHashMap`] in `bumpcar`, with room for `capacity` entries.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    #[track_caller]
+    pub fn with_capacity_in(capacity: usize, bumpcar: &'bump BumpCar<A>) -> Self {
+        let mut map = Self::new_in(bumpcar);
+        map.reserve(capacity);
+        map
+    }
+}
+
+impl<'bump, K, V, A: Allocator, S> BumpHashMap<'bump, K, V, A, S> {
+    /// Creates a new, empty [`BumpHashMap`] in `bumpcar` hashing its keys with `hasher`,
+    /// without allocating.
+    pub fn with_hasher_in(hasher: S, bumpcar: &'bump BumpCar<A>) -> Self {
+        Self {
+            bumpcar,
+            slots: NonNull::dangling(),
+            buckets: 0,
+            len: 0,
+            hasher,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of entries in the [`BumpHashMap`].
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the [`BumpHashMap`] contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of entries the [`BumpHashMap`] can hold without reallocating its
+    /// table.
+    pub fn capacity(&self) -> usize {
+        self.buckets / 4 * 3
+    }
+
+    /// Returns the arena of the [`BumpHashMap`].
+    pub fn bumpcar(&self) -> &'bump BumpCar<A> {
+        self.bumpcar
+    }
+
+    /// Returns the hasher builder of the [`BumpHashMap`].
+    pub fn hasher(&self) -> &S {
+        &self.hasher
+    }
+
+    /// Returns the slots of the table.
+    fn slots(&self) -> &[Option<(K, V)>] {
+        // SAFETY: the table holds buckets initialized slots.
+        unsafe { core::slice::from_raw_parts(self.slots.as_ptr(), self.buckets) }
+    }
+
+    /// Returns the slots of the table, mutably.
+    fn slots_mut(&mut self) -> &mut [Option<(K, V)>] {
+        // SAFETY: the table holds buckets initialized slots.
+        unsafe { core::slice::from_raw_parts_mut(self.slots.as_ptr(), self.buckets) }
+    }
+
+    /// Returns an iterator over the entries of the [`BumpHashMap`], in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.slots()
+            .iter()
+            .filter_map(|slot| slot.as_ref().map(|(k, v)| (k, v)))
+    }
+
+    /// Returns an iterator over the entries of the [`BumpHashMap`], in arbitrary order, with
+    /// mutable references to the values.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> + '_ {
+        self.slots_mut()
+            .iter_mut()
+            .filter_map(|slot| slot.as_mut().map(|(k, v)| (&*k, v)))
+    }
+
+    /// Returns an iterator over the keys of the [`BumpHashMap`], in arbitrary order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.iter().map(|(k, _)| k)
+    }
+
+    /// Returns an iterator over the values of the [`BumpHashMap`], in arbitrary order.
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.iter().map(|(_, v)| v)
+    }
+
+    /// Removes every entry of the [`BumpHashMap`], keeping its table.
+    pub fn clear(&mut self) {
+        self.slots_mut().fill_with(|| None);
+        self.len = 0;
+    }
+}
+
+impl<K: Hash + Eq, V, A: Allocator, S: BuildHasher> BumpHashMap<'_, K, V, A, S> {
+    /// Returns the ideal slot of an entry with the given hash.
+    fn bucket(&self, hash: u64) -> usize {
+        let bits = self.buckets.trailing_zeros();
+        if bits == 0 {
+            return 0;
+        }
+        (hash.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> (64 - bits)) as usize
+    }
+
+    /// Returns the slot holding `key`, or the empty slot where it should be inserted.
+    ///
+    /// The table must not be full.
+    fn find<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+    {
+        let mask = self.buckets - 1;
+        let slots = self.slots();
+        let mut i = self.bucket(self.hasher.hash_one(key));
+        loop {
+            match &slots[i] {
+                Some((k, _)) if k.borrow() == key => return Ok(i),
+                Some(_) => i = (i + 1) & mask,
+                None => return Err(i),
+            }
+        }
+    }
+
+    /// Returns the slot holding `key`, if any.
+    fn position<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        if self.len == 0 {
+            return None;
+        }
+        self.find(key).ok()
+    }
+
+    /// Ensures that at least `additional` more entries can be inserted without reallocating
+    /// the table.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), BumpError> {
+        let overflow = BumpError::new(BumpErrorKind::Overflow, None, 0);
+        let needed = self.len.checked_add(additional).ok_or(overflow)?;
+        if needed <= self.capacity() {
+            return Ok(());
+        }
+        let buckets = needed
+            .checked_add(needed / 3 + 1)
+            .and_then(usize::checked_next_power_of_two)
+            .ok_or(overflow)?
+            .max(8);
+        self.try_resize(buckets)
+    }
+
+    /// Ensures that at least `additional` more entries can be inserted without reallocating
+    /// the table.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    #[track_caller]
+    pub fn reserve(&mut self, additional: usize) {
+        expect_capacity(self.try_reserve(additional));
+    }
+
+    /// Moves the entries into a new table of `buckets` slots.
+    fn try_resize(&mut self, buckets: usize) -> Result<(), BumpError> {
+        let layout = Layout::array::<Option<(K, V)>>(buckets)
+            .map_err(|_| BumpError::new(BumpErrorKind::Overflow, None, 0))?;
+        let slots = self
+            .bumpcar
+            .allocate_typed(layout)?
+            .cast::<Option<(K, V)>>();
+        for i in 0..buckets {
+            // SAFETY: the table is valid for buckets slots.
+            unsafe { slots.add(i).write(None) };
+        }
+        let (old_slots, old_buckets) = (self.slots, self.buckets);
+        self.slots = slots;
+        self.buckets = buckets;
+        for i in 0..old_buckets {
+            // SAFETY: the old table holds old_buckets initialized slots, each read once before
+            // it is abandoned.
+            if let Some((k, v)) = unsafe { old_slots.add(i).read() } {
+                // The new table has room for every entry. The keys are distinct, unless one was
+                // modified after its insertion: the duplicate is then dropped.
+                match self.find(&k) {
+                    Err(i) => self.slots_mut()[i] = Some((k, v)),
+                    Ok(_) => self.len -= 1,
+                }
+            }
+        }
+        if old_buckets != 0 {
+            // SAFETY: the old table was allocated in the arena with this layout, and its entries
+            // have all been moved out.
+            unsafe {
+                self.bumpcar.deallocate(
+                    old_slots.cast(),
+                    Layout::array::<Option<(K, V)>>(old_buckets).unwrap_unchecked(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Inserts an entry in the [`BumpHashMap`], and returns the previous value of `key`.
+    ///
+    /// # Errors
+    /// This function returns the entry back if the arena does not have enough capacity left
+    /// to grow the table.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        match self.try_make_room(&key) {
+            Ok(existing) => Ok(self.insert_in_room(existing, key, value)),
+            Err(_) => Err((key, value)),
+        }
+    }
+
+    /// Inserts an entry in the [`BumpHashMap`], and returns the previous value of `key`.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left to grow the table.
+    #[track_caller]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let existing = expect_capacity(self.try_make_room(&key));
+        self.insert_in_room(existing, key, value)
+    }
+
+    /// Returns the slot of `key` if it is present, or grows the table to make room for it.
+    fn try_make_room(&mut self, key: &K) -> Result<Option<usize>, BumpError> {
+        if let Some(i) = self.position(key) {
+            return Ok(Some(i));
+        }
+        self.try_reserve(1)?;
+        Ok(None)
+    }
+
+    /// Inserts an entry in the table, that has room for it or holds `key` in the `existing`
+    /// slot.
+    fn insert_in_room(&mut self, existing: Option<usize>, key: K, value: V) -> Option<V> {
+        if let Some(i) = existing {
+            let (_, old) = self.slots_mut()[i].as_mut().unwrap();
+            return Some(core::mem::replace(old, value));
+        }
+        // The table has room for one more entry. The key can only be found now if its hash or
+        // equality is inconsistent.
+        let (Ok(i) | Err(i)) = self.find(&key);
+        let old = self.slots_mut()[i].replace((key, value));
+        self.len += usize::from(old.is_none());
+        old.map(|(_, v)| v)
+    }
+
+    /// Returns a reference to the value of `key`.
+    pub fn get<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+
+    /// Returns references to the stored key equal to `key`, and to its value.
+    pub fn get_key_value<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        let i = self.position(key)?;
+        self.slots()[i].as_ref().map(|(k, v)| (k, v))
+    }
+
+    /// Returns a mutable reference to the value of `key`.
+    pub fn get_mut<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        let i = self.position(key)?;
+        self.slots_mut()[i].as_mut().map(|(_, v)| v)
+    }
+
+    /// Returns `true` if the [`BumpHashMap`] contains `key`.
+    pub fn contains_key<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.position(key).is_some()
+    }
+
+    /// Removes `key` from the [`BumpHashMap`], and returns its value.
+    pub fn remove<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes `key` from the [`BumpHashMap`], and returns the stored key and its value.
+    pub fn remove_entry<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        let mut hole = self.position(key)?;
+        let entry = self.slots_mut()[hole].take();
+        self.len -= 1;
+
+        // Shifts the following entries of the probe sequence back, so that no lookup stops at
+        // the hole before reaching them.
+        let mask = self.buckets - 1;
+        let mut i = hole;
+        loop {
+            i = (i + 1) & mask;
+            let Some((k, _)) = &self.slots()[i] else {
+                break;
+            };
+            let ideal = self.bucket(self.hasher.hash_one(k));
+            // The entry can move to the hole if its ideal slot is not between the hole and it.
+            if (i.wrapping_sub(ideal) & mask) >= (i.wrapping_sub(hole) & mask) {
+                let moved = self.slots_mut()[i].take();
+                self.slots_mut()[hole] = moved;
+                hole = i;
+            }
+        }
+        entry
+    }
+}
+
+impl<K, V, A: Allocator, S> Drop for BumpHashMap<'_, K, V, A, S> {
+    /// Drops the entries, and returns the table to the arena if it is the last allocation.
+    fn drop(&mut self) {
+        if self.buckets == 0 {
+            return;
+        }
+        // SAFETY: the table holds buckets initialized slots, and was allocated in the arena with
+        // this layout.
+        unsafe {
+            NonNull::slice_from_raw_parts(self.slots, self.buckets).drop_in_place();
+            self.bumpcar.deallocate(
+                self.slots.cast(),
+                Layout::array::<Option<(K, V)>>(self.buckets).unwrap_unchecked(),
+            );
+        }
+    }
+}
+
+impl<K: Hash + Eq, V, A: Allocator, S: BuildHasher> Extend<(K, V)> for BumpHashMap<'_, K, V, A, S> {
+    /// Inserts every entry of `iter`.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, A: Allocator, S> fmt::Debug for BumpHashMap<'_, K, V, A, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Hash set with open addressing, whose table is allocated in a [`BumpCar`].
+///
+/// It is a [`BumpHashMap`] with no values, and grows the same way.
+///
+/// # Example
+/// ```rust
+/// use dodgems::{BumpCar, BumpHashSet};
+///
+/// let bumpcar = BumpCar::new(4096).unwrap();
+/// let mut seen = BumpHashSet::new_in(&bumpcar);
+/// let words = "the cat saw the dog".split(' ');
+/// let repeated: Vec<_> = words.filter(|w| !seen.insert(*w)).collect();
+///
+/// assert_eq!(repeated, ["the"]);
+/// assert_eq!(seen.len(), 4);
+/// ```
+pub struct BumpHashSet<
+    'bump,
+    T,
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+    S = BumpBuildHasher,
+> {
+    map: BumpHashMap<'bump, T, (), A, S>,
+}
+
+impl<'bump, T, A: Allocator> BumpHashSet<'bump, T, A> {
+    /// Creates a new, empty [`BumpHashSet`] in `bumpcar`, without allocating.
+    pub fn new_in(bumpcar: &'bump BumpCar<A>) -> Self {
+        Self {
+            map: BumpHashMap::new_in(bumpcar),
+        }
+    }
+}
+
+impl<'bump, T: Hash + Eq, A: Allocator> BumpHashSet<'bump, T, A> {
+    /// Creates a new, empty [`BumpHashSet`] in `bumpcar`, with room for `capacity` values.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left.
+    pub fn try_with_capacity_in(
+        capacity: usize,
+        bumpcar: &'bump BumpCar<A>,
+    ) -> Result<Self, BumpError> {
+        Ok(Self {
+            map: BumpHashMap::try_with_capacity_in(capacity, bumpcar)?,
+        })
+    }
+
+    /// Creates a new, empty [`BumpHashSet`] in `bumpcar`, with room for `capacity` values.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    #[track_caller]
+    pub fn with_capacity_in(capacity: usize, bumpcar: &'bump BumpCar<A>) -> Self {
+        Self {
+            map: BumpHashMap::with_capacity_in(capacity, bumpcar),
+        }
+    }
+}
+
+impl<'bump, T, A: Allocator, S> BumpHashSet<'bump, T, A, S> {
+    /// Creates a new, empty [`BumpHashSet`] in `bumpcar` hashing its values with `hasher`,
+    /// without allocating.
+    pub fn with_hasher_in(hasher: S, bumpcar: &'bump BumpCar<A>) -> Self {
+        Self {
+            map: BumpHashMap::with_hasher_in(hasher, bumpcar),
+        }
+    }
+
+    /// Returns the number of values in the [`BumpHashSet`].
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the [`BumpHashSet`] contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the number of values the [`BumpHashSet`] can hold without reallocating its
+    /// table.
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Returns the arena of the [`BumpHashSet`].
+    pub fn bumpcar(&self) -> &'bump BumpCar<A> {
+        self.map.bumpcar()
+    }
+
+    /// Returns an iterator over the values of the [`BumpHashSet`], in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.map.keys()
+    }
+
+    /// Removes every value of the [`BumpHashSet`], keeping its table.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+}
+
+impl<T: Hash + Eq, A: Allocator, S: BuildHasher> BumpHashSet<'_, T, A, S> {
+    /// Ensures that at least `additional` more values can be inserted without reallocating
+    /// the table.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), BumpError> {
+        self.map.try_reserve(additional)
+    }
+
+    /// Adds `value` to the [`BumpHashSet`], and returns `true` if it was not already present.
+    ///
+    /// # Errors
+    /// This function returns the value back if the arena does not have enough capacity left to
+    /// grow the table.
+    pub fn try_insert(&mut self, value: T) -> Result<bool, T> {
+        match self.map.try_insert(value, ()) {
+            Ok(old) => Ok(old.is_none()),
+            Err((value, ())) => Err(value),
+        }
+    }
+
+    /// Adds `value` to the [`BumpHashSet`], and returns `true` if it was not already present.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left to grow the table.
+    #[track_caller]
+    pub fn insert(&mut self, value: T) -> bool {
+        self.map.insert(value, ()).is_none()
+    }
+
+    /// Returns `true` if the [`BumpHashSet`] contains `value`.
+    pub fn contains<Q: Hash + Eq + ?Sized>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Returns a reference to the stored value equal to `value`.
+    pub fn get<Q: Hash + Eq + ?Sized>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        self.map.get_key_value(value).map(|(k, _)| k)
+    }
+
+    /// Removes `value` from the [`BumpHashSet`], and returns `true` if it was present.
+    pub fn remove<Q: Hash + Eq + ?Sized>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    /// Removes `value` from the [`BumpHashSet`], and returns the stored value.
+    pub fn take<Q: Hash + Eq + ?Sized>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+    {
+        self.map.remove_entry(value).map(|(k, ())| k)
+    }
+}
+
+impl<T: Hash + Eq, A: Allocator, S: BuildHasher> Extend<T> for BumpHashSet<'_, T, A, S> {
+    /// Adds every value of `iter`.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.map.extend(iter.into_iter().map(|value| (value, ())));
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator, S> fmt::Debug for BumpHashSet<'_, T, A, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
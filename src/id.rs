@@ -0,0 +1,363 @@
+use core::alloc::{Allocator, Layout};
+use core::cell::Cell;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut};
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::typed::expect_capacity;
+use crate::{BumpError, BumpErrorKind};
+
+/// Index of a value allocated in an [`IdCar`].
+///
+/// It is a single `u32`: the low bits hold the index of the value, and the high bits, if the
+/// [`IdCar`] has any generation bits, the generation of the arena when the value was allocated.
+/// It is resolved to a reference with [`IdCar::get`] or [`IdCar::get_mut`], or by indexing the
+/// [`IdCar`].
+#[repr(transparent)]
+pub struct Id<T> {
+    raw: u32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Id<T> {
+    /// Creates an id from its raw value, as returned by [`Id::to_raw`].
+    pub const fn from_raw(raw: u32) -> Self {
+        Self {
+            raw,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the raw value of the id.
+    pub const fn to_raw(self) -> u32 {
+        self.raw
+    }
+}
+
+impl<T> Clone for Id<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Id<T> {}
+
+impl<T> PartialEq for Id<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl<T> Eq for Id<T> {}
+
+impl<T> PartialOrd for Id<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Id<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.raw.cmp(&other.raw)
+    }
+}
+
+impl<T> Hash for Id<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.raw.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Id<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Id").field(&self.raw).finish()
+    }
+}
+
+/// Typed arena handing out [`Id`]s instead of references.
+///
+/// Values are stored one after the other in a buffer of fixed capacity, and referred to by their
+/// index. Ids are 32 bits wide, which is denser than pointers, can be serialized, and do not
+/// borrow the arena: entity systems and graphs can store them freely, and resolve them with
+/// [`IdCar::get`] when needed.
+///
+/// With [`IdCar::with_generation_bits_in`], the high bits of every id hold the number of resets
+/// of the arena (modulo the number of generations), so that ids allocated before a reset are
+/// detected as stale instead of resolving to a newer value.
+///
+/// Unlike the values moved into a [`BumpCar`](crate::BumpCar), the values of an [`IdCar`] are
+/// dropped when it is reset or dropped.
+///
+/// # Example
+/// ```rust
+/// use dodgems::IdCar;
+///
+/// struct Node {
+///     name: &'static str,
+///     edges: Vec<dodgems::Id<Node>>,
+/// }
+///
+/// let mut nodes = IdCar::with_generation_bits(64, 8).unwrap();
+/// let a = nodes.alloc(Node { name: "a", edges: vec![] });
+/// let b = nodes.alloc(Node { name: "b", edges: vec![a] });
+/// nodes[a].edges.push(b);
+/// assert_eq!(nodes[nodes[a].edges[0]].name, "b");
+///
+/// nodes.reset();
+/// assert!(nodes.get(a).is_none());
+/// ```
+pub struct IdCar<
+    T,
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    start: NonNull<T>,
+    capacity: usize,
+    len: Cell<usize>,
+    generation_bits: u32,
+    generation: u32,
+    allocator: A,
+    _marker: PhantomData<T>,
+}
+
+// SAFETY: the values and the buffer are owned by the IdCar.
+unsafe impl<T: Send, A: Allocator + Send> Send for IdCar<T, A> {}
+
+impl<T, A: Allocator> IdCar<T, A> {
+    /// Allocates a new [`IdCar`] of `capacity` values in the given allocator, whose ids have no
+    /// generation bits.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`u32::MAX`] + 1, if the
+    /// size of the buffer is greater than [`isize::MAX`], or if the underlying allocator returns
+    /// an error.
+    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        Self::with_generation_bits_in(capacity, 0, allocator)
+    }
+
+    /// Allocates a new [`IdCar`] of `capacity` values in the given allocator, whose ids keep
+    /// `generation_bits` high bits for the generation of the arena.
+    ///
+    /// # Errors
+    /// This function returns an error if `generation_bits` is greater than 31, if the capacity
+    /// does not fit in the remaining bits of the ids, if the size of the buffer is greater than
+    /// [`isize::MAX`], or if the underlying allocator returns an error.
+    pub fn with_generation_bits_in(
+        capacity: usize,
+        generation_bits: u32,
+        allocator: A,
+    ) -> Result<Self, BumpError> {
+        let overflow = BumpError::new(BumpErrorKind::Overflow, None, 0);
+        if generation_bits > 31 || (capacity as u64) > 1u64 << (32 - generation_bits) {
+            return Err(overflow);
+        }
+        let layout = Layout::array::<T>(capacity).map_err(|_| overflow)?;
+        let start = allocator
+            .allocate(layout)
+            .map_err(|_| BumpError::new(BumpErrorKind::AllocatorFailed, Some(layout), 0))?
+            .cast::<T>();
+        Ok(Self {
+            start,
+            capacity,
+            len: Cell::new(0),
+            generation_bits,
+            generation: 0,
+            allocator,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the number of values the [`IdCar`] can hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of values in the [`IdCar`].
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// Returns `true` if the [`IdCar`] holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the generation of the [`IdCar`], stored in the ids allocated until the next
+    /// reset. It is always 0 without generation bits.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Returns the number of bits of the index in the ids.
+    fn index_bits(&self) -> u32 {
+        32 - self.generation_bits
+    }
+
+    /// Returns the index of the value of `id`, if it belongs to the current generation and to
+    /// the allocated values.
+    fn index(&self, id: Id<T>) -> Option<usize> {
+        let bits = self.index_bits();
+        let generation = id.raw.checked_shr(bits).unwrap_or(0);
+        let index = (u64::from(id.raw) & ((1 << bits) - 1)) as usize;
+        (generation == self.generation && index < self.len()).then_some(index)
+    }
+
+    /// Moves `value` into the [`IdCar`], and returns its id.
+    ///
+    /// # Errors
+    /// This function returns the value back if the [`IdCar`] is full.
+    pub fn try_alloc(&self, value: T) -> Result<Id<T>, T> {
+        let index = self.len();
+        if index == self.capacity {
+            return Err(value);
+        }
+        // SAFETY: index < capacity, and the slot is not handed out yet.
+        unsafe { self.start.add(index).write(value) };
+        self.len.set(index + 1);
+        let generation = self.generation.checked_shl(self.index_bits()).unwrap_or(0);
+        Ok(Id::from_raw(generation | index as u32))
+    }
+
+    /// Moves `value` into the [`IdCar`], and returns its id.
+    ///
+    /// # Panics
+    /// This function panics if the [`IdCar`] is full.
+    #[track_caller]
+    pub fn alloc(&self, value: T) -> Id<T> {
+        expect_capacity(self.try_alloc(value).map_err(|_| {
+            BumpError::new(BumpErrorKind::CapacityExceeded, Some(Layout::new::<T>()), 0)
+        }))
+    }
+
+    /// Returns a reference to the value of `id`, or `None` if the id is stale or out of bounds.
+    pub fn get(&self, id: Id<T>) -> Option<&T> {
+        let index = self.index(id)?;
+        // SAFETY: the value at index is initialized, and only borrowed mutably through &mut self.
+        Some(unsafe { self.start.add(index).as_ref() })
+    }
+
+    /// Returns a mutable reference to the value of `id`, or `None` if the id is stale or out of
+    /// bounds.
+    pub fn get_mut(&mut self, id: Id<T>) -> Option<&mut T> {
+        let index = self.index(id)?;
+        // SAFETY: the value at index is initialized, and self is borrowed mutably.
+        Some(unsafe { self.start.add(index).as_mut() })
+    }
+
+    /// Returns the values of the [`IdCar`], in allocation order.
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the first len values are initialized.
+        unsafe { core::slice::from_raw_parts(self.start.as_ptr(), self.len()) }
+    }
+
+    /// Returns the values of the [`IdCar`], in allocation order.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the first len values are initialized, and self is borrowed mutably.
+        unsafe { core::slice::from_raw_parts_mut(self.start.as_ptr(), self.len()) }
+    }
+
+    /// Returns an iterator over the ids and values of the [`IdCar`], in allocation order.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (Id<T>, &T)> + '_ {
+        let generation = self.generation.checked_shl(self.index_bits()).unwrap_or(0);
+        self.as_slice()
+            .iter()
+            .enumerate()
+            .map(move |(i, value)| (Id::from_raw(generation | i as u32), value))
+    }
+
+    /// Drops every value, and starts a new generation.
+    ///
+    /// This requires a mutable reference, so that any previous references into the [`IdCar`]
+    /// are invalidated by the borrow checker. With generation bits, the previous ids are
+    /// detected as stale until the generation wraps around.
+    pub fn reset(&mut self) {
+        self.drop_values();
+        let generations = 1u64 << self.generation_bits;
+        self.generation = ((u64::from(self.generation) + 1) % generations) as u32;
+    }
+
+    /// Drops every value.
+    fn drop_values(&mut self) {
+        let values: *mut [T] = self.as_mut_slice();
+        self.len.set(0);
+        // SAFETY: the values are initialized, and not accessible anymore.
+        unsafe { values.drop_in_place() };
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> IdCar<T> {
+    /// Allocates an [`IdCar`] of `capacity` values with the Global allocator, whose ids have no
+    /// generation bits.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`u32::MAX`] + 1, if the
+    /// size of the buffer is greater than [`isize::MAX`], or if the global allocator returns
+    /// an error.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, Global)
+    }
+
+    /// Allocates an [`IdCar`] of `capacity` values with the Global allocator, whose ids keep
+    /// `generation_bits` high bits for the generation of the arena.
+    ///
+    /// # Errors
+    /// This function returns an error if `generation_bits` is greater than 31, if the capacity
+    /// does not fit in the remaining bits of the ids, if the size of the buffer is greater than
+    /// [`isize::MAX`], or if the global allocator returns an error.
+    pub fn with_generation_bits(capacity: usize, generation_bits: u32) -> Result<Self, BumpError> {
+        Self::with_generation_bits_in(capacity, generation_bits, Global)
+    }
+}
+
+impl<T, A: Allocator> Drop for IdCar<T, A> {
+    /// Drops the values, and deallocates the [`IdCar`]'s buffer.
+    fn drop(&mut self) {
+        self.drop_values();
+        // SAFETY: start was allocated with self.allocator, with this layout.
+        unsafe {
+            self.allocator.deallocate(
+                self.start.cast(),
+                Layout::array::<T>(self.capacity).unwrap_unchecked(),
+            );
+        }
+    }
+}
+
+impl<T, A: Allocator> Index<Id<T>> for IdCar<T, A> {
+    type Output = T;
+
+    /// # Panics
+    /// This function panics if the id is stale or out of bounds.
+    #[track_caller]
+    fn index(&self, id: Id<T>) -> &T {
+        match self.get(id) {
+            Some(value) => value,
+            None => panic!("{id:?} is stale or out of bounds of the IdCar"),
+        }
+    }
+}
+
+impl<T, A: Allocator> IndexMut<Id<T>> for IdCar<T, A> {
+    /// # Panics
+    /// This function panics if the id is stale or out of bounds.
+    #[track_caller]
+    fn index_mut(&mut self, id: Id<T>) -> &mut T {
+        match self.get_mut(id) {
+            Some(value) => value,
+            None => panic!("{id:?} is stale or out of bounds of the IdCar"),
+        }
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for IdCar<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,177 @@
+use alloc::alloc::Global;
+use alloc::vec::Vec;
+use core::alloc::{Allocator, Layout};
+use core::ptr::NonNull;
+
+use crate::{BumpCar, BumpError, BumpErrorKind};
+
+/// String interner storing deduplicated strings in a [`BumpCar`].
+///
+/// Each distinct string is copied into the arena once; interning it again returns
+/// the same reference, or the same [`Symbol`]. The index is a hash table allocated in the
+/// global allocator.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::{BumpCar, BumpInterner};
+///
+/// let bumpcar = BumpCar::new(256).unwrap();
+/// let mut interner = BumpInterner::new(&bumpcar);
+///
+/// let a = interner.intern("hello").unwrap();
+/// let b = interner.intern("hello").unwrap();
+/// assert!(core::ptr::eq(a, b));
+/// assert_eq!(bumpcar.remaining_capacity(), 251);
+/// ```
+pub struct BumpInterner<'bump, A: Allocator = Global> {
+    bumpcar: &'bump BumpCar<A>,
+    table: Vec<Option<Symbol>>,
+    strings: Vec<&'bump str>,
+}
+
+/// Small identifier of a string interned in a [`BumpInterner`].
+///
+/// Symbols are numbered in interning order, starting from 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Returns the index of the [`Symbol`].
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// FNV-1a hash of a string.
+fn hash(s: &str) -> usize {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for &b in s.as_bytes() {
+        h ^= u64::from(b);
+        h = h.wrapping_mul(0x0100_0000_01b3);
+    }
+    h as usize
+}
+
+impl<'bump, A: Allocator> BumpInterner<'bump, A> {
+    /// Creates a new, empty interner allocating its strings in `bumpcar`.
+    pub fn new(bumpcar: &'bump BumpCar<A>) -> Self {
+        Self {
+            bumpcar,
+            table: Vec::new(),
+            strings: Vec::new(),
+        }
+    }
+
+    /// Returns the number of distinct strings interned.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns `true` if no string has been interned yet.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Returns the index of the slot holding `s`, or of the empty slot where it should be inserted.
+    ///
+    /// The table must not be empty.
+    fn slot(&self, s: &str) -> usize {
+        let mask = self.table.len() - 1;
+        let mut i = hash(s) & mask;
+        loop {
+            match self.table[i] {
+                Some(existing) if self.strings[existing.index()] != s => i = (i + 1) & mask,
+                _ => return i,
+            }
+        }
+    }
+
+    /// Returns the [`Symbol`] of `s`, if it has been interned.
+    pub fn get_symbol(&self, s: &str) -> Option<Symbol> {
+        if self.table.is_empty() {
+            return None;
+        }
+        self.table[self.slot(s)]
+    }
+
+    /// Returns the interned copy of `s`, if any.
+    pub fn get(&self, s: &str) -> Option<&'bump str> {
+        self.get_symbol(s).map(|symbol| self.resolve(symbol))
+    }
+
+    /// Returns the string interned as `symbol`.
+    ///
+    /// # Panics
+    /// This function panics if `symbol` was not created by this interner.
+    pub fn resolve(&self, symbol: Symbol) -> &'bump str {
+        self.strings[symbol.index()]
+    }
+
+    /// Interns `s`, copying it into the arena if it has not been seen before.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left
+    /// to store a new string.
+    pub fn intern(&mut self, s: &str) -> Result<&'bump str, BumpError> {
+        let symbol = self.intern_symbol(s)?;
+        Ok(self.resolve(symbol))
+    }
+
+    /// Interns `s`, copying it into the arena if it has not been seen before, and returns
+    /// its [`Symbol`].
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left
+    /// to store a new string, or if more than [`u32::MAX`] strings have been interned.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::{BumpCar, BumpInterner};
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let mut interner = BumpInterner::new(&bumpcar);
+    ///
+    /// let a = interner.intern_symbol("a").unwrap();
+    /// let b = interner.intern_symbol("b").unwrap();
+    /// assert_ne!(a, b);
+    /// assert_eq!(interner.intern_symbol("a").unwrap(), a);
+    /// assert_eq!(interner.resolve(b), "b");
+    /// ```
+    pub fn intern_symbol(&mut self, s: &str) -> Result<Symbol, BumpError> {
+        if let Some(existing) = self.get_symbol(s) {
+            return Ok(existing);
+        }
+        let layout = Layout::for_value(s);
+        let symbol = Symbol(
+            u32::try_from(self.strings.len())
+                .map_err(|_| BumpError::new(BumpErrorKind::Overflow, Some(layout), 0))?,
+        );
+
+        let ptr = self.bumpcar.allocate_typed(layout)?.cast::<u8>();
+        // SAFETY: the allocation is valid for s.len() bytes, and cannot overlap s.
+        let copy = unsafe {
+            ptr.copy_from_nonoverlapping(NonNull::from(s.as_bytes()).cast(), s.len());
+            core::str::from_utf8_unchecked(core::slice::from_raw_parts(ptr.as_ptr(), s.len()))
+        };
+
+        if (self.strings.len() + 1) * 4 > self.table.len() * 3 {
+            self.grow();
+        }
+        let slot = self.slot(copy);
+        self.table[slot] = Some(symbol);
+        self.strings.push(copy);
+        Ok(symbol)
+    }
+
+    /// Doubles the size of the table and rehashes every string.
+    fn grow(&mut self) {
+        let new_len = (self.table.len() * 2).max(16);
+        self.table = alloc::vec![None; new_len];
+        for (i, s) in self.strings.iter().enumerate() {
+            let slot = self.slot(s);
+            self.table[slot] = Some(Symbol(i as u32));
+        }
+    }
+}
@@ -1,6 +1,10 @@
 #![no_std]
 #![feature(allocator_api)]
-#![feature(doc_auto_cfg)]
+#![feature(doc_cfg)]
+#![feature(strict_provenance_lints)]
+#![feature(cfg_sanitize)]
+#![feature(coerce_unsized, unsize)]
+#![deny(fuzzy_provenance_casts, lossy_provenance_casts)]
 //! # Dodgems - A simple bump allocator library
 //!
 //! This crate provides a fast, single-threaded [bump allocator](BumpCar) for use in performance
@@ -33,32 +37,352 @@
 //! # }
 //! ```
 //!
+//! Values can also be moved into the arena directly, without going through the allocator api,
+//! with [`BumpCar::alloc`] and [`BumpCar::alloc_slice_copy`], or with the [`bump_vec!`]
+//! and [`bump_format!`] macros. [`BumpVec`] and [`BumpString`] are growable collections that
+//! live in the arena without relying on the allocator parameter of the standard ones, and
+//! iterators can be collected into them with [`CollectIn::collect_in`]. A [`FixedBumpVec`] never
+//! reallocates, so that the addresses of its elements are stable. [`BumpHashMap`] and
+//! [`BumpHashSet`] keep their open-addressing tables in the arena too, for symbol tables and
+//! per-frame lookups that never touch the heap.
+//!
+//! Values moved into the arena with these methods are never dropped. To store values with
+//! destructors, such as `String`s or file handles, use a [`DropCar`], that drops them when it
+//! is reset, or [`BumpCar::alloc_box`], whose [`BumpBox`] drops its value when it goes out of
+//! scope.
+//!
+//! If the required capacity is not known in advance, [`FlexBumpCar`] allocates new chunks
+//! from the backing allocator when it is full, instead of failing. [`SpillBumpCar`] forwards the
+//! allocations that do not fit to the backing allocator directly.
+//!
+//! To share an arena between threads, use [`SyncBumpCar`], whose cursor is updated atomically,
+//! or [`ShardedBumpCar`], that hands out a private chunk to every thread.
+//!
+//! When objects of a single type need to be freed and reused individually, [`SlotCar`] is a
+//! pool of fixed-size slots with a free list, that fits the same niche as the [`BumpCar`]. When
+//! allocations are freed in reverse order, [`StackCar`] reclaims them as they go. An [`IdCar`]
+//! stores values of a single type and hands out 32-bit [`Id`]s instead of references.
+//!
+//! Without any backing allocator, a [`BumpCar`] can also be built on a borrowed buffer with
+//! [`BumpCar::from_buffer`], or on a static buffer with [`static_bumpcar!`], that can be placed
+//! in a given linker section. On small targets, a [`CompactBumpCar`] stores its cursor and
+//! capacity as `u16` or `u32` offsets, to keep the arena itself within a couple of words.
+//!
 //! Until the `allocator_api` is stable, this crate requires the nightly edition.
 //!
 //! ## Features
 //! The (default) `alloc` feature controls wether the `alloc` standard crate is used.
 //! If you want to use a different allocator and/or do not have a global allocator available,
 //! you can disable it.
+//!
+//! The `std` feature implements [`std::io::Write`] for [`BumpWriter`], and provides
+//! [`read_to_bump`] to read from a [`std::io::Read`] directly into an arena. It also provides
+//! [`BumpCar::alloc_os_str`] and [`BumpCar::alloc_path`], to copy OS strings and paths into an
+//! arena, and the thread-local arena of the [`ambient`] module, installed with [`with_bump!`].
+//!
+//! The `zeroize` feature makes [`BumpCar::reset`] and [`Drop`] overwrite the used memory with
+//! zeros, for use with sensitive data, as well as the resets and destructors of the other arenas.
+//! Only the bytes handed out are wiped: those reclaimed earlier by a deallocation or a shrink are
+//! wiped at that time. It also implements [`zeroize::Zeroize`] for [`BumpCar`], that wipes the
+//! whole capacity, for example before dropping an arena built on a buffer that already held
+//! secrets.
+//!
+//! The `debug-poison` feature makes [`BumpCar::reset`] and [`Drop`] fill the reclaimed memory
+//! with `0xDD` in debug builds, so that dangling pointers read obviously garbled data. It has no
+//! effect in release builds, or when the `zeroize` feature is enabled.
+//!
+//! The `debug-headers` feature provides the [`CheckedCar`] wrapper, that prepends a header to
+//! every allocation, and panics when a deallocation or a resize does not match the pointer and
+//! layout of a live allocation.
+//!
+//! The `leak-check` feature makes the reset and the destructor of [`CountedBumpCar`] panic in
+//! debug builds if some of its allocations are still live. It has no effect in release builds.
+//!
+//! The `unchecked` feature provides [`BumpCar::allocate_unchecked`], that skips the capacity
+//! checks of the allocations validated beforehand, for code size and latency critical builds.
+//!
+//! The `stats` feature makes [`BumpCar`] count its allocations, failures, alignment waste and
+//! resizes, exposed by [`BumpCar::stats`].
+//!
+//! The `profiling` feature makes the typed allocation methods of [`BumpCar`] record their
+//! callers, and aggregates the allocated bytes by callsite in a [`Profile`], returned by
+//! [`BumpCar::profile`], to find out which code uses up the capacity of an arena.
+//!
+//! The `allocator-api2` feature implements [`allocator_api2::alloc::Allocator`] for the arenas,
+//! for use with the [`allocator_api2`] collections, and provides the [`Compat`] adapter
+//! to back an arena with an [`allocator_api2`] allocator.
+//!
+//! The `defmt` feature implements [`defmt::Format`] for the statistics and error types, and
+//! provides the [`DefmtObserver`], that logs allocation failures.
+//!
+//! The `serde` feature provides [`BumpCar::deserialize_in`] and the [`DeserializeIn`] trait, to
+//! deserialize payloads into arena-backed [`BumpVec`]s and [`BumpString`]s, without any heap
+//! allocation.
+//!
+//! The `metrics` feature provides [`BumpCar::publish_metrics`], that reports the usage of an
+//! arena (and its counters, with the `stats` feature) through the [`metrics`] facade, so that
+//! it can be charted by any exporter.
+//!
+//! The `tracing` feature provides the [`TracingCar`], that emits [`tracing`] events for its
+//! allocations.
+//!
+//! The `trace` feature provides the [`TraceCar`], that records the operations made on an
+//! allocator in a compact [`Trace`], to be serialized and replayed against other allocators.
+//!
+//! The `valgrind` feature registers the buffer of every [`BumpCar`] as a Valgrind memory pool,
+//! so that memcheck reports the use of memory reclaimed by a reset, and leaked allocations.
+//!
+//! The `asan` feature poisons the unused part of the buffer of every [`BumpCar`] when the crate
+//! is built with `-Zsanitizer=address`, so that AddressSanitizer reports accesses to memory that
+//! has not been handed out, or has been reclaimed. It has no effect otherwise.
+//!
+//! The `critical-section` feature provides the [`CriticalBumpCar`], a static arena whose cursor
+//! is updated inside a [`critical_section`], to allocate from interrupt handlers.
+//!
+//! The `cpu-local` feature provides the [`CpuLocalBumpCar`] on Linux, a concurrent arena handing
+//! out one chunk per CPU rather than per thread, for thread pools much larger than the machine.
+//!
+//! The `mmap` feature provides the [`Mmap`] allocator on unix systems, to map the buffers of
+//! large arenas directly from the operating system, and the [`ReservedBumpCar`], that commits
+//! the pages of a reserved address range on demand. The `guard-pages` debug feature follows
+//! the buffers mapped by [`Mmap`] with an inaccessible page, to catch out-of-bounds writes.
+//! [`BumpCar::protect`] makes the used pages of a `BumpCar<Mmap>` read-only while a
+//! [`ReadOnlyCar`] view of it exists.
+//!
+//! The `mte` feature provides the [`MteBumpCar`] on AArch64 Linux, whose allocations are tagged
+//! with the Memory Tagging Extension and retagged on reset, so that stale pointers into the
+//! arena fault in hardware.
+//!
+//! The `wasm` feature provides the [`MemoryGrow`] allocator on `wasm32` targets, that claims
+//! linear memory directly with `memory.grow`, and the [`WasmGlobal`] global allocator built
+//! on it, so that a small WebAssembly module does not need a general purpose allocator.
+//!
+//! The `ffi` feature exposes a C interface to the allocator in the [`ffi`] module.
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod advisor;
+mod aligned;
+#[cfg(feature = "std")]
+pub mod ambient;
+#[cfg(feature = "allocator-api2")]
+mod api2;
+#[cfg(all(feature = "asan", sanitize = "address"))]
+mod asan;
+mod backfill;
+mod boxed;
+mod buffer;
+mod capacity;
+#[cfg(feature = "debug-headers")]
+mod checked;
+mod clone_in;
+mod collect_in;
+mod compact;
+mod counted;
+mod cow;
+#[cfg(all(feature = "cpu-local", target_os = "linux", not(miri)))]
+mod cpu_local;
+#[cfg(feature = "critical-section")]
+mod critical;
+#[cfg(feature = "serde")]
+mod de;
+mod double;
+mod down;
+mod dropcar;
+mod erased;
+mod error;
+mod fail;
+mod fallback;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod fixed;
+mod flex;
+mod frame;
+#[cfg(not(loom))]
+mod global;
+mod handle;
+mod hash_map;
+mod id;
+#[cfg(feature = "alloc")]
+mod interner;
+mod limit;
+mod macros;
+mod marker;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(all(feature = "mmap", unix, not(miri)))]
+mod mmap;
+#[cfg(all(
+    feature = "mte",
+    target_arch = "aarch64",
+    target_os = "linux",
+    not(miri)
+))]
+mod mte;
+mod observer;
+#[cfg(feature = "alloc")]
+mod pool;
+#[cfg(feature = "profiling")]
+mod profile;
+#[cfg(feature = "alloc")]
+mod relocate;
+mod report;
+#[cfg(all(feature = "mmap", unix, not(miri)))]
+mod reserve;
+mod rt;
+mod scope;
+mod sealed;
+mod sharded;
+mod slot;
+#[cfg(feature = "alloc")]
+mod snapshot;
+mod spill;
+mod split;
+mod stack;
+#[cfg(not(loom))]
+mod static_bumpcar;
+#[cfg(feature = "stats")]
+mod stats;
+mod string;
+mod sync;
+#[cfg(feature = "trace")]
+mod trace;
+#[cfg(feature = "tracing")]
+mod tracing;
+mod typed;
+#[cfg(feature = "valgrind")]
+mod valgrind;
+mod vec;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+mod wasm;
+mod writer;
+
+pub use advisor::CapacityAdvisor;
+pub use aligned::AlignedBumpCar;
+#[cfg(feature = "allocator-api2")]
+pub use api2::Compat;
+pub use backfill::BackfillBumpCar;
+pub use boxed::BumpBox;
+pub use buffer::{BorrowedBuffer, BufferBumpCar};
+pub use capacity::CapacityBuilder;
+#[cfg(feature = "debug-headers")]
+pub use checked::CheckedCar;
+pub use clone_in::CloneIn;
+pub use collect_in::{CollectIn, FromIteratorIn};
+pub use compact::{CompactBumpCar, CompactIndex};
+pub use counted::{CountedBumpCar, ResetError};
+pub use cow::{BumpCow, ToBump};
+#[cfg(all(feature = "cpu-local", target_os = "linux", not(miri)))]
+pub use cpu_local::CpuLocalBumpCar;
+#[cfg(feature = "critical-section")]
+pub use critical::CriticalBumpCar;
+#[cfg(feature = "serde")]
+pub use de::{DeserializeIn, DeserializeInSeed};
+pub use double::{BackCar, DoubleBumpCar, FrontCar};
+pub use down::DownBumpCar;
+pub use dropcar::DropCar;
+pub use erased::Bump;
+pub use error::{BumpError, BumpErrorKind};
+pub use fail::FailCar;
+pub use fallback::{Fallback, Owns};
+pub use fixed::FixedBumpVec;
+pub use flex::{ChunkInfo, Chunks, FlexBumpCar, GrowthStrategy, ResetPolicy};
+pub use frame::FrameCar;
+#[cfg(not(loom))]
+pub use global::BumpGlobal;
+pub use handle::{Handle, SliceHandle, TaggedHandle};
+pub use hash_map::{BumpBuildHasher, BumpHashMap, BumpHashSet, BumpHasher};
+pub use id::{Id, IdCar};
+#[cfg(feature = "alloc")]
+pub use interner::{BumpInterner, Symbol};
+pub use limit::LimitCar;
+pub use marker::Marker;
+#[cfg(feature = "metrics")]
+pub use metrics::describe_metrics;
+#[cfg(all(feature = "mmap", unix, not(miri)))]
+pub use mmap::{page_size, Mmap, ReadOnlyCar};
+#[cfg(all(
+    feature = "mte",
+    target_arch = "aarch64",
+    target_os = "linux",
+    not(miri)
+))]
+pub use mte::MteBumpCar;
+#[cfg(feature = "defmt")]
+pub use observer::DefmtObserver;
+pub use observer::{ArenaObserver, ObservedBumpCar, ThresholdObserver, Usage};
+#[cfg(feature = "alloc")]
+pub use pool::{CarPool, PooledCar};
+#[cfg(feature = "profiling")]
+pub use profile::{Callsite, Profile, MAX_CALLSITES};
+#[cfg(feature = "alloc")]
+pub use relocate::RelocateTo;
+#[cfg(all(feature = "mmap", unix, not(miri)))]
+pub use reserve::ReservedBumpCar;
+pub use rt::{RtCar, RtGuard};
+pub use scope::{BrandedScope, BumpScope};
+pub use sealed::SealedBumpCar;
+pub use sharded::{Shard, ShardedBumpCar};
+pub use slot::SlotCar;
+#[cfg(feature = "alloc")]
+pub use snapshot::Snapshot;
+pub use spill::SpillBumpCar;
+pub use split::SplitCar;
+pub use stack::StackCar;
+#[cfg(not(loom))]
+pub use static_bumpcar::{StaticBuffer, StaticBumpCar};
+#[cfg(feature = "stats")]
+pub use stats::Stats;
+pub use string::BumpString;
+pub use sync::{CursorPadding, Padded, SyncBumpCar, Unpadded};
+#[cfg(feature = "trace")]
+pub use trace::{ReplayReport, Trace, TraceCar, TraceError, TraceEvent, TraceOp};
+#[cfg(feature = "tracing")]
+pub use tracing::TracingCar;
+pub use typed::{slice_assume_init_mut, SIMD_ALIGN};
+pub use vec::BumpVec;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub use wasm::{MemoryGrow, WasmGlobal};
+#[cfg(feature = "std")]
+pub use writer::read_to_bump;
+pub use writer::BumpWriter;
 
 #[cfg(feature = "alloc")]
 use alloc::alloc::Global;
 use core::alloc::{AllocError, Allocator, Layout};
-use core::{cell::Cell, mem::size_of, ptr::NonNull};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::{cell::Cell, fmt, mem::size_of, ptr::NonNull};
 
 /// Returns the next multiple of `align` greater than `size`
 ///
 /// # Safety
 /// `size + align` must not overflow, and `align` must be a power of two.
-///
-/// As a consequence, [`dodgems`](crate) does not support alignment values above 2^30.
 unsafe fn next_multiple(size: usize, align: usize) -> usize {
     let am = align - 1;
     (size + am) & !am
 }
 
+/// Overwrites `len` bytes starting at `ptr` with zeros, in a way that cannot be optimized out
+/// by the compiler.
+///
+/// # Safety
+/// `ptr` must be valid for writes of `len` bytes.
+#[cfg(feature = "zeroize")]
+unsafe fn wipe_bytes(ptr: NonNull<u8>, len: usize) {
+    for i in 0..len {
+        // SAFETY: guaranteed by the caller.
+        unsafe { ptr.add(i).write_volatile(0) };
+    }
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+/// Byte written over reclaimed memory with the `debug-poison` feature.
+#[cfg(all(feature = "debug-poison", debug_assertions, not(feature = "zeroize")))]
+const POISON: u8 = 0xDD;
+
 #[cfg(test)]
 #[test]
 fn next_multiple_power_of_two() {
@@ -75,6 +399,40 @@ fn next_multiple_power_of_two() {
     }
 }
 
+/// Computes the placement of an allocation described by `layout` at `current`.
+///
+/// The alignment is computed on the address of `current`, so any power of two alignment is
+/// supported, regardless of the alignment of the buffer.
+///
+/// The padding and the size are checked against the remaining capacity with a single
+/// comparison: since a [`Layout`]'s size rounded up to its alignment never exceeds
+/// [`isize::MAX`], their sum cannot overflow.
+///
+/// Returns the pointer to the allocation and the new cursor position,
+/// or `None` if the allocation does not fit before `end`.
+///
+/// # Safety
+/// `current` and `end` must be in bounds of the same allocation, and `current <= end`.
+#[inline(always)]
+unsafe fn bump(
+    current: NonNull<u8>,
+    end: NonNull<u8>,
+    layout: Layout,
+) -> Option<(NonNull<u8>, NonNull<u8>)> {
+    let addr = current.addr().get();
+    let remaining = end.addr().get() - addr;
+    let padding = addr.wrapping_neg() & (layout.align() - 1);
+
+    let offset = padding + layout.size();
+    if offset > remaining {
+        return None;
+    }
+
+    // SAFETY: padding <= offset <= remaining, so both pointers stay in bounds of the
+    // allocation. They keep the provenance of the whole allocation.
+    unsafe { Some((current.byte_add(padding), current.byte_add(offset))) }
+}
+
 /// Fast bump allocator.
 ///
 /// Allocations are made by incrementing an offset, and are tied to the lifetime of a reference
@@ -98,8 +456,25 @@ pub struct BumpCar<
     #[cfg(feature = "alloc")] A: Allocator = Global,
     #[cfg(not(feature = "alloc"))] A: Allocator,
 > {
-    pointer: NonNull<[u8]>,
-    position: Cell<usize>,
+    start: NonNull<u8>,
+    end: NonNull<u8>,
+    current: Cell<NonNull<u8>>,
+    floor: usize,
+    /// Highest position of the cursor before it last moved backwards.
+    peak: Cell<usize>,
+    /// Offset from which the buffer is known to only contain zeros, or `usize::MAX`.
+    pristine: Cell<usize>,
+    /// Number of [pinned](BumpCar::alloc_pinned) boxes that have not been dropped yet.
+    pinned: AtomicUsize,
+    /// Position below which the values of the live pinned boxes lie.
+    pinned_end: Cell<usize>,
+    align: usize,
+    #[cfg(debug_assertions)]
+    generation: Cell<u32>,
+    #[cfg(feature = "stats")]
+    stats: Cell<Stats>,
+    #[cfg(feature = "profiling")]
+    profile: core::cell::RefCell<Profile>,
     allocator: A,
 }
 
@@ -109,166 +484,1452 @@ impl<A: Allocator> BumpCar<A> {
     /// # Errors
     /// This function returns an error if the capacity (or the nearest pointer-aligned multiple)
     /// is greater than [`isize::MAX`], or if the underlying allocator returns an error.
-    #[allow(clippy::missing_panics_doc)]
-    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, AllocError> {
-        // SAFETY: capacity must be <= isize::MAX for next_multiple to be evaluated,
-        // and size_of::<usize>() is a power of two.
-        if capacity > isize::MAX as _
-            || unsafe { next_multiple(capacity, size_of::<usize>()) } > isize::MAX as _
-        {
-            return Err(AllocError);
+    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        Self::new_in_with_align(capacity, size_of::<usize>(), allocator)
+    }
+
+    /// Allocates a new [`BumpCar`] in the given allocator, with a buffer aligned to `align`
+    /// (or to the pointer size, if it is greater), such as a cache line or a page for DMA.
+    ///
+    /// # Errors
+    /// This function returns an error if `align` is not a power of two, if the capacity (or the
+    /// nearest multiple of the alignment) is greater than [`isize::MAX`], or if the underlying
+    /// allocator returns an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new_in_with_align(8192, 4096, Global).unwrap();
+    /// let page = bumpcar.alloc([0u8; 4096]);
+    /// assert_eq!(page.as_ptr().addr() % 4096, 0);
+    /// assert_eq!(bumpcar.buffer_align(), 4096);
+    /// ```
+    pub fn new_in_with_align(
+        capacity: usize,
+        align: usize,
+        allocator: A,
+    ) -> Result<Self, BumpError> {
+        Self::with_buffer(capacity, align, allocator, false)
+    }
+
+    /// Allocates a new [`BumpCar`] in the given allocator, with its capacity rounded up to a
+    /// multiple of `granularity`, such as the page size.
+    ///
+    /// A sub-page arena wastes the rest of its last page anyway: the rounded capacity is
+    /// reported by [`BumpCar::capacity`]. With the `mmap` feature, the page size of the system is
+    /// returned by `dodgems::page_size`.
+    ///
+    /// # Errors
+    /// This function returns an error if `granularity` is not a power of two, if the rounded
+    /// capacity is greater than [`isize::MAX`], or if the underlying allocator returns an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new_in_rounded(1000, 4096, Global).unwrap();
+    /// assert_eq!(bumpcar.capacity(), 4096);
+    /// ```
+    pub fn new_in_rounded(
+        capacity: usize,
+        granularity: usize,
+        allocator: A,
+    ) -> Result<Self, BumpError> {
+        if !granularity.is_power_of_two() {
+            return Err(BumpError::new(BumpErrorKind::InvalidAlignment, None, 0));
         }
+        let capacity = capacity
+            .checked_next_multiple_of(granularity)
+            .ok_or(BumpError::new(BumpErrorKind::Overflow, None, 0))?;
+        Self::new_in(capacity, allocator)
+    }
+
+    /// Allocates a new [`BumpCar`] in the given allocator, with a zeroed buffer obtained with
+    /// [`Allocator::allocate_zeroed`].
+    ///
+    /// The contents of the arena are deterministic, and the [`BumpCar`] keeps track of the part
+    /// of its buffer that was never handed out: zeroed allocations made there, such as
+    /// `Box::new_zeroed_in`, skip the memset.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity (or the nearest pointer-aligned multiple)
+    /// is greater than [`isize::MAX`], or if the underlying allocator returns an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new_zeroed_in(1024, Global).unwrap();
+    /// let table = Box::<[u64; 64], _>::new_zeroed_in(&bumpcar);
+    /// // SAFETY: an array of integers is valid when zeroed.
+    /// let table = unsafe { table.assume_init() };
+    /// assert!(table.iter().all(|&x| x == 0));
+    /// ```
+    pub fn new_zeroed_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        Self::with_buffer(capacity, size_of::<usize>(), allocator, true)
+    }
 
-        let pointer = allocator
-            .allocate(Layout::from_size_align(capacity, core::mem::size_of::<usize>()).unwrap())?;
+    /// Allocates a new [`BumpCar`] with a buffer aligned to `align`, that is zeroed if `zeroed`
+    /// is `true`.
+    fn with_buffer(
+        capacity: usize,
+        align: usize,
+        allocator: A,
+        zeroed: bool,
+    ) -> Result<Self, BumpError> {
+        if !align.is_power_of_two() {
+            return Err(BumpError::new(BumpErrorKind::InvalidAlignment, None, 0));
+        }
+        let layout = Layout::from_size_align(capacity, align.max(size_of::<usize>()))
+            .map_err(|_| BumpError::new(BumpErrorKind::Overflow, None, 0))?;
+        let pointer = if zeroed {
+            allocator.allocate_zeroed(layout)
+        } else {
+            allocator.allocate(layout)
+        };
+        let pointer =
+            pointer.map_err(|_| BumpError::new(BumpErrorKind::AllocatorFailed, Some(layout), 0))?;
+        let start = pointer.cast::<u8>();
+        #[cfg(feature = "valgrind")]
+        valgrind::create(start, pointer.len());
+        #[cfg(all(feature = "asan", sanitize = "address"))]
+        asan::create(start, pointer.len());
 
         Ok(Self {
-            pointer,
-            position: Cell::new(0),
+            start,
+            // SAFETY: the allocated buffer is pointer.len() bytes long.
+            end: unsafe { start.add(pointer.len()) },
+            current: Cell::new(start),
+            floor: 0,
+            peak: Cell::new(0),
+            pristine: Cell::new(if zeroed { 0 } else { usize::MAX }),
+            pinned: AtomicUsize::new(0),
+            pinned_end: Cell::new(0),
+            align: layout.align(),
+            #[cfg(debug_assertions)]
+            generation: Cell::new(0),
+            #[cfg(feature = "stats")]
+            stats: Cell::new(Stats::default()),
+            #[cfg(feature = "profiling")]
+            profile: core::cell::RefCell::new(Profile::new()),
             allocator,
         })
     }
 
-    /// Returns the capacity of the [`BumpCar`].
-    pub fn capacity(&self) -> usize {
-        self.pointer.len()
-    }
-
-    /// Returns the remaining capacity of the [`BumpCar`].
+    /// Allocates a new [`BumpCar`] in the given allocator, and writes to every page of its
+    /// buffer, so that allocations never take a page fault at first touch.
     ///
-    /// This does not guarantee that an allocation of this size will succeed:
-    /// the [`BumpCar`] will waste space if a new allocation with a greater alignment
-    /// than the last one is required.
+    /// This moves the cost of faulting in the pages of a large arena to its construction, instead
+    /// of the first frame using it. With the `mmap` feature, an allocator created with
+    /// `Mmap::new().prefaulted()` does the same in the kernel, which is faster.
     ///
-    /// If you need to check for the validity of an allocation in a more precise way,
-    /// use [`BumpCar::can_allocate`].
-    pub fn remaining_capacity(&self) -> usize {
-        self.capacity() - self.position.get()
+    /// # Errors
+    /// This function returns an error if the capacity (or the nearest pointer-aligned multiple)
+    /// is greater than [`isize::MAX`], or if the underlying allocator returns an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new_in_prefaulted(1024 * 1024, Global).unwrap();
+    /// assert_eq!(bumpcar.remaining_capacity(), 1024 * 1024);
+    /// ```
+    pub fn new_in_prefaulted(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        let bumpcar = Self::new_in(capacity, allocator)?;
+        bumpcar.prefault();
+        Ok(bumpcar)
     }
 
-    /// Checks wether the allocator has enough remaining capacity for the
-    /// allocation specified in `layout`.
-    pub fn can_allocate(&self, layout: Layout) -> bool {
-        // SAFETY: layout.align() is guaranteed to be a power of two,
-        // and self.position() <= pointer.len() <= isize::MAX, so the operation cannot overflow.
-        let closest_align = unsafe { next_multiple(self.position.get(), layout.align()) };
-
-        let Some(new_pos) = closest_align.checked_add(layout.size()) else {
-            return false;
-        };
+    /// Writes a zero to every page of the free part of the buffer.
+    fn prefault(&self) {
+        /// Smallest page size of the supported systems: other systems touch some pages twice.
+        const PAGE: usize = 4096;
 
-        new_pos <= self.pointer.len()
+        let (from, to) = (self.position(), self.capacity());
+        self.expose(from, to);
+        for offset in (from..to).step_by(PAGE).chain((from < to).then(|| to - 1)) {
+            // SAFETY: offset < capacity, so the byte is in bounds of the buffer. The free part
+            // of the buffer is not borrowed by any allocation.
+            unsafe { self.start.add(offset).write_volatile(0) };
+        }
+        self.set_position(from);
     }
 
-    /// Resets the [`BumpCar`]'s remaining capacity to its initial capacity.
-    ///
-    /// This requires a mutable reference, so that any previous allocations made with &self
-    /// are invalidated by the borrow checker.
-    pub fn reset(&mut self) {
-        self.position.set(0);
+    /// Returns the alignment of the buffer of the [`BumpCar`].
+    pub fn buffer_align(&self) -> usize {
+        self.align
     }
 
-    /// Create a new checkpoint.
+    /// Decomposes the [`BumpCar`] into its buffer, the number of bytes used in it, and its
+    /// allocator, without deallocating the buffer.
     ///
-    /// This function allocates the rest of the allocated space into another [`BumpCar`],
-    /// that can used to reset part of the allocated space instead of the whole allocation.
+    /// The arena can be rebuilt with [`BumpCar::from_raw_parts`], or with
+    /// [`BumpCar::from_raw_parts_with_align`] if its [buffer alignment](BumpCar::buffer_align)
+    /// was not the default one. The frozen prefix is not kept: it becomes part of the used bytes.
+    /// Neither are the values of leaked [pinned](BumpCar::alloc_pinned) boxes, that the rebuilt
+    /// arena would reclaim on reset.
     ///
     /// # Example
     /// ```rust
     /// #![feature(allocator_api)]
-    /// # extern crate alloc;
     /// use dodgems::BumpCar;
     ///
     /// let bumpcar = BumpCar::new(256).unwrap();
-    /// let alloc_half = Box::new_in([0u8; 128], &bumpcar);
+    /// bumpcar.alloc(42u64);
+    /// let (buffer, used, allocator) = bumpcar.into_raw_parts();
+    /// assert_eq!((buffer.len(), used), (256, 8));
     ///
-    /// let mut checkpoint = bumpcar.checkpoint();
-    /// let alloc_rest = Box::new_in([0u8; 128], &checkpoint);
-    /// drop(alloc_rest);
-    /// assert_eq!(checkpoint.remaining_capacity(), 0);
+    /// // SAFETY: the parts come from into_raw_parts.
+    /// let bumpcar = unsafe { BumpCar::from_raw_parts(buffer, used, allocator) };
+    /// assert_eq!(bumpcar.remaining_capacity(), 248);
+    /// ```
+    pub fn into_raw_parts(self) -> (NonNull<[u8]>, usize, A) {
+        let this = core::mem::ManuallyDrop::new(self);
+        let buffer = NonNull::slice_from_raw_parts(this.start, this.capacity());
+        // SAFETY: the allocator is moved out only once, and the BumpCar is never dropped.
+        let allocator = unsafe { core::ptr::read(&this.allocator) };
+        (buffer, this.position(), allocator)
+    }
+
+    /// Rebuilds a [`BumpCar`] from the parts returned by [`BumpCar::into_raw_parts`].
     ///
-    /// checkpoint.reset();
-    /// assert_eq!(checkpoint.remaining_capacity(), 128);
+    /// # Safety
+    /// `buffer` must have been allocated with `allocator` (or a clone of it), with the layout of
+    /// its length and an alignment of `size_of::<usize>()`, as is the buffer returned by
+    /// [`BumpCar::into_raw_parts`] for an arena created with [`BumpCar::new_in`]. `used` must be
+    /// smaller than or equal to its length. The bytes after `used` must not be in use, and the
+    /// buffer must not be deallocated elsewhere.
+    ///
+    /// If a [pinned](BumpCar::alloc_pinned) box allocated in the original arena was leaked, its
+    /// value must never be moved or overwritten: the rebuilt arena must then not be reset,
+    /// rewound below the value, or zeroized.
+    pub unsafe fn from_raw_parts(buffer: NonNull<[u8]>, used: usize, allocator: A) -> Self {
+        // SAFETY: guaranteed by the caller.
+        unsafe { Self::from_raw_parts_with_align(buffer, used, size_of::<usize>(), allocator) }
+    }
+
+    /// Rebuilds a [`BumpCar`] from the parts returned by [`BumpCar::into_raw_parts`], and the
+    /// alignment of its buffer returned by [`BumpCar::buffer_align`].
+    ///
+    /// # Safety
+    /// Same as [`BumpCar::from_raw_parts`], except that the buffer must have been allocated with
+    /// an alignment of `align`.
+    pub unsafe fn from_raw_parts_with_align(
+        buffer: NonNull<[u8]>,
+        used: usize,
+        align: usize,
+        allocator: A,
+    ) -> Self {
+        let start = buffer.cast::<u8>();
+        Self {
+            start,
+            // SAFETY: the buffer is buffer.len() bytes long.
+            end: unsafe { start.add(buffer.len()) },
+            // SAFETY: used <= buffer.len(), as guaranteed by the caller.
+            current: Cell::new(unsafe { start.add(used) }),
+            floor: 0,
+            peak: Cell::new(0),
+            pristine: Cell::new(usize::MAX),
+            pinned: AtomicUsize::new(0),
+            pinned_end: Cell::new(0),
+            align,
+            #[cfg(debug_assertions)]
+            generation: Cell::new(0),
+            #[cfg(feature = "stats")]
+            stats: Cell::new(Stats::default()),
+            #[cfg(feature = "profiling")]
+            profile: core::cell::RefCell::new(Profile::new()),
+            allocator,
+        }
+    }
+
+    /// Returns a reference to the backing allocator of the [`BumpCar`].
+    pub fn allocator(&self) -> &A {
+        &self.allocator
+    }
+
+    /// Deallocates the buffer of the [`BumpCar`], and returns its backing allocator.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::{BumpCar, CountedBumpCar};
+    ///
+    /// let counted = CountedBumpCar::new(1024).unwrap();
+    /// let bumpcar = BumpCar::new_in(256, &counted).unwrap();
+    /// assert_eq!(bumpcar.allocator().live_allocations(), 1);
+    ///
+    /// let counted = bumpcar.into_allocator();
+    /// assert_eq!(counted.live_allocations(), 0);
     /// ```
-    pub fn checkpoint<'a>(&'a self) -> BumpCar<&'a BumpCar<A>> {
-        BumpCar::new_in(
-            self.remaining_capacity() - self.remaining_capacity() % size_of::<usize>(),
-            self,
-        )
-        .unwrap()
+    pub fn into_allocator(self) -> A {
+        let mut this = core::mem::ManuallyDrop::new(self);
+        this.release();
+        // SAFETY: the allocator is moved out only once, and the BumpCar is never dropped.
+        unsafe { core::ptr::read(&this.allocator) }
     }
-}
 
-#[cfg(feature = "alloc")]
-impl BumpCar {
-    /// Allocates a [`BumpCar`] with the Global allocator.
+    /// Returns the offset of the cursor from the start of the buffer.
+    fn position(&self) -> usize {
+        self.current.get().addr().get() - self.start.addr().get()
+    }
+
+    /// Moves the cursor to `position` bytes from the start of the buffer.
     ///
-    /// # Errors
-    /// This function returns an error if the capacity (or its nearest pointer-aligned multiple)
-    /// is greater than [`isize::MAX`], or if the global returns an error.
-    pub fn new(capacity: usize) -> Result<Self, AllocError> {
-        Self::new_in(capacity, Global)
+    /// `position` must be smaller than or equal to the capacity.
+    fn set_position(&self, position: usize) {
+        debug_assert!(position <= self.capacity());
+        let position = self.reclaim_from(position);
+        self.record_peak();
+        // SAFETY: position <= capacity, so the pointer stays in bounds of the buffer.
+        self.current.set(unsafe { self.start.add(position) });
+        #[cfg(feature = "valgrind")]
+        valgrind::trim(self.start, position, self.capacity());
+        #[cfg(all(feature = "asan", sanitize = "address"))]
+        asan::trim(self.start, position, self.capacity());
     }
-}
 
-impl<A: Allocator> Drop for BumpCar<A> {
-    /// Deallocates the [`BumpCar`]'s buffer.
-    fn drop(&mut self) {
-        let ptr = self.pointer.cast::<u8>();
-        // SAFETY: ptr is always allocated with self.allocator
-        // and the alignement has been validated at construction of the BumpCar
-        unsafe {
-            self.allocator.deallocate(
-                ptr,
-                Layout::from_size_align_unchecked(self.pointer.len(), size_of::<usize>()),
-            );
+    /// Returns the position from which the memory can be reclaimed by moving the cursor back to
+    /// `position`: the values of live pinned boxes must stay in place until they are dropped.
+    fn reclaim_from(&self, position: usize) -> usize {
+        if self.pinned.load(Ordering::Acquire) == 0 {
+            position
+        } else {
+            position.max(self.pinned_end.get())
         }
     }
-}
 
-unsafe impl<A: Allocator> Allocator for &BumpCar<A> {
-    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
-        // SAFETY: layout.align() is guaranteed to be a power of two,
-        // and self.position() <= pointer.len() <= isize::MAX, so the operation cannot overflow.
-        let closest_align = unsafe { next_multiple(self.position.get(), layout.align()) };
+    /// Returns `true` if some pinned boxes allocated in the [`BumpCar`] have not been dropped.
+    pub(crate) fn has_pinned(&self) -> bool {
+        self.pinned.load(Ordering::Acquire) != 0
+    }
 
-        let new_pos = closest_align.checked_add(layout.size()).ok_or(AllocError)?;
-        if new_pos > self.pointer.len() {
-            return Err(AllocError);
+    /// Registers a pinned box, whose value ends at the cursor, and returns the counter to
+    /// decrement when it is dropped.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn register_pinned(&self) -> &AtomicUsize {
+        if self.pinned.load(Ordering::Acquire) == 0 {
+            self.pinned_end.set(0);
         }
+        self.pinned_end
+            .set(self.pinned_end.get().max(self.position()));
+        self.pinned.fetch_add(1, Ordering::Relaxed);
+        &self.pinned
+    }
 
-        // SAFETY: closest_align + layout.size() <= pointer.len() <= isize::MAX
-        let ptr = unsafe { self.pointer.as_ptr().cast::<u8>().add(closest_align) };
-        self.position.set(new_pos);
-        Ok(NonNull::slice_from_raw_parts(
-            // SAFETY: pointer is non null, and closest_align + layout.size() <= pointer.len(),
-            // so ptr = pointer + closest_align is non null.
-            unsafe { NonNull::new_unchecked(ptr) },
-            layout.size(),
-        ))
+    /// Records the position of the cursor in the high-water mark, before it moves backwards.
+    ///
+    /// The cursor only moves forward on the allocation path, so the high-water mark does not
+    /// need to be updated there. The bytes handed out below the cursor are no longer pristine.
+    fn record_peak(&self) {
+        let position = self.position();
+        self.peak.set(self.peak.get().max(position));
+        self.pristine.set(self.pristine.get().max(position));
     }
 
-    /// The [`BumpCar`] does not perform deallocation unless it's reset or dropped.
-    unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {}
+    /// Computes the placement of an allocation described by `layout` at the cursor.
+    ///
+    /// Returns the pointer to the allocation and the new cursor position,
+    /// or `None` if the allocation does not fit.
+    #[inline(always)]
+    fn bump(&self, layout: Layout) -> Option<(NonNull<u8>, NonNull<u8>)> {
+        // SAFETY: current and end are in bounds of the buffer, and current <= end.
+        unsafe { bump(self.current.get(), self.end, layout) }
+    }
 
-    /// Shrinks an allocated region.
+    /// Slow path of a failed allocation, kept out of line so that it does not weigh on
+    /// the inlined fast path.
+    #[cold]
+    #[inline(never)]
+    fn allocation_failed(&self) -> AllocError {
+        #[cfg(feature = "stats")]
+        self.record(|stats| stats.failed_allocations += 1);
+        AllocError
+    }
+
+    /// Slow path of a resize that can not happen in place: allocates a region for `new_layout`,
+    /// and copies the first `size` bytes of the allocation at `ptr` into it.
     ///
-    /// The [`BumpCar`] allocator has the extra requirement
-    /// that the old layout's alignment MUST be bigger than the new one.
-    unsafe fn shrink(
+    /// Kept out of line like [`BumpCar::allocation_failed`], so that the in-place paths of
+    /// `grow` and `shrink` stay small enough to be inlined.
+    ///
+    /// # Safety
+    /// `ptr` must be valid for reads of `size` bytes, and `size <= new_layout.size()`.
+    #[cold]
+    #[inline(never)]
+    unsafe fn reallocate(
         &self,
         ptr: NonNull<u8>,
-        old_layout: Layout,
+        size: usize,
         new_layout: Layout,
     ) -> Result<NonNull<[u8]>, AllocError> {
-        debug_assert!(
-            new_layout.size() <= old_layout.size(),
-            "`new_layout.size()` must be smaller than or equal to `old_layout.size()`"
-        );
-        if old_layout.align() < new_layout.align() {
+        let new_ptr = self.allocate(new_layout)?;
+        // SAFETY: the new allocation is valid for new_layout.size() >= size bytes, and cannot
+        // overlap the old allocation.
+        unsafe { new_ptr.cast::<u8>().copy_from_nonoverlapping(ptr, size) };
+        Ok(new_ptr)
+    }
+
+    /// Deallocates the buffer. The [`BumpCar`] must not be used afterwards.
+    fn release(&mut self) {
+        if self.has_pinned() {
+            // A pinned value was leaked: its memory must stay valid, so the buffer is leaked too.
+            return;
+        }
+        #[cfg(feature = "zeroize")]
+        self.wipe(0, self.position());
+        #[cfg(all(feature = "debug-poison", debug_assertions, not(feature = "zeroize")))]
+        self.poison(0, self.position());
+        #[cfg(feature = "valgrind")]
+        valgrind::destroy(self.start, self.capacity());
+        #[cfg(all(feature = "asan", sanitize = "address"))]
+        asan::destroy(self.start, self.capacity());
+
+        // SAFETY: start is always allocated with self.allocator, with a size of
+        // end - start bytes, and the alignement has been validated at construction of the BumpCar
+        unsafe {
+            self.allocator.deallocate(
+                self.start,
+                Layout::from_size_align_unchecked(self.capacity(), self.align),
+            );
+        }
+    }
+
+    /// Grows or shrinks the buffer to `capacity` bytes with the backing allocator, keeping its
+    /// used part.
+    ///
+    /// `capacity` must be greater than or equal to the position of the cursor.
+    fn resize_buffer(&mut self, capacity: usize) -> Result<(), AllocError> {
+        if self.has_pinned() {
+            // The buffer could be moved, along with the values of the leaked pinned boxes.
             return Err(AllocError);
         }
+        let position = self.position();
+        debug_assert!(capacity >= position);
+        let align = self.align;
+        let new_layout = Layout::from_size_align(capacity, align).map_err(|_| AllocError)?;
+        // SAFETY: the buffer was allocated with this layout.
+        let old_layout = unsafe { Layout::from_size_align_unchecked(self.capacity(), align) };
+        #[cfg(feature = "valgrind")]
+        valgrind::destroy(self.start, self.capacity());
+        #[cfg(all(feature = "asan", sanitize = "address"))]
+        asan::destroy(self.start, self.capacity());
+
+        // SAFETY: the buffer was allocated by self.allocator with old_layout, and the sizes are
+        // ordered as required by grow and shrink.
+        let result = unsafe {
+            if capacity >= old_layout.size() && self.pristine.get() != usize::MAX {
+                self.allocator
+                    .grow_zeroed(self.start, old_layout, new_layout)
+            } else if capacity >= old_layout.size() {
+                self.allocator.grow(self.start, old_layout, new_layout)
+            } else {
+                self.allocator.shrink(self.start, old_layout, new_layout)
+            }
+        };
+        if let Ok(buffer) = result {
+            self.start = buffer.cast::<u8>();
+            // SAFETY: the new buffer is buffer.len() bytes long.
+            self.end = unsafe { self.start.add(buffer.len()) };
+            // SAFETY: position <= capacity <= buffer.len().
+            self.current.set(unsafe { self.start.add(position) });
+        }
+        #[cfg(feature = "valgrind")]
+        {
+            valgrind::create(self.start, self.capacity());
+            valgrind::alloc(self.start, self.start, position);
+        }
+        #[cfg(all(feature = "asan", sanitize = "address"))]
+        {
+            asan::create(self.start, self.capacity());
+            asan::alloc(self.start, position);
+        }
+        result.map(|_| ())
+    }
+
+    /// Returns the capacity of the [`BumpCar`].
+    pub fn capacity(&self) -> usize {
+        self.end.addr().get() - self.start.addr().get()
+    }
+
+    /// Returns the remaining capacity of the [`BumpCar`].
+    ///
+    /// This does not guarantee that an allocation of this size will succeed:
+    /// the [`BumpCar`] will waste space if a new allocation with a greater alignment
+    /// than the last one is required.
+    ///
+    /// If you need to check for the validity of an allocation in a more precise way,
+    /// use [`BumpCar::can_allocate`].
+    pub fn remaining_capacity(&self) -> usize {
+        self.end.addr().get() - self.current.get().addr().get()
+    }
+
+    /// Returns the number of bytes used in the [`BumpCar`], including the alignment padding and
+    /// the frozen prefix.
+    pub fn used(&self) -> usize {
+        self.position()
+    }
+
+    /// Returns the highest number of bytes used in the [`BumpCar`] since it was created, or
+    /// since the last call to [`BumpCar::reset_peak`].
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let mut bumpcar = BumpCar::new(1024).unwrap();
+    /// for frame in 1..=4 {
+    ///     bumpcar.alloc_slice_copy(&vec![0u8; frame * 100]);
+    ///     bumpcar.reset();
+    /// }
+    /// assert_eq!(bumpcar.peak_usage(), 400);
+    ///
+    /// bumpcar.reset_peak();
+    /// bumpcar.alloc([0u8; 10]);
+    /// assert_eq!(bumpcar.peak_usage(), 10);
+    /// ```
+    pub fn peak_usage(&self) -> usize {
+        self.peak.get().max(self.position())
+    }
+
+    /// Resets the high-water mark returned by [`BumpCar::peak_usage`] to the current usage.
+    pub fn reset_peak(&self) {
+        self.peak.set(0);
+    }
+
+    /// Returns `true` if nothing is allocated in the [`BumpCar`], not even a frozen prefix.
+    pub fn is_empty(&self) -> bool {
+        self.position() == 0
+    }
+
+    /// Returns the fraction of the capacity of the [`BumpCar`] that is used, between `0.0`
+    /// and `1.0`. An arena without any capacity has a utilization of `0.0`.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// assert!(bumpcar.is_empty());
+    /// bumpcar.alloc([0u64; 8]);
+    /// assert_eq!(bumpcar.used(), 64);
+    /// assert_eq!(bumpcar.utilization(), 0.25);
+    /// ```
+    pub fn utilization(&self) -> f64 {
+        match self.capacity() {
+            0 => 0.0,
+            capacity => self.position() as f64 / capacity as f64,
+        }
+    }
 
-        Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()))
+    /// Moves the cursor to the next multiple of `align`, so that the following allocations with
+    /// a smaller or equal alignment (and a size that is a multiple of it) do not need any
+    /// padding.
+    ///
+    /// # Errors
+    /// This function returns an error if the aligned cursor would be past the end of the buffer.
+    /// The cursor is then left unchanged.
+    ///
+    /// # Panics
+    /// This function panics if `align` is not a power of two.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new_with_align(256, 16).unwrap();
+    /// bumpcar.alloc(1u8);
+    /// bumpcar.align_cursor(16).unwrap();
+    /// assert_eq!(bumpcar.used(), 16);
+    ///
+    /// let vertex = bumpcar.alloc([0f32; 4]);
+    /// assert_eq!(vertex.as_ptr().addr() % 16, 0);
+    /// ```
+    pub fn align_cursor(&self, align: usize) -> Result<(), AllocError> {
+        let layout = Layout::from_size_align(0, align).expect("alignment is not a power of two");
+        let (aligned, _) = self.bump(layout).ok_or(AllocError)?;
+        #[cfg(feature = "stats")]
+        self.record(|stats| {
+            stats.alignment_waste += aligned.addr().get() - self.current.get().addr().get();
+        });
+        self.current.set(aligned);
+        Ok(())
+    }
+
+    /// Checks wether the allocator has enough remaining capacity for the
+    /// allocation specified in `layout`.
+    pub fn can_allocate(&self, layout: Layout) -> bool {
+        self.bump(layout).is_some()
+    }
+
+    /// Checks wether the allocator has enough remaining capacity for a value of type `T`.
+    pub fn can_allocate_value<T>(&self) -> bool {
+        self.can_allocate(Layout::new::<T>())
+    }
+
+    /// Checks wether the allocator has enough remaining capacity for an array of `n` values
+    /// of type `T`.
+    ///
+    /// Returns `false` if the size of the array overflows.
+    pub fn can_allocate_array<T>(&self, n: usize) -> bool {
+        Layout::array::<T>(n).is_ok_and(|layout| self.can_allocate(layout))
+    }
+
+    /// Checks wether the allocator has enough remaining capacity for a slice of `n` values
+    /// of type `T`, such as the ones allocated by [`BumpCar::alloc_slice_fill_with`].
+    ///
+    /// This is the same check as [`BumpCar::can_allocate_array`]. Returns `false` if the size
+    /// of the slice overflows.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(64).unwrap();
+    /// assert!(bumpcar.can_allocate_slice::<u32>(16));
+    /// assert!(!bumpcar.can_allocate_slice::<u32>(17));
+    /// assert!(!bumpcar.can_allocate_slice::<u32>(usize::MAX));
+    /// ```
+    pub fn can_allocate_slice<T>(&self, n: usize) -> bool {
+        self.can_allocate_array::<T>(n)
+    }
+
+    /// Returns how many values of type `T` can be allocated contiguously at the current position.
+    ///
+    /// Returns [`usize::MAX`] for zero-sized types.
+    pub fn remaining_for<T>(&self) -> usize {
+        let Some((aligned, _)) = self.bump(Layout::new::<[T; 0]>()) else {
+            return 0;
+        };
+        (self.end.addr().get() - aligned.addr().get())
+            .checked_div(size_of::<T>())
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Allocates the memory described by `layout`, without checking that it fits in the
+    /// remaining capacity.
+    ///
+    /// This skips the capacity and overflow checks of [`Allocator::allocate`], for the callers
+    /// that have already checked them with [`BumpCar::can_allocate`], such as inner loops
+    /// allocating from a reserved budget. Without the error path, the allocation inlines to a
+    /// few instructions. In debug builds, the capacity is still checked with an assertion.
+    ///
+    /// # Safety
+    /// [`BumpCar::can_allocate`] must return `true` for `layout`.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use core::alloc::Layout;
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(1024).unwrap();
+    /// let layout = Layout::new::<[u64; 8]>();
+    /// while bumpcar.can_allocate(layout) {
+    ///     // SAFETY: the allocation fits in the remaining capacity.
+    ///     let ptr = unsafe { bumpcar.allocate_unchecked(layout) };
+    ///     assert_eq!(ptr.addr().get() % 8, 0);
+    /// }
+    /// assert_eq!(bumpcar.remaining_capacity(), 0);
+    /// ```
+    #[cfg(feature = "unchecked")]
+    #[inline(always)]
+    pub unsafe fn allocate_unchecked(&self, layout: Layout) -> NonNull<u8> {
+        debug_assert!(
+            self.can_allocate(layout),
+            "`allocate_unchecked` called without enough capacity"
+        );
+        let current = self.current.get();
+        let padding = current.addr().get().wrapping_neg() & (layout.align() - 1);
+        // SAFETY: the allocation fits in the remaining capacity, as guaranteed by the caller, so
+        // both pointers stay in bounds of the buffer.
+        let (ptr, new_current) = unsafe {
+            (
+                current.byte_add(padding),
+                current.byte_add(padding + layout.size()),
+            )
+        };
+        #[cfg(feature = "stats")]
+        self.record(|stats| {
+            stats.allocations += 1;
+            stats.bytes_allocated += layout.size();
+            stats.alignment_waste += padding;
+        });
+        #[cfg(feature = "valgrind")]
+        valgrind::alloc(self.start, ptr, layout.size());
+        #[cfg(all(feature = "asan", sanitize = "address"))]
+        asan::alloc(ptr, layout.size());
+        self.current.set(new_current);
+        ptr
+    }
+
+    /// Allocates at least the memory described by `layout`, and returns the whole usable block.
+    ///
+    /// When there is enough capacity left, the size is rounded up to a multiple of the alignment:
+    /// the bytes that would be lost as padding before the next allocation of the same alignment
+    /// can be used by the caller instead.
+    ///
+    /// To be reclaimed by [`Allocator::deallocate`], the block must be deallocated with
+    /// the returned length as its size.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use core::alloc::Layout;
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(64).unwrap();
+    /// let block = bumpcar.allocate_at_least(Layout::from_size_align(5, 4).unwrap()).unwrap();
+    /// assert_eq!(block.len(), 8);
+    /// ```
+    pub fn allocate_at_least(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let padded = layout.pad_to_align();
+        self.allocate(if self.can_allocate(padded) {
+            padded
+        } else {
+            layout
+        })
+    }
+
+    /// Allocates the memory described by `layout`, and returns a pointer to its start.
+    ///
+    /// This is the same allocation as [`Allocator::allocate`], for unsafe code, FFI shims and
+    /// custom containers that do not go through the `Allocator` trait. As with any allocation in
+    /// a [`BumpCar`], the memory is reclaimed by a reset, and is not initialized.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use core::alloc::Layout;
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(64).unwrap();
+    /// let ptr = bumpcar.alloc_layout(Layout::new::<u32>()).unwrap().cast::<u32>();
+    /// // SAFETY: the allocation is valid for a u32.
+    /// unsafe { ptr.write(42) };
+    /// assert_eq!(unsafe { ptr.read() }, 42);
+    /// assert!(bumpcar.alloc_layout(Layout::new::<[u8; 64]>()).is_err());
+    /// ```
+    #[inline]
+    pub fn alloc_layout(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.allocate(layout).map(NonNull::cast)
+    }
+
+    /// Checks wether `ptr` points inside the [`BumpCar`]'s buffer.
+    ///
+    /// Only the address is compared, so this is valid for any pointer. Since the buffer is
+    /// exclusively owned by the [`BumpCar`], a pointer obtained from another allocator can never
+    /// be reported as owned.
+    ///
+    /// The one-past-the-end address is not considered owned, even though zero-sized allocations
+    /// may be placed there: use [`BumpCar::owns_allocation`] to check those.
+    pub fn owns(&self, ptr: NonNull<u8>) -> bool {
+        (self.start.addr()..self.end.addr()).contains(&ptr.addr())
+    }
+
+    /// Checks wether the whole allocation described by `ptr` and `layout` lies inside
+    /// the [`BumpCar`]'s buffer.
+    pub fn owns_allocation(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        ptr.addr() >= self.start.addr()
+            && ptr
+                .addr()
+                .get()
+                .checked_add(layout.size())
+                .is_some_and(|end| end <= self.end.addr().get())
+    }
+
+    /// Deallocates the allocation described by `ptr` and `layout` if it belongs to the [`BumpCar`].
+    ///
+    /// If it is the last allocation made, its space is reclaimed. Returns `false` if the
+    /// allocation does not belong to the [`BumpCar`], in which case nothing is done.
+    ///
+    /// # Safety
+    /// If the allocation belongs to the [`BumpCar`], `ptr` and `layout` must satisfy
+    /// the requirements of [`Allocator::deallocate`].
+    pub unsafe fn try_deallocate(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        if !self.owns_allocation(ptr, layout) {
+            return false;
+        }
+
+        self.resize_in_place(ptr, layout.size(), 0);
+        true
+    }
+
+    /// Resizes the allocation at `ptr` in place, if it is the last allocation made
+    /// and there is enough capacity left.
+    ///
+    /// Allocations in the frozen prefix are never resized. With the `zeroize` feature,
+    /// the reclaimed bytes are overwritten with zeros.
+    ///
+    /// Returns wether the allocation was resized.
+    #[inline]
+    fn resize_in_place(&self, ptr: NonNull<u8>, old_size: usize, new_size: usize) -> bool {
+        let addr = ptr.addr().get();
+        let start = self.start.addr().get();
+        if addr < start + self.floor
+            || addr.checked_add(old_size) != Some(self.current.get().addr().get())
+            || new_size > self.end.addr().get() - addr
+        {
+            return false;
+        }
+
+        if new_size < old_size {
+            self.record_peak();
+            #[cfg(feature = "zeroize")]
+            self.wipe(addr - start + new_size, addr - start + old_size);
+        }
+
+        #[cfg(feature = "valgrind")]
+        valgrind::resize(self.start, ptr, old_size, new_size);
+        #[cfg(all(feature = "asan", sanitize = "address"))]
+        asan::resize(ptr, old_size, new_size);
+
+        // SAFETY: start <= addr <= addr + new_size <= end, so the pointer stays
+        // in bounds of the buffer.
+        self.current
+            .set(unsafe { self.start.byte_add(addr - start + new_size) });
+        true
+    }
+
+    /// Resets the [`BumpCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    ///
+    /// If the [`BumpCar`] is [frozen](BumpCar::freeze), the frozen prefix is kept.
+    ///
+    /// With the `zeroize` feature, the bytes handed out since the last reset are overwritten
+    /// with zeros. With the `debug-poison` feature, they are filled with `0xDD` in debug builds.
+    pub fn reset(&mut self) {
+        #[cfg(feature = "zeroize")]
+        self.wipe(self.reclaim_from(self.floor), self.position());
+        #[cfg(all(feature = "debug-poison", debug_assertions, not(feature = "zeroize")))]
+        self.poison(self.reclaim_from(self.floor), self.position());
+        self.set_position(self.floor);
+        self.next_generation();
+        #[cfg(feature = "stats")]
+        self.record(|stats| stats.resets += 1);
+    }
+
+    /// Invalidates the [tagged handles](TaggedHandle) allocated so far, outside of the frozen
+    /// prefix. This does nothing in release builds.
+    fn next_generation(&self) {
+        #[cfg(debug_assertions)]
+        self.generation.set(self.generation.get().wrapping_add(1));
+    }
+
+    /// Resets the [`BumpCar`] to its initial capacity, including the frozen prefix.
+    pub fn reset_all(&mut self) {
+        self.floor = 0;
+        self.reset();
+    }
+
+    /// Resets the [`BumpCar`], and shrinks its buffer down to `capacity` bytes with the backing
+    /// allocator, to return the memory used by a spike.
+    ///
+    /// The buffer is not shrunk below the size of the frozen prefix, and is left untouched if
+    /// its capacity is already smaller. If the backing allocator fails to shrink it, the
+    /// [`BumpCar`] keeps its capacity.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let mut bumpcar = BumpCar::new(1024).unwrap();
+    /// bumpcar.try_reserve(1024 * 1024).unwrap();
+    /// let spike = bumpcar.alloc_slice_copy(&[0u8; 1024 * 1024]);
+    /// assert_eq!(spike.len(), 1024 * 1024);
+    ///
+    /// bumpcar.reset_and_shrink_to(1024);
+    /// assert_eq!(bumpcar.capacity(), 1024);
+    /// ```
+    pub fn reset_and_shrink_to(&mut self, capacity: usize) {
+        self.reset();
+        let capacity = capacity.max(self.floor);
+        if capacity < self.capacity() {
+            let _ = self.resize_buffer(capacity);
+        }
+    }
+
+    /// Grows the buffer so that at least `additional` more bytes can be allocated, using the
+    /// backing allocator to expand it in place or move it.
+    ///
+    /// Like `Vec::try_reserve`, the capacity is at least doubled
+    /// to amortize successive calls. The used part of the buffer, including the frozen prefix,
+    /// is kept: [handles](BumpCar::alloc_handle) stay valid.
+    ///
+    /// # Errors
+    /// This function returns an error if the new capacity is greater than [`isize::MAX`],
+    /// or if the backing allocator returns an error. The [`BumpCar`] is then left unchanged.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let mut bumpcar = BumpCar::new(64).unwrap();
+    /// let handle = bumpcar.alloc_handle(42u64).unwrap();
+    ///
+    /// bumpcar.try_reserve(1000).unwrap();
+    /// assert!(bumpcar.remaining_capacity() >= 1000);
+    /// // SAFETY: the handle was allocated in this BumpCar, which was not reset.
+    /// assert_eq!(unsafe { *bumpcar.get(handle) }, 42);
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        if self.remaining_capacity() >= additional {
+            return Ok(());
+        }
+        let required = self.position().checked_add(additional).ok_or(AllocError)?;
+        self.resize_buffer(required.max(self.capacity().saturating_mul(2)))
+    }
+
+    /// Freezes the memory allocated so far, so that it is not reclaimed by [`BumpCar::reset`].
+    ///
+    /// The allocations made before freezing are invalidated by the borrow checker: they can be
+    /// accessed afterwards through [handles](BumpCar::alloc_handle).
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let mut bumpcar = BumpCar::new(256).unwrap();
+    /// let table = bumpcar.alloc_handle([1u32, 2, 3, 4]).unwrap();
+    /// bumpcar.freeze();
+    ///
+    /// for _ in 0..10 {
+    ///     let scratch = Box::new_in([0u8; 128], &bumpcar);
+    ///     // SAFETY: the handle lies in the frozen prefix, which is never reset.
+    ///     assert_eq!(unsafe { bumpcar.get(table) }[2], 3);
+    ///     drop(scratch);
+    ///     bumpcar.reset();
+    /// }
+    /// assert_eq!(bumpcar.remaining_capacity(), 240);
+    /// ```
+    pub fn freeze(&mut self) {
+        self.floor = self.position();
+    }
+
+    /// Unfreezes the frozen prefix, so that the next [`BumpCar::reset`] reclaims it.
+    pub fn unfreeze(&mut self) {
+        self.floor = 0;
+    }
+
+    /// Returns the size of the frozen prefix.
+    pub fn frozen_len(&self) -> usize {
+        self.floor
+    }
+
+    /// Overwrites the bytes of the buffer between `start` and `end` with zeros, in a way that
+    /// cannot be optimized out by the compiler.
+    #[cfg(feature = "zeroize")]
+    fn wipe(&self, start: usize, end: usize) {
+        self.expose(start, end);
+        // SAFETY: start <= end <= capacity, so the range is in bounds of the buffer.
+        unsafe { wipe_bytes(self.start.add(start), end - start) };
+    }
+
+    /// Makes the bytes of the buffer between `start` and `end` accessible to the memory
+    /// checkers, before they are overwritten. They are made inaccessible again when the cursor
+    /// is moved back.
+    #[cfg_attr(
+        not(any(feature = "valgrind", all(feature = "asan", sanitize = "address"))),
+        allow(unused_variables)
+    )]
+    #[inline]
+    fn expose(&self, start: usize, end: usize) {
+        // SAFETY: start <= capacity, so the pointer is in bounds of the buffer.
+        #[cfg(any(feature = "valgrind", all(feature = "asan", sanitize = "address")))]
+        let ptr = unsafe { self.start.add(start) };
+        #[cfg(feature = "valgrind")]
+        valgrind::expose(ptr, end - start);
+        #[cfg(all(feature = "asan", sanitize = "address"))]
+        asan::alloc(ptr, end - start);
+    }
+
+    /// Fills the bytes of the buffer between `start` and `end` with a recognizable pattern,
+    /// so that dangling pointers read obviously garbled data.
+    #[cfg(all(feature = "debug-poison", debug_assertions, not(feature = "zeroize")))]
+    fn poison(&self, start: usize, end: usize) {
+        self.expose(start, end);
+        // SAFETY: start <= end <= capacity, so the range is in bounds of the buffer.
+        unsafe { self.start.add(start).write_bytes(POISON, end - start) };
+    }
+
+    /// Create a new checkpoint.
+    ///
+    /// This function allocates the rest of the allocated space into another [`BumpCar`],
+    /// that can used to reset part of the allocated space instead of the whole allocation.
+    ///
+    /// When the checkpoint is dropped, its space is returned to the parent. Checkpoints can be
+    /// nested, to carve scratch regions in recursive algorithms.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// # extern crate alloc;
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let alloc_half = Box::new_in([0u8; 128], &bumpcar);
+    ///
+    /// let mut checkpoint = bumpcar.checkpoint();
+    /// let alloc_rest = Box::new_in([0u8; 64], &checkpoint);
+    /// let alloc_more = Box::new_in([0u8; 64], &checkpoint);
+    /// assert_eq!(checkpoint.remaining_capacity(), 0);
+    ///
+    /// drop((alloc_rest, alloc_more));
+    /// checkpoint.reset();
+    /// assert_eq!(checkpoint.remaining_capacity(), 128);
+    ///
+    /// drop(checkpoint);
+    /// assert_eq!(bumpcar.remaining_capacity(), 128);
+    /// ```
+    pub fn checkpoint(&self) -> BumpCar<&BumpCar<A>> {
+        let align = size_of::<usize>();
+        // SAFETY: align is a power of two, and the cursor is in bounds of the buffer.
+        let aligned = unsafe { next_multiple(self.current.get().addr().get(), align) };
+        let capacity = self.end.addr().get().saturating_sub(aligned) & !(align - 1);
+        BumpCar::new_in(capacity, self).unwrap()
+    }
+
+    /// Carves a child [`SubBump`] of `size` bytes out of the [`BumpCar`].
+    ///
+    /// The child has its own capacity and can be reset independently, while the parent keeps
+    /// allocating from the rest of its buffer. Allocations made in the child are confined to its
+    /// region. The memory of the child returns to the parent when the parent is reset, or when the
+    /// child is dropped if no other allocation was made in the parent in the meantime.
+    ///
+    /// The child borrows the parent, so it is always dropped first, and a long-lived arena can
+    /// hand children out to its subsystems, which can carve grandchildren out of them in turn.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let mut budget = bumpcar.carve(64).unwrap();
+    ///
+    /// assert!(Vec::<u8, _>::try_with_capacity_in(128, &budget).is_err());
+    /// let _v = Vec::<u8, _>::with_capacity_in(128, &bumpcar);
+    /// assert_eq!(budget.remaining_capacity(), 64);
+    /// ```
+    pub fn carve(&self, size: usize) -> Result<SubBump<'_, A>, BumpError> {
+        BumpCar::new_in(size, self)
+    }
+
+    /// Allocates a copy of the [`BumpCar`] and of its contents, in a clone of the backing
+    /// allocator.
+    ///
+    /// The used part of the buffer is copied byte for byte, and the cursor (as well as the frozen
+    /// prefix) is kept, so that the copy can be used to snapshot a state and explore it
+    /// speculatively. [Handles](Handle) are offsets into the buffer, so they stay valid in the
+    /// copy. On the other hand, pointers stored in the arena keep pointing into the original.
+    ///
+    /// # Errors
+    /// This function returns an error if the backing allocator returns an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let board = bumpcar.alloc_handle([0u8; 9]).unwrap();
+    ///
+    /// let mut snapshot = bumpcar.try_clone().unwrap();
+    /// // SAFETY: the handle was allocated in the cloned arena.
+    /// unsafe { snapshot.get_mut(board)[4] = 1 };
+    ///
+    /// assert_eq!(unsafe { bumpcar.get(board) }[4], 0);
+    /// assert_eq!(unsafe { snapshot.get(board) }[4], 1);
+    /// assert_eq!(snapshot.remaining_capacity(), bumpcar.remaining_capacity());
+    /// ```
+    pub fn try_clone(&self) -> Result<Self, BumpError>
+    where
+        A: Clone,
+    {
+        let mut clone =
+            Self::new_in_with_align(self.capacity(), self.align, self.allocator.clone())?;
+        let used = self.position();
+        // SAFETY: the new buffer is at least as big as this one, and aligned the same way, so
+        // that the used prefix fits at its start.
+        let prefix = clone
+            .allocate(unsafe { Layout::from_size_align_unchecked(used, 1) })
+            .map_err(|_| BumpError::new(BumpErrorKind::CapacityExceeded, None, 0))?;
+        // SAFETY: both buffers are valid for used bytes, and do not overlap.
+        unsafe {
+            prefix
+                .cast::<u8>()
+                .copy_from_nonoverlapping(self.start, used);
+        }
+        clone.floor = self.floor;
+        clone.peak.set(self.peak.get());
+        #[cfg(debug_assertions)]
+        clone.generation.set(self.generation.get());
+        #[cfg(feature = "stats")]
+        clone.stats.set(self.stats.get());
+        #[cfg(feature = "profiling")]
+        clone.profile.replace(self.profile());
+        Ok(clone)
+    }
+
+    /// Allocates a new, empty [`BumpCar`] with the same capacity and buffer alignment, in a clone
+    /// of the backing allocator.
+    ///
+    /// The contents of the current [`BumpCar`] are **not** copied: the new one starts with its
+    /// full capacity available.
+    ///
+    /// # Errors
+    /// This function returns an error if the backing allocator returns an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let prototype = BumpCar::new(256).unwrap();
+    /// let sibling = prototype.try_clone_empty().unwrap();
+    /// assert_eq!(sibling.capacity(), prototype.capacity());
+    /// ```
+    pub fn try_clone_empty(&self) -> Result<Self, BumpError>
+    where
+        A: Clone,
+    {
+        Self::new_in_with_align(self.capacity(), self.align, self.allocator.clone())
+    }
+
+    /// Leaks the [`BumpCar`], so that it lives (and allocates) for the rest of the program.
+    ///
+    /// The [`BumpCar`] moves into its own buffer, which is never deallocated: this costs
+    /// `size_of::<BumpCar<A>>()` bytes of its capacity, but does not require any other allocator.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left to hold itself.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// thread_local! {
+    ///     static STRINGS: &'static BumpCar = BumpCar::new(4096).unwrap().leak();
+    /// }
+    ///
+    /// fn intern(s: &str) -> &'static str {
+    ///     STRINGS.with(|bumpcar| bumpcar.alloc_str(s))
+    /// }
+    ///
+    /// let keywords = [intern("fn"), intern("let")];
+    /// assert_eq!(keywords, ["fn", "let"]);
+    /// ```
+    pub fn leak(self) -> &'static BumpCar<A>
+    where
+        A: 'static,
+    {
+        let slot = Allocator::allocate(&self, Layout::new::<Self>())
+            .expect("not enough capacity to leak the BumpCar")
+            .cast::<Self>();
+        // SAFETY: the slot is valid for a BumpCar, and moving the BumpCar does not move its
+        // buffer. It is never dropped, so the slot is never reclaimed.
+        unsafe {
+            slot.write(self);
+            slot.as_ref()
+        }
+    }
+
+    /// Returns the [`BumpCar`] as a `&dyn Allocator`.
+    ///
+    /// Code that is not performance critical can take a `&dyn Allocator` instead of being
+    /// generic over the arena and its backing allocator, which avoids monomorphizing it for every
+    /// allocator type. Each allocation then goes through a virtual call, and can not be inlined:
+    /// in the `allocate` benchmarks, boxing values through `&dyn Allocator` takes about twice as
+    /// long as with the generic allocator, so hot paths should stay generic.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Allocator;
+    /// use dodgems::BumpCar;
+    ///
+    /// fn build(allocator: &dyn Allocator) -> Vec<u32, &dyn Allocator> {
+    ///     let mut v = Vec::new_in(allocator);
+    ///     v.extend([1, 2, 3]);
+    ///     v
+    /// }
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// assert_eq!(build(bumpcar.as_dyn()), [1, 2, 3]);
+    /// ```
+    pub fn as_dyn(&self) -> &dyn Allocator {
+        self
+    }
+}
+
+/// Child arena carved out of a parent [`BumpCar`] with [`BumpCar::carve`].
+pub type SubBump<'a, #[cfg(feature = "alloc")] A = Global, #[cfg(not(feature = "alloc"))] A> =
+    BumpCar<&'a BumpCar<A>>;
+
+#[cfg(feature = "alloc")]
+impl BumpCar {
+    /// Allocates a [`BumpCar`] with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity (or its nearest pointer-aligned multiple)
+    /// is greater than [`isize::MAX`], or if the global returns an error.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, Global)
+    }
+
+    /// Allocates a [`BumpCar`] with the Global allocator, with a buffer aligned to `align`.
+    ///
+    /// # Errors
+    /// This function returns an error if `align` is not a power of two, if the capacity (or the
+    /// nearest multiple of the alignment) is greater than [`isize::MAX`], or if the global
+    /// allocator returns an error.
+    pub fn new_with_align(capacity: usize, align: usize) -> Result<Self, BumpError> {
+        Self::new_in_with_align(capacity, align, Global)
+    }
+
+    /// Allocates a [`BumpCar`] with the Global allocator, with its capacity rounded up to a
+    /// multiple of `granularity` (see [`BumpCar::new_in_rounded`]).
+    ///
+    /// # Errors
+    /// This function returns an error if `granularity` is not a power of two, if the rounded
+    /// capacity is greater than [`isize::MAX`], or if the global allocator returns an error.
+    pub fn new_rounded(capacity: usize, granularity: usize) -> Result<Self, BumpError> {
+        Self::new_in_rounded(capacity, granularity, Global)
+    }
+
+    /// Allocates a [`BumpCar`] with a zeroed buffer from the Global allocator (see
+    /// [`BumpCar::new_zeroed_in`]).
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity (or its nearest pointer-aligned multiple)
+    /// is greater than [`isize::MAX`], or if the global returns an error.
+    pub fn new_zeroed(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_zeroed_in(capacity, Global)
+    }
+
+    /// Allocates a [`BumpCar`] with the Global allocator, and writes to every page of its
+    /// buffer (see [`BumpCar::new_in_prefaulted`]).
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity (or its nearest pointer-aligned multiple)
+    /// is greater than [`isize::MAX`], or if the global returns an error.
+    pub fn new_prefaulted(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in_prefaulted(capacity, Global)
+    }
+}
+
+impl<A: Allocator> Drop for BumpCar<A> {
+    /// Deallocates the [`BumpCar`]'s buffer.
+    ///
+    /// With the `zeroize` feature, the used part of the buffer is overwritten with zeros first.
+    /// With the `debug-poison` feature, it is filled with `0xDD` in debug builds.
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Shows the usage of the [`BumpCar`] and its backing allocator.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::BumpCar;
+///
+/// let bumpcar = BumpCar::new(256).unwrap();
+/// bumpcar.alloc(0u64);
+/// assert_eq!(
+///     format!("{bumpcar:?}"),
+///     "BumpCar { capacity: 256, used: 8, remaining: 248, frozen: 0, allocator: Global }"
+/// );
+/// ```
+impl<A: Allocator + fmt::Debug> fmt::Debug for BumpCar<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BumpCar")
+            .field("capacity", &self.capacity())
+            .field("used", &self.used())
+            .field("remaining", &self.remaining_capacity())
+            .field("frozen", &self.floor)
+            .field("allocator", &self.allocator)
+            .finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<A: Allocator> zeroize::Zeroize for BumpCar<A> {
+    /// Overwrites the whole buffer with zeros (not only the used part) and resets the [`BumpCar`].
+    fn zeroize(&mut self) {
+        self.wipe(self.reclaim_from(0), self.capacity());
+        self.floor = 0;
+        self.set_position(0);
+        self.next_generation();
+    }
+}
+
+/// Allocations are usually made through a shared reference, but an owned [`BumpCar`] can also
+/// be used as the allocator of a collection, that then owns its arena. To share the arena between
+/// several owners, wrap it in an `Rc`, that is an allocator as well.
+///
+/// The buffer does not move with the [`BumpCar`], so allocations stay valid until it is dropped.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::BumpCar;
+///
+/// fn make_list() -> Vec<u32, BumpCar> {
+///     let mut v = Vec::with_capacity_in(4, BumpCar::new(64).unwrap());
+///     v.extend([1, 2, 3, 4]);
+///     v
+/// }
+///
+/// let v = make_list();
+/// assert_eq!(v.allocator().remaining_capacity(), 48);
+/// ```
+unsafe impl<A: Allocator> Allocator for BumpCar<A> {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let Some((ptr, new_current)) = self.bump(layout) else {
+            return Err(self.allocation_failed());
+        };
+        #[cfg(feature = "stats")]
+        self.record(|stats| {
+            stats.allocations += 1;
+            stats.bytes_allocated += layout.size();
+            stats.alignment_waste += ptr.addr().get() - self.current.get().addr().get();
+        });
+        #[cfg(feature = "valgrind")]
+        valgrind::alloc(self.start, ptr, layout.size());
+        #[cfg(all(feature = "asan", sanitize = "address"))]
+        asan::alloc(ptr, layout.size());
+        self.current.set(new_current);
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    /// Allocates a zeroed region.
+    ///
+    /// Only the bytes handed out are zeroed, not the alignment padding before them. In a
+    /// [zeroed](BumpCar::new_zeroed_in) [`BumpCar`], regions that were never handed out are
+    /// already zeroed, and are not written to.
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = self.allocate(layout)?;
+        if ptr.cast::<u8>().addr().get() - self.start.addr().get() < self.pristine.get() {
+            // SAFETY: the allocation is valid for layout.size() bytes.
+            unsafe { ptr.cast::<u8>().write_bytes(0, layout.size()) };
+        }
+        Ok(ptr)
+    }
+
+    /// Deallocates an allocated region.
+    ///
+    /// If it is the last allocation made, its space is reclaimed. Otherwise, the [`BumpCar`] does
+    /// not perform deallocation unless it's reset or dropped.
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.resize_in_place(ptr, layout.size(), 0);
+    }
+
+    /// Grows an allocated region.
+    ///
+    /// If it is the last allocation made, it is extended in place when there is enough capacity
+    /// left. Otherwise, a new region is allocated and the data is copied into it.
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        debug_assert!(
+            new_layout.size() >= old_layout.size(),
+            "`new_layout.size()` must be greater than or equal to `old_layout.size()`"
+        );
+        #[cfg(feature = "stats")]
+        self.record(|stats| stats.grows += 1);
+        if ptr.addr().get() & (new_layout.align() - 1) == 0
+            && self.resize_in_place(ptr, old_layout.size(), new_layout.size())
+        {
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+        // SAFETY: the allocation is valid for old_layout.size() <= new_layout.size() bytes.
+        unsafe { self.reallocate(ptr, old_layout.size(), new_layout) }
+    }
+
+    /// Grows an allocated region, and zeroes the new bytes.
+    ///
+    /// Like [`BumpCar::grow`](Allocator::grow), the region is extended in place if possible.
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: the caller upholds the contract of Allocator::grow_zeroed.
+        let new_ptr = unsafe { self.grow(ptr, old_layout, new_layout) }?;
+        // SAFETY: the new region is valid for new_layout.size() bytes.
+        unsafe {
+            new_ptr
+                .cast::<u8>()
+                .add(old_layout.size())
+                .write_bytes(0, new_layout.size() - old_layout.size());
+        }
+        Ok(new_ptr)
+    }
+
+    /// Shrinks an allocated region.
+    ///
+    /// If it is the last allocation made, the freed tail is reclaimed.
+    ///
+    /// If the new layout has a greater alignment that the current pointer does not satisfy,
+    /// a new region is allocated and the data is copied into it.
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        debug_assert!(
+            new_layout.size() <= old_layout.size(),
+            "`new_layout.size()` must be smaller than or equal to `old_layout.size()`"
+        );
+        #[cfg(feature = "stats")]
+        self.record(|stats| stats.shrinks += 1);
+        if ptr.addr().get() & (new_layout.align() - 1) == 0 {
+            self.resize_in_place(ptr, old_layout.size(), new_layout.size());
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+
+        if !self.can_allocate(new_layout) {
+            return Err(AllocError);
+        }
+        // SAFETY: the allocation is valid for new_layout.size() <= old_layout.size() bytes.
+        unsafe { self.reallocate(ptr, new_layout.size(), new_layout) }
     }
 }
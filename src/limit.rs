@@ -0,0 +1,149 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::ptr::NonNull;
+
+/// Allocator wrapper enforcing a byte budget on top of an inner allocator.
+///
+/// Allocations that would bring the live bytes above the limit fail, without reaching the inner
+/// allocator. Deallocating or shrinking returns the bytes to the budget.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::{BumpCar, LimitCar};
+///
+/// let bumpcar = BumpCar::new(1024).unwrap();
+/// let sandbox = LimitCar::new(&bumpcar, 100);
+///
+/// let v = Vec::<u8, _>::with_capacity_in(64, &sandbox);
+/// assert!(Vec::<u8, _>::try_with_capacity_in(64, &sandbox).is_err());
+/// assert_eq!(sandbox.consumed(), 64);
+/// assert!(bumpcar.remaining_capacity() > 900);
+/// ```
+#[derive(Debug)]
+pub struct LimitCar<A> {
+    inner: A,
+    limit: usize,
+    consumed: Cell<usize>,
+    peak: Cell<usize>,
+}
+
+impl<A: Allocator> LimitCar<A> {
+    /// Wraps `inner`, allowing at most `limit` live bytes to be allocated through the wrapper.
+    pub const fn new(inner: A, limit: usize) -> Self {
+        Self {
+            inner,
+            limit,
+            consumed: Cell::new(0),
+            peak: Cell::new(0),
+        }
+    }
+
+    /// Returns the byte budget.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns the number of bytes currently allocated through the wrapper.
+    pub fn consumed(&self) -> usize {
+        self.consumed.get()
+    }
+
+    /// Returns the number of bytes left in the budget.
+    pub fn remaining(&self) -> usize {
+        self.limit - self.consumed.get()
+    }
+
+    /// Returns the highest number of bytes allocated at once through the wrapper.
+    pub fn peak(&self) -> usize {
+        self.peak.get()
+    }
+
+    /// Returns a reference to the inner allocator.
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Unwraps the inner allocator.
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+
+    /// Reserves `size` bytes of the budget.
+    fn charge(&self, size: usize) -> Result<(), AllocError> {
+        if size > self.remaining() {
+            return Err(AllocError);
+        }
+        let consumed = self.consumed.get() + size;
+        self.consumed.set(consumed);
+        self.peak.set(self.peak.get().max(consumed));
+        Ok(())
+    }
+
+    /// Returns `size` bytes to the budget.
+    fn refund(&self, size: usize) {
+        self.consumed.set(self.consumed.get() - size);
+    }
+
+    /// Charges `size` bytes, and refunds them if `f` fails.
+    fn charged<T>(
+        &self,
+        size: usize,
+        f: impl FnOnce() -> Result<T, AllocError>,
+    ) -> Result<T, AllocError> {
+        self.charge(size)?;
+        f().inspect_err(|_| self.refund(size))
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for LimitCar<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.charged(layout.size(), || self.inner.allocate(layout))
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.charged(layout.size(), || self.inner.allocate_zeroed(layout))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.refund(layout.size());
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.inner.deallocate(ptr, layout) }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.charged(new_layout.size() - old_layout.size(), || {
+            // SAFETY: guaranteed by the caller.
+            unsafe { self.inner.grow(ptr, old_layout, new_layout) }
+        })
+    }
+
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.charged(new_layout.size() - old_layout.size(), || {
+            // SAFETY: guaranteed by the caller.
+            unsafe { self.inner.grow_zeroed(ptr, old_layout, new_layout) }
+        })
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        let new_ptr = unsafe { self.inner.shrink(ptr, old_layout, new_layout) }?;
+        self.refund(old_layout.size() - new_layout.size());
+        Ok(new_ptr)
+    }
+}
@@ -0,0 +1,129 @@
+/// Creates a slice in a [`BumpCar`](crate::BumpCar), with the same syntax as [`vec!`].
+///
+/// The arena expression is given first, followed by a semicolon. The result is a mutable slice
+/// borrowed from the arena.
+///
+/// # Panics
+/// This macro panics if the arena does not have enough capacity left.
+///
+/// # Example
+/// ```rust
+/// use dodgems::{bump_vec, BumpCar};
+///
+/// let bumpcar = BumpCar::new(256).unwrap();
+/// let a = bump_vec![in &bumpcar; 1, 2, 3];
+/// let b = bump_vec![in &bumpcar; String::from("x"); 2];
+/// assert_eq!(a, [1, 2, 3]);
+/// assert_eq!(b, ["x", "x"]);
+/// # b.iter_mut().for_each(|s| drop(std::mem::take(s)));
+/// ```
+#[macro_export]
+macro_rules! bump_vec {
+    (in $bumpcar:expr; $elem:expr; $n:expr) => {{
+        let elem = $elem;
+        $crate::BumpCar::alloc_slice_fill_with($bumpcar, $n, |_| ::core::clone::Clone::clone(&elem))
+    }};
+    (in $bumpcar:expr; $($x:expr),* $(,)?) => {{
+        let slice: &mut [_] = $crate::BumpCar::alloc_with($bumpcar, || [$($x),*]);
+        slice
+    }};
+}
+
+/// Formats a string in a [`BumpCar`](crate::BumpCar), with the same syntax as [`format!`].
+///
+/// The arena expression is given first, followed by a comma. The result is a string slice
+/// borrowed from the arena.
+///
+/// # Panics
+/// This macro panics if the arena does not have enough capacity left.
+///
+/// # Example
+/// ```rust
+/// use dodgems::{bump_format, BumpCar};
+///
+/// let bumpcar = BumpCar::new(256).unwrap();
+/// let x = 3;
+/// let s = bump_format!(in &bumpcar, "x = {}, y = {y}", x, y = x * 2);
+/// assert_eq!(s, "x = 3, y = 6");
+/// ```
+#[macro_export]
+macro_rules! bump_format {
+    (in $bumpcar:expr, $($arg:tt)*) => {
+        $crate::BumpCar::alloc_fmt($bumpcar, ::core::format_args!($($arg)*))
+    };
+}
+
+/// Runs a block with a new [`BumpCar`](crate::BumpCar) of the given capacity as the current
+/// arena of the thread, for the free functions of the [`ambient`](crate::ambient) module.
+///
+/// The block is run inside of a closure: `return` and `?` exit the block, not the enclosing
+/// function.
+///
+/// # Panics
+/// This macro panics if the arena could not be allocated.
+///
+/// # Example
+/// ```rust
+/// use dodgems::{ambient, with_bump};
+///
+/// let answer = with_bump!(256, {
+///     let x = ambient::alloc(40);
+///     let y = with_bump!(64, { *ambient::alloc(2) });
+///     *x + y
+/// });
+/// assert_eq!(answer, 42);
+/// assert!(ambient::current().is_none());
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! with_bump {
+    ($capacity:expr, $body:block) => {
+        $crate::ambient::with_capacity($capacity, || $body)
+    };
+}
+
+/// Declares a static arena of the given capacity, and returns it as a
+/// [`BufferBumpCar<'static>`](crate::BufferBumpCar) the first time it is evaluated, or `None`
+/// afterwards.
+///
+/// Unlike a [`StaticBumpCar`](crate::StaticBumpCar), the buffer can be placed in a named linker
+/// section with `section = "..."`, to choose the RAM bank it lives in (external SRAM, DTCM, a
+/// `.noinit` section that is not zeroed at startup...). The flag that records whether the
+/// arena was taken stays in the default section.
+///
+/// Every invocation of the macro declares its own arena.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::{static_bumpcar, BufferBumpCar};
+///
+/// fn scratch() -> Option<BufferBumpCar<'static>> {
+///     static_bumpcar!(1024)
+/// }
+///
+/// let mut bumpcar = scratch().unwrap();
+/// assert!(scratch().is_none());
+///
+/// let v = Box::new_in([1u32, 2, 3], &bumpcar);
+/// drop(v);
+/// bumpcar.reset();
+/// assert_eq!(bumpcar.remaining_capacity(), 1024);
+/// ```
+///
+/// On an embedded target, the buffer can be placed in a dedicated section:
+/// ```rust,ignore
+/// let bumpcar = static_bumpcar!(64 * 1024, section = ".dtcm").unwrap();
+/// ```
+#[cfg(not(loom))]
+#[macro_export]
+macro_rules! static_bumpcar {
+    ($capacity:expr $(, section = $section:literal)? $(,)?) => {{
+        $(#[link_section = $section])?
+        static BUFFER: $crate::StaticBuffer<{ $capacity }> = $crate::StaticBuffer::new();
+        static TAKEN: ::core::sync::atomic::AtomicBool =
+            ::core::sync::atomic::AtomicBool::new(false);
+        // SAFETY: TAKEN is the take flag of BUFFER, and of no other buffer.
+        unsafe { BUFFER.take(&TAKEN) }
+    }};
+}
@@ -0,0 +1,122 @@
+use core::alloc::Allocator;
+
+use crate::BumpCar;
+
+/// Saved position of the cursor of a [`BumpCar`], created with [`BumpCar::marker`].
+///
+/// Markers are ordered by position: a marker taken later compares greater, unless the
+/// [`BumpCar`] was rewound in between.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Marker {
+    position: usize,
+    #[cfg(feature = "stats")]
+    allocations: usize,
+}
+
+impl Marker {
+    /// Returns the offset of the marker from the start of the buffer.
+    pub fn offset(self) -> usize {
+        self.position
+    }
+}
+
+impl<A: Allocator> BumpCar<A> {
+    /// Saves the current position of the cursor, to roll the [`BumpCar`] back to it later
+    /// with [`BumpCar::rewind`] or [`BumpCar::rewind_to`].
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let mut bumpcar = BumpCar::new(256).unwrap();
+    /// let table = bumpcar.alloc_handle([1u32, 2, 3, 4]).unwrap();
+    /// let marker = bumpcar.marker();
+    ///
+    /// for i in 0..10 {
+    ///     let scratch = bumpcar.alloc([i as u8; 128]);
+    ///     assert_eq!(scratch[0], i as u8);
+    ///     bumpcar.rewind(marker);
+    /// }
+    /// assert_eq!(bumpcar.remaining_capacity(), 240);
+    /// // SAFETY: the handle was allocated before the marker.
+    /// assert_eq!(unsafe { bumpcar.get(table) }[3], 4);
+    /// ```
+    pub fn marker(&self) -> Marker {
+        Marker {
+            position: self.position(),
+            #[cfg(feature = "stats")]
+            allocations: self.stats().allocations,
+        }
+    }
+
+    /// Returns the number of bytes allocated since `marker` was taken, including the alignment
+    /// padding, to measure the memory used by each phase of a computation.
+    ///
+    /// This is the distance the cursor moved since the marker: regions reclaimed since then
+    /// are not counted, and it is 0 if the [`BumpCar`] was reset or rewound before the marker.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(1024).unwrap();
+    /// let parsing = bumpcar.marker();
+    /// bumpcar.alloc_slice_copy(&[0u8; 100]);
+    /// let lowering = bumpcar.marker();
+    /// bumpcar.alloc([0u64; 40]);
+    ///
+    /// assert_eq!(bumpcar.bytes_allocated_since(lowering), 324);
+    /// assert_eq!(bumpcar.bytes_allocated_since(parsing), 424);
+    /// ```
+    pub fn bytes_allocated_since(&self, marker: Marker) -> usize {
+        self.position().saturating_sub(marker.position)
+    }
+
+    /// Returns the number of successful allocations made since `marker` was taken, counted
+    /// like [`Stats::allocations`](crate::Stats::allocations).
+    ///
+    /// It is 0 if the counters were cleared with [`BumpCar::reset_stats`] since.
+    #[cfg(feature = "stats")]
+    pub fn allocations_since(&self, marker: Marker) -> usize {
+        self.stats().allocations.saturating_sub(marker.allocations)
+    }
+
+    /// Rolls the cursor back to `marker`, reclaiming every allocation made since.
+    ///
+    /// This requires a mutable reference, so that the allocations made with &self are
+    /// invalidated by the borrow checker.
+    ///
+    /// With the `zeroize` feature, the reclaimed bytes are overwritten with zeros.
+    ///
+    /// # Panics
+    /// This function panics if the marker is ahead of the cursor, or inside the
+    /// [frozen](BumpCar::freeze) prefix.
+    pub fn rewind(&mut self, marker: Marker) {
+        // SAFETY: the mutable borrow guarantees that no allocation is still in use.
+        unsafe { self.rewind_to(marker) }
+    }
+
+    /// Rolls the cursor back to `marker`, reclaiming every allocation made since.
+    ///
+    /// With the `zeroize` feature, the reclaimed bytes are overwritten with zeros.
+    ///
+    /// # Safety
+    /// The allocations made after `marker` was created must not be used afterwards,
+    /// and `marker` must have been created by this [`BumpCar`].
+    ///
+    /// # Panics
+    /// This function panics if the marker is ahead of the cursor, or inside the
+    /// [frozen](BumpCar::freeze) prefix.
+    pub unsafe fn rewind_to(&self, marker: Marker) {
+        let position = self.position();
+        assert!(
+            self.floor <= marker.position && marker.position <= position,
+            "invalid BumpCar marker"
+        );
+        #[cfg(feature = "zeroize")]
+        self.wipe(self.reclaim_from(marker.position), position);
+        self.set_position(marker.position);
+    }
+}
@@ -0,0 +1,82 @@
+use core::alloc::Allocator;
+
+use metrics::{describe_counter, describe_gauge, gauge, SharedString, Unit};
+
+use crate::BumpCar;
+
+impl<A: Allocator> BumpCar<A> {
+    /// Publishes the usage of the [`BumpCar`] through the [`metrics`] facade, with an `arena`
+    /// label set to `arena`.
+    ///
+    /// The gauges `dodgems_capacity_bytes`, `dodgems_used_bytes` and `dodgems_peak_bytes` are
+    /// always published. With the `stats` feature, the counters `dodgems_allocations_total`,
+    /// `dodgems_failed_allocations_total`, `dodgems_allocated_bytes_total` and
+    /// `dodgems_resets_total` are published as well, set to the values of [`BumpCar::stats`].
+    ///
+    /// It can be called on demand (for example when an arena is reset, or at the end of a
+    /// frame), or periodically from a timer. [`describe_metrics`](crate::describe_metrics)
+    /// registers the descriptions and units of the metrics.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let mut bumpcar = BumpCar::new(1024).unwrap();
+    /// for _ in 0..10 {
+    ///     bumpcar.alloc_slice_copy(b"request");
+    ///     bumpcar.publish_metrics("requests");
+    ///     bumpcar.reset();
+    /// }
+    /// ```
+    pub fn publish_metrics(&self, arena: impl Into<SharedString>) {
+        let arena = arena.into();
+        gauge!("dodgems_capacity_bytes", "arena" => arena.clone()).set(self.capacity() as f64);
+        gauge!("dodgems_used_bytes", "arena" => arena.clone()).set(self.used() as f64);
+        gauge!("dodgems_peak_bytes", "arena" => arena.clone()).set(self.peak_usage() as f64);
+
+        #[cfg(feature = "stats")]
+        {
+            let stats = self.stats();
+            metrics::counter!("dodgems_allocations_total", "arena" => arena.clone())
+                .absolute(stats.allocations as u64);
+            metrics::counter!("dodgems_failed_allocations_total", "arena" => arena.clone())
+                .absolute(stats.failed_allocations as u64);
+            metrics::counter!("dodgems_allocated_bytes_total", "arena" => arena.clone())
+                .absolute(stats.bytes_allocated as u64);
+            metrics::counter!("dodgems_resets_total", "arena" => arena)
+                .absolute(stats.resets as u64);
+        }
+    }
+}
+
+/// Registers the descriptions and units of the metrics published by
+/// [`BumpCar::publish_metrics`] with the installed recorder.
+pub fn describe_metrics() {
+    describe_gauge!(
+        "dodgems_capacity_bytes",
+        Unit::Bytes,
+        "Capacity of the arena"
+    );
+    describe_gauge!("dodgems_used_bytes", Unit::Bytes, "Bytes used in the arena");
+    describe_gauge!(
+        "dodgems_peak_bytes",
+        Unit::Bytes,
+        "Highest number of bytes used in the arena since its peak was last reset"
+    );
+    describe_counter!(
+        "dodgems_allocations_total",
+        Unit::Count,
+        "Successful allocations in the arena"
+    );
+    describe_counter!(
+        "dodgems_failed_allocations_total",
+        Unit::Count,
+        "Allocations that failed for lack of capacity in the arena"
+    );
+    describe_counter!(
+        "dodgems_allocated_bytes_total",
+        Unit::Bytes,
+        "Bytes handed out by the arena"
+    );
+    describe_counter!("dodgems_resets_total", Unit::Count, "Resets of the arena");
+}
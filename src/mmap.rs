@@ -0,0 +1,463 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::ptr::NonNull;
+
+use crate::{BumpCar, Handle, SliceHandle};
+
+/// Returns the size of a memory page of the system, to round the capacity of an arena with
+/// [`BumpCar::new_in_rounded`].
+pub fn page_size() -> usize {
+    // SAFETY: sysconf has no safety requirements.
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    usize::try_from(size).unwrap_or(4096)
+}
+
+/// Size of the huge pages requested by [`Mmap::with_huge_pages`].
+const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+/// Rounds `size` up to a multiple of the page size.
+pub(crate) fn round_to_pages(size: usize) -> Option<usize> {
+    let mask = page_size() - 1;
+    Some(size.checked_add(mask)? & !mask)
+}
+
+/// Allocator mapping every allocation directly from the operating system with `mmap`.
+///
+/// It is meant to back large arenas, such as a `BumpCar<Mmap>`: their buffer does not go through
+/// the general purpose heap, and its pages are only faulted in when they are first written to.
+/// Dropping the arena unmaps the buffer.
+///
+/// Allocations are aligned to the page size; a greater alignment can not be satisfied.
+///
+/// With the `guard-pages` feature, every mapping is followed by an inaccessible guard page, and
+/// allocations are placed right before it (up to their alignment), so that writing past the end
+/// of an arena faults immediately instead of corrupting other memory.
+///
+/// For very large arenas, [`Mmap::with_huge_pages`] requests huge pages to reduce TLB misses.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::{BumpCar, Mmap};
+///
+/// let mut bumpcar = BumpCar::new_in(256 * 1024 * 1024, Mmap::new()).unwrap();
+/// let v = bumpcar.alloc_slice_copy(&[1u8; 4096]);
+/// assert_eq!(v.len(), 4096);
+/// bumpcar.reset_and_release();
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Mmap {
+    huge_pages: bool,
+    node: Option<u8>,
+    populate: bool,
+}
+
+impl Mmap {
+    /// Creates a new [`Mmap`] allocator, mapping regular pages.
+    pub const fn new() -> Self {
+        Self {
+            huge_pages: false,
+            node: None,
+            populate: false,
+        }
+    }
+
+    /// Creates a new [`Mmap`] allocator, requesting huge pages for its mappings.
+    ///
+    /// Mappings are rounded up to a multiple of 2MB. On Linux, explicit huge pages
+    /// (`MAP_HUGETLB`) are used if some are available, and transparent huge pages
+    /// (`MADV_HUGEPAGE`) otherwise. If neither is available, or on other systems,
+    /// regular pages are used.
+    ///
+    /// Explicit huge pages are never used with the `guard-pages` feature, since the guard page
+    /// could not be protected on its own.
+    pub const fn with_huge_pages() -> Self {
+        Self {
+            huge_pages: true,
+            node: None,
+            populate: false,
+        }
+    }
+
+    /// Returns `true` if the [`Mmap`] allocator requests huge pages.
+    pub const fn huge_pages(&self) -> bool {
+        self.huge_pages
+    }
+
+    /// Binds the pages mapped by the [`Mmap`] allocator to the NUMA node `node`, so that an
+    /// arena is backed by memory local to the threads running on it.
+    ///
+    /// This is only supported on Linux, for the first 64 nodes. If the node does not exist,
+    /// or on systems without NUMA support, the memory is not bound to any node.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::{BumpCar, Mmap};
+    ///
+    /// let bumpcar = BumpCar::new_in(1024 * 1024, Mmap::new().bound_to_node(0)).unwrap();
+    /// assert_eq!(*bumpcar.alloc(42u32), 42);
+    /// ```
+    pub const fn bound_to_node(self, node: u8) -> Self {
+        Self {
+            node: Some(node),
+            ..self
+        }
+    }
+
+    /// Returns the NUMA node the [`Mmap`] allocator binds its pages to, if any.
+    pub const fn node(&self) -> Option<u8> {
+        self.node
+    }
+
+    /// Prefaults the pages mapped by the [`Mmap`] allocator, so that an arena never takes a page
+    /// fault at first touch.
+    ///
+    /// On Linux, the mappings are populated by the kernel with `MAP_POPULATE`. On other systems,
+    /// every page is written to after it is mapped.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::{BumpCar, Mmap};
+    ///
+    /// let bumpcar = BumpCar::new_in(16 * 1024 * 1024, Mmap::new().prefaulted()).unwrap();
+    /// assert_eq!(*bumpcar.alloc(42u32), 42);
+    /// ```
+    pub const fn prefaulted(self) -> Self {
+        Self {
+            populate: true,
+            ..self
+        }
+    }
+
+    /// Returns `true` if the [`Mmap`] allocator prefaults its pages.
+    pub const fn is_prefaulted(&self) -> bool {
+        self.populate
+    }
+
+    /// Binds the `len` bytes at `ptr` to the NUMA node of the allocator, if any.
+    ///
+    /// Failure is ignored, and leaves the memory unbound.
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+    fn bind(&self, ptr: NonNull<u8>, len: usize) {
+        #[cfg(target_os = "linux")]
+        if let Some(node) = self.node.filter(|&node| node < 64) {
+            const MPOL_BIND: libc::c_long = 2;
+            let mask: u64 = 1 << node;
+            // SAFETY: the range is a mapping created by the allocator, and mask is valid for
+            // reads of 64 bits.
+            unsafe {
+                libc::syscall(
+                    libc::SYS_mbind,
+                    ptr.as_ptr(),
+                    len,
+                    MPOL_BIND,
+                    &raw const mask,
+                    64 + 1,
+                    0,
+                )
+            };
+        }
+    }
+
+    /// Rounds `size` up to the length of the mapping holding it, without the guard page.
+    fn mapping_len(&self, size: usize) -> Option<usize> {
+        if self.huge_pages {
+            Some(size.checked_add(HUGE_PAGE_SIZE - 1)? & !(HUGE_PAGE_SIZE - 1))
+        } else {
+            round_to_pages(size)
+        }
+    }
+
+    /// Writes to every page of the `len` bytes at `ptr`.
+    fn prefault(ptr: NonNull<u8>, len: usize) {
+        for offset in (0..len).step_by(page_size()) {
+            // SAFETY: offset < len, so the byte is in bounds of the new mapping.
+            unsafe { ptr.add(offset).write_volatile(0) };
+        }
+    }
+
+    /// Returns `MAP_POPULATE` if the kernel can prefault the mappings, or 0 if the pages must be
+    /// written to once they are bound to their NUMA node, or on other systems.
+    fn populate_flag(&self) -> libc::c_int {
+        #[cfg(target_os = "linux")]
+        if self.populate && self.node.is_none() {
+            return libc::MAP_POPULATE;
+        }
+        0
+    }
+
+    /// Maps `len` bytes of anonymous memory, with the given extra flags.
+    fn map(len: usize, flags: libc::c_int) -> Option<NonNull<u8>> {
+        // SAFETY: this creates a new private anonymous mapping, with no requirements.
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | flags,
+                -1,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return None;
+        }
+        NonNull::new(ptr.cast::<u8>())
+    }
+}
+
+unsafe impl Allocator for Mmap {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout.align() > page_size() {
+            return Err(AllocError);
+        }
+        if layout.size() == 0 {
+            return Ok(NonNull::slice_from_raw_parts(layout.dangling_ptr(), 0));
+        }
+        let len = self.mapping_len(layout.size()).ok_or(AllocError)?;
+        #[cfg(feature = "guard-pages")]
+        let map_len = len.checked_add(page_size()).ok_or(AllocError)?;
+        #[cfg(not(feature = "guard-pages"))]
+        let map_len = len;
+
+        let populate = self.populate_flag();
+        #[cfg(all(target_os = "linux", not(feature = "guard-pages")))]
+        let huge = if self.huge_pages {
+            Self::map(map_len, libc::MAP_HUGETLB | populate)
+        } else {
+            None
+        };
+        #[cfg(not(all(target_os = "linux", not(feature = "guard-pages"))))]
+        let huge = None;
+        let (ptr, populated) = match huge {
+            Some(ptr) => (ptr, populate != 0),
+            None => {
+                // Pages populated at once would not be transparent huge pages.
+                let flags = if self.huge_pages { 0 } else { populate };
+                let ptr = Self::map(map_len, flags).ok_or(AllocError)?;
+                #[cfg(target_os = "linux")]
+                if self.huge_pages {
+                    // SAFETY: the range is in bounds of the new mapping. Failure is ignored,
+                    // and regular pages are used instead.
+                    unsafe { libc::madvise(ptr.as_ptr().cast(), len, libc::MADV_HUGEPAGE) };
+                }
+                (ptr, flags != 0)
+            }
+        };
+        self.bind(ptr, len);
+        if self.populate && !populated {
+            Self::prefault(ptr, len);
+        }
+
+        #[cfg(feature = "guard-pages")]
+        {
+            // SAFETY: the guard page is the last page of the mapping.
+            let guard = unsafe { ptr.add(len) };
+            // SAFETY: the guard page is page aligned and in bounds of the mapping.
+            if unsafe { libc::mprotect(guard.as_ptr().cast(), page_size(), libc::PROT_NONE) } != 0 {
+                // SAFETY: the mapping was just created with this length.
+                unsafe { libc::munmap(ptr.as_ptr().cast(), map_len) };
+                return Err(AllocError);
+            }
+            // Place the allocation right before the guard page. The padding is smaller than
+            // a page, since size <= len.
+            let padding = (len - layout.size()) & !(layout.align() - 1);
+            // SAFETY: padding + size <= len, so the allocation is in bounds of the mapping.
+            let ptr = unsafe { ptr.add(padding) };
+            Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+        }
+        #[cfg(not(feature = "guard-pages"))]
+        Ok(NonNull::slice_from_raw_parts(ptr, len))
+    }
+
+    /// Allocates a zeroed region.
+    ///
+    /// Anonymous mappings are always zeroed, so this is the same as [`Allocator::allocate`].
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() == 0 {
+            return;
+        }
+        // SAFETY: the length was computed in allocate without overflowing.
+        let len = unsafe { self.mapping_len(layout.size()).unwrap_unchecked() };
+        #[cfg(feature = "guard-pages")]
+        let (ptr, len) = {
+            // The allocation ends less than its alignment, so less than a page, before the
+            // guard page, which is len bytes after the start of the mapping.
+            // SAFETY: the allocation is in bounds of the mapping.
+            let end = unsafe { ptr.add(layout.size()) };
+            // SAFETY: the guard page starts at a page aligned address, after the mapping.
+            let guard = unsafe { round_to_pages(end.addr().get()).unwrap_unchecked() };
+            // SAFETY: the mapping starts len bytes before the guard page.
+            (
+                unsafe { end.byte_sub(end.addr().get() - (guard - len)) },
+                len + page_size(),
+            )
+        };
+        // SAFETY: the mapping was created by allocate with this length.
+        unsafe { libc::munmap(ptr.as_ptr().cast(), len) };
+    }
+}
+
+impl BumpCar<Mmap> {
+    /// Resets the [`BumpCar`], and returns the physical memory of its used pages to the
+    /// operating system with `madvise(MADV_DONTNEED)`.
+    ///
+    /// The pages stay mapped, and are faulted in again (zeroed) when they are next written to.
+    /// The frozen prefix is kept.
+    pub fn reset_and_release(&mut self) {
+        let start = self.start.addr().get();
+        let used = start + self.position();
+        self.reset();
+
+        let (Some(from), Some(to)) = (round_to_pages(start + self.floor), round_to_pages(used))
+        else {
+            return;
+        };
+        if from < to {
+            // SAFETY: from and to are page aligned and in bounds of the mapping, whose length
+            // is rounded up to the page size.
+            unsafe {
+                libc::madvise(
+                    self.start
+                        .with_addr(from.try_into().unwrap_unchecked())
+                        .as_ptr()
+                        .cast(),
+                    to - from,
+                    libc::MADV_DONTNEED,
+                );
+            }
+        }
+    }
+}
+
+impl BumpCar<Mmap> {
+    /// Makes the used pages of the [`BumpCar`] read-only with `mprotect`, until the returned
+    /// view is dropped.
+    ///
+    /// This is meant for lookup tables that are built once and then only read: the view only
+    /// gives shared access to the allocated values, and any write to them, for example through
+    /// interior mutability or a stale raw pointer, faults immediately instead of silently
+    /// corrupting the table. Dropping the view makes the pages writable again, so that the
+    /// [`BumpCar`] can be reset or allocated from.
+    ///
+    /// The whole pages holding the used part of the buffer are protected. Failure to protect
+    /// them is ignored, and leaves them writable.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::{BumpCar, Mmap};
+    ///
+    /// let mut bumpcar = BumpCar::new_in(1 << 20, Mmap::new()).unwrap();
+    /// let table = bumpcar.alloc_slice_handle(&[1u32, 2, 3]).unwrap();
+    ///
+    /// let view = bumpcar.protect();
+    /// // SAFETY: the handle was allocated on this arena, which has not been reset.
+    /// assert_eq!(unsafe { view.get_slice(table) }, &[1, 2, 3]);
+    /// drop(view);
+    ///
+    /// bumpcar.reset();
+    /// ```
+    pub fn protect(&mut self) -> ReadOnlyCar<'_> {
+        let start = self.start.addr().get() & !(page_size() - 1);
+        let len =
+            round_to_pages(self.start.addr().get() + self.position()).map_or(0, |end| end - start);
+        let protected = len > 0 && {
+            // SAFETY: the pages are in bounds of the mapping, which starts at most a page before
+            // the buffer, and whose length is rounded up to the page size.
+            unsafe {
+                libc::mprotect(
+                    self.start
+                        .with_addr(start.try_into().unwrap_unchecked())
+                        .as_ptr()
+                        .cast(),
+                    len,
+                    libc::PROT_READ,
+                ) == 0
+            }
+        };
+        ReadOnlyCar {
+            bumpcar: self,
+            protected: if protected { len } else { 0 },
+        }
+    }
+}
+
+/// Read-only view of a [`BumpCar<Mmap>`], returned by [`BumpCar::protect`].
+///
+/// The used pages of the arena are protected against writes while the view exists, and made
+/// writable again when it is dropped.
+#[derive(Debug)]
+pub struct ReadOnlyCar<'a> {
+    bumpcar: &'a mut BumpCar<Mmap>,
+    protected: usize,
+}
+
+impl ReadOnlyCar<'_> {
+    /// Resolves a handle to a shared reference.
+    ///
+    /// # Safety
+    /// Same as [`BumpCar::get`].
+    ///
+    /// # Panics
+    /// This function panics if the handle is out of bounds or misaligned.
+    pub unsafe fn get<T>(&self, handle: Handle<T>) -> &T {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.get(handle) }
+    }
+
+    /// Resolves a slice handle to a shared slice.
+    ///
+    /// # Safety
+    /// Same as [`BumpCar::get_slice`].
+    ///
+    /// # Panics
+    /// This function panics if the handle is out of bounds or misaligned.
+    pub unsafe fn get_slice<T>(&self, handle: SliceHandle<T>) -> &[T] {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.get_slice(handle) }
+    }
+
+    /// Returns the number of bytes used in the underlying [`BumpCar`].
+    pub fn used(&self) -> usize {
+        self.bumpcar.used()
+    }
+
+    /// Returns the capacity of the underlying [`BumpCar`].
+    pub fn capacity(&self) -> usize {
+        self.bumpcar.capacity()
+    }
+
+    /// Returns the number of bytes actually protected against writes, rounded up to the page
+    /// size, or 0 if protecting them failed.
+    pub fn protected_len(&self) -> usize {
+        self.protected
+    }
+}
+
+impl Drop for ReadOnlyCar<'_> {
+    fn drop(&mut self) {
+        if self.protected == 0 {
+            return;
+        }
+        let start = self.bumpcar.start.addr().get() & !(page_size() - 1);
+        // SAFETY: these are the pages protected in BumpCar::protect, and start is not null since
+        // it is the page holding the buffer.
+        unsafe {
+            libc::mprotect(
+                self.bumpcar
+                    .start
+                    .with_addr(start.try_into().unwrap_unchecked())
+                    .as_ptr()
+                    .cast(),
+                self.protected,
+                libc::PROT_READ | libc::PROT_WRITE,
+            );
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::arch::asm;
+use core::num::NonZeroUsize;
+use core::ptr::NonNull;
+
+use crate::{BumpCar, BumpError, BumpErrorKind, Mmap};
+
+/// Size of the memory granules that share a tag.
+const GRANULE: usize = 16;
+/// Position of the 4 bits of the tag in an address.
+const TAG_SHIFT: u32 = 56;
+/// Bits of the tag in an address.
+const TAG_MASK: usize = 0xF << TAG_SHIFT;
+
+/// `mprotect` flag enabling tag checks on a mapping.
+const PROT_MTE: libc::c_int = 0x20;
+/// Bit of `AT_HWCAP2` set when the CPU and the kernel support MTE.
+const HWCAP2_MTE: libc::c_ulong = 1 << 18;
+const PR_SET_TAGGED_ADDR_CTRL: libc::c_int = 55;
+const PR_TAGGED_ADDR_ENABLE: libc::c_ulong = 1;
+const PR_MTE_TCF_SYNC: libc::c_ulong = 1 << 1;
+const PR_MTE_TAG_SHIFT: u32 = 3;
+
+/// Returns `ptr` with a random tag, other than 0.
+///
+/// # Safety
+/// The CPU must support MTE.
+#[target_feature(enable = "mte")]
+unsafe fn random_tag(ptr: NonNull<u8>) -> NonNull<u8> {
+    let addr: usize;
+    // SAFETY: irg only computes a new address, excluding the tag 0 (bit 0 of the mask).
+    unsafe {
+        asm!(
+            "irg {addr}, {ptr}, {exclude}",
+            addr = lateout(reg) addr,
+            ptr = in(reg) ptr.addr().get(),
+            exclude = in(reg) 1usize,
+            options(nomem, nostack, preserves_flags),
+        );
+    }
+    // SAFETY: only the tag bits of the address changed, so it is not null.
+    ptr.with_addr(unsafe { NonZeroUsize::new_unchecked(addr) })
+}
+
+/// Sets the tag of the granules of the `len` bytes at `ptr` to the tag of `ptr`.
+///
+/// # Safety
+/// The CPU must support MTE, `ptr` must be aligned to a granule, and the range must be in bounds
+/// of a mapping with tag checks enabled.
+#[target_feature(enable = "mte")]
+unsafe fn set_tags(ptr: NonNull<u8>, len: usize) {
+    let mut addr = ptr.addr().get();
+    let end = addr + len;
+    while addr < end {
+        // SAFETY: the granule is in bounds of the mapping.
+        unsafe { asm!("stg {0}, [{0}]", in(reg) addr, options(nostack, preserves_flags)) };
+        addr += GRANULE;
+    }
+}
+
+/// Returns `ptr` without its tag.
+fn untag(ptr: NonNull<u8>) -> NonNull<u8> {
+    // SAFETY: the address of a tagged pointer has more bits set than its tag.
+    ptr.map_addr(|addr| unsafe { NonZeroUsize::new_unchecked(addr.get() & !TAG_MASK) })
+}
+
+/// Returns the layout of the granules holding an allocation of `layout`.
+fn granules(layout: Layout) -> Result<Layout, AllocError> {
+    let size = layout
+        .size()
+        .checked_next_multiple_of(GRANULE)
+        .ok_or(AllocError)?;
+    Layout::from_size_align(size, layout.align().max(GRANULE)).map_err(|_| AllocError)
+}
+
+/// [`BumpCar`] tagging its allocations with ARM Memory Tagging Extension.
+///
+/// Every allocation takes whole 16-byte granules, tagged with a random tag that is also stored
+/// in the top byte of the returned pointer. Deallocated granules, and the whole used buffer on
+/// reset, are retagged with the tag 0, that is never handed out: accessing an allocation after
+/// it was deallocated or reset, or past its end, faults in hardware instead of silently reading
+/// reused memory. A stale pointer into reused memory is detected with a probability of 14/15.
+///
+/// The buffer is mapped with [`Mmap`], with tag checks enabled. Faults are only raised once the
+/// process opted in with [`MteBumpCar::enable_tag_checks`], or the equivalent `prctl` call.
+///
+/// It is only available on AArch64 Linux, with the `mte` feature.
+///
+/// # Example
+/// ```rust,no_run
+/// #![feature(allocator_api)]
+/// use dodgems::MteBumpCar;
+///
+/// assert!(MteBumpCar::enable_tag_checks());
+/// let mut bumpcar = MteBumpCar::new(1 << 20).unwrap();
+/// let b = Box::new_in([1u8; 100], &bumpcar);
+/// let stale = core::ptr::from_ref(&*b);
+/// std::mem::forget(b);
+/// bumpcar.reset();
+/// // Reading through `stale` now raises a SIGSEGV.
+/// ```
+#[derive(Debug)]
+pub struct MteBumpCar {
+    bumpcar: BumpCar<Mmap>,
+}
+
+impl MteBumpCar {
+    /// Maps a new [`MteBumpCar`] of `capacity` bytes, with tag checks enabled.
+    ///
+    /// # Errors
+    /// This function returns an error if the CPU or the kernel do not support MTE, if the
+    /// capacity is greater than [`isize::MAX`], or if the buffer could not be mapped.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        let failed = BumpError::new(BumpErrorKind::AllocatorFailed, None, 0);
+        if !Self::is_supported() {
+            return Err(failed);
+        }
+        let bumpcar = BumpCar::new_in(capacity, Mmap::new())?;
+        if capacity > 0 {
+            // SAFETY: the buffer is a mapping of at least capacity bytes created by Mmap.
+            let result = unsafe {
+                libc::mprotect(
+                    bumpcar.start.as_ptr().cast(),
+                    capacity,
+                    libc::PROT_READ | libc::PROT_WRITE | PROT_MTE,
+                )
+            };
+            if result != 0 {
+                return Err(failed);
+            }
+        }
+        Ok(Self { bumpcar })
+    }
+
+    /// Returns `true` if the CPU and the kernel support MTE.
+    pub fn is_supported() -> bool {
+        // SAFETY: getauxval has no preconditions.
+        unsafe { libc::getauxval(libc::AT_HWCAP2) & HWCAP2_MTE != 0 }
+    }
+
+    /// Enables synchronous tag check faults for the calling thread, with every tag but 0
+    /// available to the allocations, and returns `true` on success.
+    ///
+    /// Threads created afterwards inherit the setting.
+    pub fn enable_tag_checks() -> bool {
+        let flags = PR_TAGGED_ADDR_ENABLE | PR_MTE_TCF_SYNC | (0xFFFE << PR_MTE_TAG_SHIFT);
+        // SAFETY: this only changes the tag checking mode of the thread.
+        unsafe { libc::prctl(PR_SET_TAGGED_ADDR_CTRL, flags, 0, 0, 0) == 0 }
+    }
+
+    /// Returns the capacity of the [`MteBumpCar`].
+    pub fn capacity(&self) -> usize {
+        self.bumpcar.capacity()
+    }
+
+    /// Returns the remaining capacity of the [`MteBumpCar`].
+    pub fn remaining_capacity(&self) -> usize {
+        self.bumpcar.remaining_capacity()
+    }
+
+    /// Retags the used part of the buffer with the tag 0, so that every pointer handed out so
+    /// far faults, and resets the [`MteBumpCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    pub fn reset(&mut self) {
+        let used = self.bumpcar.position().next_multiple_of(GRANULE);
+        // SAFETY: MTE is supported, and the used granules are in bounds of the buffer.
+        unsafe { set_tags(self.bumpcar.start, used) };
+        self.bumpcar.reset();
+    }
+
+    /// Retags the `len` bytes at the untagged pointer `ptr` with the tag 0.
+    ///
+    /// # Safety
+    /// The range must be whole granules in bounds of the buffer.
+    unsafe fn clear_tags(&self, ptr: NonNull<u8>, len: usize) {
+        // SAFETY: MTE is supported, and the range is valid as guaranteed by the caller.
+        unsafe { set_tags(ptr, len) }
+    }
+
+    /// Moves an allocation to a new one of `new_layout`.
+    ///
+    /// # Safety
+    /// Same as [`Allocator::grow`].
+    unsafe fn relocate(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let new_ptr = self.allocate(new_layout)?;
+        // SAFETY: both regions are valid for the smallest of the two sizes, and distinct. Both
+        // pointers carry the tag of their region.
+        unsafe {
+            new_ptr
+                .cast::<u8>()
+                .copy_from_nonoverlapping(ptr, old_layout.size().min(new_layout.size()));
+            self.deallocate(ptr, old_layout);
+        }
+        Ok(new_ptr)
+    }
+}
+
+unsafe impl Allocator for MteBumpCar {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let outer = granules(layout)?;
+        let ptr = self.bumpcar.allocate(outer)?.cast::<u8>();
+        if outer.size() == 0 {
+            return Ok(NonNull::slice_from_raw_parts(ptr, 0));
+        }
+        // SAFETY: MTE is supported, and the allocation is whole granules in bounds of the buffer.
+        let ptr = unsafe {
+            let ptr = random_tag(ptr);
+            set_tags(ptr, outer.size());
+            ptr
+        };
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: the layout was valid when the region was allocated.
+        let outer = unsafe { granules(layout).unwrap_unchecked() };
+        let ptr = untag(ptr);
+        // SAFETY: the region is whole granules in bounds of the buffer, and is retagged before
+        // the BumpCar accesses it through an untagged pointer.
+        unsafe {
+            self.clear_tags(ptr, outer.size());
+            self.bumpcar.deallocate(ptr, outer);
+        }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: the old layout was valid when the region was allocated.
+        let old = unsafe { granules(old_layout).unwrap_unchecked() };
+        let new = granules(new_layout)?;
+        let base = untag(ptr);
+        if old.size() > 0
+            && base.addr().get().is_multiple_of(new.align())
+            && self.bumpcar.resize_in_place(base, old.size(), new.size())
+        {
+            // SAFETY: the new granules follow the old ones in bounds of the buffer, and are
+            // tagged like them.
+            unsafe { set_tags(ptr.add(old.size()), new.size() - old.size()) };
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.relocate(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: the old layout was valid when the region was allocated.
+        let old = unsafe { granules(old_layout).unwrap_unchecked() };
+        let new = granules(new_layout)?;
+        let base = untag(ptr);
+        if new.size() > 0 && base.addr().get().is_multiple_of(new.align()) {
+            // SAFETY: the freed granules are in bounds of the allocation, and are retagged
+            // before the BumpCar accesses them through an untagged pointer.
+            unsafe { self.clear_tags(base.add(new.size()), old.size() - new.size()) };
+            // The allocation is kept in place even if its tail cannot be reclaimed.
+            self.bumpcar.resize_in_place(base, old.size(), new.size());
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.relocate(ptr, old_layout, new_layout) }
+    }
+}
@@ -0,0 +1,285 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{BumpCar, BumpError};
+
+/// Usage of an arena when an [`ArenaObserver`] is notified.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Usage {
+    /// Number of bytes used, alignment padding included.
+    pub used: usize,
+    /// Capacity of the arena.
+    pub capacity: usize,
+}
+
+/// Callbacks notified of the allocations of an [`ObservedBumpCar`].
+///
+/// Every method does nothing by default.
+pub trait ArenaObserver {
+    /// Called after a successful allocation of `layout`.
+    fn on_alloc(&self, layout: Layout, usage: Usage) {
+        let _ = (layout, usage);
+    }
+
+    /// Called when an allocation of `layout` fails for lack of capacity.
+    fn on_oom(&self, layout: Layout, usage: Usage) {
+        let _ = (layout, usage);
+    }
+
+    /// Called right before the arena is reset, with its usage at that time.
+    fn on_reset(&self, usage: Usage) {
+        let _ = usage;
+    }
+}
+
+impl ArenaObserver for () {}
+
+impl<O: ArenaObserver + ?Sized> ArenaObserver for &O {
+    fn on_alloc(&self, layout: Layout, usage: Usage) {
+        (**self).on_alloc(layout, usage);
+    }
+
+    fn on_oom(&self, layout: Layout, usage: Usage) {
+        (**self).on_oom(layout, usage);
+    }
+
+    fn on_reset(&self, usage: Usage) {
+        (**self).on_reset(usage);
+    }
+}
+
+/// [`ArenaObserver`] calling a function the first time the utilization of the arena reaches a
+/// threshold, between two resets.
+///
+/// This gives an early warning that an arena is about to fail allocations, without checking its
+/// usage after every allocation.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use core::cell::Cell;
+/// use dodgems::{ObservedBumpCar, ThresholdObserver};
+///
+/// let warnings = Cell::new(0);
+/// let observer = ThresholdObserver::new(90, |usage| {
+///     assert!(usage.used * 10 >= usage.capacity * 9);
+///     warnings.set(warnings.get() + 1);
+/// });
+/// let mut bumpcar = ObservedBumpCar::new(1000, observer).unwrap();
+/// for _ in 0..3 {
+///     for _ in 0..120 {
+///         Box::leak(Box::new_in(0u64, &bumpcar));
+///     }
+///     bumpcar.reset();
+/// }
+/// assert_eq!(warnings.get(), 3);
+/// ```
+pub struct ThresholdObserver<F: Fn(Usage)> {
+    percent: u8,
+    callback: F,
+    fired: Cell<bool>,
+}
+
+impl<F: Fn(Usage)> ThresholdObserver<F> {
+    /// Creates an observer calling `callback` the first time `percent`% of the capacity of the
+    /// arena is used, until the next reset.
+    ///
+    /// # Panics
+    /// This function panics if `percent` is greater than 100.
+    pub fn new(percent: u8, callback: F) -> Self {
+        assert!(percent <= 100, "threshold of {percent}% is above 100%");
+        Self {
+            percent,
+            callback,
+            fired: Cell::new(false),
+        }
+    }
+
+    /// Returns the threshold, in percent of the capacity.
+    pub fn percent(&self) -> u8 {
+        self.percent
+    }
+
+    /// Returns `true` if the threshold was reached since the last reset.
+    pub fn fired(&self) -> bool {
+        self.fired.get()
+    }
+}
+
+impl<F: Fn(Usage)> ArenaObserver for ThresholdObserver<F> {
+    fn on_alloc(&self, _: Layout, usage: Usage) {
+        let reached = usage.used as u128 * 100 >= usage.capacity as u128 * u128::from(self.percent);
+        if reached && !self.fired.replace(true) {
+            (self.callback)(usage);
+        }
+    }
+
+    fn on_reset(&self, _: Usage) {
+        self.fired.set(false);
+    }
+}
+
+/// [`ArenaObserver`] logging allocation failures with [`defmt`], with the requested layout and
+/// the usage of the arena.
+///
+/// It requires a `defmt` global logger.
+#[cfg(feature = "defmt")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DefmtObserver;
+
+#[cfg(feature = "defmt")]
+impl ArenaObserver for DefmtObserver {
+    #[inline]
+    fn on_oom(&self, layout: Layout, usage: Usage) {
+        defmt::warn!(
+            "BumpCar allocation failed: size {=usize}, align {=usize}, remaining {=usize}/{=usize}",
+            layout.size(),
+            layout.align(),
+            usage.capacity - usage.used,
+            usage.capacity,
+        );
+    }
+}
+
+/// [`BumpCar`] notifying an [`ArenaObserver`] of its allocations and failures.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use core::alloc::Layout;
+/// use core::cell::Cell;
+/// use dodgems::{ArenaObserver, ObservedBumpCar, Usage};
+///
+/// #[derive(Default)]
+/// struct Alert(Cell<bool>);
+///
+/// impl ArenaObserver for Alert {
+///     fn on_alloc(&self, _: Layout, usage: Usage) {
+///         if usage.used * 4 >= usage.capacity * 3 {
+///             self.0.set(true);
+///         }
+///     }
+/// }
+///
+/// let bumpcar = ObservedBumpCar::new(256, Alert::default()).unwrap();
+/// let v = Box::new_in([0u8; 128], &bumpcar);
+/// assert!(!bumpcar.observer().0.get());
+/// let w = Box::new_in([0u8; 64], &bumpcar);
+/// assert!(bumpcar.observer().0.get());
+/// ```
+pub struct ObservedBumpCar<
+    O: ArenaObserver,
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    bumpcar: BumpCar<A>,
+    observer: O,
+}
+
+impl<O: ArenaObserver, A: Allocator> ObservedBumpCar<O, A> {
+    /// Allocates a new [`ObservedBumpCar`] in the given allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, observer: O, allocator: A) -> Result<Self, BumpError> {
+        Ok(Self {
+            bumpcar: BumpCar::new_in(capacity, allocator)?,
+            observer,
+        })
+    }
+
+    /// Returns the underlying [`BumpCar`].
+    ///
+    /// Allocations made directly in it are not observed.
+    pub fn bumpcar(&self) -> &BumpCar<A> {
+        &self.bumpcar
+    }
+
+    /// Returns the observer.
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    /// Returns the current usage of the arena.
+    pub fn usage(&self) -> Usage {
+        Usage {
+            used: self.bumpcar.position(),
+            capacity: self.bumpcar.capacity(),
+        }
+    }
+
+    /// Resets the [`ObservedBumpCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// The observer is notified first, with the usage of the arena before the reset.
+    pub fn reset(&mut self) {
+        self.observer.on_reset(self.usage());
+        self.bumpcar.reset();
+    }
+
+    /// Notifies the observer of the result of an allocation of `layout`.
+    fn notify<T>(&self, layout: Layout, result: Result<T, AllocError>) -> Result<T, AllocError> {
+        match result {
+            Ok(_) => self.observer.on_alloc(layout, self.usage()),
+            Err(AllocError) => self.observer.on_oom(layout, self.usage()),
+        }
+        result
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<O: ArenaObserver> ObservedBumpCar<O> {
+    /// Allocates an [`ObservedBumpCar`] with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize, observer: O) -> Result<Self, BumpError> {
+        Self::new_in(capacity, observer, Global)
+    }
+}
+
+/// Growing a region that cannot be extended in place is notified as a new allocation.
+unsafe impl<O: ArenaObserver, A: Allocator> Allocator for ObservedBumpCar<O, A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.notify(layout, self.bumpcar.allocate(layout))
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.notify(layout, self.bumpcar.allocate_zeroed(layout))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.deallocate(ptr, layout) }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        let result = unsafe { self.bumpcar.grow(ptr, old_layout, new_layout) };
+        match result {
+            Ok(new_ptr) if new_ptr.cast::<u8>() == ptr => result,
+            _ => self.notify(new_layout, result),
+        }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.shrink(ptr, old_layout, new_layout) }
+    }
+}
@@ -0,0 +1,159 @@
+use alloc::alloc::Global;
+use alloc::vec::Vec;
+use core::alloc::Allocator;
+use core::cell::RefCell;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+
+use crate::{BumpCar, BumpError, CapacityAdvisor};
+
+/// Pool of reusable [`BumpCar`]s of the same capacity.
+///
+/// Checking out an arena reuses an idle one if possible, instead of allocating a new buffer
+/// from the backing allocator. When the returned [`PooledCar`] is dropped, the arena is reset
+/// and returned to the pool, unless the pool already holds `max_idle` idle arenas. Its peak
+/// usage is recorded to [suggest a capacity](CarPool::suggested_capacity) for the pool.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::CarPool;
+///
+/// let pool = CarPool::new(4, 1024).unwrap();
+/// for request in 0..100 {
+///     let bumpcar = pool.checkout().unwrap();
+///     let mut v = Vec::new_in(&*bumpcar);
+///     v.push(request);
+/// }
+/// assert_eq!(pool.idle(), 4);
+/// ```
+pub struct CarPool<A: Allocator + Clone = Global> {
+    idle: RefCell<Vec<BumpCar<A>>>,
+    advisor: RefCell<CapacityAdvisor>,
+    max_idle: usize,
+    capacity: usize,
+    allocator: A,
+}
+
+impl<A: Allocator + Clone> CarPool<A> {
+    /// Creates a new [`CarPool`], with `max_idle` arenas of `capacity` bytes allocated upfront
+    /// in the given allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(max_idle: usize, capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        let idle = (0..max_idle)
+            .map(|_| BumpCar::new_in(capacity, allocator.clone()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            idle: RefCell::new(idle),
+            advisor: RefCell::new(CapacityAdvisor::new()),
+            max_idle,
+            capacity,
+            allocator,
+        })
+    }
+
+    /// Returns the capacity of the arenas of the [`CarPool`].
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of idle arenas in the [`CarPool`].
+    pub fn idle(&self) -> usize {
+        self.idle.borrow().len()
+    }
+
+    /// Returns the maximum number of idle arenas kept by the [`CarPool`].
+    pub fn max_idle(&self) -> usize {
+        self.max_idle
+    }
+
+    /// Returns a capacity suited to the last 64 arenas returned to the [`CarPool`]: the 99th
+    /// percentile of their peak usages, plus 10%.
+    ///
+    /// It can be used to create a right-sized pool, instead of tuning the capacity by hand.
+    /// Returns `None` if no arena was returned yet.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::CarPool;
+    ///
+    /// let pool = CarPool::new(1, 64 * 1024).unwrap();
+    /// for _ in 0..10 {
+    ///     let bumpcar = pool.checkout().unwrap();
+    ///     bumpcar.alloc([0u8; 1000]);
+    /// }
+    /// assert_eq!(pool.suggested_capacity(), Some(1100));
+    /// ```
+    pub fn suggested_capacity(&self) -> Option<usize> {
+        self.advisor.borrow().suggested_capacity()
+    }
+
+    /// Checks out an arena from the [`CarPool`], allocating a new one if none is idle.
+    ///
+    /// # Errors
+    /// This function returns an error if no arena is idle and the underlying allocator returns
+    /// an error.
+    pub fn checkout(&self) -> Result<PooledCar<'_, A>, BumpError> {
+        let bumpcar = match self.idle.borrow_mut().pop() {
+            Some(bumpcar) => bumpcar,
+            None => BumpCar::new_in(self.capacity, self.allocator.clone())?,
+        };
+        Ok(PooledCar {
+            pool: self,
+            bumpcar: ManuallyDrop::new(bumpcar),
+        })
+    }
+}
+
+impl CarPool {
+    /// Creates a new [`CarPool`], with `max_idle` arenas of `capacity` bytes allocated upfront
+    /// with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(max_idle: usize, capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(max_idle, capacity, Global)
+    }
+}
+
+/// [`BumpCar`] checked out of a [`CarPool`].
+///
+/// It is reset and returned to the pool when dropped.
+pub struct PooledCar<'pool, A: Allocator + Clone = Global> {
+    pool: &'pool CarPool<A>,
+    bumpcar: ManuallyDrop<BumpCar<A>>,
+}
+
+impl<A: Allocator + Clone> Deref for PooledCar<'_, A> {
+    type Target = BumpCar<A>;
+
+    fn deref(&self) -> &BumpCar<A> {
+        &self.bumpcar
+    }
+}
+
+impl<A: Allocator + Clone> DerefMut for PooledCar<'_, A> {
+    fn deref_mut(&mut self) -> &mut BumpCar<A> {
+        &mut self.bumpcar
+    }
+}
+
+impl<A: Allocator + Clone> Drop for PooledCar<'_, A> {
+    /// Resets the arena, and returns it to the pool if it is not full.
+    fn drop(&mut self) {
+        // SAFETY: the arena is never used again after being taken.
+        let mut bumpcar = unsafe { ManuallyDrop::take(&mut self.bumpcar) };
+        self.pool.advisor.borrow_mut().record(bumpcar.peak_usage());
+        let mut idle = self.pool.idle.borrow_mut();
+        if idle.len() < self.pool.max_idle {
+            bumpcar.reset_all();
+            bumpcar.reset_peak();
+            idle.push(bumpcar);
+        }
+    }
+}
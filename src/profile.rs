@@ -0,0 +1,146 @@
+use core::alloc::{Allocator, Layout};
+use core::fmt;
+use core::panic::Location;
+
+use crate::BumpCar;
+
+/// Maximum number of callsites recorded by a [`Profile`].
+pub const MAX_CALLSITES: usize = 32;
+
+/// Allocations made from a single callsite, recorded in a [`Profile`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Callsite {
+    /// Location of the call to the typed allocation method.
+    pub location: &'static Location<'static>,
+    /// Number of successful allocations made from this location.
+    pub allocations: usize,
+    /// Number of bytes handed out to this location.
+    pub bytes: usize,
+}
+
+/// Allocations of a [`BumpCar`] aggregated by callsite, returned by [`BumpCar::profile`].
+///
+/// Only the typed allocation methods (such as [`BumpCar::alloc`] or
+/// [`BumpCar::alloc_slice_copy`]) record their caller: the allocations made through the
+/// [`Allocator`] trait are not profiled. Once [`MAX_CALLSITES`] callsites have been recorded,
+/// the allocations from new callsites are only counted as untracked.
+///
+/// Like [`Stats`](crate::Stats), the profile is not cleared when the [`BumpCar`] is reset, but
+/// with [`BumpCar::reset_profile`]. It is displayed as a report, with the callsites that
+/// allocated the most bytes first.
+///
+/// # Example
+/// ```rust
+/// use dodgems::BumpCar;
+///
+/// let bumpcar = BumpCar::new(1024).unwrap();
+/// for _ in 0..4 {
+///     bumpcar.alloc([0u8; 100]);
+/// }
+/// bumpcar.alloc_str("hello");
+///
+/// let profile = bumpcar.profile();
+/// let top = profile.callsites().max_by_key(|site| site.bytes).unwrap();
+/// assert_eq!((top.allocations, top.bytes), (4, 400));
+/// println!("{profile}");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Profile {
+    callsites: [Option<Callsite>; MAX_CALLSITES],
+    untracked_allocations: usize,
+    untracked_bytes: usize,
+}
+
+impl Profile {
+    pub(crate) const fn new() -> Self {
+        Self {
+            callsites: [None; MAX_CALLSITES],
+            untracked_allocations: 0,
+            untracked_bytes: 0,
+        }
+    }
+
+    /// Returns the recorded callsites, in the order of their first allocation.
+    pub fn callsites(&self) -> impl Iterator<Item = &Callsite> {
+        self.callsites.iter().map_while(Option::as_ref)
+    }
+
+    /// Returns the number of allocations made from callsites that could not be recorded.
+    pub fn untracked_allocations(&self) -> usize {
+        self.untracked_allocations
+    }
+
+    /// Returns the number of bytes handed out to callsites that could not be recorded.
+    pub fn untracked_bytes(&self) -> usize {
+        self.untracked_bytes
+    }
+
+    /// Records an allocation of `size` bytes from `location`.
+    fn record(&mut self, location: &'static Location<'static>, size: usize) {
+        for slot in &mut self.callsites {
+            match slot {
+                Some(site) if site.location == location => {
+                    site.allocations += 1;
+                    site.bytes += size;
+                    return;
+                }
+                Some(_) => {}
+                None => {
+                    *slot = Some(Callsite {
+                        location,
+                        allocations: 1,
+                        bytes: size,
+                    });
+                    return;
+                }
+            }
+        }
+        self.untracked_allocations += 1;
+        self.untracked_bytes += size;
+    }
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut callsites = self.callsites;
+        callsites.sort_unstable_by_key(|site| site.map(|site| core::cmp::Reverse(site.bytes)));
+        for site in callsites.iter().flatten() {
+            writeln!(
+                f,
+                "{:>12} bytes {:>8} allocations  {}",
+                site.bytes, site.allocations, site.location
+            )?;
+        }
+        if self.untracked_allocations != 0 {
+            writeln!(
+                f,
+                "{:>12} bytes {:>8} allocations  (untracked callsites)",
+                self.untracked_bytes, self.untracked_allocations
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<A: Allocator> BumpCar<A> {
+    /// Returns the allocations of the typed allocation methods, aggregated by callsite.
+    pub fn profile(&self) -> Profile {
+        *self.profile.borrow()
+    }
+
+    /// Clears the allocation profile of the [`BumpCar`].
+    pub fn reset_profile(&self) {
+        *self.profile.borrow_mut() = Profile::new();
+    }
+
+    /// Records a typed allocation made from `location`.
+    pub(crate) fn record_callsite(&self, location: &'static Location<'static>, layout: Layout) {
+        self.profile.borrow_mut().record(location, layout.size());
+    }
+}
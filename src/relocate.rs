@@ -0,0 +1,188 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::alloc::{AllocError, Allocator};
+
+use crate::{BumpBox, BumpCow, BumpString, BumpVec};
+
+/// Deep-copying of a value out of an arena, into another allocator.
+///
+/// This is the counterpart of [`CloneIn`](crate::CloneIn): the few results of a frame that must
+/// outlive it are relocated, for example into the global allocator, right before the arena is
+/// reset. The [`Relocated`](RelocateTo::Relocated) value does not reference the arena anymore.
+///
+/// Arena-backed slices, strings, [`BumpBox`]es and [`BumpVec`]s are relocated into boxes and
+/// vectors of the target allocator. Other types can implement the trait by relocating each of
+/// their fields.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use std::alloc::Global;
+/// use dodgems::{BumpCar, BumpVec, RelocateTo};
+///
+/// let mut bumpcar = BumpCar::new(1024).unwrap();
+/// let kept = {
+///     let mut names = BumpVec::new_in(&bumpcar);
+///     for i in 0..4 {
+///         names.push(bumpcar.alloc_str(&format!("name {i}")) as &str);
+///     }
+///     names.relocate_to(Global)
+/// };
+/// bumpcar.reset();
+/// assert_eq!(kept.len(), 4);
+/// assert_eq!(&*kept[3], "name 3");
+/// ```
+pub trait RelocateTo<A: Allocator + Clone> {
+    /// The type of the relocated value.
+    type Relocated;
+
+    /// Copies the value into `allocator`.
+    ///
+    /// # Errors
+    /// This function returns an error if the allocator fails.
+    fn try_relocate_to(&self, allocator: A) -> Result<Self::Relocated, AllocError>;
+
+    /// Copies the value into `allocator`.
+    ///
+    /// # Panics
+    /// This function panics if the allocator fails.
+    fn relocate_to(&self, allocator: A) -> Self::Relocated {
+        match self.try_relocate_to(allocator) {
+            Ok(relocated) => relocated,
+            Err(AllocError) => panic!("allocation failed while relocating a value"),
+        }
+    }
+}
+
+macro_rules! impl_relocate_to_copy {
+    ($($t:ty),*) => {$(
+        impl<A: Allocator + Clone> RelocateTo<A> for $t {
+            type Relocated = $t;
+
+            fn try_relocate_to(&self, _: A) -> Result<$t, AllocError> {
+                Ok(*self)
+            }
+        }
+    )*};
+}
+
+impl_relocate_to_copy!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64
+);
+
+impl<A: Allocator + Clone> RelocateTo<A> for str {
+    type Relocated = Box<str, A>;
+
+    fn try_relocate_to(&self, allocator: A) -> Result<Box<str, A>, AllocError> {
+        let mut bytes = Vec::new_in(allocator);
+        bytes
+            .try_reserve_exact(self.len())
+            .map_err(|_| AllocError)?;
+        bytes.extend_from_slice(self.as_bytes());
+        let (ptr, allocator) = Box::into_raw_with_allocator(bytes.into_boxed_slice());
+        // SAFETY: the bytes were copied from a str, so they are valid UTF-8.
+        Ok(unsafe { Box::from_raw_in(ptr as *mut str, allocator) })
+    }
+}
+
+impl<A: Allocator + Clone, T: RelocateTo<A>> RelocateTo<A> for [T] {
+    type Relocated = Box<[T::Relocated], A>;
+
+    fn try_relocate_to(&self, allocator: A) -> Result<Self::Relocated, AllocError> {
+        Ok(relocate_slice(self, allocator)?.into_boxed_slice())
+    }
+}
+
+/// Relocates the elements of a slice into a vector of exactly the same length.
+fn relocate_slice<A: Allocator + Clone, T: RelocateTo<A>>(
+    slice: &[T],
+    allocator: A,
+) -> Result<Vec<T::Relocated, A>, AllocError> {
+    let mut v = Vec::new_in(allocator.clone());
+    v.try_reserve_exact(slice.len()).map_err(|_| AllocError)?;
+    for x in slice {
+        v.push(x.try_relocate_to(allocator.clone())?);
+    }
+    Ok(v)
+}
+
+impl<A: Allocator + Clone, T: RelocateTo<A> + ?Sized> RelocateTo<A> for &T {
+    type Relocated = T::Relocated;
+
+    fn try_relocate_to(&self, allocator: A) -> Result<T::Relocated, AllocError> {
+        (**self).try_relocate_to(allocator)
+    }
+}
+
+impl<A: Allocator + Clone, T: RelocateTo<A> + ?Sized> RelocateTo<A> for &mut T {
+    type Relocated = T::Relocated;
+
+    fn try_relocate_to(&self, allocator: A) -> Result<T::Relocated, AllocError> {
+        (**self).try_relocate_to(allocator)
+    }
+}
+
+impl<A: Allocator + Clone, T: RelocateTo<A>> RelocateTo<A> for Option<T> {
+    type Relocated = Option<T::Relocated>;
+
+    fn try_relocate_to(&self, allocator: A) -> Result<Option<T::Relocated>, AllocError> {
+        self.as_ref()
+            .map(|x| x.try_relocate_to(allocator))
+            .transpose()
+    }
+}
+
+impl<A: Allocator + Clone, T: RelocateTo<A>> RelocateTo<A> for BumpBox<'_, T> {
+    type Relocated = Box<T::Relocated, A>;
+
+    fn try_relocate_to(&self, allocator: A) -> Result<Self::Relocated, AllocError> {
+        Box::try_new_in((**self).try_relocate_to(allocator.clone())?, allocator)
+    }
+}
+
+impl<A: Allocator + Clone, T: RelocateTo<A> + ?Sized> RelocateTo<A> for BumpCow<'_, T> {
+    type Relocated = T::Relocated;
+
+    fn try_relocate_to(&self, allocator: A) -> Result<T::Relocated, AllocError> {
+        (**self).try_relocate_to(allocator)
+    }
+}
+
+impl<A: Allocator + Clone, B: Allocator, T: RelocateTo<A>> RelocateTo<A> for BumpVec<'_, T, B> {
+    type Relocated = Vec<T::Relocated, A>;
+
+    fn try_relocate_to(&self, allocator: A) -> Result<Self::Relocated, AllocError> {
+        relocate_slice(self.as_slice(), allocator)
+    }
+}
+
+impl<A: Allocator + Clone, B: Allocator> RelocateTo<A> for BumpString<'_, B> {
+    type Relocated = Box<str, A>;
+
+    fn try_relocate_to(&self, allocator: A) -> Result<Box<str, A>, AllocError> {
+        self.as_str().try_relocate_to(allocator)
+    }
+}
+
+impl<A: Allocator + Clone, B: Allocator, T: RelocateTo<A>> RelocateTo<A> for Vec<T, B> {
+    type Relocated = Vec<T::Relocated, A>;
+
+    fn try_relocate_to(&self, allocator: A) -> Result<Self::Relocated, AllocError> {
+        relocate_slice(self.as_slice(), allocator)
+    }
+}
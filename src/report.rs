@@ -0,0 +1,103 @@
+use core::alloc::Allocator;
+use core::fmt;
+
+use crate::{BumpCar, FlexBumpCar};
+
+/// Usage summary of a [`BumpCar`], returned by [`BumpCar::report`].
+struct Report<'a, A: Allocator>(&'a BumpCar<A>);
+
+impl<A: Allocator> fmt::Display for Report<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bumpcar = self.0;
+        write!(
+            f,
+            "{} bytes used of {} ({:.1}%), peak {} bytes",
+            bumpcar.used(),
+            bumpcar.capacity(),
+            bumpcar.utilization() * 100.0,
+            bumpcar.peak_usage(),
+        )?;
+        if bumpcar.frozen_len() != 0 {
+            write!(f, ", {} bytes frozen", bumpcar.frozen_len())?;
+        }
+        #[cfg(feature = "stats")]
+        {
+            let stats = bumpcar.stats();
+            write!(
+                f,
+                ", {} allocations ({} failed), {} bytes of alignment waste, {} resets",
+                stats.allocations, stats.failed_allocations, stats.alignment_waste, stats.resets,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Usage summary of a [`FlexBumpCar`], returned by [`FlexBumpCar::report`].
+struct FlexReport<'a, A: Allocator>(&'a FlexBumpCar<A>);
+
+impl<A: Allocator> fmt::Display for FlexReport<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bumpcar = self.0;
+        let (used, capacity) = (bumpcar.used(), bumpcar.capacity());
+        write!(
+            f,
+            "{used} bytes used of {capacity} ({:.1}%) in {} chunks",
+            used as f64 * 100.0 / capacity.max(1) as f64,
+            bumpcar.chunk_count(),
+        )?;
+        for (i, chunk) in bumpcar.chunks().enumerate() {
+            write!(
+                f,
+                "\n  chunk {i}: {} bytes used of {}",
+                chunk.used, chunk.capacity
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<A: Allocator> BumpCar<A> {
+    /// Returns a one-line summary of the usage of the [`BumpCar`], to be written to logs when an
+    /// allocation fails or at shutdown.
+    ///
+    /// It shows the used bytes, the capacity, the peak usage and the frozen prefix. With the
+    /// `stats` feature, it also shows the counters of [`BumpCar::stats`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(1024).unwrap();
+    /// bumpcar.alloc([0u64; 32]);
+    /// let report = bumpcar.report().to_string();
+    /// assert!(report.starts_with("256 bytes used of 1024 (25.0%), peak 256 bytes"));
+    /// ```
+    pub fn report(&self) -> impl fmt::Display + '_ {
+        Report(self)
+    }
+}
+
+impl<A: Allocator> FlexBumpCar<A> {
+    /// Returns a summary of the usage of the [`FlexBumpCar`], with a line for each of its
+    /// chunks, from the current one to the oldest one.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::FlexBumpCar;
+    ///
+    /// let bumpcar = FlexBumpCar::new(64).unwrap();
+    /// let a = Box::new_in([0u8; 48], &bumpcar);
+    /// let b = Box::new_in([0u8; 32], &bumpcar);
+    /// assert_eq!(
+    ///     bumpcar.report().to_string(),
+    ///     "80 bytes used of 192 (41.7%) in 2 chunks\n  \
+    ///      chunk 0: 32 bytes used of 128\n  \
+    ///      chunk 1: 48 bytes used of 64",
+    /// );
+    /// ```
+    pub fn report(&self) -> impl fmt::Display + '_ {
+        FlexReport(self)
+    }
+}
@@ -0,0 +1,198 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::ptr::NonNull;
+
+use crate::mmap::round_to_pages;
+use crate::{bump, BumpError, BumpErrorKind};
+
+/// Bump allocator reserving a large range of virtual addresses upfront, and committing its
+/// pages on demand as the cursor advances.
+///
+/// It behaves like a growable arena whose allocations never move: there is no chaining of
+/// chunks and no reallocation, and only the committed pages use physical memory. The last
+/// allocation can always be grown in place, up to the reserved size.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::ReservedBumpCar;
+///
+/// // reserve 1GB of address space
+/// let bumpcar = ReservedBumpCar::new(1 << 30).unwrap();
+/// assert_eq!(bumpcar.committed(), 0);
+///
+/// let mut v = Vec::new_in(&bumpcar);
+/// v.extend(0..100_000u32);
+/// assert!(bumpcar.committed() >= 400_000);
+/// ```
+pub struct ReservedBumpCar {
+    start: NonNull<u8>,
+    end: NonNull<u8>,
+    committed: Cell<NonNull<u8>>,
+    current: Cell<NonNull<u8>>,
+}
+
+impl ReservedBumpCar {
+    /// Reserves `reserved` bytes of address space for a new [`ReservedBumpCar`], without
+    /// committing any memory.
+    ///
+    /// # Errors
+    /// This function returns an error if the reserved size is zero or greater than
+    /// [`isize::MAX`], or if the operating system refuses the reservation.
+    pub fn new(reserved: usize) -> Result<Self, BumpError> {
+        let len = round_to_pages(reserved)
+            .filter(|&len| len != 0 && len <= isize::MAX as usize)
+            .ok_or(BumpError::new(BumpErrorKind::Overflow, None, 0))?;
+        // SAFETY: this creates a new private anonymous mapping, with no requirements.
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                len,
+                libc::PROT_NONE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_NORESERVE,
+                -1,
+                0,
+            )
+        };
+        let failed = BumpError::new(BumpErrorKind::AllocatorFailed, None, 0);
+        if ptr == libc::MAP_FAILED {
+            return Err(failed);
+        }
+        let start = NonNull::new(ptr.cast::<u8>()).ok_or(failed)?;
+
+        Ok(Self {
+            start,
+            // SAFETY: the mapping is len bytes long.
+            end: unsafe { start.add(len) },
+            committed: Cell::new(start),
+            current: Cell::new(start),
+        })
+    }
+
+    /// Returns the size of the reserved address range.
+    pub fn reserved(&self) -> usize {
+        self.end.addr().get() - self.start.addr().get()
+    }
+
+    /// Returns the number of committed bytes.
+    pub fn committed(&self) -> usize {
+        self.committed.get().addr().get() - self.start.addr().get()
+    }
+
+    /// Returns the number of bytes allocated since the last reset.
+    pub fn used(&self) -> usize {
+        self.current.get().addr().get() - self.start.addr().get()
+    }
+
+    /// Commits the pages up to `new_current`, if they are not already.
+    fn commit(&self, new_current: NonNull<u8>) -> Result<(), AllocError> {
+        let committed = self.committed.get();
+        if new_current <= committed {
+            return Ok(());
+        }
+        // Commit at least as much as is already committed, to amortize the system calls.
+        let target = (new_current.addr().get() - self.start.addr().get())
+            .max(2 * self.committed())
+            .min(self.reserved());
+        let target = round_to_pages(target).ok_or(AllocError)?;
+        let len = target - self.committed();
+        // SAFETY: committed is page aligned, and committed + len <= end is in bounds of the
+        // reserved mapping.
+        if unsafe {
+            libc::mprotect(
+                committed.as_ptr().cast(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+            )
+        } != 0
+        {
+            return Err(AllocError);
+        }
+        // SAFETY: the new committed pointer is in bounds of the mapping.
+        self.committed.set(unsafe { committed.add(len) });
+        Ok(())
+    }
+
+    /// Resets the [`ReservedBumpCar`], keeping its pages committed.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    pub fn reset(&mut self) {
+        self.current.set(self.start);
+    }
+
+    /// Resets the [`ReservedBumpCar`], and decommits all its pages.
+    pub fn reset_and_decommit(&mut self) {
+        self.reset();
+        let len = self.committed();
+        if len != 0 {
+            // SAFETY: the committed range is page aligned and in bounds of the mapping.
+            unsafe {
+                libc::madvise(self.start.as_ptr().cast(), len, libc::MADV_DONTNEED);
+                libc::mprotect(self.start.as_ptr().cast(), len, libc::PROT_NONE);
+            }
+            self.committed.set(self.start);
+        }
+    }
+}
+
+impl Drop for ReservedBumpCar {
+    /// Unmaps the reserved address range.
+    fn drop(&mut self) {
+        // SAFETY: the mapping was created in new with this length.
+        unsafe { libc::munmap(self.start.as_ptr().cast(), self.reserved()) };
+    }
+}
+
+unsafe impl Allocator for ReservedBumpCar {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: current and end are in bounds of the mapping, and current <= end.
+        let (ptr, new_current) =
+            unsafe { bump(self.current.get(), self.end, layout) }.ok_or(AllocError)?;
+        self.commit(new_current)?;
+        self.current.set(new_current);
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    /// Deallocates an allocated region.
+    ///
+    /// If it is the last allocation made, its space is reclaimed.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: the allocation is layout.size() bytes long, in bounds of the mapping.
+        if unsafe { ptr.add(layout.size()) } == self.current.get() {
+            self.current.set(ptr);
+        }
+    }
+
+    /// Grows an allocated region, in place if it is the last allocation made.
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: the allocation is old_layout.size() bytes long, in bounds of the mapping.
+        let is_last = unsafe { ptr.add(old_layout.size()) } == self.current.get();
+        if is_last
+            && ptr.addr().get() & (new_layout.align() - 1) == 0
+            && new_layout.size() <= self.end.addr().get() - ptr.addr().get()
+        {
+            // SAFETY: the new size fits before the end of the mapping.
+            let new_current = unsafe { ptr.add(new_layout.size()) };
+            self.commit(new_current)?;
+            self.current.set(new_current);
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+
+        let new_ptr = self.allocate(new_layout)?;
+        // SAFETY: the new allocation is valid for new_layout.size() >= old_layout.size() bytes,
+        // and cannot overlap the old allocation.
+        unsafe {
+            new_ptr
+                .cast::<u8>()
+                .copy_from_nonoverlapping(ptr, old_layout.size());
+        }
+        Ok(new_ptr)
+    }
+}
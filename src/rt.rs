@@ -0,0 +1,207 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::ptr::NonNull;
+
+use crate::Owns;
+
+/// Allocator wrapper checking that real-time code never leaves the arena.
+///
+/// While an [`RtGuard`] returned by [`RtCar::guard`] is alive, every violation is recorded and,
+/// with debug assertions, turned into a panic. What a violation is depends on the constructor:
+/// - [`RtCar::new`] wraps an arena, and a request it fails to serve (an arena miss) is a
+///   violation. As the primary allocator of a [`Fallback`](crate::Fallback), it catches every
+///   request about to spill.
+/// - [`RtCar::backing`] wraps a backing allocator, such as the global allocator, and any request
+///   reaching it is a violation, including deallocations.
+///
+/// Outside of a guard, requests are forwarded without any check.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use std::alloc::Global;
+/// use dodgems::{BumpCar, Fallback, RtCar};
+///
+/// let bumpcar = BumpCar::new(1024).unwrap();
+/// let heap = RtCar::backing(Global);
+/// let alloc = Fallback(&bumpcar, &heap);
+///
+/// let guard = heap.guard();
+/// let mut samples = Vec::with_capacity_in(64, &alloc);
+/// samples.extend(0..64u32);
+/// drop(guard);
+/// assert_eq!(heap.violations(), 0);
+/// ```
+#[derive(Debug)]
+pub struct RtCar<A> {
+    inner: A,
+    backing: bool,
+    guards: Cell<usize>,
+    violations: Cell<usize>,
+}
+
+impl<A: Allocator> RtCar<A> {
+    /// Wraps an arena, forbidding the requests it fails to serve while guarded.
+    pub const fn new(inner: A) -> Self {
+        Self {
+            inner,
+            backing: false,
+            guards: Cell::new(0),
+            violations: Cell::new(0),
+        }
+    }
+
+    /// Wraps a backing allocator, forbidding any request while guarded.
+    pub const fn backing(inner: A) -> Self {
+        Self {
+            inner,
+            backing: true,
+            guards: Cell::new(0),
+            violations: Cell::new(0),
+        }
+    }
+
+    /// Starts a real-time scope, that lasts until the returned guard is dropped.
+    ///
+    /// Guards can be nested.
+    pub fn guard(&self) -> RtGuard<'_, A> {
+        self.guards.set(self.guards.get() + 1);
+        RtGuard { rtcar: self }
+    }
+
+    /// Returns `true` if a guard is alive.
+    pub fn is_guarded(&self) -> bool {
+        self.guards.get() > 0
+    }
+
+    /// Returns the number of violations recorded since the creation of the wrapper, or the last
+    /// call to [`RtCar::clear_violations`].
+    ///
+    /// Without debug assertions, violations are only recorded, and must be checked with this
+    /// method.
+    pub fn violations(&self) -> usize {
+        self.violations.get()
+    }
+
+    /// Clears the recorded violations.
+    pub fn clear_violations(&self) {
+        self.violations.set(0);
+    }
+
+    /// Returns a reference to the inner allocator.
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Unwraps the inner allocator.
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+
+    /// Records a violation if a guard is alive.
+    #[cold]
+    fn violation(&self, what: &str, layout: Layout) {
+        if !self.is_guarded() {
+            return;
+        }
+        self.violations.set(self.violations.get() + 1);
+        #[cfg(debug_assertions)]
+        panic!(
+            "{what} of {} bytes aligned to {} in a real-time scope",
+            layout.size(),
+            layout.align()
+        );
+        #[cfg(not(debug_assertions))]
+        let _ = (what, layout);
+    }
+
+    /// Forwards a request to the inner allocator, and checks it.
+    fn checked(
+        &self,
+        layout: Layout,
+        f: impl FnOnce() -> Result<NonNull<[u8]>, AllocError>,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if self.backing && self.is_guarded() {
+            self.violation("backing allocation", layout);
+        }
+        let result = f();
+        if !self.backing && result.is_err() {
+            self.violation("arena miss", layout);
+        }
+        result
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for RtCar<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.checked(layout, || self.inner.allocate(layout))
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.checked(layout, || self.inner.allocate_zeroed(layout))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if self.backing {
+            self.violation("backing deallocation", layout);
+        }
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.inner.deallocate(ptr, layout) }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.checked(new_layout, || {
+            // SAFETY: guaranteed by the caller.
+            unsafe { self.inner.grow(ptr, old_layout, new_layout) }
+        })
+    }
+
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.checked(new_layout, || {
+            // SAFETY: guaranteed by the caller.
+            unsafe { self.inner.grow_zeroed(ptr, old_layout, new_layout) }
+        })
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.checked(new_layout, || {
+            // SAFETY: guaranteed by the caller.
+            unsafe { self.inner.shrink(ptr, old_layout, new_layout) }
+        })
+    }
+}
+
+// SAFETY: forwarded to the inner allocator, that makes every allocation.
+unsafe impl<A: Owns> Owns for RtCar<A> {
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        self.inner.owns(ptr, layout)
+    }
+}
+
+/// Real-time scope of an [`RtCar`], ended when the guard is dropped.
+#[derive(Debug)]
+#[must_use = "the real-time scope ends when the guard is dropped"]
+pub struct RtGuard<'a, A: Allocator> {
+    rtcar: &'a RtCar<A>,
+}
+
+impl<A: Allocator> Drop for RtGuard<'_, A> {
+    fn drop(&mut self) {
+        self.rtcar.guards.set(self.rtcar.guards.get() - 1);
+    }
+}
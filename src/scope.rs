@@ -0,0 +1,267 @@
+use core::alloc::Allocator;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use crate::typed::expect_capacity;
+use crate::{BumpCar, BumpError};
+
+/// Handle to a [`BumpCar`] inside a scope created with [`BumpCar::scope`].
+///
+/// It dereferences to the [`BumpCar`], so that allocations can be made through it. Every
+/// allocation made in the scope is reclaimed when the scope ends.
+pub struct BumpScope<'a, A: Allocator> {
+    bumpcar: &'a BumpCar<A>,
+}
+
+/// Handle to a [`BumpCar`] inside a scope created with [`BumpCar::branded_scope`].
+///
+/// The scope is branded with the invariant lifetime `'id`, that is unique to the closure it is
+/// passed to. Allocations are returned with this lifetime instead of borrowing the handle, so:
+/// - they can be used as long as the scope lasts, even inside nested scopes;
+/// - they cannot escape the closure, as its return type cannot name `'id`;
+/// - the [`BumpCar`] cannot be reset (or allocated from directly) while the scope exists,
+///   since it is borrowed mutably, and the outer scope cannot allocate while a nested scope
+///   exists, for the same reason.
+///
+/// All of this is checked at compile time: a library can hand out nested scopes of its
+/// arena without any runtime check.
+///
+/// # Example
+/// Allocations cannot escape their scope:
+/// ```rust,compile_fail
+/// use dodgems::BumpCar;
+///
+/// let mut bumpcar = BumpCar::new(256).unwrap();
+/// let escaped = bumpcar.branded_scope(|scope| scope.alloc(1u32));
+/// ```
+///
+/// The outer scope cannot allocate while a nested one exists:
+/// ```rust,compile_fail
+/// use dodgems::BumpCar;
+///
+/// let mut bumpcar = BumpCar::new(256).unwrap();
+/// bumpcar.branded_scope(|outer| {
+///     outer.scope(|inner| {
+///         let x = outer.alloc(1u32);
+///         let y = inner.alloc(2u32);
+///     })
+/// });
+/// ```
+pub struct BrandedScope<'id, 'a, A: Allocator> {
+    bumpcar: &'a BumpCar<A>,
+    brand: PhantomData<fn(&'id ()) -> &'id ()>,
+}
+
+/// Rolls the cursor back when the scope ends, even if the closure panics.
+struct RewindGuard<'a, A: Allocator> {
+    bumpcar: &'a BumpCar<A>,
+    position: usize,
+}
+
+impl<A: Allocator> Drop for RewindGuard<'_, A> {
+    fn drop(&mut self) {
+        // The cursor can only be below the saved position if allocations made before the scope
+        // were deallocated through unsafe code.
+        let position = self.bumpcar.position();
+        if position > self.position {
+            #[cfg(feature = "zeroize")]
+            self.bumpcar
+                .wipe(self.bumpcar.reclaim_from(self.position), position);
+            self.bumpcar.set_position(self.position);
+        }
+    }
+}
+
+/// Runs `f` with a new branded scope of `bumpcar`, and rewinds the cursor afterwards.
+fn run_branded_scope<'a, A: Allocator, R>(
+    bumpcar: &'a BumpCar<A>,
+    f: impl for<'id> FnOnce(&mut BrandedScope<'id, 'a, A>) -> R,
+) -> R {
+    let _guard = RewindGuard {
+        bumpcar,
+        position: bumpcar.position(),
+    };
+    f(&mut BrandedScope {
+        bumpcar,
+        brand: PhantomData,
+    })
+}
+
+/// Runs `f` with a new scope of `bumpcar`, and rewinds the cursor afterwards.
+fn run_scope<A: Allocator, R>(
+    bumpcar: &BumpCar<A>,
+    f: impl for<'s> FnOnce(&mut BumpScope<'s, A>) -> R,
+) -> R {
+    let _guard = RewindGuard {
+        bumpcar,
+        position: bumpcar.position(),
+    };
+    f(&mut BumpScope { bumpcar })
+}
+
+impl<A: Allocator> BumpCar<A> {
+    /// Calls `f` with a scoped handle to the [`BumpCar`], and reclaims every allocation made
+    /// through it when `f` returns.
+    ///
+    /// The allocations cannot escape the closure, as they borrow the handle. Scopes can be
+    /// nested with [`BumpScope::scope`].
+    ///
+    /// With the `zeroize` feature, the reclaimed bytes are overwritten with zeros.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let mut bumpcar = BumpCar::new(256).unwrap();
+    /// let sum = bumpcar.scope(|scope| {
+    ///     let inner = scope.scope(|inner| {
+    ///         let big = inner.alloc([4u32; 32]);
+    ///         big.iter().sum::<u32>()
+    ///     });
+    ///     assert_eq!(scope.remaining_capacity(), 256);
+    ///     let v = scope.alloc_slice_copy(&[1u32, 2, 3]);
+    ///     v.iter().sum::<u32>() + inner
+    /// });
+    /// assert_eq!(sum, 134);
+    /// assert_eq!(bumpcar.remaining_capacity(), 256);
+    /// ```
+    pub fn scope<R>(&mut self, f: impl for<'s> FnOnce(&mut BumpScope<'s, A>) -> R) -> R {
+        run_scope(self, f)
+    }
+
+    /// Calls `f` with a [branded](BrandedScope) handle to the [`BumpCar`], and reclaims every
+    /// allocation made through it when `f` returns.
+    ///
+    /// Unlike with [`BumpCar::scope`], the allocations do not borrow the handle, so that the
+    /// allocations of a scope can still be used inside its nested scopes.
+    ///
+    /// With the `zeroize` feature, the reclaimed bytes are overwritten with zeros.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let mut bumpcar = BumpCar::new(256).unwrap();
+    /// let sum = bumpcar.branded_scope(|outer| {
+    ///     let table = outer.alloc_slice_copy(&[1u32, 2, 3]);
+    ///     let total = outer.scope(|inner| {
+    ///         let doubled = inner.alloc([table[0] * 2, table[1] * 2, table[2] * 2]);
+    ///         table[0] = 10;
+    ///         doubled.iter().sum::<u32>()
+    ///     });
+    ///     total + table.iter().sum::<u32>()
+    /// });
+    /// assert_eq!(sum, 27);
+    /// assert_eq!(bumpcar.remaining_capacity(), 256);
+    /// ```
+    pub fn branded_scope<R>(
+        &mut self,
+        f: impl for<'id> FnOnce(&mut BrandedScope<'id, '_, A>) -> R,
+    ) -> R {
+        run_branded_scope(self, f)
+    }
+}
+
+impl<A: Allocator> BumpScope<'_, A> {
+    /// Calls `f` with a nested scope, and reclaims every allocation made through it when `f`
+    /// returns.
+    ///
+    /// The allocations made in the outer scope are kept, but they cannot be used until the
+    /// nested scope ends, as it borrows the outer handle mutably.
+    pub fn scope<R>(&mut self, f: impl for<'s> FnOnce(&mut BumpScope<'s, A>) -> R) -> R {
+        run_scope(self.bumpcar, f)
+    }
+}
+
+impl<A: Allocator> Deref for BumpScope<'_, A> {
+    type Target = BumpCar<A>;
+
+    fn deref(&self) -> &BumpCar<A> {
+        self.bumpcar
+    }
+}
+
+impl<'id, 'a, A: Allocator> BrandedScope<'id, 'a, A> {
+    /// Calls `f` with a nested scope, and reclaims every allocation made through it when `f`
+    /// returns.
+    ///
+    /// The allocations made in the outer scope can still be used in the nested scope, but the
+    /// outer scope cannot allocate until the nested scope ends.
+    pub fn scope<R>(
+        &mut self,
+        f: impl for<'inner> FnOnce(&mut BrandedScope<'inner, 'a, A>) -> R,
+    ) -> R {
+        run_branded_scope(self.bumpcar, f)
+    }
+
+    /// Returns the remaining capacity of the [`BumpCar`].
+    pub fn remaining_capacity(&self) -> usize {
+        self.bumpcar.remaining_capacity()
+    }
+
+    /// Moves `value` into the scope, and returns a mutable reference to it, valid until the
+    /// end of the scope.
+    ///
+    /// The value is never dropped.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    pub fn try_alloc<T>(&self, value: T) -> Result<&'id mut T, BumpError> {
+        let value: *mut T = self.bumpcar.try_alloc(value)?;
+        // SAFETY: the allocation is only reclaimed when the scope ends, and the brand 'id cannot
+        // outlive the scope.
+        Ok(unsafe { &mut *value })
+    }
+
+    /// Moves `value` into the scope, and returns a mutable reference to it, valid until the
+    /// end of the scope.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    pub fn alloc<T>(&self, value: T) -> &'id mut T {
+        expect_capacity(self.try_alloc(value))
+    }
+
+    /// Copies `values` into the scope, and returns a mutable reference to the copy, valid until
+    /// the end of the scope.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    pub fn try_alloc_slice_copy<T: Copy>(&self, values: &[T]) -> Result<&'id mut [T], BumpError> {
+        let values: *mut [T] = self.bumpcar.try_alloc_slice_copy(values)?;
+        // SAFETY: the allocation is only reclaimed when the scope ends, and the brand 'id cannot
+        // outlive the scope.
+        Ok(unsafe { &mut *values })
+    }
+
+    /// Copies `values` into the scope, and returns a mutable reference to the copy, valid until
+    /// the end of the scope.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    pub fn alloc_slice_copy<T: Copy>(&self, values: &[T]) -> &'id mut [T] {
+        expect_capacity(self.try_alloc_slice_copy(values))
+    }
+
+    /// Copies `s` into the scope, and returns a mutable reference to the copy, valid until the
+    /// end of the scope.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    pub fn try_alloc_str(&self, s: &str) -> Result<&'id mut str, BumpError> {
+        let s: *mut str = self.bumpcar.try_alloc_str(s)?;
+        // SAFETY: the allocation is only reclaimed when the scope ends, and the brand 'id cannot
+        // outlive the scope.
+        Ok(unsafe { &mut *s })
+    }
+
+    /// Copies `s` into the scope, and returns a mutable reference to the copy, valid until the
+    /// end of the scope.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    pub fn alloc_str(&self, s: &str) -> &'id mut str {
+        expect_capacity(self.try_alloc_str(s))
+    }
+}
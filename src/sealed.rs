@@ -0,0 +1,115 @@
+use core::alloc::Allocator;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{BumpCar, Handle, SliceHandle};
+
+/// [`BumpCar`] sealed with [`BumpCar::seal`], to be moved to another thread with its contents.
+///
+/// A [`BumpCar`] can not be sent to another thread, since allocations made through a shared
+/// reference would then be reachable from both threads. Sealing it consumes it, so no borrow of
+/// the arena or its allocations can remain, and the sealed arena is [`Send`] if its backing
+/// allocator is. On the receiving thread, the data can be read back through
+/// [handles](Handle), and the arena can be reused with [`SealedBumpCar::into_reset`].
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::BumpCar;
+///
+/// let (sealed, handle) = std::thread::spawn(|| {
+///     let bumpcar = BumpCar::new(256).unwrap();
+///     let handle = bumpcar.alloc_slice_handle(&[1u32, 2, 3]).unwrap();
+///     (bumpcar.seal(), handle)
+/// })
+/// .join()
+/// .unwrap();
+///
+/// // SAFETY: the handle was allocated in the sealed arena.
+/// assert_eq!(unsafe { sealed.get_slice(handle) }, [1, 2, 3]);
+/// let bumpcar = sealed.into_reset();
+/// assert_eq!(bumpcar.remaining_capacity(), 256);
+/// ```
+#[derive(Debug)]
+pub struct SealedBumpCar<
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    bumpcar: BumpCar<A>,
+}
+
+// SAFETY: the SealedBumpCar owns its buffer, and no reference into it can exist. Values in the
+// buffer are only reached through the methods below, that require them to be Send.
+unsafe impl<A: Allocator + Send> Send for SealedBumpCar<A> {}
+
+impl<A: Allocator> BumpCar<A> {
+    /// Seals the [`BumpCar`] with its contents, so that it can be sent to another thread.
+    pub fn seal(self) -> SealedBumpCar<A> {
+        SealedBumpCar { bumpcar: self }
+    }
+}
+
+impl<A: Allocator> SealedBumpCar<A> {
+    /// Returns the capacity of the sealed arena.
+    pub fn capacity(&self) -> usize {
+        self.bumpcar.capacity()
+    }
+
+    /// Returns the remaining capacity of the sealed arena.
+    pub fn remaining_capacity(&self) -> usize {
+        self.bumpcar.remaining_capacity()
+    }
+
+    /// Resolves a handle to a value allocated in the arena before it was sealed.
+    ///
+    /// # Safety
+    /// Same as [`BumpCar::get`].
+    ///
+    /// # Panics
+    /// This function panics if the handle is out of bounds or misaligned.
+    pub unsafe fn get<T: Send>(&self, handle: Handle<T>) -> &T {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.get(handle) }
+    }
+
+    /// Resolves a handle to a value allocated in the arena before it was sealed, mutably.
+    ///
+    /// # Safety
+    /// Same as [`BumpCar::get`].
+    ///
+    /// # Panics
+    /// This function panics if the handle is out of bounds or misaligned.
+    pub unsafe fn get_mut<T: Send>(&mut self, handle: Handle<T>) -> &mut T {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.get_mut(handle) }
+    }
+
+    /// Resolves a slice handle to a slice allocated in the arena before it was sealed.
+    ///
+    /// # Safety
+    /// Same as [`BumpCar::get_slice`].
+    ///
+    /// # Panics
+    /// This function panics if the handle is out of bounds or misaligned.
+    pub unsafe fn get_slice<T: Send>(&self, handle: SliceHandle<T>) -> &[T] {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.get_slice(handle) }
+    }
+
+    /// Resets the arena entirely, including its frozen prefix, and returns it as a
+    /// [`BumpCar`] to be reused on the current thread.
+    pub fn into_reset(mut self) -> BumpCar<A> {
+        self.bumpcar.reset_all();
+        self.bumpcar
+    }
+
+    /// Returns the [`BumpCar`] with its contents, on the current thread.
+    ///
+    /// # Safety
+    /// If the arena was sealed on another thread, the values allocated in it that are accessed
+    /// afterwards must be [`Send`].
+    pub unsafe fn unseal(self) -> BumpCar<A> {
+        self.bumpcar
+    }
+}
@@ -0,0 +1,206 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::mem::size_of;
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{bump, BumpError, SyncBumpCar};
+
+/// Concurrent bump allocator, handing out a private chunk to every thread.
+///
+/// Each thread allocates through its own [`Shard`], that bumps a local cursor without any
+/// synchronization, and only touches the shared reserve to get a new chunk when its current
+/// one is full. This avoids contention on a single atomic cursor, as with [`SyncBumpCar`].
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::ShardedBumpCar;
+///
+/// let mut bumpcar = ShardedBumpCar::new(4096, 256).unwrap();
+/// std::thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| {
+///             let shard = bumpcar.shard();
+///             let v: Vec<u64, _> = Vec::with_capacity_in(16, &shard);
+///             drop(v);
+///         });
+///     }
+/// });
+/// assert_eq!(bumpcar.remaining_capacity(), 4096 - 4 * 256);
+///
+/// bumpcar.reset();
+/// assert_eq!(bumpcar.remaining_capacity(), 4096);
+/// ```
+pub struct ShardedBumpCar<
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    reserve: SyncBumpCar<A>,
+    chunk_size: usize,
+}
+
+impl<A: Allocator> ShardedBumpCar<A> {
+    /// Allocates a new [`ShardedBumpCar`] in the given allocator, with a shared reserve of
+    /// `capacity` bytes, handed out to the shards in chunks of `chunk_size` bytes.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, chunk_size: usize, allocator: A) -> Result<Self, BumpError> {
+        Ok(Self {
+            reserve: SyncBumpCar::new_in(capacity, allocator)?,
+            chunk_size,
+        })
+    }
+
+    /// Creates a new, empty [`Shard`] of the [`ShardedBumpCar`], to allocate from one thread.
+    ///
+    /// The shard takes its first chunk from the reserve when it first allocates.
+    pub fn shard(&self) -> Shard<'_, A> {
+        Shard {
+            parent: self,
+            current: Cell::new(NonNull::dangling()),
+            end: Cell::new(NonNull::dangling()),
+        }
+    }
+
+    /// Returns the capacity of the [`ShardedBumpCar`].
+    pub fn capacity(&self) -> usize {
+        self.reserve.capacity()
+    }
+
+    /// Returns the remaining capacity of the shared reserve.
+    ///
+    /// The unused space in the current chunk of every shard is not included.
+    pub fn remaining_capacity(&self) -> usize {
+        self.reserve.remaining_capacity()
+    }
+
+    /// Returns the size of the chunks handed out to the shards.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Resets the [`ShardedBumpCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// This requires a mutable reference, so that every [`Shard`] and every allocation
+    /// are invalidated by the borrow checker.
+    pub fn reset(&mut self) {
+        self.reserve.reset();
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ShardedBumpCar {
+    /// Allocates a [`ShardedBumpCar`] with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize, chunk_size: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, chunk_size, Global)
+    }
+}
+
+/// Single-threaded view of a [`ShardedBumpCar`], allocating in a private chunk.
+///
+/// It is created with [`ShardedBumpCar::shard`]. Allocations bigger than the chunk size
+/// are made directly in the shared reserve.
+///
+/// Like the cursor of the reserve, the shard is aligned to a cache line, so that the cursors of
+/// shards stored next to each other do not share a cache line.
+#[cfg_attr(
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "powerpc64"
+    ),
+    repr(align(128))
+)]
+#[cfg_attr(
+    not(any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "powerpc64"
+    )),
+    repr(align(64))
+)]
+pub struct Shard<
+    'a,
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    parent: &'a ShardedBumpCar<A>,
+    current: Cell<NonNull<u8>>,
+    end: Cell<NonNull<u8>>,
+}
+
+// SAFETY: the chunk of the shard is private, and the parent is only accessed through
+// a shared reference.
+unsafe impl<A: Allocator + Sync> Send for Shard<'_, A> {}
+
+impl<A: Allocator> Shard<'_, A> {
+    /// Returns the remaining capacity of the current chunk of the [`Shard`].
+    pub fn remaining_capacity(&self) -> usize {
+        self.end.get().addr().get() - self.current.get().addr().get()
+    }
+
+    /// Takes a new chunk from the reserve, and allocates `layout` in it.
+    #[cold]
+    fn refill(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        refill(
+            &self.parent.reserve,
+            self.parent.chunk_size,
+            &self.current,
+            &self.end,
+            layout,
+        )
+    }
+}
+
+/// Takes a new chunk of `chunk_size` bytes from `reserve` and allocates `layout` in it, updating
+/// the cursor and end of the chunk. Allocations bigger than the chunk size are made directly in
+/// the reserve.
+pub(crate) fn refill<A: Allocator>(
+    reserve: &SyncBumpCar<A>,
+    chunk_size: usize,
+    current: &Cell<NonNull<u8>>,
+    end: &Cell<NonNull<u8>>,
+    layout: Layout,
+) -> Result<NonNull<u8>, AllocError> {
+    if layout.size() > chunk_size {
+        return Ok(reserve.allocate(layout)?.cast());
+    }
+
+    let chunk_layout = Layout::from_size_align(chunk_size, layout.align().max(size_of::<usize>()))
+        .map_err(|_| AllocError)?;
+    let chunk = reserve.allocate(chunk_layout)?.cast::<u8>();
+    // SAFETY: the chunk is chunk_size bytes long.
+    let chunk_end = unsafe { chunk.add(chunk_size) };
+    end.set(chunk_end);
+    // SAFETY: chunk and chunk_end are the bounds of the chunk.
+    let (ptr, new_current) = unsafe { bump(chunk, chunk_end, layout) }.ok_or(AllocError)?;
+    current.set(new_current);
+    Ok(ptr)
+}
+
+unsafe impl<A: Allocator> Allocator for &Shard<'_, A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: current and end are in bounds of the current chunk, and current <= end.
+        let ptr = match unsafe { bump(self.current.get(), self.end.get(), layout) } {
+            Some((ptr, new_current)) => {
+                self.current.set(new_current);
+                ptr
+            }
+            None => self.refill(layout)?,
+        };
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    /// The [`Shard`] does not perform deallocation, the memory is reclaimed when the
+    /// [`ShardedBumpCar`] is reset.
+    unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {}
+}
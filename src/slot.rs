@@ -0,0 +1,231 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::fmt;
+use core::mem::{align_of, size_of};
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{BumpError, BumpErrorKind};
+
+/// Fixed-size block allocator, carving equally sized slots out of a single buffer.
+///
+/// Unlike a [`BumpCar`](crate::BumpCar), it supports real deallocation: freed slots are kept in
+/// a free list, and reused by the next allocations. Every allocation takes a whole slot, so it
+/// is meant for many objects of the same type, such as the nodes of a tree or a linked list.
+///
+/// Allocations that do not fit in a slot (because they are too large or too aligned) fail.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use core::alloc::Layout;
+/// use dodgems::SlotCar;
+///
+/// let slotcar = SlotCar::new(Layout::new::<[u64; 4]>(), 2).unwrap();
+/// let a = Box::new_in([1u64; 4], &slotcar);
+/// let b = Box::new_in([2u64; 4], &slotcar);
+/// assert_eq!(slotcar.available(), 0);
+/// assert!(Box::try_new_in([3u64; 4], &slotcar).is_err());
+///
+/// drop(a);
+/// let c = Box::new_in([3u64; 4], &slotcar);
+/// assert_eq!(*b, [2; 4]);
+/// assert_eq!(*c, [3; 4]);
+/// ```
+pub struct SlotCar<
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    start: NonNull<u8>,
+    slot: Layout,
+    slots: usize,
+    /// Head of the list of freed slots, whose first word holds the next free slot.
+    free: Cell<Option<NonNull<u8>>>,
+    /// Number of slots handed out at least once. The slots after it are not in the free list.
+    carved: Cell<usize>,
+    available: Cell<usize>,
+    allocator: A,
+}
+
+impl<A: Allocator> SlotCar<A> {
+    /// Allocates a new [`SlotCar`] of `slots` slots fitting `layout`, in the given allocator.
+    ///
+    /// Slots are at least one pointer large and pointer aligned, to hold the free list.
+    ///
+    /// # Errors
+    /// This function returns an error if the total size is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(layout: Layout, slots: usize, allocator: A) -> Result<Self, BumpError> {
+        let overflow = BumpError::new(BumpErrorKind::Overflow, None, 0);
+        let slot = Layout::from_size_align(
+            layout.size().max(size_of::<usize>()),
+            layout.align().max(align_of::<usize>()),
+        )
+        .map_err(|_| overflow)?
+        .pad_to_align();
+        let size = slot.size().checked_mul(slots).ok_or(overflow)?;
+        let buffer = Layout::from_size_align(size, slot.align()).map_err(|_| overflow)?;
+        let start = allocator
+            .allocate(buffer)
+            .map_err(|_| BumpError::new(BumpErrorKind::AllocatorFailed, Some(buffer), 0))?
+            .cast::<u8>();
+
+        Ok(Self {
+            start,
+            slot,
+            slots,
+            free: Cell::new(None),
+            carved: Cell::new(0),
+            available: Cell::new(slots),
+            allocator,
+        })
+    }
+
+    /// Returns the size and alignment of a slot.
+    pub fn slot_layout(&self) -> Layout {
+        self.slot
+    }
+
+    /// Returns the total number of slots of the [`SlotCar`].
+    pub fn capacity(&self) -> usize {
+        self.slots
+    }
+
+    /// Returns the number of free slots.
+    pub fn available(&self) -> usize {
+        self.available.get()
+    }
+
+    /// Frees every slot at once.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    pub fn reset(&mut self) {
+        self.free.set(None);
+        self.carved.set(0);
+        self.available.set(self.slots);
+    }
+
+    /// Returns `true` if an allocation of `layout` fits in a slot.
+    fn fits(&self, layout: Layout) -> bool {
+        layout.size() <= self.slot.size() && layout.align() <= self.slot.align()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SlotCar {
+    /// Allocates a [`SlotCar`] of `slots` slots fitting `layout`, with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the total size is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(layout: Layout, slots: usize) -> Result<Self, BumpError> {
+        Self::new_in(layout, slots, Global)
+    }
+}
+
+impl<A: Allocator> Drop for SlotCar<A> {
+    /// Deallocates the [`SlotCar`]'s buffer.
+    fn drop(&mut self) {
+        // SAFETY: start was allocated with self.allocator, with this layout.
+        unsafe {
+            self.allocator.deallocate(
+                self.start,
+                Layout::from_size_align_unchecked(self.slot.size() * self.slots, self.slot.align()),
+            );
+        }
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for SlotCar<A> {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if !self.fits(layout) {
+            return Err(AllocError);
+        }
+
+        let ptr = if let Some(head) = self.free.get() {
+            // SAFETY: freed slots hold the next free slot in their first word.
+            self.free
+                .set(unsafe { head.cast::<Option<NonNull<u8>>>().read() });
+            head
+        } else if self.carved.get() < self.slots {
+            let index = self.carved.get();
+            self.carved.set(index + 1);
+            // SAFETY: index < slots, so the slot is in bounds of the buffer.
+            unsafe { self.start.add(index * self.slot.size()) }
+        } else {
+            return Err(AllocError);
+        };
+
+        self.available.set(self.available.get() - 1);
+        Ok(NonNull::slice_from_raw_parts(ptr, self.slot.size()))
+    }
+
+    /// Returns the slot to the free list.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, _: Layout) {
+        // SAFETY: ptr is a slot of this SlotCar, large and aligned enough for a pointer,
+        // and is no longer in use.
+        unsafe { ptr.cast::<Option<NonNull<u8>>>().write(self.free.get()) };
+        self.free.set(Some(ptr));
+        self.available.set(self.available.get() + 1);
+    }
+
+    /// Grows the allocation in its slot, or fails if the new layout does not fit in a slot.
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        _: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if self.fits(new_layout) {
+            Ok(NonNull::slice_from_raw_parts(ptr, self.slot.size()))
+        } else {
+            Err(AllocError)
+        }
+    }
+
+    /// Grows the allocation in its slot, or fails if the new layout does not fit in a slot.
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        let new = unsafe { self.grow(ptr, old_layout, new_layout)? };
+        // SAFETY: the slot is valid for its whole size, past the old allocation.
+        unsafe {
+            ptr.add(old_layout.size())
+                .write_bytes(0, new.len() - old_layout.size());
+        }
+        Ok(new)
+    }
+
+    /// Shrinks the allocation in its slot.
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        _: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if self.fits(new_layout) {
+            Ok(NonNull::slice_from_raw_parts(ptr, self.slot.size()))
+        } else {
+            Err(AllocError)
+        }
+    }
+}
+
+impl<A: Allocator + fmt::Debug> fmt::Debug for SlotCar<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlotCar")
+            .field("slot_layout", &self.slot)
+            .field("capacity", &self.capacity())
+            .field("available", &self.available())
+            .field("allocator", &self.allocator)
+            .finish()
+    }
+}
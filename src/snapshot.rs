@@ -0,0 +1,133 @@
+use alloc::boxed::Box;
+use core::alloc::{Allocator, Layout};
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ptr::NonNull;
+
+use crate::{BumpCar, BumpError, BumpErrorKind};
+
+/// Copy of the used part of a [`BumpCar`], taken by [`BumpCar::snapshot`].
+///
+/// It can be written back with [`BumpCar::restore`], to replay a deterministic computation from
+/// a known state of the arena.
+#[derive(Clone)]
+pub struct Snapshot {
+    /// Copy of the used part of the buffer, that contains uninitialized padding and
+    /// allocations that were never written.
+    bytes: Box<[MaybeUninit<u8>]>,
+    floor: usize,
+    #[cfg(debug_assertions)]
+    generation: u32,
+}
+
+impl Snapshot {
+    /// Returns the number of bytes used in the arena when the snapshot was taken.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns `true` if the arena was empty when the snapshot was taken.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns the copied bytes.
+    ///
+    /// # Safety
+    /// Every byte of the used part of the arena must have been initialized when the snapshot
+    /// was taken: alignment padding, and allocations that were never written, are not.
+    pub unsafe fn as_bytes(&self) -> &[u8] {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bytes.assume_init_ref() }
+    }
+}
+
+impl fmt::Debug for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Snapshot")
+            .field("len", &self.len())
+            .field("frozen", &self.floor)
+            .finish()
+    }
+}
+
+impl<A: Allocator> BumpCar<A> {
+    /// Copies the used part of the [`BumpCar`] and its cursor into a [`Snapshot`], allocated
+    /// with the global allocator.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let mut bumpcar = BumpCar::new(256).unwrap();
+    /// let state = bumpcar.alloc_handle([0u32; 4]).unwrap();
+    /// let snapshot = bumpcar.snapshot();
+    ///
+    /// // SAFETY: the handle was allocated in this BumpCar, which was not reset.
+    /// unsafe { bumpcar.get_mut(state)[0] = 42 };
+    /// bumpcar.alloc([0u8; 100]);
+    ///
+    /// bumpcar.restore(&snapshot).unwrap();
+    /// // SAFETY: the handle was allocated before the snapshot was taken.
+    /// assert_eq!(unsafe { bumpcar.get(state) }[0], 0);
+    /// assert_eq!(bumpcar.used(), snapshot.len());
+    /// ```
+    pub fn snapshot(&self) -> Snapshot {
+        let used = self.position();
+        let mut bytes = Box::new_uninit_slice(used);
+        // SAFETY: the used part of the buffer is valid for used bytes, and does not overlap the
+        // new box. Uninitialized bytes are copied as such.
+        unsafe {
+            bytes
+                .as_mut_ptr()
+                .cast::<u8>()
+                .copy_from_nonoverlapping(self.start.as_ptr(), used);
+        }
+        Snapshot {
+            bytes,
+            floor: self.floor,
+            #[cfg(debug_assertions)]
+            generation: self.generation.get(),
+        }
+    }
+
+    /// Resets the [`BumpCar`], and writes the contents of `snapshot` back at the start of its
+    /// buffer.
+    ///
+    /// The cursor and the frozen prefix are restored: [handles](crate::Handle) allocated before
+    /// the snapshot was taken are valid again. The snapshot can be restored into another
+    /// [`BumpCar`], as long as it is large enough.
+    ///
+    /// # Errors
+    /// This function returns an error if the snapshot does not fit in the capacity of the
+    /// [`BumpCar`], or if the value of a [pinned](BumpCar::alloc_pinned) box was leaked in it.
+    /// The [`BumpCar`] is then left unchanged.
+    pub fn restore(&mut self, snapshot: &Snapshot) -> Result<(), BumpError> {
+        let len = snapshot.len();
+        if len > self.capacity() || self.has_pinned() {
+            let layout = Layout::from_size_align(len, 1).ok();
+            return Err(BumpError::new(
+                BumpErrorKind::CapacityExceeded,
+                layout,
+                self.capacity(),
+            ));
+        }
+
+        self.reset_all();
+        // SAFETY: len <= capacity <= isize::MAX.
+        let prefix = self
+            .allocate(unsafe { Layout::from_size_align_unchecked(len, 1) })
+            .map_err(|_| BumpError::new(BumpErrorKind::CapacityExceeded, None, 0))?;
+        // SAFETY: the prefix is valid for len bytes, and does not overlap the snapshot.
+        unsafe {
+            prefix
+                .cast::<u8>()
+                .copy_from_nonoverlapping(NonNull::from(&*snapshot.bytes).cast(), len);
+        }
+        self.floor = snapshot.floor;
+        #[cfg(debug_assertions)]
+        self.generation.set(snapshot.generation);
+        Ok(())
+    }
+}
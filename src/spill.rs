@@ -0,0 +1,212 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{BumpCar, BumpError};
+
+/// Header placed before every block spilled to the backing allocator.
+struct SpillHeader {
+    /// Previously spilled block, if any.
+    prev: Option<NonNull<SpillHeader>>,
+    /// Layout the block was allocated with, header included.
+    layout: Layout,
+}
+
+/// [`BumpCar`] forwarding the allocations that do not fit in its buffer to the backing
+/// allocator, instead of failing.
+///
+/// The spilled blocks are kept in a list, and are only freed when the [`SpillBumpCar`] is reset
+/// or dropped, like the rest of the arena.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::SpillBumpCar;
+///
+/// let mut bumpcar = SpillBumpCar::new(64).unwrap();
+/// let small = Box::new_in([0u8; 32], &bumpcar);
+/// let big = Box::new_in([0u8; 256], &bumpcar);
+/// assert_eq!(bumpcar.spilled_blocks(), 1);
+///
+/// drop((small, big));
+/// bumpcar.reset();
+/// assert_eq!(bumpcar.spilled_blocks(), 0);
+/// ```
+pub struct SpillBumpCar<
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    bumpcar: BumpCar<A>,
+    spilled: Cell<Option<NonNull<SpillHeader>>>,
+    spilled_blocks: Cell<usize>,
+}
+
+impl<A: Allocator> SpillBumpCar<A> {
+    /// Allocates a new [`SpillBumpCar`] in the given allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        Ok(Self {
+            bumpcar: BumpCar::new_in(capacity, allocator)?,
+            spilled: Cell::new(None),
+            spilled_blocks: Cell::new(0),
+        })
+    }
+
+    /// Returns the underlying [`BumpCar`].
+    pub fn bumpcar(&self) -> &BumpCar<A> {
+        &self.bumpcar
+    }
+
+    /// Returns the number of blocks currently spilled to the backing allocator.
+    pub fn spilled_blocks(&self) -> usize {
+        self.spilled_blocks.get()
+    }
+
+    /// Resets the [`SpillBumpCar`]'s remaining capacity to its initial capacity, and frees
+    /// the spilled blocks.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    pub fn reset(&mut self) {
+        // SAFETY: the mutable borrow guarantees that no spilled block is still in use.
+        unsafe { self.free_spilled() };
+        self.bumpcar.reset();
+    }
+
+    /// Allocates `layout` in the backing allocator, and links it in the spilled list.
+    #[cold]
+    fn spill(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let (block_layout, offset) = Layout::new::<SpillHeader>()
+            .extend(layout)
+            .map_err(|_| AllocError)?;
+        let header = self
+            .bumpcar
+            .allocator
+            .allocate(block_layout)?
+            .cast::<SpillHeader>();
+        // SAFETY: the block is valid for block_layout, which starts with a SpillHeader and
+        // holds layout.size() bytes at offset.
+        unsafe {
+            header.write(SpillHeader {
+                prev: self.spilled.get(),
+                layout: block_layout,
+            });
+            self.spilled.set(Some(header));
+            self.spilled_blocks.set(self.spilled_blocks.get() + 1);
+            Ok(NonNull::slice_from_raw_parts(
+                header.cast::<u8>().add(offset),
+                layout.size(),
+            ))
+        }
+    }
+
+    /// Frees every spilled block.
+    ///
+    /// # Safety
+    /// The spilled blocks must not be used afterwards.
+    unsafe fn free_spilled(&self) {
+        while let Some(header) = self.spilled.take() {
+            // SAFETY: the block is live, and was allocated with the layout of its header.
+            unsafe {
+                let SpillHeader { prev, layout } = header.read();
+                self.bumpcar.allocator.deallocate(header.cast(), layout);
+                self.spilled.set(prev);
+            }
+        }
+        self.spilled_blocks.set(0);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SpillBumpCar {
+    /// Allocates a [`SpillBumpCar`] with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, Global)
+    }
+}
+
+impl<A: Allocator> Drop for SpillBumpCar<A> {
+    /// Frees the spilled blocks, before the buffer is deallocated.
+    fn drop(&mut self) {
+        // SAFETY: the spilled blocks are owned by self, which is being dropped.
+        unsafe { self.free_spilled() };
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for SpillBumpCar<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.bumpcar
+            .allocate(layout)
+            .or_else(|_| self.spill(layout))
+    }
+
+    /// Deallocates an allocated region.
+    ///
+    /// Spilled blocks are only freed when the [`SpillBumpCar`] is reset or dropped.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if self.bumpcar.owns_allocation(ptr, layout) {
+            // SAFETY: the region was allocated in the arena.
+            unsafe { self.bumpcar.deallocate(ptr, layout) }
+        }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if self.bumpcar.owns_allocation(ptr, old_layout) {
+            // SAFETY: the region was allocated in the arena.
+            if let Ok(new_ptr) = unsafe { self.bumpcar.grow(ptr, old_layout, new_layout) } {
+                return Ok(new_ptr);
+            }
+        }
+
+        let new_ptr = self.allocate(new_layout)?;
+        // SAFETY: the new allocation is valid for new_layout.size() >= old_layout.size() bytes,
+        // and cannot overlap the old allocation.
+        unsafe {
+            new_ptr
+                .cast::<u8>()
+                .copy_from_nonoverlapping(ptr, old_layout.size());
+        }
+        Ok(new_ptr)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if self.bumpcar.owns_allocation(ptr, old_layout) {
+            // SAFETY: the region was allocated in the arena.
+            if let Ok(new_ptr) = unsafe { self.bumpcar.shrink(ptr, old_layout, new_layout) } {
+                return Ok(new_ptr);
+            }
+        } else if ptr.addr().get() & (new_layout.align() - 1) == 0 {
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+
+        let new_ptr = self.allocate(new_layout)?;
+        // SAFETY: the new allocation is valid for new_layout.size() bytes,
+        // and cannot overlap the old allocation.
+        unsafe {
+            new_ptr
+                .cast::<u8>()
+                .copy_from_nonoverlapping(ptr, new_layout.size());
+        }
+        Ok(new_ptr)
+    }
+}
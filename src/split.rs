@@ -0,0 +1,221 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::mem::{size_of, MaybeUninit};
+use core::ptr::NonNull;
+
+use crate::{next_multiple, BorrowedBuffer, BumpCar, BumpError};
+
+/// Disjoint part of the remaining capacity of a [`BumpCar`], created with
+/// [`BumpCar::split_n`] or [`BumpCar::split_at`].
+///
+/// Unlike a [`BumpCar`], a [`SplitCar`] can be sent to another thread, so that parallel workers
+/// (such as rayon tasks) each allocate in their own part without any synchronization.
+#[derive(Debug)]
+pub struct SplitCar<'a> {
+    bumpcar: BumpCar<BorrowedBuffer<'a>>,
+}
+
+// SAFETY: the split owns a region of the parent buffer that no other split or reference can
+// reach. Allocations made in it borrow the split, so they can not be sent with it, and values
+// left in its buffer can not be reached from another thread.
+unsafe impl Send for SplitCar<'_> {}
+
+impl<A: Allocator> BumpCar<A> {
+    /// Divides the remaining capacity of the [`BumpCar`] into `n` disjoint [`SplitCar`]s of
+    /// equal capacity, that can be used from different threads.
+    ///
+    /// The [`BumpCar`] is borrowed mutably while the splits are alive. Its cursor does not
+    /// move: once the splits are dropped, their capacity is available to the [`BumpCar`] again.
+    ///
+    /// # Panics
+    /// This function panics if `n` is zero.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let mut bumpcar = BumpCar::new(4096).unwrap();
+    /// let sums: Vec<u64> = std::thread::scope(|s| {
+    ///     let workers: Vec<_> = bumpcar
+    ///         .split_n(4)
+    ///         .enumerate()
+    ///         .map(|(i, split)| {
+    ///             s.spawn(move || {
+    ///                 let mut v = Vec::with_capacity_in(100, &split);
+    ///                 v.extend((0..100).map(|j| (i * 100 + j) as u64));
+    ///                 v.iter().sum()
+    ///             })
+    ///         })
+    ///         .collect();
+    ///     workers.into_iter().map(|w| w.join().unwrap()).collect()
+    /// });
+    /// assert_eq!(sums.iter().sum::<u64>(), (0..400).sum());
+    /// assert_eq!(bumpcar.remaining_capacity(), 4096);
+    /// ```
+    pub fn split_n(&mut self, n: usize) -> impl ExactSizeIterator<Item = SplitCar<'_>> {
+        assert!(n != 0, "cannot split a BumpCar in zero parts");
+        let align = size_of::<usize>();
+        // SAFETY: align is a power of two, and the cursor is in bounds of the buffer.
+        let base = unsafe { next_multiple(self.current.get().addr().get(), align) };
+        let chunk = (self.end.addr().get().saturating_sub(base) / n) & !(align - 1);
+        // The splits write past the cursor, so their bytes are no longer pristine once the
+        // cursor moves over them again.
+        let end = base + n * chunk - self.start.addr().get();
+        self.pristine.set(self.pristine.get().max(end));
+        let current = self.current.get();
+        (0..n).map(move |i| {
+            // SAFETY: the n chunks of chunk bytes after base are in bounds of the buffer.
+            let start = unsafe { current.byte_add(base - current.addr().get() + i * chunk) };
+            // SAFETY: the chunks are disjoint and unused by the BumpCar, which stays mutably
+            // borrowed while the splits are alive.
+            let buffer = unsafe {
+                core::slice::from_raw_parts_mut(start.cast::<MaybeUninit<u8>>().as_ptr(), chunk)
+            };
+            SplitCar {
+                bumpcar: BumpCar::from_buffer(buffer),
+            }
+        })
+    }
+
+    /// Divides the remaining capacity of the [`BumpCar`] into two disjoint [`SplitCar`]s, the
+    /// first one holding the `mid` bytes after the cursor, and the second one the rest.
+    ///
+    /// Each half has its own cursor and can be reset on its own, or sent to another thread.
+    /// As with [`BumpCar::split_n`], the [`BumpCar`] is borrowed mutably while the halves are
+    /// alive, and its cursor does not move.
+    ///
+    /// # Panics
+    /// This function panics if `mid` is greater than the remaining capacity.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let mut bumpcar = BumpCar::new(1024).unwrap();
+    /// let (mut parser, lexer) = bumpcar.split_at(256);
+    /// assert_eq!(parser.capacity(), 256);
+    /// assert_eq!(lexer.capacity(), 768);
+    ///
+    /// let tokens = lexer.alloc_slice_copy(&[1u8, 2, 3]);
+    /// parser.alloc([0u64; 8]);
+    /// parser.reset();
+    /// assert_eq!(tokens, &[1, 2, 3]);
+    /// ```
+    pub fn split_at(&mut self, mid: usize) -> (SplitCar<'_>, SplitCar<'_>) {
+        let remaining = self.remaining_capacity();
+        assert!(
+            mid <= remaining,
+            "split point {mid} is out of bounds of the remaining capacity {remaining}"
+        );
+        // As for split_n, the halves may write anywhere in the remaining capacity.
+        self.pristine.set(usize::MAX);
+        let current = self.current.get().cast::<MaybeUninit<u8>>();
+        // SAFETY: the remaining capacity after the cursor is unused by the BumpCar, which stays
+        // mutably borrowed while the halves are alive, and mid is in bounds of it.
+        let (front, back) = unsafe { core::slice::from_raw_parts_mut(current.as_ptr(), remaining) }
+            .split_at_mut(mid);
+        (
+            SplitCar {
+                bumpcar: BumpCar::from_buffer(front),
+            },
+            SplitCar {
+                bumpcar: BumpCar::from_buffer(back),
+            },
+        )
+    }
+}
+
+impl SplitCar<'_> {
+    /// Returns the capacity of the [`SplitCar`].
+    pub fn capacity(&self) -> usize {
+        self.bumpcar.capacity()
+    }
+
+    /// Returns the remaining capacity of the [`SplitCar`].
+    pub fn remaining_capacity(&self) -> usize {
+        self.bumpcar.remaining_capacity()
+    }
+
+    /// Allocates `value` in the [`SplitCar`].
+    ///
+    /// # Errors
+    /// This function returns an error if the [`SplitCar`] does not have enough capacity left.
+    pub fn try_alloc<T>(&self, value: T) -> Result<&mut T, BumpError> {
+        self.bumpcar.try_alloc(value)
+    }
+
+    /// Allocates `value` in the [`SplitCar`].
+    ///
+    /// # Panics
+    /// This function panics if the [`SplitCar`] does not have enough capacity left.
+    pub fn alloc<T>(&self, value: T) -> &mut T {
+        self.bumpcar.alloc(value)
+    }
+
+    /// Copies `values` in the [`SplitCar`].
+    ///
+    /// # Panics
+    /// This function panics if the [`SplitCar`] does not have enough capacity left.
+    pub fn alloc_slice_copy<T: Copy>(&self, values: &[T]) -> &mut [T] {
+        self.bumpcar.alloc_slice_copy(values)
+    }
+
+    /// Copies `s` in the [`SplitCar`].
+    ///
+    /// # Panics
+    /// This function panics if the [`SplitCar`] does not have enough capacity left.
+    pub fn alloc_str(&self, s: &str) -> &mut str {
+        self.bumpcar.alloc_str(s)
+    }
+
+    /// Resets the [`SplitCar`]'s remaining capacity to its initial capacity.
+    pub fn reset(&mut self) {
+        self.bumpcar.reset();
+    }
+}
+
+unsafe impl Allocator for SplitCar<'_> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.bumpcar.allocate(layout)
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.bumpcar.allocate_zeroed(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.deallocate(ptr, layout) }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.grow(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.grow_zeroed(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.shrink(ptr, old_layout, new_layout) }
+    }
+}
@@ -0,0 +1,239 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::Cell;
+use core::fmt;
+use core::mem::{align_of, size_of};
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{BumpError, BumpErrorKind};
+
+/// Stack allocator, that reclaims memory when allocations are freed in reverse order.
+///
+/// Every allocation is preceded by a one word header holding the previous position of the
+/// cursor, so that freeing the last allocation rewinds the cursor past its alignment padding,
+/// and the allocation before it becomes the last one again. As long as allocations are freed
+/// in LIFO order, the memory is reused continuously without any reset.
+///
+/// Freeing an allocation that is not the last one is a logic error: it panics in debug builds,
+/// and its memory is only reclaimed on [`StackCar::reset`] in release builds.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::StackCar;
+///
+/// fn depth(n: u32, stackcar: &StackCar) -> u32 {
+///     let frame = Box::new_in([n; 16], stackcar);
+///     if n == 0 { 0 } else { frame[0] + depth(n - 1, stackcar) }
+/// }
+///
+/// let stackcar = StackCar::new(4096).unwrap();
+/// for _ in 0..100 {
+///     assert_eq!(depth(10, &stackcar), 55);
+/// }
+/// assert_eq!(stackcar.remaining_capacity(), 4096);
+/// ```
+pub struct StackCar<
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    start: NonNull<u8>,
+    capacity: usize,
+    position: Cell<usize>,
+    allocator: A,
+}
+
+impl<A: Allocator> StackCar<A> {
+    /// Allocates a new [`StackCar`] in the given allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        let layout = Layout::from_size_align(capacity, size_of::<usize>())
+            .map_err(|_| BumpError::new(BumpErrorKind::Overflow, None, 0))?;
+        let start = allocator
+            .allocate(layout)
+            .map_err(|_| BumpError::new(BumpErrorKind::AllocatorFailed, Some(layout), 0))?
+            .cast::<u8>();
+
+        Ok(Self {
+            start,
+            capacity,
+            position: Cell::new(0),
+            allocator,
+        })
+    }
+
+    /// Returns the capacity of the [`StackCar`].
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the remaining capacity of the [`StackCar`].
+    ///
+    /// As for [`BumpCar::remaining_capacity`](crate::BumpCar::remaining_capacity), this does not
+    /// account for alignment, nor for the headers of the allocations.
+    pub fn remaining_capacity(&self) -> usize {
+        self.capacity - self.position.get()
+    }
+
+    /// Resets the [`StackCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    pub fn reset(&mut self) {
+        self.position.set(0);
+    }
+
+    /// Returns the offset of `ptr` from the start of the buffer.
+    fn offset(&self, ptr: NonNull<u8>) -> usize {
+        ptr.addr().get() - self.start.addr().get()
+    }
+
+    /// Returns `true` if the allocation at `ptr` of `size` bytes is the last one.
+    fn is_last(&self, ptr: NonNull<u8>, size: usize) -> bool {
+        self.offset(ptr) + size == self.position.get()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl StackCar {
+    /// Allocates a [`StackCar`] with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, Global)
+    }
+}
+
+impl<A: Allocator> Drop for StackCar<A> {
+    /// Deallocates the [`StackCar`]'s buffer.
+    fn drop(&mut self) {
+        // SAFETY: start was allocated with self.allocator, with this layout.
+        unsafe {
+            self.allocator.deallocate(
+                self.start,
+                Layout::from_size_align_unchecked(self.capacity, size_of::<usize>()),
+            );
+        }
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for StackCar<A> {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let base = self.start.addr().get();
+        let position = self.position.get();
+        let align_mask = layout.align().max(align_of::<usize>()) - 1;
+
+        let aligned = (base + position)
+            .checked_add(size_of::<usize>() + align_mask)
+            .ok_or(AllocError)?
+            & !align_mask;
+        let offset = aligned - base;
+        let end = offset.checked_add(layout.size()).ok_or(AllocError)?;
+        if end > self.capacity {
+            return Err(AllocError);
+        }
+
+        // SAFETY: position + size_of::<usize>() <= offset <= capacity, so both the header and
+        // the allocation are in bounds of the buffer, and the header is aligned to usize.
+        let ptr = unsafe {
+            let ptr = self.start.add(offset);
+            ptr.cast::<usize>().sub(1).write(position);
+            ptr
+        };
+        self.position.set(end);
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    /// Deallocates an allocated region.
+    ///
+    /// If it is the last allocation made, the cursor is rewound to its position before the
+    /// allocation. Otherwise, this panics in debug builds, and does nothing in release builds.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        let is_last = self.is_last(ptr, layout.size());
+        debug_assert!(is_last, "StackCar allocations must be freed in LIFO order");
+        if is_last {
+            // SAFETY: the allocation was preceded by its header by StackCar::allocate.
+            self.position
+                .set(unsafe { ptr.cast::<usize>().sub(1).read() });
+        }
+    }
+
+    /// Grows an allocated region.
+    ///
+    /// If it is the last allocation made, it is extended in place when there is enough capacity
+    /// left. Otherwise, a new region is allocated and the data is copied into it; the old region
+    /// is only reclaimed once an earlier allocation is deallocated.
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if ptr.addr().get() & (new_layout.align() - 1) == 0 && self.is_last(ptr, old_layout.size())
+        {
+            let end = self.offset(ptr) + new_layout.size();
+            if end > self.capacity {
+                return Err(AllocError);
+            }
+            self.position.set(end);
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+
+        let new_ptr = self.allocate(new_layout)?;
+        // SAFETY: the new allocation is valid for new_layout.size() >= old_layout.size() bytes,
+        // and cannot overlap the old allocation.
+        unsafe {
+            new_ptr
+                .cast::<u8>()
+                .copy_from_nonoverlapping(ptr, old_layout.size());
+        }
+        Ok(new_ptr)
+    }
+
+    /// Shrinks an allocated region.
+    ///
+    /// If it is the last allocation made, the freed tail is reclaimed. If the region is not
+    /// aligned enough for the new layout, a new region is allocated and the data is copied into
+    /// it; the old region is only reclaimed once an earlier allocation is deallocated.
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if ptr.addr().get() & (new_layout.align() - 1) != 0 {
+            let new_ptr = self.allocate(new_layout)?;
+            // SAFETY: the new allocation is valid for new_layout.size() <= old_layout.size()
+            // bytes, and cannot overlap the old allocation.
+            unsafe {
+                new_ptr
+                    .cast::<u8>()
+                    .copy_from_nonoverlapping(ptr, new_layout.size());
+            }
+            return Ok(new_ptr);
+        }
+        if self.is_last(ptr, old_layout.size()) {
+            self.position.set(self.offset(ptr) + new_layout.size());
+        }
+        Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()))
+    }
+}
+
+impl<A: Allocator + fmt::Debug> fmt::Debug for StackCar<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StackCar")
+            .field("capacity", &self.capacity())
+            .field("used", &(self.capacity() - self.remaining_capacity()))
+            .field("remaining", &self.remaining_capacity())
+            .field("allocator", &self.allocator)
+            .finish()
+    }
+}
@@ -0,0 +1,178 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::sync::{bump_atomic, CachePadded};
+use crate::{BufferBumpCar, BumpCar};
+
+/// Bump allocator with inline storage, that can be placed in a `static`.
+///
+/// Unlike [`BumpCar`](crate::BumpCar), it does not need a backing allocator, and it can be
+/// shared between threads: the cursor is updated atomically.
+///
+/// Since a `static` can never be borrowed mutably, [`StaticBumpCar::reset`] is unsafe.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::StaticBumpCar;
+///
+/// static ARENA: StaticBumpCar<1024> = StaticBumpCar::new();
+///
+/// let v = Box::new_in([1u32, 2, 3], &ARENA);
+/// assert_eq!(ARENA.remaining_capacity(), 1012);
+/// ```
+pub struct StaticBumpCar<const N: usize> {
+    buffer: UnsafeCell<[MaybeUninit<u8>; N]>,
+    position: CachePadded<AtomicUsize>,
+}
+
+// SAFETY: the buffer is only accessed through disjoint allocations,
+// handed out by atomic updates of the cursor.
+unsafe impl<const N: usize> Sync for StaticBumpCar<N> {}
+
+impl<const N: usize> StaticBumpCar<N> {
+    /// Creates a new [`StaticBumpCar`] with a capacity of `N` bytes.
+    pub const fn new() -> Self {
+        Self {
+            buffer: UnsafeCell::new([MaybeUninit::uninit(); N]),
+            position: CachePadded::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Returns the capacity of the [`StaticBumpCar`].
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the remaining capacity of the [`StaticBumpCar`].
+    ///
+    /// As for [`BumpCar::remaining_capacity`](crate::BumpCar::remaining_capacity), this does not
+    /// account for alignment.
+    pub fn remaining_capacity(&self) -> usize {
+        N - self.position.load(Ordering::Relaxed)
+    }
+
+    /// Takes exclusive ownership of the storage, as a [`BumpCar`] that can be reset safely.
+    ///
+    /// Returns `None` if the storage has already been taken, or if allocations have already been
+    /// made through `&StaticBumpCar`. Afterwards, allocating through `&StaticBumpCar` fails.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::StaticBumpCar;
+    ///
+    /// static ARENA: StaticBumpCar<1024> = StaticBumpCar::new();
+    ///
+    /// let mut bumpcar = ARENA.init().unwrap();
+    /// assert!(ARENA.init().is_none());
+    ///
+    /// let v = Box::new_in([1u32, 2, 3], &bumpcar);
+    /// drop(v);
+    /// bumpcar.reset();
+    /// assert_eq!(bumpcar.remaining_capacity(), 1024);
+    /// ```
+    pub fn init(&self) -> Option<BufferBumpCar<'_>> {
+        self.position
+            .compare_exchange(0, N, Ordering::Acquire, Ordering::Relaxed)
+            .ok()?;
+        // SAFETY: the cursor now marks the whole buffer as allocated, so no other allocation
+        // or call to init can access it until it is reset.
+        let buffer = unsafe { &mut *self.buffer.get() };
+        Some(BumpCar::from_buffer(buffer))
+    }
+
+    /// Resets the [`StaticBumpCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// # Safety
+    /// No allocation made with this [`StaticBumpCar`] (nor the [`BumpCar`] returned by
+    /// [`StaticBumpCar::init`]) may be used after the reset, and no allocation may
+    /// happen concurrently.
+    ///
+    /// With the `zeroize` feature, the used memory is overwritten with zeros.
+    pub unsafe fn reset(&self) {
+        #[cfg(feature = "zeroize")]
+        // SAFETY: the used part of the buffer is no longer in use, as guaranteed by the caller.
+        unsafe {
+            crate::wipe_bytes(
+                NonNull::new_unchecked(self.buffer.get().cast::<u8>()),
+                self.position.load(Ordering::Relaxed),
+            );
+        }
+        self.position.store(0, Ordering::Relaxed);
+    }
+
+    /// Resets the [`StaticBumpCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// This is the safe version of [`StaticBumpCar::reset`], for non-static instances.
+    pub fn reset_mut(&mut self) {
+        // SAFETY: the mutable borrow guarantees that no allocation is still in use.
+        unsafe { self.reset() };
+    }
+}
+
+impl<const N: usize> Default for StaticBumpCar<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<const N: usize> Allocator for &StaticBumpCar<N> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: the pointer to the buffer is non null.
+        let start = unsafe { NonNull::new_unchecked(self.buffer.get().cast::<u8>()) };
+        // SAFETY: the buffer is valid for N bytes, and the cursor never exceeds it.
+        unsafe { bump_atomic(start, N, &self.position, layout) }
+    }
+
+    /// The [`StaticBumpCar`] does not perform deallocation unless it's reset.
+    unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {}
+}
+
+impl<const N: usize> fmt::Debug for StaticBumpCar<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticBumpCar")
+            .field("capacity", &self.capacity())
+            .field("used", &(self.capacity() - self.remaining_capacity()))
+            .field("remaining", &self.remaining_capacity())
+            .finish()
+    }
+}
+
+/// Storage of an arena declared by [`static_bumpcar!`](crate::static_bumpcar).
+///
+/// It is kept apart from its take flag, so that it can be placed in a linker section that is
+/// not initialized at startup.
+#[doc(hidden)]
+pub struct StaticBuffer<const N: usize> {
+    buffer: UnsafeCell<[MaybeUninit<u8>; N]>,
+}
+
+// SAFETY: the buffer is only accessed by the single BumpCar returned by StaticBuffer::take.
+unsafe impl<const N: usize> Sync for StaticBuffer<N> {}
+
+impl<const N: usize> StaticBuffer<N> {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            buffer: UnsafeCell::new([MaybeUninit::uninit(); N]),
+        }
+    }
+
+    /// Returns a [`BumpCar`] in the buffer, the first time it is called.
+    ///
+    /// # Safety
+    /// `taken` must be used as the take flag of this buffer only.
+    pub unsafe fn take(&'static self, taken: &AtomicBool) -> Option<BufferBumpCar<'static>> {
+        if taken.swap(true, Ordering::Acquire) {
+            return None;
+        }
+        // SAFETY: the flag was not set, so the buffer has never been borrowed.
+        let buffer = unsafe { &mut *self.buffer.get() };
+        Some(BumpCar::from_buffer(buffer))
+    }
+}
@@ -0,0 +1,62 @@
+use core::alloc::Allocator;
+
+use crate::BumpCar;
+
+/// Allocation counters of a [`BumpCar`], returned by [`BumpCar::stats`].
+///
+/// The counters are cumulative: they are not cleared when the [`BumpCar`] is reset,
+/// but with [`BumpCar::reset_stats`].
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of successful allocations, including the ones made to move a grown region.
+    pub allocations: usize,
+    /// Number of allocations that failed for lack of capacity.
+    pub failed_allocations: usize,
+    /// Number of bytes handed out by successful allocations.
+    pub bytes_allocated: usize,
+    /// Number of bytes skipped to align allocations.
+    pub alignment_waste: usize,
+    /// Number of calls to [`Allocator::grow`] and [`Allocator::grow_zeroed`].
+    pub grows: usize,
+    /// Number of calls to [`Allocator::shrink`].
+    pub shrinks: usize,
+    /// Number of resets of the [`BumpCar`], including the ones that keep its frozen prefix.
+    pub resets: usize,
+}
+
+impl<A: Allocator> BumpCar<A> {
+    /// Returns the allocation counters of the [`BumpCar`].
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(64).unwrap();
+    /// bumpcar.alloc(1u8);
+    /// bumpcar.alloc(2u64);
+    /// assert!(bumpcar.try_alloc([0u8; 64]).is_err());
+    ///
+    /// let stats = bumpcar.stats();
+    /// assert_eq!(stats.allocations, 2);
+    /// assert_eq!(stats.failed_allocations, 1);
+    /// assert_eq!(stats.bytes_allocated, 9);
+    /// assert_eq!(stats.alignment_waste, 7);
+    /// ```
+    pub fn stats(&self) -> Stats {
+        self.stats.get()
+    }
+
+    /// Clears the allocation counters of the [`BumpCar`].
+    pub fn reset_stats(&self) {
+        self.stats.set(Stats::default());
+    }
+
+    /// Updates the allocation counters with `f`.
+    pub(crate) fn record(&self, f: impl FnOnce(&mut Stats)) {
+        let mut stats = self.stats.get();
+        f(&mut stats);
+        self.stats.set(stats);
+    }
+}
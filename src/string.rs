@@ -0,0 +1,202 @@
+use core::alloc::{AllocError, Allocator};
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{BumpCar, BumpVec};
+
+/// Growable UTF-8 string allocated in a [`BumpCar`].
+///
+/// It is a [`BumpVec`] of bytes, and grows in place the same way.
+///
+/// # Example
+/// ```rust
+/// use core::fmt::Write;
+/// use dodgems::{BumpCar, BumpString};
+///
+/// let bumpcar = BumpCar::new(256).unwrap();
+/// let mut s = BumpString::new_in(&bumpcar);
+/// s.push_str("hello");
+/// s.push(' ');
+/// write!(s, "{}", 42).unwrap();
+/// assert_eq!(s, "hello 42");
+///
+/// let s: &str = s.into_str();
+/// assert_eq!(s.len(), 8);
+/// ```
+pub struct BumpString<
+    'bump,
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    bytes: BumpVec<'bump, u8, A>,
+}
+
+impl<'bump, A: Allocator> BumpString<'bump, A> {
+    /// Creates a new, empty [`BumpString`] in `bumpcar`, without allocating.
+    pub fn new_in(bumpcar: &'bump BumpCar<A>) -> Self {
+        Self {
+            bytes: BumpVec::new_in(bumpcar),
+        }
+    }
+
+    /// Creates a new, empty [`BumpString`] in `bumpcar`, with room for `capacity` bytes.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left.
+    pub fn try_with_capacity_in(
+        capacity: usize,
+        bumpcar: &'bump BumpCar<A>,
+    ) -> Result<Self, AllocError> {
+        Ok(Self {
+            bytes: BumpVec::try_with_capacity_in(capacity, bumpcar)?,
+        })
+    }
+
+    /// Creates a new, empty [`BumpString`] in `bumpcar`, with room for `capacity` bytes.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    pub fn with_capacity_in(capacity: usize, bumpcar: &'bump BumpCar<A>) -> Self {
+        Self {
+            bytes: BumpVec::with_capacity_in(capacity, bumpcar),
+        }
+    }
+
+    /// Returns the length of the [`BumpString`], in bytes.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns `true` if the [`BumpString`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns the number of bytes the [`BumpString`] can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    /// Appends `s` to the end of the [`BumpString`].
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left,
+    /// in which case the [`BumpString`] is left unchanged.
+    pub fn try_push_str(&mut self, s: &str) -> Result<(), AllocError> {
+        self.bytes.try_reserve(s.len())?;
+        self.bytes.extend(s.as_bytes());
+        Ok(())
+    }
+
+    /// Appends `s` to the end of the [`BumpString`].
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    pub fn push_str(&mut self, s: &str) {
+        if self.try_push_str(s).is_err() {
+            panic!("BumpCar capacity exceeded");
+        }
+    }
+
+    /// Appends `c` to the end of the [`BumpString`].
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    pub fn push(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Removes every character of the [`BumpString`].
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+
+    /// Returns the contents of the [`BumpString`] as a string slice.
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the bytes are only ever appended from valid strings.
+        unsafe { core::str::from_utf8_unchecked(&self.bytes) }
+    }
+
+    /// Returns the contents of the [`BumpString`] as a mutable string slice.
+    pub fn as_mut_str(&mut self) -> &mut str {
+        // SAFETY: the bytes are only ever appended from valid strings.
+        unsafe { core::str::from_utf8_unchecked_mut(&mut self.bytes) }
+    }
+
+    /// Converts the [`BumpString`] into a string slice with the lifetime of the arena.
+    ///
+    /// The unused capacity is returned to the arena if possible.
+    pub fn into_str(self) -> &'bump mut str {
+        // SAFETY: the bytes are only ever appended from valid strings.
+        unsafe { core::str::from_utf8_unchecked_mut(self.bytes.into_slice()) }
+    }
+}
+
+impl<A: Allocator> Deref for BumpString<'_, A> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<A: Allocator> DerefMut for BumpString<'_, A> {
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl<A: Allocator> fmt::Write for BumpString<'_, A> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+impl<'a, A: Allocator> Extend<&'a str> for BumpString<'_, A> {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        for s in iter {
+            self.push_str(s);
+        }
+    }
+}
+
+impl<A: Allocator> Extend<char> for BumpString<'_, A> {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        for c in iter {
+            self.push(c);
+        }
+    }
+}
+
+impl<A: Allocator> fmt::Debug for BumpString<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl<A: Allocator> fmt::Display for BumpString<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl<A: Allocator> PartialEq<str> for BumpString<'_, A> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<A: Allocator> PartialEq<&str> for BumpString<'_, A> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<A: Allocator, B: Allocator> PartialEq<BumpString<'_, B>> for BumpString<'_, A> {
+    fn eq(&self, other: &BumpString<'_, B>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
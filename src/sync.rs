@@ -0,0 +1,360 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::borrow::Borrow;
+use core::fmt;
+use core::mem::size_of;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+
+// The atomics are replaced by the ones of `loom` when model checking (see `tests/loom.rs`).
+#[cfg(loom)]
+use loom::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(not(loom))]
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{BumpError, BumpErrorKind};
+
+/// Value aligned to (and padded to a multiple of) the size of a cache line, so that it does not
+/// share its cache line with any other value.
+///
+/// Contended cursors are wrapped in it, so that the updates made by one thread do not invalidate
+/// the cache line of unrelated data read by the others (false sharing). The size of the padding
+/// is picked for the target: 128 bytes on `x86_64`, `aarch64` and `powerpc64`, whose cache lines
+/// are prefetched in pairs or are 128 bytes long, and 64 bytes elsewhere.
+#[cfg_attr(
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "powerpc64"
+    ),
+    repr(align(128))
+)]
+#[cfg_attr(
+    not(any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "powerpc64"
+    )),
+    repr(align(64))
+)]
+#[derive(Default)]
+pub struct CachePadded<T>(T);
+
+impl<T> CachePadded<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for CachePadded<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> Borrow<T> for CachePadded<T> {
+    fn borrow(&self) -> &T {
+        &self.0
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Layout of the cursor of a [`SyncBumpCar`]: [`Padded`] or [`Unpadded`].
+///
+/// This trait is sealed, and cannot be implemented outside of this crate.
+pub trait CursorPadding: private::Sealed {
+    /// Atomic cursor, with or without its padding.
+    #[doc(hidden)]
+    type Cursor: Borrow<AtomicUsize>;
+
+    /// Creates a cursor at position 0.
+    #[doc(hidden)]
+    fn cursor() -> Self::Cursor;
+}
+
+/// Cursor padded to a cache line, so that the arena does not suffer from false sharing with
+/// the neighbouring data. It is the default padding of a [`SyncBumpCar`].
+#[derive(Debug)]
+pub enum Padded {}
+
+/// Cursor without padding, that keeps a [`SyncBumpCar`] a few words large.
+///
+/// It suits arenas that are mostly used from one thread at a time, or that are stored far
+/// from each other: adjacent arenas allocated from different threads invalidate each other's
+/// cursor at every allocation.
+#[derive(Debug)]
+pub enum Unpadded {}
+
+impl private::Sealed for Padded {}
+
+impl CursorPadding for Padded {
+    type Cursor = CachePadded<AtomicUsize>;
+
+    fn cursor() -> Self::Cursor {
+        CachePadded::new(AtomicUsize::new(0))
+    }
+}
+
+impl private::Sealed for Unpadded {}
+
+impl CursorPadding for Unpadded {
+    type Cursor = AtomicUsize;
+
+    fn cursor() -> Self::Cursor {
+        AtomicUsize::new(0)
+    }
+}
+
+/// Atomically bumps `position` for an allocation described by `layout`, in a buffer of
+/// `capacity` bytes starting at `start`.
+///
+/// # Safety
+/// `start` must be valid for `capacity` bytes, and `position` must be smaller than or equal
+/// to `capacity`.
+pub(crate) unsafe fn bump_atomic(
+    start: NonNull<u8>,
+    capacity: usize,
+    position: &AtomicUsize,
+    layout: Layout,
+) -> Result<NonNull<[u8]>, AllocError> {
+    let base = start.addr().get();
+    let align_mask = layout.align() - 1;
+
+    let mut current = position.load(Ordering::Relaxed);
+    loop {
+        let aligned = (base + current).checked_add(align_mask).ok_or(AllocError)? & !align_mask;
+        let offset = aligned - base;
+        let new_position = offset.checked_add(layout.size()).ok_or(AllocError)?;
+        if new_position > capacity {
+            return Err(AllocError);
+        }
+
+        match position.compare_exchange_weak(
+            current,
+            new_position,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => {
+                // SAFETY: offset <= new_position <= capacity, so the pointer is in bounds
+                // of the buffer.
+                let ptr = unsafe { start.add(offset) };
+                return Ok(NonNull::slice_from_raw_parts(ptr, layout.size()));
+            }
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+/// Thread-safe bump allocator.
+///
+/// It works like a [`BumpCar`](crate::BumpCar), but its cursor is updated atomically, so that
+/// it can be shared between threads. The cursor is padded to a cache line, unless the arena is
+/// created with [`SyncBumpCar::new_unpadded_in`].
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::SyncBumpCar;
+///
+/// let bumpcar = SyncBumpCar::new(1024).unwrap();
+/// std::thread::scope(|s| {
+///     for i in 0..4u64 {
+///         let bumpcar = &bumpcar;
+///         s.spawn(move || Box::new_in(i, bumpcar));
+///     }
+/// });
+/// assert_eq!(bumpcar.remaining_capacity(), 1024 - 32);
+/// ```
+pub struct SyncBumpCar<
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+    P: CursorPadding = Padded,
+> {
+    start: NonNull<u8>,
+    capacity: usize,
+    position: P::Cursor,
+    allocator: A,
+}
+
+// SAFETY: the buffer is owned by the SyncBumpCar, and is deallocated with the allocator.
+unsafe impl<A: Allocator + Send, P: CursorPadding> Send for SyncBumpCar<A, P> {}
+// SAFETY: the buffer is only accessed through disjoint allocations,
+// handed out by atomic updates of the cursor.
+unsafe impl<A: Allocator + Sync, P: CursorPadding> Sync for SyncBumpCar<A, P> {}
+
+impl<A: Allocator> SyncBumpCar<A> {
+    /// Allocates a new [`SyncBumpCar`] in the given allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        Self::with_padding_in(capacity, allocator)
+    }
+}
+
+impl<A: Allocator> SyncBumpCar<A, Unpadded> {
+    /// Allocates a new [`SyncBumpCar`] in the given allocator, whose cursor is not padded to a
+    /// cache line.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_unpadded_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        Self::with_padding_in(capacity, allocator)
+    }
+}
+
+impl<A: Allocator, P: CursorPadding> SyncBumpCar<A, P> {
+    /// Allocates a new [`SyncBumpCar`] in the given allocator, with the cursor layout of `P`.
+    fn with_padding_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        let layout = Layout::from_size_align(capacity, size_of::<usize>())
+            .map_err(|_| BumpError::new(BumpErrorKind::Overflow, None, 0))?;
+        let pointer = allocator
+            .allocate(layout)
+            .map_err(|_| BumpError::new(BumpErrorKind::AllocatorFailed, Some(layout), 0))?;
+
+        Ok(Self {
+            start: pointer.cast(),
+            capacity,
+            position: P::cursor(),
+            allocator,
+        })
+    }
+
+    /// Returns the atomic cursor of the [`SyncBumpCar`].
+    fn position(&self) -> &AtomicUsize {
+        self.position.borrow()
+    }
+
+    /// Returns the capacity of the [`SyncBumpCar`].
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the remaining capacity of the [`SyncBumpCar`].
+    ///
+    /// As for [`BumpCar::remaining_capacity`](crate::BumpCar::remaining_capacity), this does not
+    /// account for alignment. It may also be outdated as soon as it is returned, if other threads
+    /// are allocating.
+    pub fn remaining_capacity(&self) -> usize {
+        self.capacity - self.position().load(Ordering::Relaxed)
+    }
+
+    /// Resets the [`SyncBumpCar`]'s remaining capacity to its initial capacity.
+    ///
+    /// This requires a mutable reference, so that any previous allocations made with &self
+    /// are invalidated by the borrow checker.
+    ///
+    /// With the `zeroize` feature, the used memory is overwritten with zeros.
+    pub fn reset(&mut self) {
+        #[cfg(feature = "zeroize")]
+        // SAFETY: the used part of the buffer is position bytes long.
+        unsafe {
+            crate::wipe_bytes(self.start, self.position().load(Ordering::Relaxed));
+        }
+        self.position().store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SyncBumpCar {
+    /// Allocates a [`SyncBumpCar`] with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, Global)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SyncBumpCar<Global, Unpadded> {
+    /// Allocates a [`SyncBumpCar`] with the Global allocator, whose cursor is not padded to a
+    /// cache line.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new_unpadded(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_unpadded_in(capacity, Global)
+    }
+}
+
+impl<A: Allocator, P: CursorPadding> Drop for SyncBumpCar<A, P> {
+    /// Deallocates the [`SyncBumpCar`]'s buffer.
+    ///
+    /// With the `zeroize` feature, the used memory is overwritten with zeros first.
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        // SAFETY: the used part of the buffer is position bytes long.
+        unsafe {
+            crate::wipe_bytes(self.start, self.position().load(Ordering::Relaxed));
+        }
+
+        // SAFETY: start was allocated with self.allocator, with this layout.
+        unsafe {
+            self.allocator.deallocate(
+                self.start,
+                Layout::from_size_align_unchecked(self.capacity, size_of::<usize>()),
+            );
+        }
+    }
+}
+
+/// Allocations are usually made through a shared reference, but the [`SyncBumpCar`] can also be
+/// shared through an `Arc`, that can be used as an allocator on its own.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use std::sync::Arc;
+/// use dodgems::SyncBumpCar;
+///
+/// let bumpcar = Arc::new(SyncBumpCar::new(1024).unwrap());
+/// let handles: Vec<_> = (0..4u64)
+///     .map(|i| {
+///         let bumpcar = bumpcar.clone();
+///         std::thread::spawn(move || *Box::new_in(i, bumpcar))
+///     })
+///     .collect();
+/// let sum: u64 = handles.into_iter().map(|h| h.join().unwrap()).sum();
+/// assert_eq!(sum, 6);
+/// assert_eq!(bumpcar.remaining_capacity(), 1024 - 32);
+/// ```
+unsafe impl<A: Allocator, P: CursorPadding> Allocator for SyncBumpCar<A, P> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: the buffer is valid for capacity bytes, and the cursor never exceeds it.
+        unsafe { bump_atomic(self.start, self.capacity, self.position(), layout) }
+    }
+
+    /// The [`SyncBumpCar`] does not perform deallocation unless it's reset or dropped.
+    unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {}
+}
+
+impl<A: Allocator + fmt::Debug, P: CursorPadding> fmt::Debug for SyncBumpCar<A, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncBumpCar")
+            .field("capacity", &self.capacity())
+            .field("used", &(self.capacity() - self.remaining_capacity()))
+            .field("remaining", &self.remaining_capacity())
+            .field("allocator", &self.allocator)
+            .finish()
+    }
+}
@@ -0,0 +1,501 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::alloc::{AllocError, Allocator, Layout};
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use core::ptr::NonNull;
+
+/// First bytes of a serialized [`Trace`], with its format version.
+const MAGIC: &[u8; 4] = b"DGT\x01";
+
+/// Kind of operation of a [`TraceEvent`].
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TraceOp {
+    /// [`Allocator::allocate`].
+    Allocate,
+    /// [`Allocator::allocate_zeroed`].
+    AllocateZeroed,
+    /// [`Allocator::deallocate`].
+    Deallocate,
+    /// [`Allocator::grow`].
+    Grow,
+    /// [`Allocator::grow_zeroed`].
+    GrowZeroed,
+    /// [`Allocator::shrink`].
+    Shrink,
+    /// Reset of the arena, recorded with [`TraceCar::record_reset`].
+    Reset,
+}
+
+impl TraceOp {
+    const ALL: [Self; 7] = [
+        Self::Allocate,
+        Self::AllocateZeroed,
+        Self::Deallocate,
+        Self::Grow,
+        Self::GrowZeroed,
+        Self::Shrink,
+        Self::Reset,
+    ];
+}
+
+/// Operation recorded in a [`Trace`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TraceEvent {
+    /// Kind of operation.
+    pub op: TraceOp,
+    /// Index of the allocation the operation applies to: the number of allocations recorded
+    /// before it. It is 0 for resets.
+    pub id: u32,
+    /// Layout requested by the operation: the new layout of a resize, and 0 bytes aligned to 1
+    /// for resets.
+    pub layout: Layout,
+    /// Whether the operation succeeded. Deallocations and resets always succeed.
+    pub ok: bool,
+}
+
+/// Error returned by [`Trace::from_bytes`] for malformed input.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraceError {
+    offset: usize,
+}
+
+impl TraceError {
+    /// Returns the offset of the first invalid byte.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid allocation trace at byte {}", self.offset)
+    }
+}
+
+impl core::error::Error for TraceError {}
+
+/// Summary of a [`Trace::replay`].
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    /// Number of operations run on the allocator, the final deallocations excluded.
+    pub operations: usize,
+    /// Number of allocations and resizes that failed.
+    pub failures: usize,
+    /// Number of operations whose success differed from the recording, and of invalid
+    /// resizes that were skipped.
+    pub mismatches: usize,
+}
+
+/// Sequence of allocator operations, recorded by a [`TraceCar`].
+///
+/// A trace can be serialized to a compact binary form with [`Trace::to_bytes`], to be stored
+/// along with tests or benchmarks, and replayed against any allocator with [`Trace::replay`].
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::{BumpCar, Trace, TraceCar};
+///
+/// let recorder = TraceCar::new(BumpCar::new(1024).unwrap());
+/// let mut v = Vec::new_in(&recorder);
+/// v.extend(0..100u32);
+/// drop(v);
+/// let bytes = recorder.take_trace().to_bytes();
+///
+/// let trace = Trace::from_bytes(&bytes).unwrap();
+/// let mut bumpcar = BumpCar::new(1024).unwrap();
+/// let report = trace.replay(&mut bumpcar, BumpCar::reset);
+/// assert_eq!(report.operations, trace.events().len());
+/// assert_eq!(report.mismatches, 0);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trace {
+    events: Vec<TraceEvent>,
+    allocations: u32,
+}
+
+impl Trace {
+    /// Creates an empty [`Trace`].
+    pub const fn new() -> Self {
+        Self {
+            events: Vec::new(),
+            allocations: 0,
+        }
+    }
+
+    /// Returns the recorded events, in order.
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// Appends an event, assigning the next allocation id to allocations.
+    fn push(&mut self, op: TraceOp, id: u32, layout: Layout, ok: bool) -> u32 {
+        let id = match op {
+            TraceOp::Allocate | TraceOp::AllocateZeroed => {
+                self.allocations = self.allocations.wrapping_add(1);
+                self.allocations - 1
+            }
+            _ => id,
+        };
+        self.events.push(TraceEvent { op, id, layout, ok });
+        id
+    }
+
+    /// Serializes the trace.
+    ///
+    /// Each event takes a few bytes: its kind and result, the id of the allocation it applies
+    /// to (except for allocations, whose ids are implicit), and its layout, with the sizes and
+    /// ids encoded as LEB128 varints.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(MAGIC.len() + self.events.len() * 4);
+        bytes.extend_from_slice(MAGIC);
+        for event in &self.events {
+            bytes.push(event.op as u8 | u8::from(event.ok) << 7);
+            match event.op {
+                TraceOp::Allocate | TraceOp::AllocateZeroed => {}
+                TraceOp::Reset => continue,
+                _ => write_varint(&mut bytes, u64::from(event.id)),
+            }
+            write_varint(&mut bytes, event.layout.size() as u64);
+            bytes.push(event.layout.align().trailing_zeros() as u8);
+        }
+        bytes
+    }
+
+    /// Deserializes a trace serialized with [`Trace::to_bytes`].
+    ///
+    /// # Errors
+    /// This function returns an error if the bytes are not a valid trace.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TraceError> {
+        if bytes.get(..MAGIC.len()) != Some(MAGIC) {
+            return Err(TraceError { offset: 0 });
+        }
+        let mut trace = Self::new();
+        let mut offset = MAGIC.len();
+        while let Some(&tag) = bytes.get(offset) {
+            let invalid = TraceError { offset };
+            offset += 1;
+            let op = *TraceOp::ALL.get(usize::from(tag & 0x7F)).ok_or(invalid)?;
+            let ok = tag & 0x80 != 0;
+            let (id, layout) = match op {
+                TraceOp::Reset => (0, Layout::new::<()>()),
+                _ => {
+                    let id = match op {
+                        TraceOp::Allocate | TraceOp::AllocateZeroed => 0,
+                        _ => u32::try_from(read_varint(bytes, &mut offset)?)
+                            .map_err(|_| TraceError { offset })?,
+                    };
+                    let size = usize::try_from(read_varint(bytes, &mut offset)?)
+                        .map_err(|_| TraceError { offset })?;
+                    let align = bytes
+                        .get(offset)
+                        .and_then(|&log| 1usize.checked_shl(u32::from(log)))
+                        .ok_or(TraceError { offset })?;
+                    let layout =
+                        Layout::from_size_align(size, align).map_err(|_| TraceError { offset })?;
+                    offset += 1;
+                    (id, layout)
+                }
+            };
+            trace.push(op, id, layout, ok);
+        }
+        Ok(trace)
+    }
+
+    /// Runs the recorded operations on `allocator`, calling `reset` on the recorded resets.
+    ///
+    /// Allocations are tracked by id: the operations applying to an allocation that failed
+    /// during the replay are skipped. The allocations still live at a reset are forgotten, and
+    /// the ones still live at the end are deallocated. A resize in the wrong direction, which
+    /// can only come from a malformed trace, is skipped and counted as a mismatch.
+    ///
+    /// Traces are usually replayed against an arena, such as a [`BumpCar`](crate::BumpCar)
+    /// reset by [`BumpCar::reset`](crate::BumpCar::reset), or against the global allocator with
+    /// a `reset` that does nothing, to compare their performance on a real workload.
+    pub fn replay<A: Allocator>(
+        &self,
+        allocator: &mut A,
+        mut reset: impl FnMut(&mut A),
+    ) -> ReplayReport {
+        let mut report = ReplayReport::default();
+        let mut live: Vec<Option<(NonNull<u8>, Layout)>> = Vec::new();
+        for event in &self.events {
+            let slot = usize::try_from(event.id).unwrap_or(usize::MAX);
+            let result = match event.op {
+                TraceOp::Allocate | TraceOp::AllocateZeroed => {
+                    let result = if event.op == TraceOp::Allocate {
+                        allocator.allocate(event.layout)
+                    } else {
+                        allocator.allocate_zeroed(event.layout)
+                    };
+                    if live.len() <= slot {
+                        live.resize(slot + 1, None);
+                    }
+                    live[slot] = result.ok().map(|ptr| (ptr.cast(), event.layout));
+                    result.is_ok()
+                }
+                TraceOp::Deallocate => {
+                    let Some((ptr, layout)) = live.get_mut(slot).and_then(Option::take) else {
+                        continue;
+                    };
+                    // SAFETY: ptr is a live allocation of allocator, with this layout.
+                    unsafe { allocator.deallocate(ptr, layout) };
+                    true
+                }
+                TraceOp::Grow | TraceOp::GrowZeroed | TraceOp::Shrink => {
+                    let Some(Some((ptr, layout))) = live.get_mut(slot) else {
+                        continue;
+                    };
+                    // A trace read from untrusted bytes can resize in the wrong direction.
+                    let ordered = match event.op {
+                        TraceOp::Shrink => event.layout.size() <= layout.size(),
+                        _ => event.layout.size() >= layout.size(),
+                    };
+                    if !ordered {
+                        report.mismatches += 1;
+                        continue;
+                    }
+                    // SAFETY: ptr is a live allocation of allocator, with this layout, and the
+                    // new size is ordered like the operation requires.
+                    let result = unsafe {
+                        match event.op {
+                            TraceOp::Grow => allocator.grow(*ptr, *layout, event.layout),
+                            TraceOp::GrowZeroed => {
+                                allocator.grow_zeroed(*ptr, *layout, event.layout)
+                            }
+                            _ => allocator.shrink(*ptr, *layout, event.layout),
+                        }
+                    };
+                    if let Ok(new_ptr) = result {
+                        *ptr = new_ptr.cast();
+                        *layout = event.layout;
+                    }
+                    result.is_ok()
+                }
+                TraceOp::Reset => {
+                    live.clear();
+                    reset(allocator);
+                    true
+                }
+            };
+            report.operations += 1;
+            report.failures += usize::from(!result);
+            report.mismatches += usize::from(result != event.ok);
+        }
+        for (ptr, layout) in live.into_iter().flatten() {
+            // SAFETY: ptr is a live allocation of allocator, with this layout.
+            unsafe { allocator.deallocate(ptr, layout) };
+        }
+        report
+    }
+}
+
+/// Writes `value` as a LEB128 varint.
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Reads a LEB128 varint at `offset`, and moves the offset past it.
+fn read_varint(bytes: &[u8], offset: &mut usize) -> Result<u64, TraceError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*offset).ok_or(TraceError { offset: *offset })?;
+        *offset += 1;
+        value |= u64::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(TraceError { offset: *offset })
+}
+
+/// Allocator wrapper recording every operation made through it in a [`Trace`].
+///
+/// The recorder keeps the id of every live allocation, so the trace refers to allocations by
+/// their order instead of their address. It can be replayed against any allocator.
+///
+/// It is only available with the `trace` feature.
+pub struct TraceCar<A> {
+    inner: A,
+    trace: RefCell<Trace>,
+    /// Ids of the live allocations, by address.
+    live: RefCell<BTreeMap<usize, u32>>,
+    recording: Cell<bool>,
+}
+
+impl<A: Allocator> TraceCar<A> {
+    /// Wraps an allocator, recording the operations made through the wrapper.
+    pub const fn new(inner: A) -> Self {
+        Self {
+            inner,
+            trace: RefCell::new(Trace::new()),
+            live: RefCell::new(BTreeMap::new()),
+            recording: Cell::new(true),
+        }
+    }
+
+    /// Returns a reference to the inner allocator.
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner allocator, for example to reset it after
+    /// [`TraceCar::record_reset`].
+    pub fn inner_mut(&mut self) -> &mut A {
+        &mut self.inner
+    }
+
+    /// Unwraps the inner allocator and the recorded trace.
+    pub fn into_parts(self) -> (A, Trace) {
+        (self.inner, self.trace.into_inner())
+    }
+
+    /// Returns the number of events recorded so far.
+    pub fn events(&self) -> usize {
+        self.trace.borrow().events.len()
+    }
+
+    /// Pauses or resumes the recording. Operations on allocations made while paused are not
+    /// recorded either.
+    pub fn set_recording(&self, recording: bool) {
+        self.recording.set(recording);
+    }
+
+    /// Records a reset of the inner allocator, forgetting the live allocations.
+    ///
+    /// The inner allocator itself is not reset: this is to be called right before resetting it.
+    pub fn record_reset(&mut self) {
+        self.live.get_mut().clear();
+        if self.recording.get() {
+            self.trace
+                .get_mut()
+                .push(TraceOp::Reset, 0, Layout::new::<()>(), true);
+        }
+    }
+
+    /// Takes the trace recorded so far, leaving an empty one.
+    ///
+    /// The allocations made before the call are no longer tracked, so that the new trace only
+    /// refers to allocations it records.
+    pub fn take_trace(&self) -> Trace {
+        self.live.borrow_mut().clear();
+        core::mem::take(&mut *self.trace.borrow_mut())
+    }
+
+    /// Records an allocation, and its id if it succeeded.
+    fn record_alloc(
+        &self,
+        op: TraceOp,
+        layout: Layout,
+        result: &Result<NonNull<[u8]>, AllocError>,
+    ) {
+        if !self.recording.get() {
+            return;
+        }
+        let id = self.trace.borrow_mut().push(op, 0, layout, result.is_ok());
+        if let Ok(ptr) = result {
+            self.live
+                .borrow_mut()
+                .insert(ptr.cast::<u8>().addr().get(), id);
+        }
+    }
+
+    /// Records a resize of the allocation at `ptr`, if it is tracked.
+    fn record_resize(
+        &self,
+        op: TraceOp,
+        ptr: NonNull<u8>,
+        new_layout: Layout,
+        result: &Result<NonNull<[u8]>, AllocError>,
+    ) {
+        let mut live = self.live.borrow_mut();
+        let Some(&id) = live.get(&ptr.addr().get()) else {
+            return;
+        };
+        self.trace
+            .borrow_mut()
+            .push(op, id, new_layout, result.is_ok());
+        if let Ok(new_ptr) = result {
+            live.remove(&ptr.addr().get());
+            live.insert(new_ptr.cast::<u8>().addr().get(), id);
+        }
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for TraceCar<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let result = self.inner.allocate(layout);
+        self.record_alloc(TraceOp::Allocate, layout, &result);
+        result
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let result = self.inner.allocate_zeroed(layout);
+        self.record_alloc(TraceOp::AllocateZeroed, layout, &result);
+        result
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if let Some(id) = self.live.borrow_mut().remove(&ptr.addr().get()) {
+            self.trace
+                .borrow_mut()
+                .push(TraceOp::Deallocate, id, layout, true);
+        }
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.inner.deallocate(ptr, layout) }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        let result = unsafe { self.inner.grow(ptr, old_layout, new_layout) };
+        self.record_resize(TraceOp::Grow, ptr, new_layout, &result);
+        result
+    }
+
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        let result = unsafe { self.inner.grow_zeroed(ptr, old_layout, new_layout) };
+        self.record_resize(TraceOp::GrowZeroed, ptr, new_layout, &result);
+        result
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        let result = unsafe { self.inner.shrink(ptr, old_layout, new_layout) };
+        self.record_resize(TraceOp::Shrink, ptr, new_layout, &result);
+        result
+    }
+}
+
+impl<A: fmt::Debug> fmt::Debug for TraceCar<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TraceCar")
+            .field("inner", &self.inner)
+            .field("events", &self.trace.borrow().events.len())
+            .field("live", &self.live.borrow().len())
+            .finish()
+    }
+}
@@ -0,0 +1,165 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::{BumpCar, BumpError};
+
+/// [`BumpCar`] emitting [`tracing`] events for its allocations, resizes, failures and resets.
+///
+/// Every event records the sizes involved and the remaining capacity of the arena, at the `TRACE`
+/// level, except allocation failures which are emitted at the `WARN` level. The events are
+/// recorded in the current span, so that the pressure on the arena can be correlated with the
+/// work being done.
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::TracingCar;
+///
+/// let mut bumpcar = TracingCar::new(256).unwrap();
+/// let v = Box::new_in([0u64; 4], &bumpcar);
+/// drop(v);
+/// bumpcar.reset();
+/// ```
+pub struct TracingCar<
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    bumpcar: BumpCar<A>,
+}
+
+impl<A: Allocator> TracingCar<A> {
+    /// Allocates a new [`TracingCar`] in the given allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the underlying allocator returns an error.
+    pub fn new_in(capacity: usize, allocator: A) -> Result<Self, BumpError> {
+        Ok(Self {
+            bumpcar: BumpCar::new_in(capacity, allocator)?,
+        })
+    }
+
+    /// Returns the underlying [`BumpCar`].
+    pub fn bumpcar(&self) -> &BumpCar<A> {
+        &self.bumpcar
+    }
+
+    /// Returns the capacity of the [`TracingCar`].
+    pub fn capacity(&self) -> usize {
+        self.bumpcar.capacity()
+    }
+
+    /// Returns the remaining capacity of the [`TracingCar`].
+    pub fn remaining_capacity(&self) -> usize {
+        self.bumpcar.remaining_capacity()
+    }
+
+    /// Resets the [`TracingCar`]'s remaining capacity to its initial capacity.
+    pub fn reset(&mut self) {
+        let used = self.bumpcar.position();
+        self.bumpcar.reset();
+        tracing::trace!(
+            used,
+            remaining = self.bumpcar.remaining_capacity(),
+            "BumpCar reset"
+        );
+    }
+
+    /// Emits the event of an allocation or resize.
+    fn trace(
+        &self,
+        operation: &'static str,
+        old_size: usize,
+        layout: Layout,
+        result: Result<NonNull<[u8]>, AllocError>,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let remaining = self.bumpcar.remaining_capacity();
+        match result {
+            Ok(_) => tracing::trace!(
+                operation,
+                old_size,
+                size = layout.size(),
+                align = layout.align(),
+                remaining,
+                "BumpCar allocation"
+            ),
+            Err(_) => tracing::warn!(
+                operation,
+                old_size,
+                size = layout.size(),
+                align = layout.align(),
+                remaining,
+                "BumpCar allocation failed"
+            ),
+        }
+        result
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TracingCar {
+    /// Allocates a [`TracingCar`] with the Global allocator.
+    ///
+    /// # Errors
+    /// This function returns an error if the capacity is greater than [`isize::MAX`],
+    /// or if the global allocator returns an error.
+    pub fn new(capacity: usize) -> Result<Self, BumpError> {
+        Self::new_in(capacity, Global)
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for TracingCar<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.trace("allocate", 0, layout, self.bumpcar.allocate(layout))
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.trace(
+            "allocate_zeroed",
+            0,
+            layout,
+            self.bumpcar.allocate_zeroed(layout),
+        )
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: guaranteed by the caller.
+        unsafe { self.bumpcar.deallocate(ptr, layout) }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        let result = unsafe { self.bumpcar.grow(ptr, old_layout, new_layout) };
+        self.trace("grow", old_layout.size(), new_layout, result)
+    }
+
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        let result = unsafe { self.bumpcar.grow_zeroed(ptr, old_layout, new_layout) };
+        self.trace("grow_zeroed", old_layout.size(), new_layout, result)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: guaranteed by the caller.
+        let result = unsafe { self.bumpcar.shrink(ptr, old_layout, new_layout) };
+        self.trace("shrink", old_layout.size(), new_layout, result)
+    }
+}
@@ -0,0 +1,906 @@
+use core::alloc::{Allocator, Layout};
+use core::ffi::CStr;
+use core::fmt;
+use core::marker::Unsize;
+use core::mem::MaybeUninit;
+use core::ptr::NonNull;
+
+use crate::{BumpCar, BumpError, BumpErrorKind, BumpWriter};
+
+/// Unwraps the result of an allocation in a [`BumpCar`].
+///
+/// # Panics
+/// This function panics if the allocation failed.
+#[track_caller]
+pub(crate) fn expect_capacity<T>(result: Result<T, BumpError>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(err) => panic!("{err}"),
+    }
+}
+
+/// Assumes that every element of `slice` is initialized, such as a slice returned by
+/// [`BumpCar::alloc_uninit_slice`] once it has been filled.
+///
+/// # Safety
+/// Every element of `slice` must be initialized.
+pub unsafe fn slice_assume_init_mut<T>(slice: &mut [MaybeUninit<T>]) -> &mut [T] {
+    // SAFETY: MaybeUninit<T> has the same layout as T, and the elements are initialized.
+    unsafe { &mut *(core::ptr::from_mut(slice) as *mut [T]) }
+}
+
+/// Alignment of the slices allocated by [`BumpCar::alloc_simd`]: the size of an AVX-512 vector,
+/// and of a cache line on most targets.
+pub const SIMD_ALIGN: usize = 64;
+
+/// Returns the layout of an array of `len` elements of type `T`.
+fn array_layout<T>(len: usize) -> Result<Layout, BumpError> {
+    Layout::array::<T>(len).map_err(|_| BumpError::new(BumpErrorKind::Overflow, None, 0))
+}
+
+impl<A: Allocator> BumpCar<A> {
+    /// Allocates a region described by `layout`, for the typed allocation methods.
+    #[inline(always)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub(crate) fn allocate_typed(&self, layout: Layout) -> Result<NonNull<[u8]>, BumpError> {
+        let result = self.allocate(layout).map_err(|_| {
+            BumpError::new(
+                BumpErrorKind::CapacityExceeded,
+                Some(layout),
+                self.remaining_capacity(),
+            )
+        });
+        #[cfg(feature = "profiling")]
+        if result.is_ok() {
+            self.record_callsite(core::panic::Location::caller(), layout);
+        }
+        result
+    }
+
+    /// Moves `value` into the [`BumpCar`], and returns a mutable reference to it.
+    ///
+    /// The value is never dropped: its memory is simply reclaimed when the [`BumpCar`] is reset.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc<T>(&self, value: T) -> Result<&mut T, BumpError> {
+        self.try_alloc_with(|| value)
+    }
+
+    /// Moves `value` into the [`BumpCar`], and returns a mutable reference to it.
+    ///
+    /// The value is never dropped: its memory is simply reclaimed when the [`BumpCar`] is reset.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let x = bumpcar.alloc(42u64);
+    /// *x += 1;
+    /// assert_eq!(*x, 43);
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn alloc<T>(&self, value: T) -> &mut T {
+        expect_capacity(self.try_alloc(value))
+    }
+
+    /// Allocates space for a `T`, and initializes it with the result of `f`.
+    ///
+    /// If the allocation fails, `f` is not called.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_with<T>(&self, f: impl FnOnce() -> T) -> Result<&mut T, BumpError> {
+        let ptr = self.allocate_typed(Layout::new::<T>())?.cast::<T>();
+        // SAFETY: the allocation is valid for a T, and is not handed out again
+        // while self is borrowed.
+        unsafe {
+            ptr.write(f());
+            Ok(&mut *ptr.as_ptr())
+        }
+    }
+
+    /// Allocates space for a `T`, and initializes it with the result of `f`.
+    ///
+    /// Unlike [`BumpCar::alloc`], the space is reserved before calling `f`, so that the value can
+    /// be written directly into the arena instead of being moved from the stack.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(8192).unwrap();
+    /// let frame = bumpcar.alloc_with(|| [0u8; 4096]);
+    /// assert_eq!(frame.len(), 4096);
+    /// ```
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn alloc_with<T>(&self, f: impl FnOnce() -> T) -> &mut T {
+        expect_capacity(self.try_alloc_with(f))
+    }
+
+    /// Allocates space for a `T`, and initializes it with the result of the fallible
+    /// constructor `f`.
+    ///
+    /// If the allocation fails, `f` is not called. If `f` fails, the space is returned to the
+    /// [`BumpCar`], unless `f` made allocations in it that are still live.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left, and
+    /// an inner error if `f` fails.
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_try_with<T, E>(
+        &self,
+        f: impl FnOnce() -> Result<T, E>,
+    ) -> Result<Result<&mut T, E>, BumpError> {
+        let layout = Layout::new::<T>();
+        let current = self.current.get();
+        let ptr = self.allocate_typed(layout)?.cast::<T>();
+        match f() {
+            // SAFETY: the allocation is valid for a T, and is not handed out again
+            // while self is borrowed.
+            Ok(value) => unsafe {
+                ptr.write(value);
+                Ok(Ok(&mut *ptr.as_ptr()))
+            },
+            Err(err) => {
+                // SAFETY: the allocation was made with this layout, and was never handed out.
+                unsafe { self.deallocate(ptr.cast(), layout) };
+                // The alignment padding is reclaimed too, if the allocation was.
+                if self.current.get() == ptr.cast() {
+                    self.current.set(current);
+                }
+                Ok(Err(err))
+            }
+        }
+    }
+
+    /// Allocates space for a `T`, and initializes it with the result of the fallible
+    /// constructor `f`.
+    ///
+    /// If `f` fails, the space is returned to the [`BumpCar`], unless `f` made allocations in it
+    /// that are still live, so that failed constructions do not waste capacity.
+    ///
+    /// # Errors
+    /// This function returns the error of `f` if it fails.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// assert!(bumpcar.alloc_try_with(|| "12x".parse::<u64>()).is_err());
+    /// assert_eq!(bumpcar.used(), 0);
+    ///
+    /// let n = bumpcar.alloc_try_with(|| "12".parse::<u64>()).unwrap();
+    /// assert_eq!(*n, 12);
+    /// ```
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn alloc_try_with<T, E>(&self, f: impl FnOnce() -> Result<T, E>) -> Result<&mut T, E> {
+        expect_capacity(self.try_alloc_try_with(f))
+    }
+
+    /// Moves `value` into the [`BumpCar`], and returns a mutable reference to it as an unsized
+    /// type `U`, such as a trait object or a slice.
+    ///
+    /// As with [`BumpCar::alloc`], the value is never dropped.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_unsize<'a, U: ?Sized>(
+        &'a self,
+        value: impl Unsize<U> + 'a,
+    ) -> Result<&'a mut U, BumpError> {
+        self.try_alloc(value).map(|value| value as &mut U)
+    }
+
+    /// Moves `value` into the [`BumpCar`], and returns a mutable reference to it as an unsized
+    /// type `U`, such as a trait object or a slice.
+    ///
+    /// As with [`BumpCar::alloc`], the value is never dropped.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use core::fmt::Display;
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let handlers = bumpcar.alloc_slice_copy::<&dyn Display>(&[
+    ///     bumpcar.alloc_unsize::<dyn Display>(42),
+    ///     bumpcar.alloc_unsize::<dyn Display>("answer"),
+    /// ]);
+    /// assert_eq!(format!("{} {}", handlers[1], handlers[0]), "answer 42");
+    ///
+    /// let slice = bumpcar.alloc_unsize::<[u16]>([1, 2, 3]);
+    /// assert_eq!(slice.len(), 3);
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn alloc_unsize<'a, U: ?Sized>(&'a self, value: impl Unsize<U> + 'a) -> &'a mut U {
+        expect_capacity(self.try_alloc_unsize(value))
+    }
+
+    /// Allocates a slice of `len` elements, and initializes the element at index `i`
+    /// with `f(i)`.
+    ///
+    /// If `f` panics, the elements initialized so far are leaked.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left,
+    /// or if the size of the slice overflows.
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_slice_fill_with<T>(
+        &self,
+        len: usize,
+        f: impl FnMut(usize) -> T,
+    ) -> Result<&mut [T], BumpError> {
+        self.fill_slice(array_layout::<T>(len)?, len, f)
+    }
+
+    /// Allocates a slice of `len` elements described by `layout`, and initializes the element at
+    /// index `i` with `f(i)`.
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    fn fill_slice<T>(
+        &self,
+        layout: Layout,
+        len: usize,
+        mut f: impl FnMut(usize) -> T,
+    ) -> Result<&mut [T], BumpError> {
+        let ptr = self.allocate_typed(layout)?.cast::<T>();
+        for i in 0..len {
+            // SAFETY: the allocation is valid for len elements.
+            unsafe { ptr.add(i).write(f(i)) };
+        }
+        // SAFETY: every element has been initialized, and the allocation is not handed out again
+        // while self is borrowed.
+        Ok(unsafe { core::slice::from_raw_parts_mut(ptr.as_ptr(), len) })
+    }
+
+    /// Allocates a slice of `len` elements, and initializes the element at index `i`
+    /// with `f(i)`.
+    ///
+    /// If `f` panics, the elements initialized so far are leaked.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left,
+    /// or if the size of the slice overflows.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let squares = bumpcar.alloc_slice_fill_with(5, |i| i * i);
+    /// assert_eq!(squares, [0, 1, 4, 9, 16]);
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn alloc_slice_fill_with<T>(&self, len: usize, f: impl FnMut(usize) -> T) -> &mut [T] {
+        expect_capacity(self.try_alloc_slice_fill_with(len, f))
+    }
+
+    /// Allocates a slice holding the elements of `iter`, written in place in a single pass.
+    ///
+    /// The space is reserved from the length reported by the iterator. If it yields fewer
+    /// elements, the slice is truncated to the elements it yielded (and the unused tail is
+    /// reclaimed if possible); if it yields more, the extra elements are not consumed.
+    ///
+    /// If the iterator panics, the elements written so far are leaked.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left,
+    /// or if the size of the slice overflows.
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_slice_from_iter_exact<T, I>(&self, iter: I) -> Result<&mut [T], BumpError>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        let len = iter.len();
+        let ptr = self.allocate_typed(array_layout::<T>(len)?)?.cast::<T>();
+        let mut written = 0;
+        for value in iter.take(len) {
+            // SAFETY: written < len, so the allocation is valid for this element.
+            unsafe { ptr.add(written).write(value) };
+            written += 1;
+        }
+        if written < len {
+            let size = size_of::<T>();
+            self.resize_in_place(ptr.cast(), len * size, written * size);
+        }
+        // SAFETY: the first written elements have been initialized, and the allocation is not
+        // handed out again while self is borrowed.
+        Ok(unsafe { core::slice::from_raw_parts_mut(ptr.as_ptr(), written) })
+    }
+
+    /// Allocates a slice holding the elements of `iter`, written in place in a single pass
+    /// (see [`BumpCar::try_alloc_slice_from_iter_exact`]).
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left,
+    /// or if the size of the slice overflows.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let words = ["a", "bc", "def"];
+    /// let lengths = bumpcar.alloc_slice_from_iter_exact(words.iter().map(|w| w.len()));
+    /// assert_eq!(lengths, [1, 2, 3]);
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn alloc_slice_from_iter_exact<T, I>(&self, iter: I) -> &mut [T]
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        expect_capacity(self.try_alloc_slice_from_iter_exact(iter))
+    }
+
+    /// Copies `values` into the [`BumpCar`], and returns a mutable reference to the copy.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_slice_copy<T: Copy>(&self, values: &[T]) -> Result<&mut [T], BumpError> {
+        let ptr = self.allocate_typed(Layout::for_value(values))?.cast::<T>();
+        // SAFETY: the allocation is valid for values.len() elements, cannot overlap values,
+        // and is not handed out again while self is borrowed.
+        unsafe {
+            ptr.copy_from_nonoverlapping(NonNull::from(values).cast(), values.len());
+            Ok(core::slice::from_raw_parts_mut(ptr.as_ptr(), values.len()))
+        }
+    }
+
+    /// Copies `values` into the [`BumpCar`], and returns a mutable reference to the copy.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let bytes = bumpcar.alloc_slice_copy(b"hello");
+    /// bytes[0] = b'j';
+    /// assert_eq!(bytes, b"jello");
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn alloc_slice_copy<T: Copy>(&self, values: &[T]) -> &mut [T] {
+        expect_capacity(self.try_alloc_slice_copy(values))
+    }
+
+    /// Appends `extra` to `slice`, a slice allocated in the [`BumpCar`].
+    ///
+    /// If `slice` is the last allocation made, it is extended in place, since the bytes right
+    /// after it are free. Otherwise, it is copied to a new allocation with `extra`, and the old
+    /// one is abandoned.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left,
+    /// or if the size of the slice overflows. The slice is then left unchanged.
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_extend_slice_copy<'a, T: Copy>(
+        &'a self,
+        slice: &mut &'a mut [T],
+        extra: &[T],
+    ) -> Result<(), BumpError> {
+        let len = slice.len();
+        let new_len =
+            len.checked_add(extra.len())
+                .ok_or(BumpError::new(BumpErrorKind::Overflow, None, 0))?;
+        let old_layout = Layout::for_value::<[T]>(slice);
+        let new_layout = array_layout::<T>(new_len)?;
+        let ptr = NonNull::from(&mut **slice).cast::<u8>();
+
+        let new_ptr = if old_layout.size() > 0 && self.owns_allocation(ptr, old_layout) {
+            // The slice is extended with the provenance of the whole buffer.
+            let ptr = self.start.with_addr(ptr.addr());
+            // SAFETY: the slice lies in the buffer, so it was allocated by the BumpCar (which
+            // only needs its size and alignment to resize it), and is not used afterwards.
+            unsafe { Allocator::grow(self, ptr, old_layout, new_layout) }.map_err(|_| {
+                BumpError::new(
+                    BumpErrorKind::CapacityExceeded,
+                    Some(new_layout),
+                    self.remaining_capacity(),
+                )
+            })?
+        } else {
+            let new_ptr = self.allocate_typed(new_layout)?;
+            // SAFETY: the new allocation is valid for the old_layout.size() bytes of the slice,
+            // and cannot overlap it.
+            unsafe {
+                new_ptr
+                    .cast::<u8>()
+                    .copy_from_nonoverlapping(ptr, old_layout.size());
+            }
+            new_ptr
+        }
+        .cast::<T>();
+
+        // SAFETY: the allocation is valid for new_len elements, the first len of which are
+        // initialized, and extra cannot overlap it since slice is borrowed mutably.
+        unsafe {
+            new_ptr
+                .add(len)
+                .copy_from_nonoverlapping(NonNull::from(extra).cast(), extra.len());
+            *slice = core::slice::from_raw_parts_mut(new_ptr.as_ptr(), new_len);
+        }
+        Ok(())
+    }
+
+    /// Appends `extra` to `slice`, a slice allocated in the [`BumpCar`], in place if it is the
+    /// last allocation made.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left,
+    /// or if the size of the slice overflows.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let mut token = bumpcar.alloc_slice_copy(b"ab");
+    /// let start = token.as_ptr();
+    /// bumpcar.extend_slice_copy(&mut token, b"cd");
+    /// bumpcar.extend_slice_copy(&mut token, b"ef");
+    /// assert_eq!(token, b"abcdef");
+    /// assert_eq!(token.as_ptr(), start);
+    /// assert_eq!(bumpcar.remaining_capacity(), 250);
+    /// ```
+    #[track_caller]
+    pub fn extend_slice_copy<'a, T: Copy>(&'a self, slice: &mut &'a mut [T], extra: &[T]) {
+        expect_capacity(self.try_extend_slice_copy(slice, extra));
+    }
+
+    /// Allocates an uninitialized slice of `len` elements.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left,
+    /// or if the size of the slice overflows.
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_uninit_slice<T>(
+        &self,
+        len: usize,
+    ) -> Result<&mut [MaybeUninit<T>], BumpError> {
+        let ptr = self
+            .allocate_typed(array_layout::<T>(len)?)?
+            .cast::<MaybeUninit<T>>();
+        // SAFETY: the allocation is valid for len elements, which do not need to be initialized,
+        // and is not handed out again while self is borrowed.
+        Ok(unsafe { core::slice::from_raw_parts_mut(ptr.as_ptr(), len) })
+    }
+
+    /// Allocates an uninitialized slice of `len` elements, to be filled in place (for example by
+    /// a reader) before [`slice_assume_init_mut`](crate::slice_assume_init_mut) is called.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left,
+    /// or if the size of the slice overflows.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::{slice_assume_init_mut, BumpCar};
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let buffer = bumpcar.alloc_uninit_slice::<u8>(5);
+    /// for (byte, value) in buffer.iter_mut().zip(b"hello") {
+    ///     byte.write(*value);
+    /// }
+    /// // SAFETY: every byte has been written.
+    /// let bytes = unsafe { slice_assume_init_mut(buffer) };
+    /// assert_eq!(bytes, b"hello");
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn alloc_uninit_slice<T>(&self, len: usize) -> &mut [MaybeUninit<T>] {
+        expect_capacity(self.try_alloc_uninit_slice(len))
+    }
+
+    /// Clones `values` into the [`BumpCar`], and returns a mutable reference to the clone.
+    ///
+    /// If cloning an element panics, the elements cloned so far are leaked.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_slice_clone<T: Clone>(&self, values: &[T]) -> Result<&mut [T], BumpError> {
+        self.try_alloc_slice_fill_with(values.len(), |i| values[i].clone())
+    }
+
+    /// Clones `values` into the [`BumpCar`], and returns a mutable reference to the clone.
+    ///
+    /// If cloning an element panics, the elements cloned so far are leaked.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let names = [String::from("ada"), String::from("grace")];
+    /// let cloned = bumpcar.alloc_slice_clone(&names);
+    /// assert_eq!(cloned, names);
+    /// # cloned.iter_mut().for_each(|s| drop(std::mem::take(s)));
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn alloc_slice_clone<T: Clone>(&self, values: &[T]) -> &mut [T] {
+        expect_capacity(self.try_alloc_slice_clone(values))
+    }
+
+    /// Allocates a slice of `len` default values.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left,
+    /// or if the size of the slice overflows.
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_slice_fill_default<T: Default>(
+        &self,
+        len: usize,
+    ) -> Result<&mut [T], BumpError> {
+        self.try_alloc_slice_fill_with(len, |_| T::default())
+    }
+
+    /// Allocates a slice of `len` default values.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left,
+    /// or if the size of the slice overflows.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let counters = bumpcar.alloc_slice_fill_default::<u32>(4);
+    /// counters[1] += 1;
+    /// assert_eq!(counters, [0, 1, 0, 0]);
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn alloc_slice_fill_default<T: Default>(&self, len: usize) -> &mut [T] {
+        expect_capacity(self.try_alloc_slice_fill_default(len))
+    }
+
+    /// Allocates a slice of `len` default values, aligned to `align` bytes (or to the alignment
+    /// of `T`, if it is greater).
+    ///
+    /// # Errors
+    /// This function returns an error if `align` is not a power of two, if the [`BumpCar`] does
+    /// not have enough capacity left, or if the size of the slice overflows.
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_slice_aligned<T: Default>(
+        &self,
+        len: usize,
+        align: usize,
+    ) -> Result<&mut [T], BumpError> {
+        if !align.is_power_of_two() {
+            return Err(BumpError::new(BumpErrorKind::InvalidAlignment, None, 0));
+        }
+        let layout = array_layout::<T>(len)?
+            .align_to(align)
+            .map_err(|_| BumpError::new(BumpErrorKind::Overflow, None, 0))?;
+        self.fill_slice(layout, len, |_| T::default())
+    }
+
+    /// Allocates a slice of `len` default values, aligned to `align` bytes (or to the alignment
+    /// of `T`, if it is greater), such as a buffer for a vectorized kernel.
+    ///
+    /// # Panics
+    /// This function panics if `align` is not a power of two, if the [`BumpCar`] does not have
+    /// enough capacity left, or if the size of the slice overflows.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(1024).unwrap();
+    /// bumpcar.alloc(1u8);
+    /// let samples = bumpcar.alloc_slice_aligned::<f32>(64, 32);
+    /// assert_eq!(samples.as_ptr().addr() % 32, 0);
+    /// assert!(samples.iter().all(|&x| x == 0.0));
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn alloc_slice_aligned<T: Default>(&self, len: usize, align: usize) -> &mut [T] {
+        expect_capacity(self.try_alloc_slice_aligned(len, align))
+    }
+
+    /// Allocates a slice of `len` default values, aligned to [`SIMD_ALIGN`](crate::SIMD_ALIGN)
+    /// bytes, so that it can be loaded with the widest vector instructions (AVX-512, and AVX or
+    /// NEON with whole cache lines).
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left,
+    /// or if the size of the slice overflows.
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_simd<T: Default>(&self, len: usize) -> Result<&mut [T], BumpError> {
+        self.try_alloc_slice_aligned(len, SIMD_ALIGN)
+    }
+
+    /// Allocates a slice of `len` default values, aligned to [`SIMD_ALIGN`](crate::SIMD_ALIGN)
+    /// bytes.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left,
+    /// or if the size of the slice overflows.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::{BumpCar, SIMD_ALIGN};
+    ///
+    /// let bumpcar = BumpCar::new(1024).unwrap();
+    /// let weights = bumpcar.alloc_simd::<f32>(16);
+    /// assert_eq!(weights.as_ptr().addr() % SIMD_ALIGN, 0);
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn alloc_simd<T: Default>(&self, len: usize) -> &mut [T] {
+        expect_capacity(self.try_alloc_simd(len))
+    }
+
+    /// Copies `bytes` into the [`BumpCar`] followed by a NUL terminator, and returns the
+    /// resulting C string.
+    ///
+    /// # Errors
+    /// This function returns an error if `bytes` contains a NUL byte, or if the [`BumpCar`] does
+    /// not have enough capacity left.
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_cstr_from_bytes(&self, bytes: &[u8]) -> Result<&CStr, BumpError> {
+        if bytes.contains(&0) {
+            return Err(BumpError::new(BumpErrorKind::InteriorNul, None, 0));
+        }
+        let len = bytes.len();
+        // A slice is at most isize::MAX bytes long, so this does not overflow.
+        let layout = array_layout::<u8>(len + 1)?;
+        let ptr = self.allocate_typed(layout)?.cast::<u8>();
+        // SAFETY: the allocation is valid for len + 1 bytes, and cannot overlap bytes.
+        unsafe {
+            ptr.copy_from_nonoverlapping(NonNull::from(bytes).cast(), len);
+            ptr.add(len).write(0);
+        }
+        // SAFETY: the len + 1 bytes end with the only NUL byte, and are not handed out again
+        // while self is borrowed.
+        Ok(unsafe {
+            CStr::from_bytes_with_nul_unchecked(core::slice::from_raw_parts(ptr.as_ptr(), len + 1))
+        })
+    }
+
+    /// Copies `bytes` into the [`BumpCar`] followed by a NUL terminator, and returns the
+    /// resulting C string.
+    ///
+    /// # Panics
+    /// This function panics if `bytes` contains a NUL byte, or if the [`BumpCar`] does not have
+    /// enough capacity left.
+    #[track_caller]
+    pub fn alloc_cstr_from_bytes(&self, bytes: &[u8]) -> &CStr {
+        expect_capacity(self.try_alloc_cstr_from_bytes(bytes))
+    }
+
+    /// Copies `s` into the [`BumpCar`] followed by a NUL terminator, and returns the resulting
+    /// C string, to be passed to foreign functions.
+    ///
+    /// # Errors
+    /// This function returns an error if `s` contains a NUL character, or if the [`BumpCar`]
+    /// does not have enough capacity left.
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_cstr(&self, s: &str) -> Result<&CStr, BumpError> {
+        self.try_alloc_cstr_from_bytes(s.as_bytes())
+    }
+
+    /// Copies `s` into the [`BumpCar`] followed by a NUL terminator, and returns the resulting
+    /// C string, to be passed to foreign functions.
+    ///
+    /// # Panics
+    /// This function panics if `s` contains a NUL character, or if the [`BumpCar`] does not
+    /// have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let name = bumpcar.alloc_cstr("texture.png");
+    /// assert_eq!(name.to_bytes_with_nul(), b"texture.png\0");
+    /// assert!(bumpcar.try_alloc_cstr("a\0b").is_err());
+    /// ```
+    #[track_caller]
+    pub fn alloc_cstr(&self, s: &str) -> &CStr {
+        expect_capacity(self.try_alloc_cstr(s))
+    }
+
+    /// Copies `s` into the [`BumpCar`], and returns a mutable reference to the copy.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    #[allow(clippy::mut_from_ref)]
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_str(&self, s: &str) -> Result<&mut str, BumpError> {
+        let bytes = self.try_alloc_slice_copy(s.as_bytes())?;
+        // SAFETY: the bytes are a copy of a valid str.
+        Ok(unsafe { core::str::from_utf8_unchecked_mut(bytes) })
+    }
+
+    /// Copies `s` into the [`BumpCar`], and returns a mutable reference to the copy.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let s = bumpcar.alloc_str("hello");
+    /// s.make_ascii_uppercase();
+    /// assert_eq!(s, "HELLO");
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    #[track_caller]
+    pub fn alloc_str(&self, s: &str) -> &mut str {
+        expect_capacity(self.try_alloc_str(s))
+    }
+
+    /// Formats `args` directly into the [`BumpCar`], and returns the resulting string.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left,
+    /// or if a formatting trait implementation returns an error.
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_fmt(&self, args: fmt::Arguments<'_>) -> Result<&str, BumpError> {
+        /// Writes formatted strings at the tip of the arena, and remembers the size of the
+        /// request that did not fit.
+        struct Adapter<'a, 'bump, A: Allocator> {
+            writer: &'a mut BumpWriter<'bump, A>,
+            requested: Option<usize>,
+        }
+
+        impl<A: Allocator> fmt::Write for Adapter<'_, '_, A> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.writer.try_extend(s.as_bytes()).map_err(|_| {
+                    self.requested = Some(self.writer.len().saturating_add(s.len()));
+                    fmt::Error
+                })
+            }
+        }
+
+        let mut writer = BumpWriter::new(self);
+        let mut adapter = Adapter {
+            writer: &mut writer,
+            requested: None,
+        };
+        if fmt::write(&mut adapter, args).is_err() {
+            return Err(match adapter.requested {
+                Some(size) => BumpError::new(
+                    BumpErrorKind::CapacityExceeded,
+                    Layout::array::<u8>(size).ok(),
+                    self.remaining_capacity(),
+                ),
+                None => BumpError::new(BumpErrorKind::Format, None, 0),
+            });
+        }
+        // SAFETY: only valid strs have been written.
+        Ok(unsafe { core::str::from_utf8_unchecked(writer.finish()) })
+    }
+
+    /// Formats `args` directly into the [`BumpCar`], and returns the resulting string.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left,
+    /// or if a formatting trait implementation returns an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let x = 42;
+    /// let s = bumpcar.alloc_fmt(format_args!("x = {x}"));
+    /// assert_eq!(s, "x = 42");
+    /// ```
+    #[track_caller]
+    pub fn alloc_fmt(&self, args: fmt::Arguments<'_>) -> &str {
+        expect_capacity(self.try_alloc_fmt(args))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: Allocator> BumpCar<A> {
+    /// Copies `s` into the [`BumpCar`], and returns a reference to the copy.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_os_str(&self, s: &std::ffi::OsStr) -> Result<&std::ffi::OsStr, BumpError> {
+        let bytes = self.try_alloc_slice_copy(s.as_encoded_bytes())?;
+        // SAFETY: the bytes are a copy of the encoded bytes of a valid OsStr.
+        Ok(unsafe { std::ffi::OsStr::from_encoded_bytes_unchecked(bytes) })
+    }
+
+    /// Copies `s` into the [`BumpCar`], and returns a reference to the copy.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    #[track_caller]
+    pub fn alloc_os_str(&self, s: &std::ffi::OsStr) -> &std::ffi::OsStr {
+        expect_capacity(self.try_alloc_os_str(s))
+    }
+
+    /// Copies `path` into the [`BumpCar`], and returns a reference to the copy.
+    ///
+    /// # Errors
+    /// This function returns an error if the [`BumpCar`] does not have enough capacity left.
+    #[cfg_attr(feature = "profiling", track_caller)]
+    pub fn try_alloc_path(&self, path: &std::path::Path) -> Result<&std::path::Path, BumpError> {
+        self.try_alloc_os_str(path.as_os_str())
+            .map(std::path::Path::new)
+    }
+
+    /// Copies `path` into the [`BumpCar`], and returns a reference to the copy, to collect
+    /// paths without allocating a `PathBuf` for each of them.
+    ///
+    /// # Panics
+    /// This function panics if the [`BumpCar`] does not have enough capacity left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::path::Path;
+    /// use dodgems::BumpCar;
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let dir = Path::new("/usr/share");
+    /// let paths: Vec<&Path> = ["doc", "man"]
+    ///     .iter()
+    ///     .map(|name| bumpcar.alloc_path(&dir.join(name)))
+    ///     .collect();
+    /// assert_eq!(paths[1], Path::new("/usr/share/man"));
+    /// ```
+    #[track_caller]
+    pub fn alloc_path(&self, path: &std::path::Path) -> &std::path::Path {
+        expect_capacity(self.try_alloc_path(path))
+    }
+}
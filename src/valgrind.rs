@@ -0,0 +1,93 @@
+//! Valgrind client requests describing a [`BumpCar`](crate::BumpCar) as a memory pool.
+//!
+//! The buffer of the arena is the pool's superblock, anchored at its start address, and every
+//! allocation is a chunk of the pool. Outside of Valgrind, the requests do nothing.
+
+use core::ptr::NonNull;
+
+const CREATE_MEMPOOL: usize = 0x1303;
+const DESTROY_MEMPOOL: usize = 0x1304;
+const MEMPOOL_ALLOC: usize = 0x1305;
+const MEMPOOL_FREE: usize = 0x1306;
+const MEMPOOL_TRIM: usize = 0x1307;
+const MEMPOOL_CHANGE: usize = 0x1309;
+const MAKE_MEM_NOACCESS: usize = 0x4d43_0000;
+const MAKE_MEM_UNDEFINED: usize = 0x4d43_0001;
+
+/// Sends a client request to Valgrind, if the program runs under it.
+#[cfg(all(target_arch = "x86_64", not(miri)))]
+#[inline]
+fn request(args: [usize; 6]) {
+    // SAFETY: this is the special instruction sequence recognized by Valgrind. Natively,
+    // the rotations of rdi sum up to 128 bits, and the exchange of rbx with itself does nothing.
+    unsafe {
+        core::arch::asm!(
+            "rol rdi, 3",
+            "rol rdi, 13",
+            "rol rdi, 61",
+            "rol rdi, 51",
+            "xchg rbx, rbx",
+            in("rax") args.as_ptr(),
+            inout("rdx") 0usize => _,
+            out("rdi") _,
+            options(nostack, preserves_flags),
+        );
+    }
+}
+
+/// Client requests are only supported on `x86_64`.
+#[cfg(not(all(target_arch = "x86_64", not(miri))))]
+#[inline]
+fn request(_: [usize; 6]) {}
+
+/// Registers the buffer of `len` bytes at `pool` as a memory pool, with no accessible bytes.
+pub(crate) fn create(pool: NonNull<u8>, len: usize) {
+    let pool = pool.addr().get();
+    request([CREATE_MEMPOOL, pool, 0, 0, 0, 0]);
+    request([MAKE_MEM_NOACCESS, pool, len, 0, 0, 0]);
+}
+
+/// Unregisters the memory pool at `pool`, and makes its `len` bytes addressable again.
+pub(crate) fn destroy(pool: NonNull<u8>, len: usize) {
+    let pool = pool.addr().get();
+    request([DESTROY_MEMPOOL, pool, 0, 0, 0, 0]);
+    request([MAKE_MEM_UNDEFINED, pool, len, 0, 0, 0]);
+}
+
+/// Records the allocation of `size` bytes at `ptr` in the pool.
+pub(crate) fn alloc(pool: NonNull<u8>, ptr: NonNull<u8>, size: usize) {
+    if size != 0 {
+        request([
+            MEMPOOL_ALLOC,
+            pool.addr().get(),
+            ptr.addr().get(),
+            size,
+            0,
+            0,
+        ]);
+    }
+}
+
+/// Records the resize of the allocation at `ptr` in the pool.
+pub(crate) fn resize(pool: NonNull<u8>, ptr: NonNull<u8>, old_size: usize, new_size: usize) {
+    let (pool, addr) = (pool.addr().get(), ptr.addr().get());
+    match (old_size, new_size) {
+        (0, 0) => {}
+        (0, size) => request([MEMPOOL_ALLOC, pool, addr, size, 0, 0]),
+        (_, 0) => request([MEMPOOL_FREE, pool, addr, 0, 0, 0]),
+        (_, size) => request([MEMPOOL_CHANGE, pool, addr, addr, size, 0]),
+    }
+}
+
+/// Frees the chunks of the pool past its first `position` bytes, and makes the rest of its
+/// `len` bytes inaccessible.
+pub(crate) fn trim(pool: NonNull<u8>, position: usize, len: usize) {
+    let pool = pool.addr().get();
+    request([MEMPOOL_TRIM, pool, pool, position, 0, 0]);
+    request([MAKE_MEM_NOACCESS, pool + position, len - position, 0, 0, 0]);
+}
+
+/// Makes the `len` bytes at `ptr` accessible, without defining them.
+pub(crate) fn expose(ptr: NonNull<u8>, len: usize) {
+    request([MAKE_MEM_UNDEFINED, ptr.addr().get(), len, 0, 0, 0]);
+}
@@ -0,0 +1,303 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::fmt;
+use core::mem::{size_of, ManuallyDrop};
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::alloc::Global;
+
+use crate::BumpCar;
+
+/// Growable array allocated in a [`BumpCar`].
+///
+/// Unlike `Vec`, it does not depend on the unstable allocator parameter of the standard
+/// collections. As long as it is the last allocation of the arena, it grows in place.
+///
+/// # Example
+/// ```rust
+/// use dodgems::{BumpCar, BumpVec};
+///
+/// let bumpcar = BumpCar::new(256).unwrap();
+/// let mut v = BumpVec::new_in(&bumpcar);
+/// v.extend([1, 2, 3]);
+/// v.push(4);
+/// assert_eq!(v, [1, 2, 3, 4]);
+///
+/// let slice: &mut [i32] = v.into_slice();
+/// assert_eq!(slice.iter().sum::<i32>(), 10);
+/// ```
+pub struct BumpVec<
+    'bump,
+    T,
+    #[cfg(feature = "alloc")] A: Allocator = Global,
+    #[cfg(not(feature = "alloc"))] A: Allocator,
+> {
+    bumpcar: &'bump BumpCar<A>,
+    ptr: NonNull<T>,
+    len: usize,
+    capacity: usize,
+}
+
+impl<'bump, T, A: Allocator> BumpVec<'bump, T, A> {
+    /// Creates a new, empty [`BumpVec`] in `bumpcar`, without allocating.
+    pub fn new_in(bumpcar: &'bump BumpCar<A>) -> Self {
+        Self {
+            bumpcar,
+            ptr: NonNull::dangling(),
+            len: 0,
+            capacity: if size_of::<T>() == 0 { usize::MAX } else { 0 },
+        }
+    }
+
+    /// Creates a new, empty [`BumpVec`] in `bumpcar`, with room for `capacity` elements.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left.
+    pub fn try_with_capacity_in(
+        capacity: usize,
+        bumpcar: &'bump BumpCar<A>,
+    ) -> Result<Self, AllocError> {
+        let mut v = Self::new_in(bumpcar);
+        v.try_reserve(capacity)?;
+        Ok(v)
+    }
+
+    /// Creates a new, empty [`BumpVec`] in `bumpcar`, with room for `capacity` elements.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    pub fn with_capacity_in(capacity: usize, bumpcar: &'bump BumpCar<A>) -> Self {
+        let mut v = Self::new_in(bumpcar);
+        v.reserve(capacity);
+        v
+    }
+
+    /// Returns the number of elements in the [`BumpVec`].
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the [`BumpVec`] contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the [`BumpVec`] can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the arena of the [`BumpVec`].
+    pub fn bumpcar(&self) -> &'bump BumpCar<A> {
+        self.bumpcar
+    }
+
+    /// Ensures that at least `additional` more elements can be pushed.
+    ///
+    /// The capacity is at least doubled, unless the arena does not have enough capacity left
+    /// for it, in which case exactly `additional` more elements are reserved.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        let needed = self.len.checked_add(additional).ok_or(AllocError)?;
+        if needed <= self.capacity {
+            return Ok(());
+        }
+        self.try_grow_to(needed.max(self.capacity * 2))
+            .or_else(|_| self.try_grow_to(needed))
+    }
+
+    /// Ensures that at least `additional` more elements can be pushed.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    pub fn reserve(&mut self, additional: usize) {
+        if self.try_reserve(additional).is_err() {
+            panic!("BumpCar capacity exceeded");
+        }
+    }
+
+    /// Reallocates the buffer to hold exactly `capacity` elements.
+    fn try_grow_to(&mut self, capacity: usize) -> Result<(), AllocError> {
+        let new_layout = Layout::array::<T>(capacity).map_err(|_| AllocError)?;
+        let new_ptr = if self.capacity == 0 {
+            self.bumpcar.allocate(new_layout)?
+        } else {
+            // SAFETY: the buffer was allocated in the arena with the layout of an array of
+            // capacity elements, and the new layout is bigger.
+            unsafe {
+                self.bumpcar.grow(
+                    self.ptr.cast(),
+                    Layout::array::<T>(self.capacity).unwrap_unchecked(),
+                    new_layout,
+                )?
+            }
+        };
+        self.ptr = new_ptr.cast();
+        self.capacity = capacity;
+        Ok(())
+    }
+
+    /// Appends `value` to the end of the [`BumpVec`].
+    ///
+    /// # Errors
+    /// This function returns `value` back if the arena does not have enough capacity left.
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        if self.try_reserve(1).is_err() {
+            return Err(value);
+        }
+        // SAFETY: the buffer has room for at least len + 1 elements.
+        unsafe { self.ptr.add(self.len).write(value) };
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Appends `value` to the end of the [`BumpVec`].
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    pub fn push(&mut self, value: T) {
+        if self.try_push(value).is_err() {
+            panic!("BumpCar capacity exceeded");
+        }
+    }
+
+    /// Removes the last element of the [`BumpVec`] and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: the element at index len was initialized, and is now out of bounds.
+        Some(unsafe { self.ptr.add(self.len).read() })
+    }
+
+    /// Shortens the [`BumpVec`] to `len` elements, dropping the rest.
+    ///
+    /// Does nothing if `len` is greater than the current length.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail = NonNull::slice_from_raw_parts(
+            // SAFETY: len < self.len, so the pointer is in bounds of the buffer.
+            unsafe { self.ptr.add(len) },
+            self.len - len,
+        );
+        self.len = len;
+        // SAFETY: the tail elements were initialized, and are now out of bounds.
+        unsafe { tail.drop_in_place() };
+    }
+
+    /// Removes every element of the [`BumpVec`].
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Returns the elements of the [`BumpVec`] as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the first len elements are initialized.
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Returns the elements of the [`BumpVec`] as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the first len elements are initialized.
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Converts the [`BumpVec`] into a slice with the lifetime of the arena.
+    ///
+    /// The unused capacity is returned to the arena if possible. The elements are never dropped.
+    pub fn into_slice(self) -> &'bump mut [T] {
+        let this = ManuallyDrop::new(self);
+        if size_of::<T>() != 0 {
+            this.bumpcar.resize_in_place(
+                this.ptr.cast(),
+                this.capacity * size_of::<T>(),
+                this.len * size_of::<T>(),
+            );
+        }
+        // SAFETY: the first len elements are initialized, and the buffer lives as long as
+        // the arena borrow.
+        unsafe { core::slice::from_raw_parts_mut(this.ptr.as_ptr(), this.len) }
+    }
+}
+
+impl<T, A: Allocator> Drop for BumpVec<'_, T, A> {
+    /// Drops the elements, and returns the buffer to the arena if it is the last allocation.
+    fn drop(&mut self) {
+        self.clear();
+        if size_of::<T>() != 0 && self.capacity != 0 {
+            // SAFETY: the buffer was allocated in the arena with this layout.
+            unsafe {
+                self.bumpcar.deallocate(
+                    self.ptr.cast(),
+                    Layout::array::<T>(self.capacity).unwrap_unchecked(),
+                );
+            }
+        }
+    }
+}
+
+impl<T, A: Allocator> Deref for BumpVec<'_, T, A> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, A: Allocator> DerefMut for BumpVec<'_, T, A> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, A: Allocator> Extend<T> for BumpVec<'_, T, A> {
+    /// Appends every element of `iter`.
+    ///
+    /// # Panics
+    /// This function panics if the arena does not have enough capacity left.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a, A: Allocator> Extend<&'a T> for BumpVec<'_, T, A> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for BumpVec<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator> PartialEq<[U]> for BumpVec<'_, T, A> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator, const N: usize> PartialEq<[U; N]> for BumpVec<'_, T, A> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator, B: Allocator> PartialEq<BumpVec<'_, U, B>>
+    for BumpVec<'_, T, A>
+{
+    fn eq(&self, other: &BumpVec<'_, U, B>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
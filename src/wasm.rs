@@ -0,0 +1,149 @@
+use core::alloc::{AllocError, Allocator, GlobalAlloc, Layout};
+use core::arch::wasm32::memory_grow;
+use core::cell::Cell;
+use core::ptr::{self, NonNull};
+
+/// Size of a WebAssembly page.
+const PAGE_SIZE: usize = 64 * 1024;
+
+/// Grows the linear memory by enough pages to hold `size` bytes.
+///
+/// Returns the address of the new pages and their total length.
+fn grow(size: usize) -> Option<(usize, usize)> {
+    let pages = size.div_ceil(PAGE_SIZE);
+    let previous = memory_grow(0, pages);
+    if previous == usize::MAX {
+        return None;
+    }
+    Some((previous * PAGE_SIZE, pages * PAGE_SIZE))
+}
+
+/// Allocator claiming WebAssembly linear memory directly with `memory.grow`.
+///
+/// It is meant to back a `BumpCar<MemoryGrow>` in a WebAssembly module, without pulling in a
+/// general purpose allocator. Every allocation is rounded up to whole 64KiB pages, and aligned
+/// to the page size; a greater alignment can not be satisfied.
+///
+/// Linear memory can not shrink: deallocated pages are never returned.
+///
+/// # Example
+/// ```rust,ignore
+/// #![feature(allocator_api)]
+/// use dodgems::{BumpCar, MemoryGrow};
+///
+/// let mut bumpcar = BumpCar::new_in(1024 * 1024, MemoryGrow).unwrap();
+/// let v = bumpcar.alloc_slice_copy(&[1u8; 4096]);
+/// assert_eq!(v.len(), 4096);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryGrow;
+
+unsafe impl Allocator for MemoryGrow {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout.align() > PAGE_SIZE {
+            return Err(AllocError);
+        }
+        if layout.size() == 0 {
+            return Ok(NonNull::slice_from_raw_parts(layout.dangling_ptr(), 0));
+        }
+        let (addr, len) = grow(layout.size()).ok_or(AllocError)?;
+        let ptr = NonNull::new(ptr::with_exposed_provenance_mut::<u8>(addr)).ok_or(AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(ptr, len))
+    }
+
+    /// Allocates a zeroed region.
+    ///
+    /// New pages of linear memory are always zeroed, so this is the same as
+    /// [`Allocator::allocate`].
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.allocate(layout)
+    }
+
+    /// Linear memory can not shrink, so the [`MemoryGrow`] allocator never deallocates.
+    unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {}
+}
+
+/// Global bump allocator over WebAssembly linear memory.
+///
+/// It can be registered with `#[global_allocator]` in a small WebAssembly module, to be its only
+/// allocator. Its arena grows with `memory.grow` when it is exhausted, and memory is never
+/// reclaimed.
+///
+/// It is only [`Sync`] when the module is built without the `atomics` target feature, since
+/// its cursor is not updated atomically.
+///
+/// # Example
+/// ```rust,ignore
+/// use dodgems::WasmGlobal;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: WasmGlobal = WasmGlobal::new();
+/// ```
+#[derive(Debug, Default)]
+pub struct WasmGlobal {
+    current: Cell<usize>,
+    end: Cell<usize>,
+}
+
+impl WasmGlobal {
+    /// Creates a new, empty [`WasmGlobal`]. It claims memory on its first allocation.
+    pub const fn new() -> Self {
+        Self {
+            current: Cell::new(0),
+            end: Cell::new(0),
+        }
+    }
+
+    /// Returns the number of bytes left in the current region of the [`WasmGlobal`], before it
+    /// grows the linear memory.
+    pub fn remaining_capacity(&self) -> usize {
+        self.end.get() - self.current.get()
+    }
+
+    /// Bumps the cursor for `layout`, if it fits in the current region.
+    fn bump(&self, layout: Layout) -> Option<usize> {
+        let mask = layout.align() - 1;
+        let addr = self.current.get().checked_add(mask)? & !mask;
+        let new_current = addr.checked_add(layout.size())?;
+        if new_current > self.end.get() {
+            return None;
+        }
+        self.current.set(new_current);
+        Some(addr)
+    }
+}
+
+// SAFETY: without the atomics target feature, WebAssembly modules are single-threaded.
+#[cfg(not(target_feature = "atomics"))]
+unsafe impl Sync for WasmGlobal {}
+
+unsafe impl GlobalAlloc for WasmGlobal {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.align() > PAGE_SIZE {
+            return ptr::null_mut();
+        }
+        let addr = match self.bump(layout) {
+            Some(addr) => addr,
+            None => {
+                let Some((addr, len)) = grow(layout.size()) else {
+                    return ptr::null_mut();
+                };
+                // If the new pages directly follow the current region, extend it. Since its end
+                // is then page aligned, the allocation fits in the region. Otherwise, start a
+                // new region in the new pages, and leave the rest of the current one unused.
+                if addr != self.end.get() {
+                    self.current.set(addr);
+                }
+                self.end.set(addr + len);
+                match self.bump(layout) {
+                    Some(addr) => addr,
+                    None => return ptr::null_mut(),
+                }
+            }
+        };
+        ptr::with_exposed_provenance_mut(addr)
+    }
+
+    /// The [`WasmGlobal`] never deallocates.
+    unsafe fn dealloc(&self, _: *mut u8, _: Layout) {}
+}
@@ -0,0 +1,237 @@
+use core::alloc::{AllocError, Allocator, Layout};
+use core::fmt;
+use core::ptr::NonNull;
+
+use crate::BumpCar;
+
+/// Byte buffer growing at the tip of a [`BumpCar`].
+///
+/// As long as no other allocation is made in the arena, the buffer grows in place.
+/// Otherwise, it is copied to a new, larger allocation, and the old one is abandoned.
+///
+/// It implements [`fmt::Write`], so that formatted text can be written with [`write!`] without
+/// an intermediate `String`, and with the `std` feature, it implements [`std::io::Write`].
+///
+/// # Example
+/// ```rust
+/// #![feature(allocator_api)]
+/// use dodgems::{BumpCar, BumpWriter};
+///
+/// let bumpcar = BumpCar::new(256).unwrap();
+/// let mut writer = BumpWriter::new(&bumpcar);
+/// writer.try_extend(b"hello ").unwrap();
+/// writer.try_extend(b"world").unwrap();
+///
+/// assert_eq!(writer.finish(), b"hello world");
+/// assert_eq!(bumpcar.remaining_capacity(), 245);
+/// ```
+pub struct BumpWriter<'bump, A: Allocator> {
+    bumpcar: &'bump BumpCar<A>,
+    ptr: NonNull<u8>,
+    len: usize,
+    capacity: usize,
+}
+
+impl<'bump, A: Allocator> BumpWriter<'bump, A> {
+    /// Creates a new, empty writer at the tip of `bumpcar`.
+    pub fn new(bumpcar: &'bump BumpCar<A>) -> Self {
+        Self {
+            bumpcar,
+            ptr: bumpcar.current.get(),
+            len: 0,
+            capacity: 0,
+        }
+    }
+
+    /// Returns the number of bytes written.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no bytes have been written.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the bytes written so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: the first len bytes of the buffer are initialized.
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Ensures that at least `additional` more bytes can be written.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        let needed = self.len.checked_add(additional).ok_or(AllocError)?;
+        if needed <= self.capacity {
+            return Ok(());
+        }
+        if self
+            .bumpcar
+            .resize_in_place(self.ptr, self.capacity, needed)
+        {
+            self.capacity = needed;
+            return Ok(());
+        }
+
+        let layout = Layout::array::<u8>(needed.max(self.capacity * 2)).map_err(|_| AllocError)?;
+        let new_ptr = Allocator::allocate(&self.bumpcar, layout).or_else(|_| {
+            Allocator::allocate(&self.bumpcar, Layout::array::<u8>(needed).unwrap())
+        })?;
+        // SAFETY: the new allocation is at least len bytes long, and cannot overlap the old one.
+        unsafe {
+            new_ptr
+                .cast::<u8>()
+                .copy_from_nonoverlapping(self.ptr, self.len);
+        }
+        self.ptr = new_ptr.cast();
+        self.capacity = new_ptr.len();
+        Ok(())
+    }
+
+    /// Appends `bytes` to the buffer.
+    ///
+    /// # Errors
+    /// This function returns an error if the arena does not have enough capacity left,
+    /// in which case nothing is written.
+    pub fn try_extend(&mut self, bytes: &[u8]) -> Result<(), AllocError> {
+        self.try_reserve(bytes.len())?;
+        // SAFETY: the buffer has at least len + bytes.len() bytes of capacity,
+        // and cannot overlap bytes which is borrowed immutably.
+        unsafe {
+            self.ptr
+                .add(self.len)
+                .copy_from_nonoverlapping(NonNull::from(bytes).cast(), bytes.len());
+        }
+        self.len += bytes.len();
+        Ok(())
+    }
+
+    /// Returns the written bytes, with the lifetime of the arena.
+    ///
+    /// The unused capacity is returned to the arena if possible.
+    pub fn finish(self) -> &'bump [u8] {
+        self.finish_mut()
+    }
+
+    /// Returns the written bytes mutably, with the lifetime of the arena.
+    fn finish_mut(self) -> &'bump mut [u8] {
+        self.bumpcar
+            .resize_in_place(self.ptr, self.capacity, self.len);
+        // SAFETY: the first len bytes of the buffer are initialized,
+        // and the buffer lives as long as the arena borrow.
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+/// Reads from `reader` into `bumpcar`, until the end of the stream or until `max_len` bytes have
+/// been read, and returns the bytes read.
+///
+/// The bytes are read directly into arena memory, growing in place at the tip of the arena,
+/// without any intermediate buffer.
+///
+/// # Errors
+/// This function returns the errors of `reader` (except for
+/// [`Interrupted`](std::io::ErrorKind::Interrupted) errors, which are retried), and an
+/// [`OutOfMemory`](std::io::ErrorKind::OutOfMemory) error if the arena runs out of capacity.
+/// The memory used so far is then returned to the arena if possible.
+///
+/// # Example
+/// ```rust
+/// use dodgems::{read_to_bump, BumpCar};
+///
+/// let bumpcar = BumpCar::new(1024).unwrap();
+/// let mut reader: &[u8] = b"GET / HTTP/1.1";
+/// let request = read_to_bump(&bumpcar, &mut reader, 512).unwrap();
+/// assert_eq!(request, b"GET / HTTP/1.1");
+/// assert_eq!(bumpcar.remaining_capacity(), 1010);
+/// ```
+#[cfg(feature = "std")]
+#[allow(clippy::mut_from_ref)]
+pub fn read_to_bump<'bump, A: Allocator, R: std::io::Read + ?Sized>(
+    bumpcar: &'bump BumpCar<A>,
+    reader: &mut R,
+    max_len: usize,
+) -> std::io::Result<&'bump mut [u8]> {
+    let mut writer = BumpWriter::new(bumpcar);
+    let result = (|| {
+        // The bytes between len and capacity are always initialized, so that they can be
+        // handed to the reader.
+        while writer.len < max_len {
+            if writer.len == writer.capacity {
+                let wanted = writer.capacity.max(64).min(max_len - writer.len);
+                let available = bumpcar.remaining_capacity().min(wanted);
+                if writer.try_reserve(wanted).is_err()
+                    && (available == 0 || writer.try_reserve(available).is_err())
+                {
+                    return Err(std::io::ErrorKind::OutOfMemory.into());
+                }
+                // SAFETY: the buffer is valid for capacity bytes.
+                unsafe {
+                    writer
+                        .ptr
+                        .add(writer.len)
+                        .write_bytes(0, writer.capacity - writer.len);
+                }
+            }
+            let end = writer.capacity.min(max_len);
+            // SAFETY: the bytes between len and capacity are initialized, and not borrowed.
+            let spare = unsafe {
+                core::slice::from_raw_parts_mut(
+                    writer.ptr.add(writer.len).as_ptr(),
+                    end - writer.len,
+                )
+            };
+            match reader.read(spare) {
+                Ok(0) => break,
+                Ok(n) => writer.len += n,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    })();
+    match result {
+        Ok(()) => Ok(writer.finish_mut()),
+        Err(err) => {
+            bumpcar.resize_in_place(writer.ptr, writer.capacity, 0);
+            Err(err)
+        }
+    }
+}
+
+impl<A: Allocator> fmt::Write for BumpWriter<'_, A> {
+    /// Appends the bytes of `s`, or returns an error if the arena does not have enough capacity
+    /// left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use dodgems::{BumpCar, BumpWriter};
+    ///
+    /// let bumpcar = BumpCar::new(256).unwrap();
+    /// let mut writer = BumpWriter::new(&bumpcar);
+    /// write!(writer, "{} + {} = {}", 1, 2, 1 + 2).unwrap();
+    /// assert_eq!(writer.finish(), b"1 + 2 = 3");
+    /// ```
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_extend(s.as_bytes()).map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: Allocator> std::io::Write for BumpWriter<'_, A> {
+    /// Writes the whole buffer, or returns an [`OutOfMemory`](std::io::ErrorKind::OutOfMemory)
+    /// error if the arena does not have enough capacity left.
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.try_extend(buf)
+            .map_err(|_| std::io::ErrorKind::OutOfMemory)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
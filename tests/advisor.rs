@@ -0,0 +1,45 @@
+#![cfg(feature = "alloc")]
+
+use dodgems::{BumpCar, CapacityAdvisor};
+
+#[test]
+fn advisor_ignores_rare_spikes() {
+    let mut advisor = CapacityAdvisor::<200>::new();
+    assert_eq!(advisor.suggested_capacity(), None);
+    for i in 0..199 {
+        advisor.record(1000 + i % 10);
+    }
+    advisor.record(1_000_000);
+    assert_eq!(advisor.len(), 200);
+    assert_eq!(advisor.suggested_capacity(), Some(1009 + 101));
+
+    let advisor = advisor.with_headroom(0);
+    assert_eq!(advisor.headroom(), 0);
+    assert_eq!(advisor.suggested_capacity(), Some(1009));
+}
+
+#[test]
+fn advisor_window_slides() {
+    let mut advisor = CapacityAdvisor::<4>::new().with_headroom(50);
+    for peak in [4000, 4000, 4000, 4000, 100, 200, 300, 400] {
+        advisor.record(peak);
+    }
+    assert_eq!(advisor.len(), 4);
+    assert_eq!(advisor.suggested_capacity(), Some(600));
+
+    advisor.clear();
+    assert!(advisor.is_empty());
+    assert_eq!(advisor.suggested_capacity(), None);
+}
+
+#[test]
+fn advisor_records_resets() {
+    let mut advisor = CapacityAdvisor::<8>::new().with_headroom(0);
+    let mut bumpcar = BumpCar::new(1024).unwrap();
+    for size in [300, 100, 200] {
+        bumpcar.alloc_slice_fill_with(size, |_| 0u8);
+        advisor.record_reset(&mut bumpcar);
+        assert_eq!(bumpcar.peak_usage(), 0);
+    }
+    assert_eq!(advisor.suggested_capacity(), Some(300));
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+
+use dodgems::AlignedBumpCar;
+
+#[test]
+fn aligned_cursor() {
+    let bumpcar = AlignedBumpCar::<16>::new(256).unwrap();
+    for size in [1, 3, 8, 17] {
+        let ptr = bumpcar
+            .allocate(Layout::from_size_align(size, 1).unwrap())
+            .unwrap();
+        assert_eq!(ptr.cast::<u8>().addr().get() % 16, 0);
+        assert_eq!(ptr.len(), size);
+    }
+
+    let ptr = bumpcar
+        .allocate(Layout::from_size_align(4, 64).unwrap())
+        .unwrap();
+    assert_eq!(ptr.cast::<u8>().addr().get() % 64, 0);
+    let next = bumpcar.allocate(Layout::new::<u8>()).unwrap();
+    assert_eq!(next.cast::<u8>().addr().get() % 16, 0);
+}
+
+#[test]
+fn aligned_vec_grows_in_place() {
+    let mut bumpcar = AlignedBumpCar::<8>::new(256).unwrap();
+    let mut v = Vec::new_in(&bumpcar);
+    v.extend_from_slice(&[1u8; 3]);
+    let ptr = v.as_ptr();
+    v.extend_from_slice(&[2u8; 61]);
+    assert_eq!(v.as_ptr(), ptr);
+    v.truncate(5);
+    v.shrink_to_fit();
+    assert_eq!(bumpcar.remaining_capacity(), 248);
+    drop(v);
+    assert_eq!(bumpcar.remaining_capacity(), 256);
+
+    bumpcar.reset();
+    assert!(bumpcar.allocate(Layout::new::<[u8; 257]>()).is_err());
+    assert!(bumpcar.allocate(Layout::new::<[u8; 256]>()).is_ok());
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "std")]
+#![feature(allocator_api)]
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+use dodgems::{ambient, with_bump, BumpCar};
+
+#[test]
+fn scopes_install_and_restore_the_arena() {
+    assert!(ambient::current().is_none());
+    assert!(ambient::try_alloc(0u8).is_err());
+
+    let outer = Rc::new(BumpCar::new(64).unwrap());
+    ambient::with_bumpcar(Rc::clone(&outer), || {
+        let a = ambient::alloc(1u64);
+        assert_eq!(outer.remaining_capacity(), 56);
+
+        with_bump!(32, {
+            let b = ambient::alloc_slice_copy(&[2u32, 3]);
+            assert_eq!(*b, [2, 3]);
+            assert_eq!(outer.remaining_capacity(), 56);
+            assert!(ambient::try_alloc([0u8; 64]).is_err());
+        });
+
+        assert!(Rc::ptr_eq(&ambient::current().unwrap(), &outer));
+        assert_eq!(*a, 1);
+    });
+    assert!(ambient::current().is_none());
+}
+
+#[test]
+fn allocations_keep_the_arena_alive() {
+    let escaped = with_bump!(64, { ambient::alloc(String::from("still here")) });
+    assert!(ambient::current().is_none());
+    assert_eq!(*escaped, "still here");
+    assert_eq!(Rc::strong_count(Box::allocator(&escaped)), 1);
+}
+
+#[test]
+fn panics_restore_the_previous_arena() {
+    let outer = Rc::new(BumpCar::new(64).unwrap());
+    ambient::with_bumpcar(Rc::clone(&outer), || {
+        let result = catch_unwind(AssertUnwindSafe(|| with_bump!(64, { panic!("oops") })));
+        assert!(result.is_err());
+        assert!(Rc::ptr_eq(&ambient::current().unwrap(), &outer));
+    });
+}
+
+#[test]
+#[should_panic = "no ambient BumpCar"]
+fn alloc_outside_of_a_scope() {
+    ambient::alloc(0u8);
+}
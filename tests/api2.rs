@@ -0,0 +1,31 @@
+#![cfg(all(feature = "alloc", feature = "allocator-api2"))]
+#![feature(allocator_api)]
+
+use allocator_api2::{alloc::Global, boxed::Box, vec::Vec};
+use dodgems::{BumpCar, Compat, FlexBumpCar};
+
+#[test]
+fn api2_collections() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    let mut v = Vec::with_capacity_in(4, &bumpcar);
+    v.extend([1u32, 2, 3, 4]);
+    assert_eq!(bumpcar.remaining_capacity(), 48);
+    assert_eq!(*Box::new_in(5u64, &bumpcar), 5);
+
+    assert!(Vec::<u8, _>::new_in(&bumpcar).try_reserve(64).is_err());
+
+    let flex = FlexBumpCar::new(16).unwrap();
+    let mut v = Vec::new_in(&flex);
+    v.extend(0u64..100);
+    assert_eq!(v.iter().sum::<u64>(), 4950);
+}
+
+#[test]
+fn api2_backing_allocator() {
+    let mut bumpcar = BumpCar::new_in(32, Compat(Global)).unwrap();
+    assert_eq!(bumpcar.capacity(), 32);
+    let b = std::boxed::Box::new_in([0u8; 32], &bumpcar);
+    drop(b);
+    bumpcar.reset();
+    assert_eq!(bumpcar.remaining_capacity(), 32);
+}
@@ -0,0 +1,23 @@
+#![cfg(all(feature = "alloc", feature = "asan"))]
+#![feature(allocator_api)]
+
+use dodgems::BumpCar;
+
+/// Without the sanitizer, the feature must not change the behavior of the arena. Under
+/// `-Zsanitizer=address`, only handed out bytes are accessed.
+#[test]
+fn asan_handed_out_bytes_are_accessible() {
+    let mut bumpcar = BumpCar::new(256).unwrap();
+    let mut v = Vec::new_in(&bumpcar);
+    v.extend(0..16u64);
+    v.shrink_to(4);
+    assert_eq!(v.iter().sum::<u64>(), 120);
+    drop(v);
+    let a = bumpcar.alloc(1u8);
+    *a += 1;
+    let b = bumpcar.alloc(2u64);
+    *b += *a as u64;
+    assert_eq!(*b, 4);
+    bumpcar.reset();
+    assert_eq!(*bumpcar.alloc(3u32), 3);
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+
+use dodgems::{BackfillBumpCar, BumpCar};
+
+#[test]
+fn backfill_saves_padding() {
+    let layouts = [
+        Layout::from_size_align(64, 64).unwrap(),
+        Layout::new::<u8>(),
+        Layout::new::<u16>(),
+        Layout::new::<u32>(),
+        Layout::new::<u64>(),
+    ];
+    let backfill = BackfillBumpCar::new(64 * 1024).unwrap();
+    let bumpcar = BumpCar::new(64 * 1024).unwrap();
+    for layout in layouts.iter().cycle().take(500) {
+        let a = backfill.allocate(*layout).unwrap().cast::<u8>();
+        assert_eq!(a.addr().get() % layout.align(), 0);
+        unsafe { a.write_bytes(0xAB, layout.size()) };
+        bumpcar.allocate(*layout).unwrap();
+    }
+    assert!(backfill.remaining_capacity() > bumpcar.remaining_capacity());
+    // Every group of small values fits in 16 bytes, without padding before the blocks.
+    assert!(backfill.capacity() - backfill.remaining_capacity() <= 100 * (64 + 16) + 64);
+}
+
+#[test]
+fn backfill_reset_forgets_the_gap() {
+    let mut bumpcar = BackfillBumpCar::new(1024).unwrap();
+    bumpcar.allocate(Layout::new::<u8>()).unwrap();
+    bumpcar
+        .allocate(Layout::from_size_align(8, 128).unwrap())
+        .unwrap();
+    assert!(bumpcar.gap_len() > 0);
+    bumpcar.reset();
+    assert_eq!(bumpcar.gap_len(), 0);
+    assert_eq!(bumpcar.remaining_capacity(), 1024);
+}
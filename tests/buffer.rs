@@ -0,0 +1,32 @@
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+use std::mem::MaybeUninit;
+
+use dodgems::BumpCar;
+
+#[test]
+fn buffer_allocate() {
+    let mut buffer = [MaybeUninit::<u64>::uninit(); 8];
+    // SAFETY: any byte of a MaybeUninit<u64> array is a valid MaybeUninit<u8>.
+    let bytes = unsafe { std::slice::from_raw_parts_mut(buffer.as_mut_ptr().cast(), 64) };
+    let mut bumpcar = BumpCar::from_buffer(bytes);
+    assert_eq!(bumpcar.capacity(), 64);
+
+    let v = Box::new_in([7u64; 4], &bumpcar);
+    assert_eq!(bumpcar.remaining_capacity(), 32);
+    assert!(bumpcar.owns(std::ptr::NonNull::from(&*v).cast()));
+    assert!(bumpcar.allocate(Layout::new::<[u8; 33]>()).is_err());
+
+    drop(v);
+    bumpcar.reset();
+    assert!(bumpcar.allocate(Layout::new::<[u8; 64]>()).is_ok());
+}
+
+#[test]
+fn buffer_empty() {
+    let bumpcar = BumpCar::from_buffer(&mut []);
+    assert_eq!(bumpcar.capacity(), 0);
+    assert!(bumpcar.allocate(Layout::new::<u8>()).is_err());
+    assert!(bumpcar.allocate(Layout::new::<()>()).is_ok());
+}
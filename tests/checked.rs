@@ -0,0 +1,75 @@
+#![cfg(all(feature = "alloc", feature = "debug-headers"))]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+
+use dodgems::{BumpCar, CheckedCar};
+
+#[test]
+fn checked_collections() {
+    let bumpcar = BumpCar::new(4096).unwrap();
+    let checked = CheckedCar::new(&bumpcar);
+    let mut v = Vec::new_in(&checked);
+    v.extend(0..200u32);
+    v.truncate(10);
+    v.shrink_to_fit();
+    assert_eq!(v, (0..10).collect::<Vec<_>>());
+    let s = String::from_utf8_lossy(b"checked").into_owned();
+    let b = Box::new_in(s, &checked);
+    assert_eq!(*b, "checked");
+    drop(b);
+    drop(v);
+    assert!(checked.allocations() >= 2);
+}
+
+#[test]
+fn checked_resize_to_greater_alignment() {
+    let checked = CheckedCar::new(BumpCar::new(1024).unwrap());
+    let old = Layout::from_size_align(64, 8).unwrap();
+    let ptr = checked.allocate(old).unwrap().cast::<u8>();
+    unsafe { ptr.write_bytes(3, 64) };
+
+    let new = Layout::from_size_align(32, 128).unwrap();
+    let shrunk = unsafe { checked.shrink(ptr, old, new) }.unwrap();
+    assert_eq!(shrunk.cast::<u8>().addr().get() % 128, 0);
+    assert!(unsafe { shrunk.as_ref() }.iter().all(|&x| x == 3));
+    unsafe { checked.deallocate(shrunk.cast(), new) };
+}
+
+#[test]
+#[should_panic = "already deallocated"]
+fn checked_double_free() {
+    let bumpcar = BumpCar::new(256).unwrap();
+    let checked = CheckedCar::new(&bumpcar);
+    let ptr = checked.allocate(Layout::new::<u64>()).unwrap().cast();
+    // Keeps the arena from reclaiming the first allocation.
+    checked.allocate(Layout::new::<u64>()).unwrap();
+    unsafe {
+        checked.deallocate(ptr, Layout::new::<u64>());
+        checked.deallocate(ptr, Layout::new::<u64>());
+    }
+}
+
+#[test]
+#[should_panic = "but allocation #1 has a size of 16 bytes"]
+fn checked_layout_mismatch() {
+    let bumpcar = BumpCar::new(256).unwrap();
+    let checked = CheckedCar::new(&bumpcar);
+    checked.allocate(Layout::new::<u8>()).unwrap();
+    let ptr = checked.allocate(Layout::new::<[u64; 2]>()).unwrap().cast();
+    unsafe { checked.grow(ptr, Layout::new::<[u64; 3]>(), Layout::new::<[u64; 4]>()) }.unwrap();
+}
+
+#[test]
+#[should_panic = "not allocated by this CheckedCar"]
+fn checked_foreign_pointer() {
+    let bumpcar = BumpCar::new(256).unwrap();
+    let checked = CheckedCar::new(&bumpcar);
+    let ptr = bumpcar.alloc([0u64; 8]);
+    unsafe {
+        checked.deallocate(
+            std::ptr::NonNull::from(&mut *ptr).cast::<u64>().add(4).cast(),
+            Layout::new::<u64>(),
+        )
+    };
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::ptr::NonNull;
+
+use dodgems::{BumpCar, CloneIn};
+
+#[test]
+fn clone_nested() {
+    let bumpcar = BumpCar::new(1024).unwrap();
+
+    let names = vec![String::from("alice"), String::from("bob")];
+    let tags: Vec<Option<&str>> = vec![Some("x"), None, Some("yz")];
+    let numbers: Box<[u32]> = Box::new([1, 2, 3]);
+
+    let names_in = names.clone_in(&bumpcar);
+    let tags_in = tags.as_slice().clone_in(&bumpcar);
+    let numbers_in = numbers.clone_in(&bumpcar);
+    drop((names, tags, numbers));
+
+    assert_eq!(names_in, ["alice", "bob"]);
+    assert_eq!(tags_in, [Some("x"), None, Some("yz")]);
+    assert_eq!(numbers_in, [1, 2, 3]);
+
+    for s in names_in.iter().chain(tags_in.iter().flatten()) {
+        assert!(bumpcar.owns(NonNull::from(*s).cast()));
+    }
+    assert!(bumpcar.owns(NonNull::from(names_in.as_slice()).cast()));
+    assert!(bumpcar.owns(NonNull::from(tags_in).cast()));
+    assert!(bumpcar.owns(NonNull::from(numbers_in).cast()));
+}
+
+#[test]
+fn clone_exhausted() {
+    let bumpcar = BumpCar::new(4).unwrap();
+    assert!("hello".try_clone_in(&bumpcar).is_err());
+    assert_eq!("hell".clone_in(&bumpcar), "hell");
+}
+
+#[test]
+fn relocate_out_of_the_arena() {
+    use std::alloc::Global;
+
+    use dodgems::{BumpString, BumpVec, RelocateTo};
+
+    let mut bumpcar = BumpCar::new(1024).unwrap();
+    let (words, tags, total) = {
+        let mut s = BumpString::new_in(&bumpcar);
+        s.push_str("kept after reset");
+        let tags = bumpcar.alloc_slice_copy(&[Some(1u8), None, Some(3)]);
+        let mut words = BumpVec::new_in(&bumpcar);
+        words.extend(s.split(' '));
+        let total = bumpcar.alloc_box(42u64);
+        (
+            words.relocate_to(Global),
+            tags.relocate_to(Global),
+            total.relocate_to(Global),
+        )
+    };
+    bumpcar.reset();
+    bumpcar.alloc_slice_copy(&[0xFFu8; 512]);
+
+    assert_eq!(words.len(), 3);
+    assert_eq!(&*words[2], "reset");
+    assert_eq!(*tags, [Some(1), None, Some(3)]);
+    assert_eq!(*total, 42);
+    for word in &words {
+        assert!(!bumpcar.owns(NonNull::from(&**word).cast()));
+    }
+}
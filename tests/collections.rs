@@ -0,0 +1,191 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use core::fmt::Write;
+use dodgems::{BumpCar, BumpCow, BumpString, BumpVec, CollectIn, FixedBumpVec};
+
+#[test]
+fn vec_push_grows_in_place() {
+    let bumpcar = BumpCar::new(1024).unwrap();
+    let mut v = BumpVec::new_in(&bumpcar);
+    v.push(1u32);
+    let ptr = v.as_ptr();
+    for i in 2..=64 {
+        v.push(i);
+    }
+    assert_eq!(v.as_ptr(), ptr);
+    assert_eq!(v.len(), 64);
+    assert_eq!(v.iter().sum::<u32>(), 64 * 65 / 2);
+
+    drop(v);
+    assert_eq!(bumpcar.remaining_capacity(), 1024);
+}
+
+#[test]
+fn vec_capacity_exceeded() {
+    let bumpcar = BumpCar::new(16).unwrap();
+    let mut v = BumpVec::new_in(&bumpcar);
+    for i in 0..4u32 {
+        v.push(i);
+    }
+    assert_eq!(v.try_push(4), Err(4));
+    assert_eq!(v, [0, 1, 2, 3]);
+    assert!(BumpVec::<u8>::try_with_capacity_in(17, &bumpcar).is_err());
+}
+
+#[test]
+fn vec_into_slice_returns_capacity() {
+    let bumpcar = BumpCar::new(1024).unwrap();
+    let mut v = BumpVec::with_capacity_in(100, &bumpcar);
+    v.extend([1u8, 2, 3]);
+    let slice = v.into_slice();
+    assert_eq!(slice, [1, 2, 3]);
+    assert_eq!(bumpcar.remaining_capacity(), 1021);
+}
+
+#[test]
+fn vec_drops_elements() {
+    let bumpcar = BumpCar::new(1024).unwrap();
+    let rc = std::rc::Rc::new(());
+    let mut v = BumpVec::new_in(&bumpcar);
+    v.extend((0..5).map(|_| rc.clone()));
+    assert_eq!(std::rc::Rc::strong_count(&rc), 6);
+    v.truncate(2);
+    assert_eq!(std::rc::Rc::strong_count(&rc), 3);
+    assert!(v.pop().is_some());
+    drop(v);
+    assert_eq!(std::rc::Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn vec_zero_sized() {
+    let bumpcar = BumpCar::new(16).unwrap();
+    let mut v = BumpVec::new_in(&bumpcar);
+    for _ in 0..1000 {
+        v.push(());
+    }
+    assert_eq!(v.len(), 1000);
+    assert_eq!(bumpcar.remaining_capacity(), 16);
+}
+
+#[test]
+fn string_push_and_write() {
+    let bumpcar = BumpCar::new(1024).unwrap();
+    let mut s = BumpString::new_in(&bumpcar);
+    s.push_str("héllo");
+    s.push('!');
+    write!(s, " {}", 12).unwrap();
+    assert_eq!(s, "héllo! 12");
+    assert_eq!(s.to_uppercase(), "HÉLLO! 12");
+
+    let s = s.into_str();
+    assert_eq!(bumpcar.remaining_capacity(), 1024 - s.len());
+}
+
+#[test]
+fn string_capacity_exceeded() {
+    let bumpcar = BumpCar::new(4).unwrap();
+    let mut s = BumpString::new_in(&bumpcar);
+    s.push_str("abc");
+    assert!(s.try_push_str("de").is_err());
+    assert_eq!(s, "abc");
+    assert!(write!(s, "{}", 10).is_err());
+}
+
+#[test]
+fn collect_in() {
+    let bumpcar = BumpCar::new(1024).unwrap();
+    let v = (0..10u32).collect_in::<BumpVec<_>>(&bumpcar);
+    assert_eq!(v.len(), 10);
+    assert_eq!(v.capacity(), 10);
+
+    let evens: BumpVec<u32> = v
+        .iter()
+        .filter(|x| *x % 2 == 0)
+        .copied()
+        .collect_in(&bumpcar);
+    assert_eq!(evens.into_slice(), [0, 2, 4, 6, 8]);
+
+    let s: BumpString = "a-b-c".split('-').collect_in(&bumpcar);
+    assert_eq!(s, "abc");
+    let s: BumpString = s.chars().rev().collect_in(&bumpcar);
+    assert_eq!(s, "cba");
+
+    let std_vec: Vec<u8, _> = b"hello".iter().copied().collect_in(&bumpcar);
+    assert_eq!(std_vec, b"hello");
+}
+
+#[test]
+fn collect_in_capacity_exceeded() {
+    let bumpcar = BumpCar::new(16).unwrap();
+    assert!((0..5u32).try_collect_in::<BumpVec<_>>(&bumpcar).is_err());
+    assert!((0..4u32).try_collect_in::<BumpVec<_>>(&bumpcar).is_ok());
+}
+
+#[test]
+fn fixed_vec_never_moves() {
+    let bumpcar = BumpCar::new(256).unwrap();
+    let mut v = FixedBumpVec::with_capacity_in(4, &bumpcar);
+    let start = v.as_ptr();
+    for i in 0..4u64 {
+        *v.push_within_capacity(i).unwrap() += 10;
+        assert_eq!(v.as_ptr(), start);
+    }
+    assert!(v.is_full());
+    assert_eq!(v.push_within_capacity(4), Err(4));
+    assert_eq!(v, [10, 11, 12, 13]);
+    assert_eq!(v.pop(), Some(13));
+    v.truncate(1);
+    assert_eq!(v.len(), 1);
+
+    let slice = v.into_slice();
+    assert_eq!(slice, [10]);
+    assert_eq!(bumpcar.remaining_capacity(), 248);
+
+    assert!(FixedBumpVec::<u64>::try_with_capacity_in(100, &bumpcar).is_err());
+    let zst = FixedBumpVec::<()>::with_capacity_in(usize::MAX, &bumpcar);
+    assert_eq!(zst.capacity(), usize::MAX);
+}
+
+#[test]
+fn fixed_vec_drops_elements() {
+    use std::rc::Rc;
+
+    let bumpcar = BumpCar::new(256).unwrap();
+    let counter = Rc::new(());
+    let mut v = FixedBumpVec::with_capacity_in(3, &bumpcar);
+    for _ in 0..3 {
+        v.push_within_capacity(counter.clone()).unwrap();
+    }
+    assert_eq!(Rc::strong_count(&counter), 4);
+    drop(v);
+    assert_eq!(Rc::strong_count(&counter), 1);
+    assert_eq!(bumpcar.remaining_capacity(), 256);
+}
+
+#[test]
+fn cow_copies_on_first_write() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    let input = [1u32, 2, 3];
+
+    let mut cow = BumpCow::from(&input[..]);
+    assert!(cow.is_borrowed());
+    assert_eq!(*cow, [1, 2, 3]);
+    assert_eq!(bumpcar.remaining_capacity(), 64);
+
+    cow.to_mut(&bumpcar)[0] = 10;
+    assert!(cow.is_owned());
+    assert_eq!(bumpcar.remaining_capacity(), 64 - 12);
+    cow.to_mut(&bumpcar)[1] = 20;
+    assert_eq!(bumpcar.remaining_capacity(), 64 - 12);
+    assert_eq!(*cow.into_ref(), [10, 20, 3]);
+    assert_eq!(input, [1, 2, 3]);
+
+    let mut word = BumpCow::Borrowed("much too long for the remaining capacity of the arena");
+    assert!(word.try_to_mut(&bumpcar).is_err());
+    assert!(word.is_borrowed());
+
+    let mut n = BumpCow::Borrowed(&5u64);
+    *n.to_mut(&bumpcar) += 1;
+    assert_eq!(format!("{n:?}"), "6");
+}
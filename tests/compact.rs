@@ -0,0 +1,78 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+use std::mem::{size_of, MaybeUninit};
+
+use dodgems::{BumpCar, CompactBumpCar};
+
+#[test]
+fn compact_header() {
+    assert!(size_of::<CompactBumpCar<'_, u16>>() <= 2 * size_of::<usize>());
+    assert!(size_of::<CompactBumpCar<'_, u32>>() <= size_of::<usize>() + 8);
+    assert!(size_of::<CompactBumpCar<'_, u32>>() < size_of::<BumpCar>());
+}
+
+#[test]
+fn capacity_must_fit_in_the_index() {
+    let mut buffer = vec![MaybeUninit::uninit(); 1 << 16];
+    assert!(CompactBumpCar::<u16>::from_buffer(&mut buffer).is_err());
+    assert!(CompactBumpCar::<u16>::from_buffer(&mut buffer[1..]).is_ok());
+    let bumpcar = CompactBumpCar::<u32>::from_buffer(&mut buffer).unwrap();
+    assert_eq!(bumpcar.capacity(), 1 << 16);
+}
+
+#[test]
+fn allocations() {
+    let mut buffer = [MaybeUninit::uninit(); 64];
+    let mut bumpcar = CompactBumpCar::<u16>::from_buffer(&mut buffer).unwrap();
+    let a = Box::new_in(1u8, &bumpcar);
+    let b = Box::new_in(2u64, &bumpcar);
+    assert_eq!(std::ptr::from_ref(&*b).addr() % 8, 0);
+    assert_eq!(*a + *b as u8, 3);
+    assert!(bumpcar.remaining_capacity() <= 64 - 9);
+    assert!(bumpcar.allocate(Layout::new::<[u8; 64]>()).is_err());
+    drop((a, b));
+
+    bumpcar.reset();
+    assert_eq!(bumpcar.remaining_capacity(), 64);
+    assert!(bumpcar.allocate(Layout::new::<[u8; 64]>()).is_ok());
+    assert!(bumpcar.allocate(Layout::new::<u8>()).is_err());
+    assert!(bumpcar.allocate(Layout::new::<()>()).is_ok());
+}
+
+#[test]
+fn last_allocation_is_reclaimed() {
+    let mut buffer = [MaybeUninit::uninit(); 64];
+    let bumpcar = CompactBumpCar::<u32>::from_buffer(&mut buffer).unwrap();
+    let a = Box::new_in([0u8; 10], &bumpcar);
+    let b = Box::new_in([0u8; 10], &bumpcar);
+    drop(a);
+    assert_eq!(bumpcar.remaining_capacity(), 44);
+    drop(b);
+    assert_eq!(bumpcar.remaining_capacity(), 54);
+}
+
+#[test]
+fn resizes_in_place() {
+    let mut buffer = [MaybeUninit::uninit(); 256];
+    let bumpcar = CompactBumpCar::<u16>::from_buffer(&mut buffer).unwrap();
+    let mut v = Vec::<u8, _>::with_capacity_in(1, &bumpcar);
+    let start = v.as_ptr();
+    for i in 0..200 {
+        v.push(i);
+    }
+    assert_eq!(v.as_ptr(), start);
+    v.truncate(10);
+    v.shrink_to_fit();
+    assert_eq!(v.as_ptr(), start);
+    assert!(bumpcar.remaining_capacity() >= 256 - 10 - 8);
+
+    // Moved when not the last allocation.
+    let other = Box::new_in(0u8, &bumpcar);
+    v.extend(0..50);
+    assert_ne!(v.as_ptr(), start);
+    assert_eq!(v[..10], (0..10).collect::<Vec<u8>>());
+    assert_eq!(v[10..], (0..50).collect::<Vec<u8>>());
+    drop(other);
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::rc::Rc;
+
+use dodgems::CountedBumpCar;
+
+#[test]
+fn counts_live_allocations() {
+    let bumpcar = Rc::new(CountedBumpCar::new(1024).unwrap());
+    let mut v = Vec::new_in(bumpcar.clone());
+    v.extend_from_slice(&[1u32; 16]);
+    v.extend_from_slice(&[2u32; 64]);
+    let b = Box::new_in(0u8, bumpcar.clone());
+    assert_eq!(bumpcar.live_allocations(), 2);
+
+    let err = bumpcar.try_reset().unwrap_err();
+    assert_eq!(err.live_allocations(), 2);
+    assert_eq!(
+        err.to_string(),
+        "cannot reset BumpCar with 2 live allocations"
+    );
+
+    drop(v);
+    assert!(bumpcar.try_reset().is_err());
+    drop(b);
+    assert_eq!(bumpcar.live_allocations(), 0);
+    bumpcar.try_reset().unwrap();
+    assert_eq!(bumpcar.remaining_capacity(), 1024);
+}
+
+#[cfg(not(all(feature = "leak-check", debug_assertions)))]
+#[test]
+fn reset_mut_clears_count() {
+    let mut bumpcar = CountedBumpCar::new(64).unwrap();
+    std::mem::forget(Box::new_in(0u64, &bumpcar));
+    assert_eq!(bumpcar.live_allocations(), 1);
+    bumpcar.reset();
+    assert_eq!(bumpcar.live_allocations(), 0);
+    assert_eq!(bumpcar.remaining_capacity(), 64);
+}
+
+#[cfg(all(feature = "leak-check", debug_assertions))]
+#[test]
+#[should_panic = "cannot reset BumpCar with 1 live allocations"]
+fn leak_check_on_reset() {
+    let mut bumpcar = CountedBumpCar::new(64).unwrap();
+    Box::leak(Box::new_in(0u64, &bumpcar));
+    bumpcar.reset();
+}
+
+#[cfg(all(feature = "leak-check", debug_assertions))]
+#[test]
+#[should_panic = "cannot reset BumpCar with 1 live allocations"]
+fn leak_check_on_drop() {
+    let bumpcar = CountedBumpCar::new(64).unwrap();
+    std::mem::forget(Vec::<u8, _>::with_capacity_in(8, &bumpcar));
+    drop(bumpcar);
+}
+
+#[cfg(all(feature = "leak-check", debug_assertions))]
+#[test]
+fn leak_check_passes() {
+    let mut bumpcar = CountedBumpCar::new(64).unwrap();
+    drop(Box::new_in(0u64, &bumpcar));
+    bumpcar.reset();
+}
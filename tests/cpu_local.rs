@@ -0,0 +1,37 @@
+#![cfg(all(feature = "cpu-local", target_os = "linux", not(miri)))]
+#![feature(allocator_api)]
+
+use dodgems::CpuLocalBumpCar;
+
+#[test]
+fn cpu_local_many_threads_share_few_chunks() {
+    let chunk_size = 4096;
+    let bumpcar = CpuLocalBumpCar::new(1 << 24, chunk_size).unwrap();
+    std::thread::scope(|s| {
+        for i in 0..256u64 {
+            let bumpcar = &bumpcar;
+            s.spawn(move || {
+                let mut v = Vec::with_capacity_in(4, bumpcar);
+                v.extend([i; 4]);
+                assert_eq!(v, [i; 4]);
+            });
+        }
+    });
+    // Each thread could have taken its own chunk, but there is at most one chunk per CPU, and
+    // allocations racing for a chunk go to the reserve.
+    let used = bumpcar.capacity() - bumpcar.remaining_capacity();
+    assert!(used <= bumpcar.cpus() * chunk_size + 256 * 32, "{used}");
+}
+
+#[test]
+fn cpu_local_large_and_reset() {
+    let mut bumpcar = CpuLocalBumpCar::new(1 << 16, 256).unwrap();
+    assert!(bumpcar.cpus() >= 1);
+    let big = Box::new_in([7u8; 1024], &bumpcar);
+    assert_eq!(big[1023], 7);
+    drop(big);
+    assert!(bumpcar.remaining_capacity() <= (1 << 16) - 1024);
+    bumpcar.reset();
+    assert_eq!(bumpcar.remaining_capacity(), 1 << 16);
+    assert_eq!(*Box::new_in(5u32, &bumpcar), 5);
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "critical-section")]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+
+use dodgems::CriticalBumpCar;
+
+#[test]
+fn critical_threads() {
+    static ARENA: CriticalBumpCar<{ 8 * 1000 }> = CriticalBumpCar::new();
+
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            std::thread::spawn(|| {
+                (0..250)
+                    .map(|i| Box::leak(Box::new_in(i as u64, &ARENA)) as *const u64 as usize)
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let mut addrs: Vec<usize> = threads
+        .into_iter()
+        .flat_map(|t| t.join().unwrap())
+        .collect();
+    addrs.sort_unstable();
+    addrs.dedup();
+    assert_eq!(addrs.len(), 1000);
+    assert_eq!(ARENA.remaining_capacity(), 0);
+    assert!((&ARENA).allocate(Layout::new::<u8>()).is_err());
+}
+
+#[test]
+fn critical_reset() {
+    let mut arena = CriticalBumpCar::<64>::new();
+    let a = (&arena).allocate(Layout::new::<[u8; 40]>()).unwrap();
+    assert!((&arena).allocate(Layout::new::<[u8; 40]>()).is_err());
+    // SAFETY: a was allocated in the arena with this layout.
+    unsafe { (&arena).deallocate(a.cast(), Layout::new::<[u8; 40]>()) };
+    assert_eq!(arena.remaining_capacity(), 64);
+    (&arena).allocate(Layout::new::<[u8; 64]>()).unwrap();
+    arena.reset_mut();
+    assert_eq!(arena.remaining_capacity(), 64);
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+
+use dodgems::{BumpErrorKind, DoubleBumpCar};
+
+#[test]
+fn double_ends_meet() {
+    let mut bumpcar = DoubleBumpCar::new(64).unwrap();
+    let (front, back) = bumpcar.split();
+    let a = front.allocate(Layout::new::<[u8; 24]>()).unwrap();
+    let b = back.allocate(Layout::new::<[u8; 32]>()).unwrap();
+    assert!(a.cast::<u8>() < b.cast::<u8>());
+    assert_eq!((front.used(), back.used()), (24, 32));
+
+    assert!(front.allocate(Layout::new::<[u8; 9]>()).is_err());
+    assert!(back.allocate(Layout::new::<[u8; 9]>()).is_err());
+    assert!(back.allocate(Layout::new::<[u8; 8]>()).is_ok());
+    assert!(front.allocate(Layout::new::<u8>()).is_err());
+
+    bumpcar.reset();
+    assert_eq!(bumpcar.remaining_capacity(), 64);
+}
+
+#[test]
+fn double_reset_back_only() {
+    let mut bumpcar = DoubleBumpCar::new(256).unwrap();
+    let (front, mut back) = bumpcar.split();
+
+    let results = Box::new_in([7u64; 8], &front);
+    for _ in 0..100 {
+        let scratch = Box::new_in([1u64; 16], &back);
+        assert_eq!(scratch.iter().sum::<u64>(), 16);
+        std::mem::forget(scratch);
+        assert_eq!(back.used(), 128);
+        back.reset();
+    }
+    assert_eq!(back.used(), 0);
+    assert_eq!(*results, [7; 8]);
+    drop(results);
+    assert_eq!(front.used(), 0);
+}
+
+#[test]
+fn promote_survives_back_reset() {
+    let mut bumpcar = DoubleBumpCar::new(128).unwrap();
+    let (front, mut back) = bumpcar.split();
+
+    let mut scratch = Vec::new_in(&back);
+    scratch.extend(0..8u32);
+    let kept = front.promote_slice(&scratch[2..6]);
+    let one = front.promote(&scratch[7]);
+    drop(scratch);
+    back.reset();
+
+    let overwrite = Box::new_in([u32::MAX; 16], &back);
+    assert_eq!(kept, [2, 3, 4, 5]);
+    assert_eq!(*one, 7);
+    assert_eq!(front.used(), 20);
+    drop(overwrite);
+
+    // The front and the back share the capacity.
+    let big = [0u8; 120];
+    let err = front.try_promote_slice(&big).unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::CapacityExceeded);
+    assert_eq!(err.layout(), Some(Layout::new::<[u8; 120]>()));
+    assert_eq!(err.remaining_capacity(), 128 - front.used());
+    assert!(front.try_promote(&[0u8; 100]).is_ok());
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+
+use dodgems::DownBumpCar;
+
+#[test]
+fn down_alignment() {
+    let bumpcar = DownBumpCar::new(256).unwrap();
+    bumpcar.allocate(Layout::new::<u8>()).unwrap();
+    let ptr = bumpcar
+        .allocate(Layout::from_size_align(8, 32).unwrap())
+        .unwrap();
+    assert_eq!(ptr.cast::<u8>().addr().get() % 32, 0);
+    assert!((216..=247).contains(&bumpcar.remaining_capacity()));
+    assert!(bumpcar.allocate(Layout::new::<[u8; 256]>()).is_err());
+}
+
+#[test]
+fn down_vec_grows() {
+    let bumpcar = DownBumpCar::new(1024).unwrap();
+    let mut v = Vec::new_in(&bumpcar);
+    v.extend(0..100u32);
+    assert_eq!(v.iter().sum::<u32>(), 4950);
+}
+
+#[test]
+fn down_deallocate_last() {
+    let bumpcar = DownBumpCar::new(64).unwrap();
+    let a = Box::new_in(0u64, &bumpcar);
+    let b = Box::new_in(0u64, &bumpcar);
+    drop(a);
+    assert_eq!(bumpcar.remaining_capacity(), 48);
+    drop(b);
+    assert_eq!(bumpcar.remaining_capacity(), 56);
+}
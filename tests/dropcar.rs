@@ -0,0 +1,52 @@
+#![cfg(feature = "alloc")]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use dodgems::DropCar;
+
+struct Noisy<'a>(u32, &'a RefCell<Vec<u32>>);
+
+impl Drop for Noisy<'_> {
+    fn drop(&mut self) {
+        self.1.borrow_mut().push(self.0);
+    }
+}
+
+#[test]
+fn drop_on_reset() {
+    let log = RefCell::new(Vec::new());
+    let mut dropcar = DropCar::new(1024).unwrap();
+    for i in 0..4 {
+        dropcar.alloc(Noisy(i, &log));
+    }
+    assert!(log.borrow().is_empty());
+
+    dropcar.reset();
+    assert_eq!(*log.borrow(), [3, 2, 1, 0]);
+    assert_eq!(dropcar.remaining_capacity(), 1024);
+
+    dropcar.alloc(Noisy(4, &log));
+    dropcar.reset();
+    assert_eq!(*log.borrow(), [3, 2, 1, 0, 4]);
+}
+
+#[test]
+fn drop_on_drop() {
+    let rc = Rc::new(());
+    let dropcar = DropCar::new(1024).unwrap();
+    dropcar.alloc(rc.clone());
+    dropcar.alloc(vec![rc.clone(), rc.clone()]);
+    assert_eq!(Rc::strong_count(&rc), 4);
+    drop(dropcar);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn no_header_without_drop() {
+    let dropcar = DropCar::new(64).unwrap();
+    dropcar.alloc(0u64);
+    assert_eq!(dropcar.remaining_capacity(), 56);
+    assert!(dropcar.try_alloc(String::new()).is_ok());
+    assert!(dropcar.remaining_capacity() < 56 - size_of::<String>());
+}
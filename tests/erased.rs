@@ -0,0 +1,40 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::ptr::NonNull;
+
+use dodgems::{Bump, BumpCar};
+
+fn push_all(v: &mut Vec<u64, Bump<'_>>, n: u64) {
+    v.extend(0..n);
+}
+
+#[test]
+fn bump_handle_allocates() {
+    let bumpcar = BumpCar::new(256).unwrap();
+    let bump = bumpcar.as_bump();
+    let mut v = Vec::with_capacity_in(4, bump);
+    push_all(&mut v, 4);
+    assert_eq!(v, [0, 1, 2, 3]);
+    assert!(bump.owns(NonNull::from(&v[0]).cast()));
+    assert_eq!(bump.remaining_capacity(), 224);
+    assert_eq!(bump.capacity(), 256);
+
+    // Growing the last allocation happens in place.
+    push_all(&mut v, 4);
+    assert_eq!(bump.remaining_capacity(), 192);
+    drop(v);
+    assert_eq!(bumpcar.remaining_capacity(), 256);
+}
+
+#[test]
+fn bump_handle_erases_allocator() {
+    let outer = BumpCar::new(256).unwrap();
+    let inner = BumpCar::new_in(64, &outer).unwrap();
+    let bumps: [Bump<'_>; 2] = [Bump::from(&outer), inner.as_bump()];
+
+    let a = Box::new_in(1u32, bumps[0]);
+    let b = Box::new_in(2u32, bumps[1]);
+    assert_eq!(*a + *b, 3);
+    assert_eq!(inner.remaining_capacity(), 60);
+}
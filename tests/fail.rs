@@ -0,0 +1,32 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use dodgems::{BumpCar, FailCar};
+
+#[test]
+fn fail_after() {
+    let bumpcar = BumpCar::new(1024).unwrap();
+    let failing = FailCar::fail_after(&bumpcar, 2);
+    let mut v = Vec::<u8, _>::new_in(&failing);
+    v.reserve_exact(8);
+    v.reserve_exact(16);
+    assert!(v.try_reserve_exact(32).is_err());
+    assert!(Box::try_new_in(0u8, &failing).is_err());
+    assert_eq!((failing.attempts(), failing.failures()), (4, 2));
+    v.shrink_to_fit();
+    assert_eq!(v.capacity(), 0);
+}
+
+#[test]
+fn fail_schedule() {
+    let bumpcar = BumpCar::new(1024).unwrap();
+    let failing = FailCar::new(&bumpcar, |attempt| attempt % 3 == 2);
+    let results: Vec<bool> = (0..9)
+        .map(|i| Box::try_new_in(i, &failing).is_ok())
+        .collect();
+    assert_eq!(
+        results,
+        [true, true, false, true, true, false, true, true, false]
+    );
+    assert_eq!(failing.failures(), 3);
+}
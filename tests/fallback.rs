@@ -0,0 +1,36 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Global, Layout};
+use std::ptr::NonNull;
+
+use dodgems::{BumpCar, Fallback, Owns};
+
+#[test]
+fn fallback_routes_blocks() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    let alloc = Fallback(&bumpcar, Global);
+
+    let small = Box::new_in([1u8; 32], &alloc);
+    assert!(bumpcar.owns(NonNull::from(&*small).cast()));
+    let big = Box::new_in([2u8; 128], &alloc);
+    assert!(!bumpcar.owns(NonNull::from(&*big).cast()));
+
+    let mut v = Vec::with_capacity_in(4, &alloc);
+    v.extend_from_slice(&[3u64; 2]);
+    assert!(bumpcar.owns(NonNull::from(&v[0]).cast()));
+    v.extend_from_slice(&[4u64; 8]);
+    assert!(!bumpcar.owns(NonNull::from(&v[0]).cast()));
+    assert_eq!(v[..3], [3, 3, 4]);
+
+    drop((small, big, v));
+}
+
+#[test]
+fn owns_through_reference() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    let layout = Layout::new::<u64>();
+    let ptr = bumpcar.allocate(layout).unwrap().cast::<u8>();
+    assert!(Owns::owns(&&bumpcar, ptr, layout));
+    assert!(!Owns::owns(&bumpcar, NonNull::from(&layout).cast(), layout));
+}
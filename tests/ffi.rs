@@ -0,0 +1,66 @@
+#![cfg(feature = "ffi")]
+
+use std::ptr;
+
+use dodgems::ffi::*;
+
+#[test]
+fn ffi_allocate() {
+    let arena = dodgems_new(64);
+    assert!(!arena.is_null());
+
+    unsafe {
+        assert_eq!(dodgems_remaining(arena), 64);
+
+        let p = dodgems_alloc(arena, 8, 8);
+        assert!(!p.is_null());
+        assert_eq!(p.addr() % 8, 0);
+        p.write_bytes(0xFF, 8);
+        assert_eq!(dodgems_remaining(arena), 56);
+
+        assert!(!dodgems_alloc(arena, 56, 1).is_null());
+        assert!(dodgems_alloc(arena, 1, 1).is_null());
+        assert_eq!(dodgems_remaining(arena), 0);
+
+        dodgems_reset(arena);
+        assert_eq!(dodgems_remaining(arena), 64);
+
+        dodgems_free(arena);
+    }
+}
+
+#[test]
+fn ffi_invalid_inputs() {
+    let arena = dodgems_new(64);
+
+    unsafe {
+        assert!(dodgems_alloc(arena, 0, 1).is_null());
+        assert!(dodgems_alloc(arena, 8, 3).is_null());
+        assert!(dodgems_alloc(arena, 8, 0).is_null());
+        assert!(dodgems_alloc(arena, usize::MAX, 1).is_null());
+        assert_eq!(dodgems_remaining(arena), 64);
+
+        assert!(dodgems_alloc(ptr::null(), 8, 8).is_null());
+        assert_eq!(dodgems_remaining(ptr::null()), 0);
+        dodgems_reset(ptr::null_mut());
+        dodgems_free(ptr::null_mut());
+
+        dodgems_free(arena);
+    }
+
+    assert!(dodgems_new(usize::MAX).is_null());
+}
+
+#[test]
+fn ffi_header_declares_all_functions() {
+    let header = include_str!("../include/dodgems.h");
+    for function in [
+        "dodgems_new(",
+        "dodgems_alloc(",
+        "dodgems_remaining(",
+        "dodgems_reset(",
+        "dodgems_free(",
+    ] {
+        assert!(header.contains(function), "{function} is not declared");
+    }
+}
@@ -0,0 +1,133 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+
+use dodgems::{FlexBumpCar, GrowthStrategy, ResetPolicy};
+
+#[test]
+fn flex_grows() {
+    let bumpcar = FlexBumpCar::new(64).unwrap();
+    assert_eq!(bumpcar.capacity(), 64);
+
+    let boxes: Vec<_> = (0u64..100).map(|i| Box::new_in(i, &bumpcar)).collect();
+    assert!(bumpcar.capacity() >= 800);
+    for (i, b) in boxes.iter().enumerate() {
+        assert_eq!(**b, i as u64);
+    }
+
+    let big = (&bumpcar)
+        .allocate(Layout::from_size_align(4096, 256).unwrap())
+        .unwrap();
+    assert_eq!(big.cast::<u8>().addr().get() % 256, 0);
+}
+
+#[test]
+fn flex_reset_keeps_last_chunk() {
+    let mut bumpcar = FlexBumpCar::new(16).unwrap();
+    for _ in 0..10 {
+        let v = Vec::<u8, _>::with_capacity_in(200, &bumpcar);
+        drop(v);
+    }
+    let capacity = bumpcar.capacity();
+    assert!(capacity > 200);
+
+    bumpcar.reset();
+    assert!(bumpcar.capacity() < capacity);
+    assert_eq!(bumpcar.remaining_capacity(), bumpcar.capacity());
+}
+
+#[test]
+fn flex_growth_strategies() {
+    let bumpcar = FlexBumpCar::new(64).unwrap();
+    assert_eq!(bumpcar.growth(), GrowthStrategy::Double);
+
+    let bumpcar = bumpcar.with_growth(GrowthStrategy::CappedDouble(200));
+    for _ in 0..4 {
+        (&bumpcar).allocate(Layout::new::<[u8; 64]>()).unwrap();
+    }
+    assert_eq!(bumpcar.capacity(), 64 + 128 + 200);
+
+    (&bumpcar).allocate(Layout::new::<[u8; 200]>()).unwrap();
+    (&bumpcar).allocate(Layout::new::<[u8; 1000]>()).unwrap();
+    assert_eq!(bumpcar.capacity(), 64 + 128 + 200 + 200 + 1000);
+
+    let bumpcar = FlexBumpCar::new(8)
+        .unwrap()
+        .with_growth(GrowthStrategy::Fixed(32));
+    for _ in 0..5 {
+        (&bumpcar).allocate(Layout::new::<[u8; 32]>()).unwrap();
+    }
+    assert_eq!(bumpcar.capacity(), 8 + 5 * 32);
+}
+
+#[test]
+fn flex_chunk_introspection() {
+    let mut bumpcar = FlexBumpCar::new(64).unwrap();
+    assert_eq!(bumpcar.chunk_count(), 1);
+    assert_eq!(bumpcar.used(), 0);
+
+    (&bumpcar).allocate(Layout::new::<[u8; 60]>()).unwrap();
+    (&bumpcar).allocate(Layout::new::<[u8; 100]>()).unwrap();
+    (&bumpcar).allocate(Layout::new::<u8>()).unwrap();
+    let chunks: Vec<_> = bumpcar.chunks().collect();
+    assert_eq!(chunks.len(), 2);
+    assert_eq!((chunks[0].capacity, chunks[0].used), (128, 101));
+    assert_eq!((chunks[1].capacity, chunks[1].used), (64, 60));
+    assert_eq!(bumpcar.used(), 161);
+    assert_eq!(
+        bumpcar.chunks().map(|chunk| chunk.capacity).sum::<usize>(),
+        bumpcar.capacity()
+    );
+
+    bumpcar.reset();
+    assert_eq!(bumpcar.chunk_count(), 1);
+    assert_eq!(bumpcar.used(), 0);
+}
+
+#[test]
+fn flex_reset_coalesces() {
+    let mut bumpcar = FlexBumpCar::new(16)
+        .unwrap()
+        .with_reset_policy(ResetPolicy::Coalesce);
+    assert_eq!(bumpcar.reset_policy(), ResetPolicy::Coalesce);
+    for _ in 0..10 {
+        (&bumpcar).allocate(Layout::new::<[u8; 16]>()).unwrap();
+    }
+    let used = bumpcar.used();
+    assert_eq!(used, 160);
+    assert!(bumpcar.chunk_count() > 1);
+
+    bumpcar.reset();
+    assert_eq!(bumpcar.chunk_count(), 1);
+    assert_eq!(bumpcar.capacity(), used);
+    assert_eq!(bumpcar.remaining_capacity(), used);
+
+    for _ in 0..10 {
+        (&bumpcar).allocate(Layout::new::<[u8; 16]>()).unwrap();
+    }
+    assert_eq!(bumpcar.chunk_count(), 1);
+    bumpcar.reset();
+    assert_eq!(bumpcar.capacity(), used);
+}
+
+#[test]
+fn flex_overflow() {
+    let bumpcar = FlexBumpCar::new(16).unwrap();
+    assert!((&bumpcar)
+        .allocate(Layout::from_size_align(isize::MAX as usize, 1).unwrap())
+        .is_err());
+    assert!(FlexBumpCar::new(usize::MAX).is_err());
+}
+
+#[test]
+#[cfg(feature = "zeroize")]
+fn flex_zeroize_on_reset() {
+    let mut bumpcar = FlexBumpCar::new(64).unwrap();
+    let secret = (&bumpcar).allocate(Layout::new::<[u8; 32]>()).unwrap();
+    unsafe { secret.cast::<u8>().write_bytes(0xA5, 32) };
+    bumpcar.reset();
+
+    let region = (&bumpcar).allocate(Layout::new::<[u8; 32]>()).unwrap();
+    assert!(unsafe { region.as_ref() }.iter().all(|&x| x == 0));
+}
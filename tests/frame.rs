@@ -0,0 +1,35 @@
+#![cfg(feature = "alloc")]
+
+use dodgems::FrameCar;
+
+#[test]
+fn frames_swap_and_reset() {
+    let mut frames = FrameCar::new(64).unwrap();
+    frames.current().alloc([1u64; 4]);
+    assert_eq!(frames.current().remaining_capacity(), 32);
+
+    frames.begin_frame();
+    assert_eq!(frames.frame(), 1);
+    assert_eq!(frames.previous().remaining_capacity(), 32);
+    assert_eq!(frames.current().remaining_capacity(), 64);
+    frames.current().alloc([2u64; 2]);
+
+    frames.begin_frame();
+    assert_eq!(frames.previous().remaining_capacity(), 48);
+    assert_eq!(frames.current().remaining_capacity(), 64);
+
+    frames.reset();
+    assert_eq!(frames.previous().remaining_capacity(), 64);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic = "use of handle after BumpCar reset"]
+fn frames_stale_handle() {
+    let mut frames = FrameCar::new(64).unwrap();
+    let handle = frames.current().alloc_tagged(1u32).unwrap();
+    frames.begin_frame();
+    frames.begin_frame();
+    // SAFETY: the handle is two frames old, which is detected in debug builds.
+    unsafe { frames.current().get_tagged(handle) };
+}
@@ -0,0 +1,18 @@
+use dodgems::BumpGlobal;
+
+#[global_allocator]
+static ALLOCATOR: BumpGlobal<{ 64 * 1024 * 1024 }> = BumpGlobal::new();
+
+#[test]
+fn global_allocations() {
+    let before = ALLOCATOR.remaining_capacity();
+    let v: Vec<u64> = (0..1000).collect();
+    assert_eq!(v.iter().sum::<u64>(), 999 * 1000 / 2);
+    assert!(ALLOCATOR.remaining_capacity() <= before - 8000);
+
+    drop(v);
+    let after = ALLOCATOR.remaining_capacity();
+    let s = String::from("hello");
+    assert!(ALLOCATOR.remaining_capacity() < after);
+    assert_eq!(s, "hello");
+}
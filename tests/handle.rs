@@ -0,0 +1,92 @@
+#![cfg(feature = "alloc")]
+
+use dodgems::{BumpCar, Handle, SliceHandle};
+
+struct Node {
+    value: u32,
+    next: Option<Handle<Node>>,
+    labels: SliceHandle<u8>,
+}
+
+#[test]
+fn handle_graph() {
+    let mut bumpcar = BumpCar::new(256).unwrap();
+
+    let labels = bumpcar.alloc_slice_handle(b"tail").unwrap();
+    let tail = bumpcar
+        .alloc_handle(Node {
+            value: 2,
+            next: None,
+            labels,
+        })
+        .unwrap();
+    let labels = bumpcar.alloc_slice_handle(b"head").unwrap();
+    let head = bumpcar
+        .alloc_handle(Node {
+            value: 1,
+            next: Some(tail),
+            labels,
+        })
+        .unwrap();
+
+    let mut values = Vec::new();
+    let mut current = Some(head);
+    while let Some(handle) = current {
+        let node = unsafe { bumpcar.get(handle) };
+        values.push((node.value, unsafe { bumpcar.get_slice(node.labels) }));
+        current = node.next;
+    }
+    assert_eq!(values, [(1, &b"head"[..]), (2, &b"tail"[..])]);
+
+    // round-trip the raw offsets
+    let raw = Handle::<Node>::from_offset(head.offset());
+    assert_eq!(raw, head);
+    let node = unsafe { bumpcar.get_mut(raw) };
+    node.value = 10;
+    let labels = SliceHandle::<u8>::from_raw_parts(node.labels.offset(), node.labels.len());
+    unsafe { bumpcar.get_slice_mut(labels) }.make_ascii_uppercase();
+
+    assert_eq!(unsafe { bumpcar.get(head) }.value, 10);
+    assert_eq!(unsafe { bumpcar.get_slice(labels) }, b"HEAD");
+}
+
+#[test]
+#[should_panic = "handle out of bounds"]
+fn handle_out_of_bounds() {
+    let bumpcar = BumpCar::new(16).unwrap();
+    unsafe { bumpcar.get(Handle::<u64>::from_offset(16)) };
+}
+
+#[test]
+#[should_panic = "misaligned handle"]
+fn handle_misaligned() {
+    let bumpcar = BumpCar::new(16).unwrap();
+    unsafe { bumpcar.get(Handle::<u64>::from_offset(1)) };
+}
+
+#[test]
+fn tagged_handle_in_generation() {
+    let mut bumpcar = BumpCar::new(256).unwrap();
+    let frozen = bumpcar.alloc_tagged(1u32).unwrap();
+    bumpcar.freeze();
+    let scratch = bumpcar.alloc_tagged(2u32).unwrap();
+    unsafe {
+        *bumpcar.get_tagged_mut(scratch) += 1;
+        assert_eq!(*bumpcar.get_tagged(scratch), 3);
+    }
+
+    bumpcar.reset();
+    assert_eq!(unsafe { *bumpcar.get_tagged(frozen) }, 1);
+    assert_eq!(scratch.handle().offset(), 4);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "use of handle after BumpCar reset")]
+fn tagged_handle_after_reset() {
+    let mut bumpcar = BumpCar::new(256).unwrap();
+    let handle = bumpcar.alloc_tagged(42u64).unwrap();
+    bumpcar.reset();
+    bumpcar.alloc(0u64);
+    let _ = unsafe { bumpcar.get_tagged(handle) };
+}
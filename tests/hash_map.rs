@@ -0,0 +1,160 @@
+#![cfg(feature = "alloc")]
+
+use std::alloc::Layout;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use dodgems::{BumpCar, BumpErrorKind, BumpHashMap, BumpHashSet};
+
+#[test]
+fn hash_map_matches_std() {
+    let bumpcar = BumpCar::new(1 << 16).unwrap();
+    let mut map = BumpHashMap::new_in(&bumpcar);
+    let mut model = HashMap::new();
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let steps = if cfg!(miri) { 300 } else { 5000 };
+    for _ in 0..steps {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let key = (state >> 8) % 200;
+        match state % 4 {
+            0 => assert_eq!(map.remove(&key), model.remove(&key)),
+            1 => assert_eq!(map.get(&key), model.get(&key)),
+            _ => assert_eq!(map.insert(key, state), model.insert(key, state)),
+        }
+        assert_eq!(map.len(), model.len());
+    }
+    for (k, v) in &model {
+        assert_eq!(map.get(k), Some(v));
+    }
+    assert_eq!(map.iter().count(), model.len());
+    assert!(map.len() <= map.capacity());
+}
+
+#[test]
+fn hash_map_borrowed_lookups() {
+    let bumpcar = BumpCar::new(4096).unwrap();
+    let mut map = BumpHashMap::new_in(&bumpcar);
+    map.insert(String::from("alpha"), 1);
+    map.insert(String::from("beta"), 2);
+    assert_eq!(map.get("alpha"), Some(&1));
+    assert!(map.contains_key("beta"));
+    assert!(!map.contains_key("gamma"));
+    *map.get_mut("beta").unwrap() *= 10;
+    assert_eq!(map.remove_entry("beta"), Some((String::from("beta"), 20)));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn hash_map_drops_entries() {
+    let counter = Rc::new(());
+    let bumpcar = BumpCar::new(4096).unwrap();
+    {
+        let mut map = BumpHashMap::with_capacity_in(50, &bumpcar);
+        for i in 0..50 {
+            map.insert(i, counter.clone());
+        }
+        assert_eq!(Rc::strong_count(&counter), 51);
+        map.remove(&7);
+        map.insert(3, counter.clone());
+        assert_eq!(Rc::strong_count(&counter), 50);
+        map.clear();
+        assert_eq!(Rc::strong_count(&counter), 1);
+        map.insert(1, counter.clone());
+    }
+    assert_eq!(Rc::strong_count(&counter), 1);
+    // The table never grew and was the last allocation, so it was reclaimed.
+    assert_eq!(bumpcar.remaining_capacity(), bumpcar.capacity());
+}
+
+/// Key whose hash and equality can change after its insertion.
+#[derive(Clone)]
+struct Mutable(Rc<Cell<u32>>);
+
+impl Hash for Mutable {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.get().hash(state);
+    }
+}
+
+impl PartialEq for Mutable {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.get() == other.0.get()
+    }
+}
+
+impl Eq for Mutable {}
+
+#[test]
+fn hash_map_mutated_keys() {
+    let bumpcar = BumpCar::new(1 << 16).unwrap();
+    let mut map = BumpHashMap::new_in(&bumpcar);
+    let keys: Vec<_> = (0..6).map(|i| Mutable(Rc::new(Cell::new(i)))).collect();
+    for (i, key) in keys.iter().enumerate() {
+        map.insert(key.clone(), i);
+    }
+    for key in &keys {
+        key.0.set(0);
+    }
+    // Growing the table rehashes the keys, that are now all equal.
+    for i in 100..200 {
+        map.insert(Mutable(Rc::new(Cell::new(i))), 0);
+    }
+    assert!(map.len() <= 106);
+    assert_eq!(map.iter().count(), map.len());
+    assert!(map.contains_key(&Mutable(Rc::new(Cell::new(0)))));
+    drop(map);
+    assert!(keys.iter().all(|key| Rc::strong_count(&key.0) == 1));
+}
+
+#[test]
+fn hash_map_out_of_capacity() {
+    let bumpcar = BumpCar::new(256).unwrap();
+    let mut map = BumpHashMap::new_in(&bumpcar);
+    let mut inserted = 0u64;
+    let rejected = loop {
+        match map.try_insert(inserted, inserted) {
+            Ok(_) => inserted += 1,
+            Err(entry) => break entry,
+        }
+    };
+    assert_eq!(rejected, (inserted, inserted));
+    assert_eq!(map.len() as u64, inserted);
+    // Existing keys can still be replaced without growing the table.
+    assert_eq!(map.try_insert(0, 42), Ok(Some(0)));
+    let err = BumpHashMap::<u64, u64>::try_with_capacity_in(1000, &bumpcar).unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::CapacityExceeded);
+    assert_eq!(
+        err.layout(),
+        Some(Layout::array::<Option<(u64, u64)>>(2048).unwrap())
+    );
+    assert_eq!(err.remaining_capacity(), bumpcar.remaining_capacity());
+}
+
+#[test]
+#[should_panic = "BumpCar capacity exceeded: 192 bytes aligned to 8 requested, 64 bytes remaining"]
+fn hash_map_insert_panics() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    let mut map = BumpHashMap::new_in(&bumpcar);
+    for i in 0..100u64 {
+        map.insert(i, i);
+    }
+}
+
+#[test]
+fn hash_set_basics() {
+    let bumpcar = BumpCar::new(4096).unwrap();
+    let mut set = BumpHashSet::with_capacity_in(10, &bumpcar);
+    assert!(set.capacity() >= 10);
+    set.extend(["a", "b", "c", "a"]);
+    assert_eq!(set.len(), 3);
+    assert!(!set.insert("b"));
+    assert!(set.contains("c"));
+    assert!(set.remove("c"));
+    assert!(!set.remove("c"));
+    assert_eq!(set.take("a"), Some("a"));
+    assert_eq!(format!("{set:?}"), r#"{"b"}"#);
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "alloc")]
+
+use std::rc::Rc;
+
+use dodgems::{BumpErrorKind, Id, IdCar};
+
+#[test]
+fn ids_resolve_to_values() {
+    let mut ids = IdCar::new(4).unwrap();
+    let a = ids.alloc(String::from("a"));
+    let b = ids.alloc(String::from("b"));
+    assert_eq!(ids.len(), 2);
+    assert_eq!(a.to_raw(), 0);
+    assert_eq!(b.to_raw(), 1);
+    assert_eq!(ids.get(a).map(String::as_str), Some("a"));
+    ids.get_mut(b).unwrap().push('!');
+    assert_eq!(ids[b], "b!");
+    assert!(ids.get(Id::from_raw(2)).is_none());
+    assert_eq!(
+        ids.iter()
+            .map(|(id, s)| (id, s.as_str()))
+            .collect::<Vec<_>>(),
+        [(a, "a"), (b, "b!")]
+    );
+}
+
+#[test]
+fn id_car_full() {
+    let ids = IdCar::new(2).unwrap();
+    ids.alloc(1u8);
+    ids.alloc(2u8);
+    assert_eq!(ids.try_alloc(3), Err(3));
+    assert_eq!(ids.as_slice(), [1, 2]);
+}
+
+#[test]
+fn generations_detect_stale_ids() {
+    let mut ids = IdCar::with_generation_bits(16, 2).unwrap();
+    let first = ids.alloc(0u32);
+    for generation in 1..=4 {
+        ids.reset();
+        assert_eq!(ids.generation(), generation % 4);
+        let id = ids.alloc(generation);
+        assert_eq!(id.to_raw() >> 30, generation % 4);
+        assert_eq!(ids[id], generation);
+        // The generation wraps around after 4 resets.
+        assert_eq!(ids.get(first).is_some(), generation == 4);
+    }
+}
+
+#[test]
+fn generation_bits_limit_capacity() {
+    assert!(IdCar::<u8>::with_generation_bits(1 << 24, 8).is_ok());
+    let err = IdCar::<u8>::with_generation_bits((1 << 24) + 1, 8).unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::Overflow);
+    assert!(IdCar::<u8>::with_generation_bits(2, 32).is_err());
+}
+
+#[test]
+#[should_panic = "BumpCar capacity exceeded: 8 bytes aligned to 8 requested, 0 bytes remaining"]
+fn id_car_alloc_panics_when_full() {
+    let ids = IdCar::new(1).unwrap();
+    ids.alloc(0u64);
+    ids.alloc(1u64);
+}
+
+#[test]
+fn id_car_drops_values() {
+    let counter = Rc::new(());
+    let mut ids = IdCar::new(8).unwrap();
+    for _ in 0..8 {
+        ids.alloc(counter.clone());
+    }
+    assert_eq!(Rc::strong_count(&counter), 9);
+    ids.reset();
+    assert_eq!(Rc::strong_count(&counter), 1);
+    ids.alloc(counter.clone());
+    drop(ids);
+    assert_eq!(Rc::strong_count(&counter), 1);
+}
+
+#[test]
+#[should_panic = "Id(3) is stale or out of bounds of the IdCar"]
+fn index_out_of_bounds() {
+    let ids = IdCar::new(4).unwrap();
+    ids.alloc(0u64);
+    let _ = ids[Id::from_raw(3)];
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "alloc")]
+
+use dodgems::{BumpCar, BumpInterner, Symbol};
+
+#[test]
+fn intern_deduplicates() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    let mut interner = BumpInterner::new(&bumpcar);
+
+    assert!(interner.get("foo").is_none());
+    let foo = interner.intern("foo").unwrap();
+    let bar = interner.intern("bar").unwrap();
+    assert_eq!(bumpcar.remaining_capacity(), 58);
+
+    let owned = String::from("foo");
+    let foo2 = interner.intern(&owned).unwrap();
+    assert!(std::ptr::eq(foo, foo2));
+    assert!(std::ptr::eq(interner.get("bar").unwrap(), bar));
+    assert!(bumpcar.owns(std::ptr::NonNull::from(foo).cast()));
+    assert_eq!(bumpcar.remaining_capacity(), 58);
+    assert_eq!(interner.len(), 2);
+}
+
+#[test]
+fn intern_many() {
+    let bumpcar = BumpCar::new(64 * 1024).unwrap();
+    let mut interner = BumpInterner::new(&bumpcar);
+
+    let strings: Vec<String> = (0..1000).map(|i| format!("ident_{i}")).collect();
+    let interned: Vec<&str> = strings.iter().map(|s| interner.intern(s).unwrap()).collect();
+    assert_eq!(interner.len(), 1000);
+
+    for (s, i) in strings.iter().zip(&interned) {
+        assert_eq!(s, i);
+        assert!(std::ptr::eq(interner.intern(s).unwrap(), *i));
+    }
+    assert_eq!(interner.len(), 1000);
+}
+
+#[test]
+fn intern_exhausted() {
+    let bumpcar = BumpCar::new(4).unwrap();
+    let mut interner = BumpInterner::new(&bumpcar);
+
+    assert!(interner.intern("abcd").is_ok());
+    assert!(interner.intern("abcd").is_ok());
+    assert!(interner.intern("e").is_err());
+    assert!(interner.get("e").is_none());
+}
+
+#[test]
+fn intern_symbols() {
+    let bumpcar = BumpCar::new(256).unwrap();
+    let mut interner = BumpInterner::new(&bumpcar);
+
+    let symbols: Vec<Symbol> = ["a", "b", "a", "c", "b"]
+        .iter()
+        .map(|s| interner.intern_symbol(s).unwrap())
+        .collect();
+    assert_eq!(symbols[0], symbols[2]);
+    assert_eq!(symbols[1], symbols[4]);
+    assert_eq!(
+        symbols.iter().map(|s| s.index()).collect::<Vec<_>>(),
+        [0, 1, 0, 2, 1]
+    );
+    assert_eq!(interner.resolve(symbols[3]), "c");
+    assert_eq!(interner.get_symbol("b"), Some(symbols[1]));
+    assert!(interner.get_symbol("d").is_none());
+    assert!(std::ptr::eq(
+        interner.intern("c").unwrap(),
+        interner.resolve(symbols[3])
+    ));
+}
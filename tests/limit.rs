@@ -0,0 +1,39 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::alloc::Global;
+
+use dodgems::{BumpCar, LimitCar};
+
+#[test]
+fn limit_budget() {
+    let bumpcar = BumpCar::new(1024).unwrap();
+    let limit = LimitCar::new(&bumpcar, 256);
+
+    let mut v = Vec::<u8, _>::new_in(&limit);
+    v.extend_from_slice(&[0; 100]);
+    let b = Box::new_in([0u8; 100], &limit);
+    assert_eq!(limit.consumed(), 200);
+    assert!(v.try_reserve_exact(100).is_err());
+    assert_eq!(limit.consumed(), 200);
+
+    v.truncate(10);
+    v.shrink_to_fit();
+    assert_eq!(limit.consumed(), 110);
+    drop(b);
+    assert_eq!(limit.consumed(), 10);
+    assert_eq!(limit.remaining(), 246);
+    assert_eq!(limit.peak(), 200);
+    drop(v);
+    assert_eq!(limit.consumed(), 0);
+}
+
+#[test]
+fn limit_global() {
+    let limit = LimitCar::new(Global, 64);
+    assert!(Box::try_new_in([0u8; 65], &limit).is_err());
+    let b = Box::new_in([0u8; 64], &limit);
+    assert_eq!(limit.remaining(), 0);
+    drop(b);
+    let _: Global = limit.into_inner();
+}
@@ -0,0 +1,118 @@
+//! Model checking of the [`SyncBumpCar`] with `loom`, exploring every interleaving of the
+//! concurrent allocations.
+//!
+//! Run with `RUSTFLAGS="--cfg loom" cargo test --release --test loom`.
+
+#![cfg(all(loom, feature = "alloc"))]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+use std::ops::Range;
+
+use dodgems::SyncBumpCar;
+use loom::sync::Arc;
+use loom::thread;
+
+/// Returns the byte range of an allocation, relative to the start of the arena.
+fn allocate_range(bumpcar: &SyncBumpCar, base: usize, layout: Layout) -> Option<Range<usize>> {
+    let ptr = bumpcar.allocate(layout).ok()?;
+    let start = ptr.cast::<u8>().addr().get() - base;
+    assert_eq!((base + start) % layout.align(), 0);
+    Some(start..start + ptr.len())
+}
+
+fn base_address(bumpcar: &SyncBumpCar) -> usize {
+    let probe = bumpcar.allocate(Layout::new::<()>()).unwrap();
+    probe.cast::<u8>().addr().get()
+}
+
+#[test]
+fn concurrent_allocations_are_disjoint() {
+    loom::model(|| {
+        let bumpcar = Arc::new(SyncBumpCar::new(64).unwrap());
+        let base = base_address(&bumpcar);
+
+        let handles: Vec<_> = [Layout::new::<u8>(), Layout::new::<u64>()]
+            .into_iter()
+            .map(|layout| {
+                let bumpcar = bumpcar.clone();
+                thread::spawn(move || allocate_range(&bumpcar, base, layout).unwrap())
+            })
+            .collect();
+        let mut ranges: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        ranges.push(allocate_range(&bumpcar, base, Layout::new::<u32>()).unwrap());
+
+        ranges.sort_by_key(|range| range.start);
+        for pair in ranges.windows(2) {
+            assert!(pair[0].end <= pair[1].start);
+        }
+        assert!(ranges.last().unwrap().end <= 64);
+    });
+}
+
+#[test]
+fn concurrent_allocations_respect_capacity() {
+    loom::model(|| {
+        let bumpcar = Arc::new(SyncBumpCar::new(24).unwrap());
+        let base = base_address(&bumpcar);
+
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let bumpcar = bumpcar.clone();
+                thread::spawn(move || allocate_range(&bumpcar, base, Layout::new::<[u64; 2]>()))
+            })
+            .collect();
+        let ranges: Vec<_> = handles
+            .into_iter()
+            .filter_map(|h| h.join().unwrap())
+            .collect();
+
+        assert_eq!(ranges, [0..16]);
+        assert_eq!(bumpcar.remaining_capacity(), 8);
+    });
+}
+
+#[test]
+fn reset_after_concurrent_allocations() {
+    loom::model(|| {
+        let mut bumpcar = Arc::new(SyncBumpCar::new(32).unwrap());
+
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let bumpcar = bumpcar.clone();
+                thread::spawn(move || {
+                    bumpcar.allocate(Layout::new::<u64>()).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let exclusive = Arc::get_mut(&mut bumpcar).unwrap();
+        assert_eq!(exclusive.remaining_capacity(), 16);
+        exclusive.reset();
+        assert_eq!(exclusive.remaining_capacity(), 32);
+    });
+}
+
+#[test]
+fn drop_on_the_last_thread() {
+    loom::model(|| {
+        let bumpcar = Arc::new(SyncBumpCar::new(32).unwrap());
+
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let bumpcar = bumpcar.clone();
+                thread::spawn(move || {
+                    let value = Box::new_in(7u64, &*bumpcar);
+                    assert_eq!(*value, 7);
+                })
+            })
+            .collect();
+        drop(bumpcar);
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    });
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "metrics")]
+
+use dodgems::{describe_metrics, BumpCar};
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use metrics_util::MetricKind;
+
+#[test]
+fn publish_arena_metrics() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+
+    metrics::with_local_recorder(&recorder, || {
+        describe_metrics();
+        let mut bumpcar = BumpCar::new(256).unwrap();
+        bumpcar.alloc([0u8; 100]);
+        bumpcar.reset();
+        bumpcar.alloc([0u8; 40]);
+        assert!(bumpcar.try_alloc([0u8; 512]).is_err());
+        bumpcar.publish_metrics("frame");
+    });
+
+    let metrics: Vec<_> = snapshotter.snapshot().into_vec();
+    let value = |name: &str| {
+        let (key, unit, _, value) = metrics
+            .iter()
+            .find(|(key, ..)| key.key().name() == name)
+            .unwrap();
+        let label = key.key().labels().next().unwrap();
+        assert_eq!((label.key(), label.value()), ("arena", "frame"));
+        assert!(unit.is_some());
+        (key.kind(), value)
+    };
+
+    assert_eq!(
+        value("dodgems_capacity_bytes"),
+        (MetricKind::Gauge, &DebugValue::Gauge(256.0.into()))
+    );
+    assert_eq!(
+        value("dodgems_used_bytes"),
+        (MetricKind::Gauge, &DebugValue::Gauge(40.0.into()))
+    );
+    assert_eq!(
+        value("dodgems_peak_bytes"),
+        (MetricKind::Gauge, &DebugValue::Gauge(100.0.into()))
+    );
+    #[cfg(feature = "stats")]
+    {
+        assert_eq!(
+            value("dodgems_allocations_total"),
+            (MetricKind::Counter, &DebugValue::Counter(2))
+        );
+        assert_eq!(
+            value("dodgems_failed_allocations_total"),
+            (MetricKind::Counter, &DebugValue::Counter(1))
+        );
+        assert_eq!(
+            value("dodgems_resets_total"),
+            (MetricKind::Counter, &DebugValue::Counter(1))
+        );
+    }
+}
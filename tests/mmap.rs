@@ -0,0 +1,127 @@
+#![cfg(all(feature = "mmap", unix, not(miri)))]
+
+use dodgems::{BumpCar, Mmap};
+
+#[test]
+fn mmap_backed_arena() {
+    let mut bumpcar = BumpCar::new_in(64 * 1024 * 1024, Mmap::new()).unwrap();
+    let slice = bumpcar.alloc_slice_copy(&[7u8; 10_000]);
+    assert!(slice.iter().all(|&b| b == 7));
+    let addr = slice.as_ptr();
+
+    bumpcar.reset_and_release();
+    assert_eq!(bumpcar.remaining_capacity(), 64 * 1024 * 1024);
+    let slice = bumpcar.alloc_slice_fill_with(10_000, |_| 0u8);
+    assert_eq!(slice.as_ptr(), addr);
+}
+
+#[test]
+fn mmap_frozen_prefix_kept() {
+    let mut bumpcar = BumpCar::new_in(1 << 20, Mmap::new()).unwrap();
+    let handle = bumpcar.alloc_handle([3u8; 5000]).unwrap();
+    bumpcar.freeze();
+    bumpcar.alloc_slice_copy(&[1u8; 20_000]);
+    bumpcar.reset_and_release();
+    // SAFETY: the handle lies in the frozen prefix.
+    assert_eq!(unsafe { bumpcar.get(handle) }, &[3u8; 5000]);
+}
+
+#[test]
+#[cfg(feature = "guard-pages")]
+fn mmap_guard_page_faults() {
+    let bumpcar = BumpCar::new_in(1000, Mmap::new()).unwrap();
+    let slice = bumpcar.alloc_slice_copy(&[0u8; 1000]);
+    let past_end = slice.as_mut_ptr_range().end;
+
+    // SAFETY: the child process only writes past the end of the arena and exits.
+    match unsafe { libc::fork() } {
+        0 => unsafe {
+            past_end.write_volatile(1);
+            libc::_exit(0);
+        },
+        pid => {
+            let mut status = 0;
+            // SAFETY: pid is the child process.
+            unsafe { libc::waitpid(pid, &mut status, 0) };
+            assert!(libc::WIFSIGNALED(status));
+            assert_eq!(libc::WTERMSIG(status), libc::SIGSEGV);
+        }
+    }
+}
+
+#[test]
+fn mmap_huge_pages() {
+    let mut bumpcar = BumpCar::new_in(8 * 1024 * 1024, Mmap::with_huge_pages()).unwrap();
+    let slice = bumpcar.alloc_slice_fill_with(4 * 1024 * 1024, |i| i as u8);
+    assert_eq!(slice[1000], 1000u16 as u8);
+    bumpcar.reset_and_release();
+    assert_eq!(bumpcar.remaining_capacity(), 8 * 1024 * 1024);
+}
+
+#[test]
+fn mmap_numa_node() {
+    for node in [0, 63, 200] {
+        let mmap = Mmap::new().bound_to_node(node);
+        assert_eq!(mmap.node(), Some(node));
+        let bumpcar = BumpCar::new_in(1024 * 1024, mmap).unwrap();
+        let slice = bumpcar.alloc_slice_fill_with(1024 * 1024, |_| 1u8);
+        assert!(slice.iter().all(|&b| b == 1));
+    }
+}
+
+#[test]
+fn mmap_prefaulted() {
+    for mmap in [
+        Mmap::new().prefaulted(),
+        Mmap::with_huge_pages().prefaulted(),
+        Mmap::new().bound_to_node(0).prefaulted(),
+    ] {
+        assert!(mmap.is_prefaulted());
+        let bumpcar = BumpCar::new_in(4 * 1024 * 1024, mmap).unwrap();
+        let slice = bumpcar.alloc_slice_fill_with(1024 * 1024, |_| 0u8);
+        assert!(slice.iter().all(|&b| b == 0));
+    }
+    assert!(!Mmap::new().is_prefaulted());
+}
+
+#[test]
+fn mmap_page_rounded_capacity() {
+    let page = dodgems::page_size();
+    assert!(page.is_power_of_two());
+    let bumpcar = BumpCar::new_rounded(page + 1, page).unwrap();
+    assert_eq!(bumpcar.capacity(), 2 * page);
+}
+
+#[test]
+fn mmap_protect_faults_on_write() {
+    let mut bumpcar = BumpCar::new_in(1 << 20, Mmap::new()).unwrap();
+    let table = bumpcar.alloc_slice_handle(&[5u64; 1000]).unwrap();
+    // SAFETY: the handle was allocated on this arena, which has not been reset.
+    let ptr = unsafe { bumpcar.get_slice_mut(table) }.as_mut_ptr();
+
+    let view = bumpcar.protect();
+    assert!(view.protected_len() >= 8000);
+    // SAFETY: the handle was allocated on this arena, which has not been reset.
+    assert!(unsafe { view.get_slice(table) }.iter().all(|&x| x == 5));
+
+    // SAFETY: the child process only writes to the protected table and exits.
+    match unsafe { libc::fork() } {
+        0 => unsafe {
+            ptr.write_volatile(1);
+            libc::_exit(0);
+        },
+        pid => {
+            let mut status = 0;
+            // SAFETY: pid is the child process.
+            unsafe { libc::waitpid(pid, &mut status, 0) };
+            assert!(libc::WIFSIGNALED(status));
+            assert_eq!(libc::WTERMSIG(status), libc::SIGSEGV);
+        }
+    }
+
+    drop(view);
+    // SAFETY: the handle was allocated on this arena, which has not been reset.
+    unsafe { bumpcar.get_slice_mut(table)[0] = 1 };
+    bumpcar.reset();
+    assert_eq!(bumpcar.alloc_slice_copy(&[2u8; 10]), &[2u8; 10]);
+}
@@ -0,0 +1,226 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+//! Model tests: random sequences of allocator operations are run against an arena, and checked
+//! against a reference model holding the live allocations and their contents.
+
+use std::alloc::{Allocator, Layout};
+use std::ptr::NonNull;
+
+use proptest::prelude::*;
+
+use dodgems::{BackfillBumpCar, BumpCar, FlexBumpCar, StackCar};
+
+/// Operation on the arena. Indices pick a live allocation, modulo their number.
+#[derive(Clone, Debug)]
+enum Op {
+    Allocate(Layout),
+    Grow {
+        index: usize,
+        extra: usize,
+        align: usize,
+    },
+    Shrink {
+        index: usize,
+        size: usize,
+        align: usize,
+    },
+    Deallocate {
+        index: usize,
+        ordered: bool,
+    },
+    Reset,
+}
+
+fn layout() -> impl Strategy<Value = Layout> {
+    let size = prop_oneof![Just(0), 0..8usize, 0..200usize];
+    (size, 0..7u32).prop_map(|(size, shift)| Layout::from_size_align(size, 1 << shift).unwrap())
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let align = (0..7u32).prop_map(|shift| 1usize << shift);
+    prop_oneof![
+        32 => layout().prop_map(Op::Allocate),
+        16 => (any::<usize>(), 0..100usize, align.clone())
+            .prop_map(|(index, extra, align)| Op::Grow { index, extra, align }),
+        8 => (any::<usize>(), any::<usize>(), align)
+            .prop_map(|(index, size, align)| Op::Shrink { index, size, align }),
+        // Deallocations mostly happen in LIFO order, which the arenas reclaim.
+        16 => (any::<usize>(), prop::bool::weighted(0.75))
+            .prop_map(|(index, ordered)| Op::Deallocate { index, ordered }),
+        1 => Just(Op::Reset),
+    ]
+}
+
+fn ops() -> impl Strategy<Value = Vec<Op>> {
+    prop::collection::vec(op(), 0..if cfg!(miri) { 50 } else { 500 })
+}
+
+fn config() -> ProptestConfig {
+    ProptestConfig {
+        cases: if cfg!(miri) { 4 } else { 256 },
+        // Miri isolates the tests from the file system.
+        failure_persistence: if cfg!(miri) {
+            None
+        } else {
+            ProptestConfig::default().failure_persistence
+        },
+        ..ProptestConfig::default()
+    }
+}
+
+/// Live allocation of the reference model, filled with a single byte.
+struct Live {
+    ptr: NonNull<u8>,
+    layout: Layout,
+    fill: u8,
+}
+
+impl Live {
+    fn new(ptr: NonNull<[u8]>, layout: Layout, fill: u8) -> Self {
+        let ptr = ptr.cast::<u8>();
+        assert_eq!(
+            ptr.addr().get() % layout.align(),
+            0,
+            "misaligned {layout:?}"
+        );
+        unsafe { ptr.write_bytes(fill, layout.size()) };
+        Self { ptr, layout, fill }
+    }
+
+    fn range(&self) -> std::ops::Range<usize> {
+        self.ptr.addr().get()..self.ptr.addr().get() + self.layout.size()
+    }
+
+    fn check(&self) {
+        let bytes = unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.layout.size()) };
+        assert!(
+            bytes.iter().all(|&b| b == self.fill),
+            "contents of {:?} at {:p} were overwritten",
+            self.layout,
+            self.ptr
+        );
+    }
+}
+
+/// Picks the allocation to resize, and its new alignment. In LIFO order, only the last
+/// allocation is resized, and its alignment is kept so that it is not moved.
+fn resized(live: &[Live], index: usize, align: usize, lifo: bool) -> (usize, usize) {
+    if lifo {
+        let i = live.len() - 1;
+        (i, live[i].layout.align())
+    } else {
+        (index % live.len(), align)
+    }
+}
+
+/// Runs `ops` on `allocator`, resetting it with `reset`. If `lifo` is set, allocations are only
+/// deallocated and resized in LIFO order.
+fn run<A: Allocator>(ops: &[Op], lifo: bool, allocator: &mut A, reset: impl Fn(&mut A)) {
+    let mut live: Vec<Live> = Vec::new();
+    for (step, op) in ops.iter().enumerate() {
+        let fill = step as u8;
+        match *op {
+            Op::Allocate(layout) => {
+                if let Ok(ptr) = allocator.allocate(layout) {
+                    live.push(Live::new(ptr, layout, fill));
+                }
+            }
+            Op::Grow {
+                index,
+                extra,
+                align,
+            } if !live.is_empty() => {
+                let (i, align) = resized(&live, index, align, lifo);
+                let old = &live[i];
+                let new_layout = Layout::from_size_align(old.layout.size() + extra, align).unwrap();
+                if let Ok(ptr) = unsafe { allocator.grow(old.ptr, old.layout, new_layout) } {
+                    let moved = Live {
+                        ptr: ptr.cast(),
+                        layout: Layout::from_size_align(old.layout.size(), 1).unwrap(),
+                        fill: old.fill,
+                    };
+                    moved.check();
+                    live[i] = Live::new(ptr, new_layout, fill);
+                }
+            }
+            Op::Shrink { index, size, align } if !live.is_empty() => {
+                let (i, align) = resized(&live, index, align, lifo);
+                let old = &live[i];
+                let new_layout =
+                    Layout::from_size_align(size % (old.layout.size() + 1), align).unwrap();
+                if let Ok(ptr) = unsafe { allocator.shrink(old.ptr, old.layout, new_layout) } {
+                    let moved = Live {
+                        ptr: ptr.cast(),
+                        layout: Layout::from_size_align(new_layout.size(), 1).unwrap(),
+                        fill: old.fill,
+                    };
+                    moved.check();
+                    live[i] = Live::new(ptr, new_layout, fill);
+                }
+            }
+            Op::Deallocate { index, ordered } if !live.is_empty() => {
+                let i = if lifo || ordered {
+                    live.len() - 1
+                } else {
+                    index % live.len()
+                };
+                let old = live.remove(i);
+                old.check();
+                unsafe { allocator.deallocate(old.ptr, old.layout) };
+            }
+            Op::Reset => {
+                live.clear();
+                reset(allocator);
+            }
+            _ => {}
+        }
+
+        let mut ranges: Vec<_> = live
+            .iter()
+            .map(Live::range)
+            .filter(|range| !range.is_empty())
+            .collect();
+        ranges.sort_by_key(|range| range.start);
+        assert!(
+            ranges.windows(2).all(|w| w[0].end <= w[1].start),
+            "overlapping allocations at step {step}"
+        );
+        live.iter().for_each(Live::check);
+    }
+}
+
+proptest! {
+    #![proptest_config(config())]
+
+    #[test]
+    fn model_bumpcar(ops in ops()) {
+        let mut bumpcar = BumpCar::new(4096).unwrap();
+        run(&ops, false, &mut &bumpcar, |_| {});
+        bumpcar.reset();
+        run(&ops, false, &mut bumpcar, BumpCar::reset);
+        prop_assert!(bumpcar.used() <= bumpcar.capacity());
+    }
+
+    #[test]
+    fn model_flex_bumpcar(ops in ops()) {
+        let mut bumpcar = FlexBumpCar::new(64).unwrap();
+        run(&ops, false, &mut &bumpcar, |_| {});
+        bumpcar.reset();
+        prop_assert_eq!(bumpcar.used(), 0);
+    }
+
+    #[test]
+    fn model_backfill_bumpcar(ops in ops()) {
+        let mut bumpcar = BackfillBumpCar::new(4096).unwrap();
+        run(&ops, false, &mut bumpcar, BackfillBumpCar::reset);
+    }
+
+    #[test]
+    fn model_stackcar(ops in ops()) {
+        let mut stackcar = StackCar::new(8192).unwrap();
+        run(&ops, true, &mut &stackcar, |_| {});
+        stackcar.reset();
+        prop_assert_eq!(stackcar.remaining_capacity(), stackcar.capacity());
+    }
+}
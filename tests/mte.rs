@@ -0,0 +1,50 @@
+#![cfg(all(feature = "mte", target_arch = "aarch64", target_os = "linux"))]
+#![feature(allocator_api)]
+
+use dodgems::MteBumpCar;
+
+/// Runs `f` in a child process, and returns `true` if it was killed by a SIGSEGV.
+fn faults(f: impl FnOnce()) -> bool {
+    // SAFETY: the child process only runs f and exits.
+    match unsafe { libc::fork() } {
+        0 => {
+            f();
+            unsafe { libc::_exit(0) }
+        }
+        pid => {
+            let mut status = 0;
+            // SAFETY: pid is the child process.
+            unsafe { libc::waitpid(pid, &mut status, 0) };
+            libc::WIFSIGNALED(status) && libc::WTERMSIG(status) == libc::SIGSEGV
+        }
+    }
+}
+
+#[test]
+fn mte_tags_allocations() {
+    if !MteBumpCar::is_supported() {
+        return;
+    }
+    assert!(MteBumpCar::enable_tag_checks());
+    let mut bumpcar = MteBumpCar::new(1 << 16).unwrap();
+    let mut v = Vec::with_capacity_in(3, &bumpcar);
+    v.extend(0..100u32);
+    assert_eq!(v.iter().sum::<u32>(), 4950);
+    assert_ne!(v.as_ptr().addr() >> 56, 0);
+    drop(v);
+
+    let b = Box::new_in([7u8; 40], &bumpcar);
+    let stale = std::ptr::from_ref(&*b).cast::<u8>();
+    assert!(!faults(|| assert_eq!(unsafe { stale.read_volatile() }, 7)));
+    // Past the end of the allocation, in the next granule.
+    assert!(faults(|| unsafe {
+        stale.add(48).read_volatile();
+    }));
+    std::mem::forget(b);
+
+    bumpcar.reset();
+    assert!(faults(|| unsafe {
+        stale.read_volatile();
+    }));
+    assert_eq!(bumpcar.remaining_capacity(), bumpcar.capacity());
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::alloc::Layout;
+use std::cell::{Cell, RefCell};
+
+use dodgems::{ArenaObserver, ObservedBumpCar, ThresholdObserver, Usage};
+
+#[derive(Default)]
+struct Log {
+    allocs: RefCell<Vec<(usize, Usage)>>,
+    ooms: RefCell<Vec<usize>>,
+}
+
+impl ArenaObserver for Log {
+    fn on_alloc(&self, layout: Layout, usage: Usage) {
+        self.allocs.borrow_mut().push((layout.size(), usage));
+    }
+
+    fn on_oom(&self, layout: Layout, _: Usage) {
+        self.ooms.borrow_mut().push(layout.size());
+    }
+}
+
+#[test]
+fn observe_allocations() {
+    let log = Log::default();
+    let bumpcar = ObservedBumpCar::new(64, &log).unwrap();
+
+    let mut v = Vec::<u8, _>::with_capacity_in(8, &bumpcar);
+    v.extend_from_slice(&[0; 16]);
+    let b = Box::new_in(0u64, &bumpcar);
+    v.extend_from_slice(&[0; 24]);
+    assert!(Box::try_new_in([0u8; 64], &bumpcar).is_err());
+
+    assert_eq!(
+        *log.allocs.borrow(),
+        [
+            (
+                8,
+                Usage {
+                    used: 8,
+                    capacity: 64
+                }
+            ),
+            (
+                8,
+                Usage {
+                    used: 24,
+                    capacity: 64
+                }
+            ),
+            (
+                40,
+                Usage {
+                    used: 64,
+                    capacity: 64
+                }
+            )
+        ]
+    );
+    assert_eq!(*log.ooms.borrow(), [64]);
+    drop((v, b));
+}
+
+#[test]
+fn unit_observer() {
+    let bumpcar = ObservedBumpCar::new(64, ()).unwrap();
+    let _b = Box::new_in(0u64, &bumpcar);
+    assert_eq!(bumpcar.usage().used, 8);
+}
+
+#[test]
+fn threshold_fires_once_per_reset() {
+    let calls = Cell::new(0);
+    let observer = ThresholdObserver::new(75, |usage: Usage| {
+        assert_eq!(usage.used, 48);
+        calls.set(calls.get() + 1);
+    });
+    let mut bumpcar = ObservedBumpCar::new(64, observer).unwrap();
+    for _ in 0..2 {
+        let a = Box::new_in([0u8; 40], &bumpcar);
+        assert!(!bumpcar.observer().fired());
+        let b = Box::new_in(0u64, &bumpcar);
+        let c = Box::new_in(0u64, &bumpcar);
+        assert!(bumpcar.observer().fired());
+        drop((a, b, c));
+        bumpcar.reset();
+        assert!(!bumpcar.observer().fired());
+    }
+    assert_eq!(calls.get(), 2);
+}
+
+#[test]
+#[should_panic = "threshold of 101% is above 100%"]
+fn threshold_above_capacity() {
+    ThresholdObserver::new(101, |_| {});
+}
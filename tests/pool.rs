@@ -0,0 +1,42 @@
+#![cfg(feature = "alloc")]
+
+use dodgems::CarPool;
+
+#[test]
+fn pool_reuses_arenas() {
+    let pool = CarPool::new(2, 64).unwrap();
+    assert_eq!(pool.idle(), 2);
+
+    let a = pool.checkout().unwrap();
+    a.alloc([1u64; 4]);
+    let ptr = a.alloc(0u8) as *const u8;
+    assert_eq!(pool.idle(), 1);
+    drop(a);
+    assert_eq!(pool.idle(), 2);
+
+    let b = pool.checkout().unwrap();
+    assert_eq!(b.remaining_capacity(), 64);
+    b.alloc([1u64; 4]);
+    assert_eq!(b.alloc(0u8) as *const u8, ptr);
+}
+
+#[test]
+fn pool_grows_and_trims() {
+    let pool = CarPool::new(1, 64).unwrap();
+    let cars: Vec<_> = (0..3).map(|_| pool.checkout().unwrap()).collect();
+    assert_eq!(pool.idle(), 0);
+    assert!(cars.iter().all(|car| car.capacity() == 64));
+    drop(cars);
+    assert_eq!(pool.idle(), 1);
+}
+
+#[test]
+fn pool_suggests_capacity() {
+    let pool = CarPool::new(2, 4096).unwrap();
+    assert_eq!(pool.suggested_capacity(), None);
+    for size in [100, 400, 200] {
+        let bumpcar = pool.checkout().unwrap();
+        bumpcar.alloc_slice_fill_with(size, |_| 0u8);
+    }
+    assert_eq!(pool.suggested_capacity(), Some(440));
+}
@@ -0,0 +1,78 @@
+#![cfg(all(feature = "alloc", feature = "profiling"))]
+
+use dodgems::{BumpCar, MAX_CALLSITES};
+
+#[test]
+fn profile_records_callsites() {
+    let bumpcar = BumpCar::new(4096).unwrap();
+    let line = line!() + 2;
+    for _ in 0..3 {
+        bumpcar.alloc([0u32; 4]);
+    }
+    bumpcar.try_alloc_slice_copy(&[1u8, 2, 3]).unwrap();
+    assert!(bumpcar.try_alloc([0u8; 8192]).is_err());
+
+    let profile = bumpcar.profile();
+    let sites: Vec<_> = profile.callsites().collect();
+    assert_eq!(sites.len(), 2);
+    assert_eq!(sites[0].location.file(), file!());
+    assert_eq!(sites[0].location.line(), line);
+    assert_eq!((sites[0].allocations, sites[0].bytes), (3, 48));
+    assert_eq!(sites[1].location.line(), line + 2);
+    assert_eq!((sites[1].allocations, sites[1].bytes), (1, 3));
+
+    let report = profile.to_string();
+    assert!(report
+        .lines()
+        .next()
+        .unwrap()
+        .ends_with(&format!("{}:{line}:17", file!())));
+
+    bumpcar.reset_profile();
+    assert_eq!(bumpcar.profile().callsites().count(), 0);
+}
+
+#[test]
+fn profile_counts_untracked_callsites() {
+    let bumpcar = BumpCar::new(4096).unwrap();
+    bumpcar.alloc(0u8);
+    bumpcar.alloc(1u8);
+    bumpcar.alloc(2u8);
+    bumpcar.alloc(3u8);
+    bumpcar.alloc(4u8);
+    bumpcar.alloc(5u8);
+    bumpcar.alloc(6u8);
+    bumpcar.alloc(7u8);
+    bumpcar.alloc(8u8);
+    bumpcar.alloc(9u8);
+    bumpcar.alloc(10u8);
+    bumpcar.alloc(11u8);
+    bumpcar.alloc(12u8);
+    bumpcar.alloc(13u8);
+    bumpcar.alloc(14u8);
+    bumpcar.alloc(15u8);
+    bumpcar.alloc(16u8);
+    bumpcar.alloc(17u8);
+    bumpcar.alloc(18u8);
+    bumpcar.alloc(19u8);
+    bumpcar.alloc(20u8);
+    bumpcar.alloc(21u8);
+    bumpcar.alloc(22u8);
+    bumpcar.alloc(23u8);
+    bumpcar.alloc(24u8);
+    bumpcar.alloc(25u8);
+    bumpcar.alloc(26u8);
+    bumpcar.alloc(27u8);
+    bumpcar.alloc(28u8);
+    bumpcar.alloc(29u8);
+    bumpcar.alloc(30u8);
+    bumpcar.alloc(31u8);
+    bumpcar.alloc(32u8);
+    bumpcar.alloc(33u8);
+
+    let profile = bumpcar.profile();
+    assert_eq!(profile.callsites().count(), MAX_CALLSITES);
+    assert_eq!(profile.untracked_allocations(), 34 - MAX_CALLSITES);
+    assert_eq!(profile.untracked_bytes(), 34 - MAX_CALLSITES);
+    assert!(profile.to_string().contains("untracked"));
+}
@@ -0,0 +1,33 @@
+#![cfg(all(feature = "mmap", unix, not(miri)))]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+
+use dodgems::ReservedBumpCar;
+
+#[test]
+fn reserved_grows_in_place() {
+    let bumpcar = ReservedBumpCar::new(1 << 30).unwrap();
+    let mut v = Vec::new_in(&bumpcar);
+    v.push(0u64);
+    let ptr = v.as_ptr();
+    v.extend(1..1_000_000);
+    assert_eq!(v.as_ptr(), ptr);
+    assert_eq!(v[999_999], 999_999);
+    assert!(bumpcar.committed() >= 8_000_000);
+    assert!(bumpcar.committed() < 1 << 30);
+}
+
+#[test]
+fn reserved_exhausted() {
+    let mut bumpcar = ReservedBumpCar::new(1 << 20).unwrap();
+    assert!(bumpcar.allocate(Layout::new::<[u8; 1 << 20]>()).is_ok());
+    assert!(bumpcar.allocate(Layout::new::<u8>()).is_err());
+    assert_eq!(bumpcar.committed(), 1 << 20);
+
+    bumpcar.reset_and_decommit();
+    assert_eq!((bumpcar.used(), bumpcar.committed()), (0, 0));
+    let ptr = bumpcar.allocate(Layout::new::<u64>()).unwrap();
+    // SAFETY: the allocation is valid for a u64.
+    unsafe { ptr.cast::<u64>().write(42) };
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Global, Layout};
+
+use dodgems::{BumpCar, Fallback, RtCar};
+
+#[test]
+fn rt_no_violation_in_arena() {
+    let bumpcar = RtCar::new(BumpCar::new(256).unwrap());
+    {
+        let _guard = bumpcar.guard();
+        let v = Vec::<u64, _>::with_capacity_in(16, &bumpcar);
+        drop(v);
+    }
+    assert_eq!(bumpcar.violations(), 0);
+    assert!(!bumpcar.is_guarded());
+}
+
+#[test]
+fn rt_unguarded_requests_are_allowed() {
+    let heap = RtCar::backing(Global);
+    let bumpcar = RtCar::new(BumpCar::new(8).unwrap());
+    drop(Box::new_in(1u64, &heap));
+    assert!(bumpcar.allocate(Layout::new::<[u8; 64]>()).is_err());
+    assert_eq!(heap.violations(), 0);
+    assert_eq!(bumpcar.violations(), 0);
+}
+
+#[test]
+#[cfg_attr(debug_assertions, should_panic = "backing allocation of 64 bytes")]
+fn rt_spill_is_a_violation() {
+    let bumpcar = BumpCar::new(32).unwrap();
+    let heap = RtCar::backing(Global);
+    let alloc = Fallback(&bumpcar, &heap);
+
+    let guard = heap.guard();
+    let nested = heap.guard();
+    drop(nested);
+    assert!(heap.is_guarded());
+    let v = Vec::<u8, _>::with_capacity_in(64, &alloc);
+    drop(guard);
+    drop(v);
+    assert_eq!(heap.violations(), 1);
+}
+
+#[test]
+#[cfg_attr(debug_assertions, should_panic = "arena miss of 64 bytes aligned to 1")]
+fn rt_arena_miss_is_a_violation() {
+    let bumpcar = RtCar::new(BumpCar::new(32).unwrap());
+    let alloc = Fallback(&bumpcar, Global);
+
+    let _guard = bumpcar.guard();
+    let ptr = alloc.allocate(Layout::new::<[u8; 64]>()).unwrap();
+    unsafe { alloc.deallocate(ptr.cast(), Layout::new::<[u8; 64]>()) };
+    assert_eq!(bumpcar.violations(), 1);
+    bumpcar.clear_violations();
+    assert_eq!(bumpcar.violations(), 0);
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "alloc")]
+
+use std::sync::mpsc;
+use std::thread;
+
+use dodgems::BumpCar;
+
+#[test]
+fn sealed_handoff() {
+    let (sender, receiver) = mpsc::channel();
+    let producer = thread::spawn(move || {
+        for round in 0..3u64 {
+            let bumpcar = BumpCar::new(1024).unwrap();
+            let value = bumpcar.alloc_handle(round * 10).unwrap();
+            let slice = bumpcar.alloc_slice_handle(&[round; 4]).unwrap();
+            sender.send((bumpcar.seal(), value, slice)).unwrap();
+        }
+    });
+
+    let mut reused = Vec::new();
+    for (round, (mut sealed, value, slice)) in receiver.iter().enumerate() {
+        let round = round as u64;
+        // SAFETY: the handles were allocated in the sealed arena.
+        unsafe {
+            assert_eq!(*sealed.get(value), round * 10);
+            assert_eq!(sealed.get_slice(slice), [round; 4]);
+            *sealed.get_mut(value) += 1;
+            assert_eq!(*sealed.get(value), round * 10 + 1);
+        }
+        assert_eq!(sealed.remaining_capacity(), 1024 - 40);
+        reused.push(sealed.into_reset());
+    }
+    producer.join().unwrap();
+
+    assert_eq!(reused.len(), 3);
+    for bumpcar in &reused {
+        assert_eq!(bumpcar.remaining_capacity(), bumpcar.capacity());
+    }
+}
+
+#[test]
+fn unseal_keeps_contents() {
+    let mut bumpcar = BumpCar::new(64).unwrap();
+    bumpcar.alloc_handle(1u8).unwrap();
+    bumpcar.freeze();
+    let handle = bumpcar.alloc_handle(7u32).unwrap();
+
+    // SAFETY: the arena was sealed on this thread.
+    let bumpcar = unsafe { bumpcar.seal().unseal() };
+    // SAFETY: the handle was allocated in this arena, which was not reset.
+    assert_eq!(unsafe { *bumpcar.get(handle) }, 7);
+    assert_eq!(bumpcar.frozen_len(), 1);
+    assert_eq!(bumpcar.seal().into_reset().frozen_len(), 0);
+}
@@ -0,0 +1,37 @@
+#![cfg(all(feature = "serde", feature = "alloc"))]
+
+use dodgems::{BumpCar, BumpString, BumpVec, DeserializeInSeed};
+use serde::de::DeserializeSeed;
+
+#[test]
+fn deserialize_nested_containers() {
+    let bumpcar = BumpCar::new(1024).unwrap();
+    let mut deserializer =
+        serde_json::Deserializer::from_str(r#"[["alpha", "beta"], [], ["gamma"]]"#);
+    let groups: BumpVec<BumpVec<BumpString>> = bumpcar.deserialize_in(&mut deserializer).unwrap();
+    assert_eq!(groups.len(), 3);
+    assert_eq!(groups[0], ["alpha", "beta"]);
+    assert!(groups[1].is_empty());
+    assert_eq!(groups[2], ["gamma"]);
+    assert!(bumpcar.remaining_capacity() < 1024);
+}
+
+#[test]
+fn deserialize_plain_values_in_arena_vec() {
+    let bumpcar = BumpCar::new(256).unwrap();
+    let mut deserializer = serde_json::Deserializer::from_str("[1, 2, 3, 4]");
+    let numbers = DeserializeInSeed::<BumpVec<u32>>::new(&bumpcar)
+        .deserialize(&mut deserializer)
+        .unwrap();
+    assert_eq!(numbers, [1, 2, 3, 4]);
+}
+
+#[test]
+fn deserialize_reports_capacity_errors() {
+    let bumpcar = BumpCar::new(8).unwrap();
+    let mut deserializer = serde_json::Deserializer::from_str(r#""a string that is too long""#);
+    let err = bumpcar
+        .deserialize_in::<BumpString, _>(&mut deserializer)
+        .unwrap_err();
+    assert!(err.to_string().contains("BumpCar capacity exceeded"));
+}
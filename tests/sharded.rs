@@ -0,0 +1,65 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+
+use dodgems::{Shard, ShardedBumpCar, SyncBumpCar};
+
+#[test]
+fn sharded_threads() {
+    let bumpcar = ShardedBumpCar::new(8 * 1024, 128).unwrap();
+
+    let mut addresses: Vec<usize> = std::thread::scope(|s| {
+        let handles: Vec<_> = (0..8u64)
+            .map(|t| {
+                let bumpcar = &bumpcar;
+                s.spawn(move || {
+                    let shard = bumpcar.shard();
+                    (0..100)
+                        .map(|i| Box::leak(Box::new_in(t * 100 + i, &shard)))
+                        .map(|p| std::ptr::from_mut(p).addr())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect()
+    });
+    addresses.sort_unstable();
+    addresses.dedup();
+    assert_eq!(addresses.len(), 800);
+}
+
+#[test]
+fn sharded_chunks() {
+    let mut bumpcar = ShardedBumpCar::new(1024, 64).unwrap();
+    assert_eq!(bumpcar.chunk_size(), 64);
+    {
+        let shard = bumpcar.shard();
+        let _a = Box::new_in(1u64, &shard);
+        assert_eq!(bumpcar.remaining_capacity(), 1024 - 64);
+        assert_eq!(shard.remaining_capacity(), 56);
+
+        let big = (&shard)
+            .allocate(Layout::from_size_align(200, 64).unwrap())
+            .unwrap();
+        assert_eq!(big.cast::<u8>().addr().get() % 64, 0);
+        assert_eq!(shard.remaining_capacity(), 56);
+
+        assert!((&shard).allocate(Layout::new::<[u8; 1024]>()).is_err());
+    }
+    bumpcar.reset();
+    assert_eq!(bumpcar.remaining_capacity(), 1024);
+}
+
+#[test]
+fn cursors_are_cache_padded() {
+    assert!(align_of::<Shard>() >= 64);
+    assert!(align_of::<SyncBumpCar>() >= 64);
+
+    let arenas: Vec<SyncBumpCar> = (0..2).map(|_| SyncBumpCar::new(64).unwrap()).collect();
+    let distance = std::ptr::from_ref(&arenas[1]).addr() - std::ptr::from_ref(&arenas[0]).addr();
+    assert!(distance >= 64);
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+use std::collections::LinkedList;
+
+use dodgems::SlotCar;
+
+#[test]
+fn slot_layout_holds_a_pointer() {
+    let slotcar = SlotCar::new(Layout::new::<u8>(), 4).unwrap();
+    assert_eq!(slotcar.slot_layout(), Layout::new::<usize>());
+    assert_eq!(slotcar.capacity(), 4);
+    assert_eq!(slotcar.available(), 4);
+
+    let slotcar = SlotCar::new(Layout::from_size_align(24, 32).unwrap(), 4).unwrap();
+    assert_eq!(
+        slotcar.slot_layout(),
+        Layout::from_size_align(32, 32).unwrap()
+    );
+    let ptr = slotcar.allocate(Layout::new::<[u8; 20]>()).unwrap();
+    assert_eq!(ptr.cast::<u8>().addr().get() % 32, 0);
+}
+
+#[test]
+fn slots_are_reused() {
+    let mut slotcar = SlotCar::new(Layout::new::<u64>(), 3).unwrap();
+    let a = Box::new_in(1u64, &slotcar);
+    let b = Box::new_in(2u64, &slotcar);
+    let address = std::ptr::from_ref(&*a).addr();
+    drop(a);
+    assert_eq!(slotcar.available(), 2);
+
+    let c = Box::new_in(3u64, &slotcar);
+    assert_eq!(std::ptr::from_ref(&*c).addr(), address);
+    let d = Box::new_in(4u64, &slotcar);
+    assert!(Box::try_new_in(5u64, &slotcar).is_err());
+    assert_eq!([*b, *c, *d], [2, 3, 4]);
+
+    drop((b, c, d));
+    assert_eq!(slotcar.available(), 3);
+    slotcar.reset();
+    assert_eq!(slotcar.available(), 3);
+}
+
+#[test]
+fn slot_rejects_larger_layouts() {
+    let slotcar = SlotCar::new(Layout::new::<u64>(), 4).unwrap();
+    assert!(slotcar.allocate(Layout::new::<[u64; 2]>()).is_err());
+    assert!(slotcar
+        .allocate(Layout::from_size_align(8, 16).unwrap())
+        .is_err());
+    assert_eq!(slotcar.available(), 4);
+
+    let mut v = Vec::<u32, _>::try_with_capacity_in(2, &slotcar).unwrap();
+    v.extend([1, 2]);
+    assert!(v.try_reserve(1).is_err());
+    v.truncate(1);
+    v.shrink_to_fit();
+    assert_eq!(v, [1]);
+}
+
+#[test]
+fn slot_linked_list() {
+    let slotcar = SlotCar::new(Layout::new::<[usize; 3]>(), 100).unwrap();
+    let mut list = LinkedList::new_in(&slotcar);
+    for round in 0..10 {
+        list.extend(0..100usize);
+        assert_eq!(slotcar.available(), 0);
+        assert_eq!(list.iter().sum::<usize>(), 4950, "round {round}");
+        list.clear();
+        assert_eq!(slotcar.available(), 100);
+    }
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use dodgems::SpillBumpCar;
+
+#[test]
+fn spill_when_full() {
+    let mut bumpcar = SpillBumpCar::new(64).unwrap();
+    let mut v = Vec::new_in(&bumpcar);
+    for i in 0..100u32 {
+        v.push(i);
+    }
+    assert!(bumpcar.spilled_blocks() > 0);
+    assert!(!bumpcar
+        .bumpcar()
+        .owns(std::ptr::NonNull::from(&v[0]).cast()));
+    assert_eq!(v.iter().sum::<u32>(), 4950);
+
+    v.truncate(4);
+    v.shrink_to_fit();
+    assert_eq!(v, [0, 1, 2, 3]);
+    drop(v);
+
+    bumpcar.reset();
+    assert_eq!(bumpcar.spilled_blocks(), 0);
+    assert_eq!(bumpcar.bumpcar().remaining_capacity(), 64);
+}
+
+#[test]
+fn spill_high_alignment() {
+    #[repr(align(256))]
+    struct Aligned([u8; 16]);
+
+    let bumpcar = SpillBumpCar::new(64).unwrap();
+    let b = Box::new_in(Aligned([7; 16]), &bumpcar);
+    assert_eq!(std::ptr::from_ref(&*b).addr() % 256, 0);
+    assert_eq!(b.0, [7; 16]);
+    drop(b);
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use dodgems::BumpCar;
+use rayon::prelude::*;
+
+#[test]
+fn split_capacity() {
+    let mut bumpcar = BumpCar::new(1024).unwrap();
+    bumpcar.alloc(1u8);
+    {
+        let splits: Vec<_> = bumpcar.split_n(3).collect();
+        assert_eq!(splits.len(), 3);
+        for split in &splits {
+            assert_eq!(split.capacity(), 336);
+            assert_eq!(*split.alloc(7u32), 7);
+            assert_eq!(split.remaining_capacity(), 332);
+        }
+        let ends: Vec<usize> = splits
+            .iter()
+            .map(|split| split.alloc_slice_copy(&[0u8; 4]).as_ptr().addr())
+            .collect();
+        assert!(ends.windows(2).all(|w| w[1] - w[0] == 336));
+    }
+    assert_eq!(bumpcar.remaining_capacity(), 1023);
+}
+
+#[test]
+#[cfg_attr(miri, ignore = "rayon is not supported under strict provenance")]
+fn split_rayon_workers() {
+    let mut bumpcar = BumpCar::new(64 * 1024).unwrap();
+    for _ in 0..2 {
+        let words: Vec<usize> = bumpcar
+            .split_n(8)
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .enumerate()
+            .map(|(i, mut split)| {
+                let mut total = 0;
+                for round in 0..4 {
+                    let s = split.alloc_str(&format!("worker {i} round {round}"));
+                    let mut v = Vec::new_in(&split);
+                    v.extend(s.split(' '));
+                    total += v.len();
+                    drop(v);
+                    split.reset();
+                }
+                total
+            })
+            .collect();
+        assert_eq!(words, [16; 8]);
+        assert_eq!(bumpcar.remaining_capacity(), 64 * 1024);
+    }
+}
+
+#[test]
+#[should_panic = "zero parts"]
+fn split_zero() {
+    let mut bumpcar = BumpCar::new(64).unwrap();
+    let _ = bumpcar.split_n(0);
+}
+
+#[test]
+fn split_at_halves() {
+    let mut bumpcar = BumpCar::new(1024).unwrap();
+    bumpcar.alloc(1u8);
+    {
+        let (mut front, back) = bumpcar.split_at(100);
+        assert_eq!(front.capacity(), 100);
+        assert_eq!(back.capacity(), 923);
+
+        let a = back.alloc_slice_copy(&[7u8; 900]);
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                assert!(front.try_alloc([0u8; 101]).is_err());
+                front.alloc([1u8; 100]);
+                front.reset();
+                assert_eq!(front.remaining_capacity(), 100);
+            });
+        });
+        assert!(a.iter().all(|&x| x == 7));
+    }
+    assert_eq!(bumpcar.remaining_capacity(), 1023);
+}
+
+#[test]
+#[should_panic = "out of bounds"]
+fn split_at_out_of_bounds() {
+    let mut bumpcar = BumpCar::new(64).unwrap();
+    let _ = bumpcar.split_at(65);
+}
+
+#[test]
+fn split_n_dirties_zeroed_memory() {
+    let mut bumpcar = BumpCar::new_zeroed(1024).unwrap();
+    {
+        let parts: Vec<_> = bumpcar.split_n(2).collect();
+        parts[0].alloc([0xBBu8; 64]);
+    }
+    let zeroed = Box::<[u8; 64], _>::new_zeroed_in(&bumpcar);
+    // SAFETY: the box was allocated with allocate_zeroed.
+    assert_eq!(*unsafe { zeroed.assume_init() }, [0; 64]);
+}
+
+#[test]
+fn split_at_dirties_zeroed_memory() {
+    let mut bumpcar = BumpCar::new_zeroed(256).unwrap();
+    {
+        let (front, _back) = bumpcar.split_at(128);
+        front.alloc([0xAAu8; 128]);
+    }
+    let zeroed = Box::<[u8; 128], _>::new_zeroed_in(&bumpcar);
+    // SAFETY: the box was allocated with allocate_zeroed.
+    assert_eq!(*unsafe { zeroed.assume_init() }, [0; 128]);
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+
+use dodgems::StackCar;
+
+#[test]
+fn stack_rewinds_padding() {
+    let stackcar = StackCar::new(256).unwrap();
+    let a = Box::new_in(1u8, &stackcar);
+    let used = stackcar.capacity() - stackcar.remaining_capacity();
+    let b = stackcar
+        .allocate(Layout::from_size_align(8, 64).unwrap())
+        .unwrap();
+    assert_eq!(b.cast::<u8>().addr().get() % 64, 0);
+    unsafe { stackcar.deallocate(b.cast(), Layout::from_size_align(8, 64).unwrap()) };
+    assert_eq!(stackcar.capacity() - stackcar.remaining_capacity(), used);
+    drop(a);
+    assert_eq!(stackcar.remaining_capacity(), 256);
+}
+
+#[test]
+fn stack_grows_last_in_place() {
+    let stackcar = StackCar::new(1024).unwrap();
+    let mut v = Vec::new_in(&stackcar);
+    v.extend(0..100u32);
+    let ptr = v.as_ptr();
+    v.extend(100..200u32);
+    assert_eq!(v.as_ptr(), ptr);
+    v.truncate(10);
+    v.shrink_to_fit();
+    assert_eq!(stackcar.remaining_capacity(), 1024 - 48);
+    drop(v);
+    assert_eq!(stackcar.remaining_capacity(), 1024);
+}
+
+#[test]
+fn stack_grow_moves_earlier_allocation() {
+    let stackcar = StackCar::new(1024).unwrap();
+    let mut v = Vec::new_in(&stackcar);
+    v.extend(0..10u32);
+    let remaining = stackcar.remaining_capacity();
+    let b = Box::new_in(1u8, &stackcar);
+    v.extend(10..100u32);
+    assert_eq!(v, (0..100).collect::<Vec<_>>());
+    // The old region of the vector stays below the box, until the allocations before it are
+    // deallocated.
+    drop(v);
+    drop(b);
+    assert_eq!(stackcar.remaining_capacity(), remaining);
+}
+
+#[test]
+fn stack_shrink_to_greater_alignment() {
+    let stackcar = StackCar::new(256).unwrap();
+    let old = Layout::from_size_align(64, 8).unwrap();
+    let mut ptr = stackcar.allocate(old).unwrap().cast::<u8>();
+    if ptr.addr().get().is_multiple_of(64) {
+        ptr = stackcar.allocate(old).unwrap().cast();
+    }
+    assert_ne!(ptr.addr().get() % 64, 0);
+    unsafe { ptr.write_bytes(7, 64) };
+
+    let new = Layout::from_size_align(16, 64).unwrap();
+    let shrunk = unsafe { stackcar.shrink(ptr, old, new) }.unwrap();
+    assert_eq!(shrunk.cast::<u8>().addr().get() % 64, 0);
+    assert!(unsafe { shrunk.as_ref() }.iter().all(|&x| x == 7));
+}
+
+#[test]
+fn stack_reset() {
+    let mut stackcar = StackCar::new(64).unwrap();
+    stackcar.allocate(Layout::new::<[u8; 40]>()).unwrap();
+    assert!(stackcar.allocate(Layout::new::<[u8; 16]>()).is_err());
+    stackcar.reset();
+    assert_eq!(stackcar.remaining_capacity(), 64);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic = "LIFO order"]
+fn stack_out_of_order() {
+    let stackcar = StackCar::new(64).unwrap();
+    let a = Box::new_in(1u64, &stackcar);
+    let b = Box::new_in(2u64, &stackcar);
+    drop(a);
+    drop(b);
+}
@@ -0,0 +1,99 @@
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Layout};
+
+use dodgems::{static_bumpcar, BufferBumpCar, StaticBumpCar};
+
+static ARENA: StaticBumpCar<256> = StaticBumpCar::new();
+
+#[test]
+fn static_allocate() {
+    assert_eq!(ARENA.capacity(), 256);
+
+    let mut v = Vec::with_capacity_in(16, &ARENA);
+    v.extend(0u32..16);
+    assert_eq!(ARENA.remaining_capacity(), 256 - 64);
+    assert!(v.as_ptr().is_aligned());
+
+    let ptr = (&ARENA)
+        .allocate(Layout::from_size_align(8, 64).unwrap())
+        .unwrap();
+    assert_eq!(ptr.cast::<u8>().addr().get() % 64, 0);
+
+    assert!((&ARENA).allocate(Layout::new::<[u8; 256]>()).is_err());
+
+    drop(v);
+    // SAFETY: no allocation from ARENA is used after this point.
+    unsafe { ARENA.reset() };
+    assert_eq!(ARENA.remaining_capacity(), 256);
+    assert!((&ARENA).allocate(Layout::new::<[u8; 256]>()).is_ok());
+}
+
+#[test]
+fn static_threads() {
+    static SHARED: StaticBumpCar<{ 8 * 800 + 8 }> = StaticBumpCar::new();
+
+    let handles: Vec<_> = (0..8)
+        .map(|t| {
+            std::thread::spawn(move || {
+                (0..100)
+                    .map(|i| Box::leak(Box::new_in(t * 100 + i as u64, &SHARED)))
+                    .map(|p| std::ptr::from_mut(p).addr())
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+
+    let mut addresses: Vec<usize> = handles
+        .into_iter()
+        .flat_map(|h| h.join().unwrap())
+        .collect();
+    assert!(SHARED.remaining_capacity() <= 8);
+    addresses.sort_unstable();
+    addresses.dedup();
+    assert_eq!(addresses.len(), 800);
+}
+
+#[test]
+fn local_reset() {
+    let mut arena = StaticBumpCar::<16>::default();
+    let _ = (&arena).allocate(Layout::new::<u64>()).unwrap();
+    arena.reset_mut();
+    assert_eq!(arena.remaining_capacity(), 16);
+}
+
+#[test]
+fn static_init_once() {
+    static TAKEN: StaticBumpCar<64> = StaticBumpCar::new();
+
+    let mut bumpcar = TAKEN.init().unwrap();
+    assert!(TAKEN.init().is_none());
+    assert_eq!(TAKEN.remaining_capacity(), 0);
+    assert!((&TAKEN).allocate(Layout::new::<u8>()).is_err());
+
+    let v = Box::new_in([0u64; 8], &bumpcar);
+    assert_eq!(bumpcar.remaining_capacity(), 0);
+    drop(v);
+    bumpcar.reset();
+    assert_eq!(bumpcar.capacity(), 64);
+}
+
+fn take_arena() -> Option<BufferBumpCar<'static>> {
+    static_bumpcar!(128, section = ".data.dodgems_test")
+}
+
+#[test]
+fn static_macro_takes_once() {
+    let mut bumpcar = take_arena().unwrap();
+    assert!(take_arena().is_none());
+    assert_eq!(bumpcar.capacity(), 128);
+
+    let v = Box::new_in([7u64; 4], &bumpcar);
+    assert_eq!(*v, [7; 4]);
+    drop(v);
+    bumpcar.reset();
+    assert_eq!(bumpcar.remaining_capacity(), 128);
+
+    let other = static_bumpcar!(64).unwrap();
+    assert_eq!(other.capacity(), 64);
+}
@@ -0,0 +1,47 @@
+#![cfg(all(feature = "alloc", feature = "stats"))]
+#![feature(allocator_api)]
+
+use dodgems::{BumpCar, Stats};
+
+#[test]
+fn stats_counters() {
+    let mut bumpcar = BumpCar::new(256).unwrap();
+    assert_eq!(bumpcar.stats(), Stats::default());
+
+    let mut v = Vec::<u8, _>::with_capacity_in(4, &bumpcar);
+    v.extend_from_slice(&[0; 16]);
+    v.shrink_to_fit();
+    bumpcar.alloc(0u64);
+    assert!(bumpcar.try_alloc([0u8; 512]).is_err());
+    drop(v);
+
+    let stats = bumpcar.stats();
+    assert_eq!(stats.allocations, 2);
+    assert_eq!(stats.failed_allocations, 1);
+    assert_eq!(stats.bytes_allocated, 12);
+    assert_eq!(stats.alignment_waste, 0);
+    assert_eq!(stats.grows, 1);
+    assert_eq!(stats.shrinks, 0);
+
+    bumpcar.reset();
+    assert_eq!(bumpcar.stats(), Stats { resets: 1, ..stats });
+    bumpcar.reset_stats();
+    assert_eq!(bumpcar.stats(), Stats::default());
+}
+
+#[test]
+fn stats_allocations_since_marker() {
+    let bumpcar = BumpCar::new(256).unwrap();
+    bumpcar.alloc(0u8);
+    let marker = bumpcar.marker();
+    let mut v = Vec::<u8, _>::new_in(&bumpcar);
+    v.extend(0..100);
+    bumpcar.alloc(0u64);
+    assert!(bumpcar.allocations_since(marker) >= 2);
+    assert_eq!(
+        bumpcar.allocations_since(marker),
+        bumpcar.stats().allocations - 1
+    );
+    bumpcar.reset_stats();
+    assert_eq!(bumpcar.allocations_since(marker), 0);
+}
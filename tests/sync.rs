@@ -0,0 +1,111 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use std::alloc::{Allocator, Global, Layout};
+
+use dodgems::{SyncBumpCar, Unpadded};
+
+#[test]
+fn sync_threads() {
+    let bumpcar = SyncBumpCar::new(8 * 800).unwrap();
+
+    let mut addresses: Vec<usize> = std::thread::scope(|s| {
+        let handles: Vec<_> = (0..8u64)
+            .map(|t| {
+                let bumpcar = &bumpcar;
+                s.spawn(move || {
+                    (0..100)
+                        .map(|i| Box::leak(Box::new_in(t * 100 + i, bumpcar)))
+                        .map(|p| std::ptr::from_mut(p).addr())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect()
+    });
+    assert_eq!(bumpcar.remaining_capacity(), 0);
+    addresses.sort_unstable();
+    addresses.dedup();
+    assert_eq!(addresses.len(), 800);
+}
+
+#[test]
+fn sync_reset() {
+    let mut bumpcar = SyncBumpCar::new(64).unwrap();
+    let ptr = bumpcar
+        .allocate(Layout::from_size_align(8, 32).unwrap())
+        .unwrap();
+    assert_eq!(ptr.cast::<u8>().addr().get() % 32, 0);
+    assert!(bumpcar.allocate(Layout::new::<[u8; 64]>()).is_err());
+
+    bumpcar.reset();
+    assert_eq!(bumpcar.remaining_capacity(), 64);
+    assert!(bumpcar.allocate(Layout::new::<[u8; 64]>()).is_ok());
+    assert!(SyncBumpCar::new(usize::MAX).is_err());
+}
+
+#[test]
+fn sync_shared_ownership() {
+    use std::sync::Arc;
+
+    let bumpcar = Arc::new(SyncBumpCar::new(1024).unwrap());
+    let handles: Vec<_> = (0..4u64)
+        .map(|t| {
+            let bumpcar = bumpcar.clone();
+            std::thread::spawn(move || {
+                let mut v = Vec::new_in(bumpcar);
+                v.extend(0..8 * t);
+                v
+            })
+        })
+        .collect();
+    let vecs: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    drop(bumpcar);
+    assert_eq!(vecs[3].len(), 24);
+    assert_eq!(vecs[3][23], 23);
+}
+
+#[test]
+#[cfg(feature = "zeroize")]
+fn sync_zeroize_on_reset() {
+    let mut bumpcar = SyncBumpCar::new(64).unwrap();
+    let secret = bumpcar.allocate(Layout::new::<[u8; 32]>()).unwrap();
+    unsafe { secret.cast::<u8>().write_bytes(0xA5, 32) };
+    bumpcar.reset();
+
+    let region = bumpcar.allocate(Layout::new::<[u8; 32]>()).unwrap();
+    assert!(unsafe { region.as_ref() }.iter().all(|&x| x == 0));
+}
+
+#[test]
+fn sync_debug() {
+    let bumpcar = SyncBumpCar::new(64).unwrap();
+    bumpcar.allocate(Layout::new::<u64>()).unwrap();
+    assert_eq!(
+        format!("{bumpcar:?}"),
+        "SyncBumpCar { capacity: 64, used: 8, remaining: 56, allocator: Global }"
+    );
+}
+
+#[test]
+fn sync_unpadded_cursor() {
+    use std::mem::size_of;
+
+    assert!(size_of::<SyncBumpCar<Global, Unpadded>>() <= 3 * size_of::<usize>());
+    assert!(size_of::<SyncBumpCar>() >= 64);
+
+    let mut bumpcar = SyncBumpCar::new_unpadded(64).unwrap();
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                bumpcar.allocate(Layout::new::<u64>()).unwrap();
+            });
+        }
+    });
+    assert_eq!(bumpcar.remaining_capacity(), 32);
+    bumpcar.reset();
+    assert_eq!(bumpcar.remaining_capacity(), 64);
+}
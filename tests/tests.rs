@@ -3,9 +3,11 @@
 use std::{
     alloc::{Allocator, Layout},
     mem::size_of,
+    ptr::NonNull,
+    sync::atomic::{AtomicPtr, Ordering},
 };
 
-use dodgems::BumpCar;
+use dodgems::{BumpCar, BumpErrorKind, CapacityBuilder};
 
 #[test]
 fn allocate_vec() {
@@ -20,20 +22,21 @@ fn allocate_vec() {
     }
     assert_eq!(b.remaining_capacity(), 3072 * size_of::<i32>());
 
-    // Grow the vector (reallocation in this bump allocator)
+    // Grow the vector (in place, since it is the last allocation)
     for x in 1024..2048 {
         v.push(x);
     }
 
-    assert_eq!(b.remaining_capacity(), 1024 * size_of::<i32>());
+    assert_eq!(b.remaining_capacity(), 2048 * size_of::<i32>());
 
-    // Shrink the vector (does not add capacity)
+    // Shrink the vector (reclaims the tail, since it is the last allocation)
     v.truncate(1024);
     v.shrink_to_fit();
+    assert_eq!(b.remaining_capacity(), 3072 * size_of::<i32>());
 
-    // Deallocate (noop)
+    // Deallocate (reclaims the last allocation)
     drop(v);
-    assert_eq!(b.remaining_capacity(), 1024 * size_of::<i32>());
+    assert_eq!(b.remaining_capacity(), 4096 * size_of::<i32>());
 
     b.reset();
     assert_eq!(b.remaining_capacity(), 4096 * size_of::<i32>());
@@ -47,7 +50,7 @@ fn allocate_failure() {
 
     let big_box = Box::new_in([0u8; 256], &b);
     let mut extra: Vec<u8, _> = Vec::new_in(&b);
-    assert!(matches!(extra.try_reserve(128), Err(_)));
+    assert!(extra.try_reserve(128).is_err());
 
     drop(big_box);
     drop(extra);
@@ -62,9 +65,7 @@ fn allocate_failure() {
 fn allocate_zero_size() {
     let b = BumpCar::new(256).unwrap();
 
-    let _zero_size_alloc = (&b)
-        .allocate(Layout::from_size_align(0, 1).unwrap())
-        .unwrap();
+    let _zero_size_alloc = b.allocate(Layout::from_size_align(0, 1).unwrap()).unwrap();
 
     assert_eq!(b.remaining_capacity(), 256);
 
@@ -72,9 +73,7 @@ fn allocate_zero_size() {
 
     assert_eq!(b.remaining_capacity(), 0);
 
-    let _zero_size_alloc = (&b)
-        .allocate(Layout::from_size_align(0, 1).unwrap())
-        .unwrap();
+    let _zero_size_alloc = b.allocate(Layout::from_size_align(0, 1).unwrap()).unwrap();
 
     assert_eq!(b.remaining_capacity(), 0);
 }
@@ -104,7 +103,6 @@ fn allocate_checkpoint() {
     let b = BumpCar::new(256).unwrap();
 
     let alloc_half = Vec::<u8, _>::with_capacity_in(128, &b);
-    drop(alloc_half);
 
     assert_eq!(b.remaining_capacity(), 128);
 
@@ -115,17 +113,972 @@ fn allocate_checkpoint() {
     assert_eq!(checkpoint.remaining_capacity(), 128);
 
     let alloc_rest = Vec::<u8, _>::with_capacity_in(128, &checkpoint);
-    drop(alloc_rest);
 
     assert_eq!(checkpoint.remaining_capacity(), 0);
 
     let checkpoint2 = checkpoint.checkpoint();
     assert_eq!(checkpoint2.capacity(), 0);
     drop(checkpoint2);
+    drop(alloc_rest);
 
     checkpoint.reset();
     assert_eq!(checkpoint.remaining_capacity(), 128);
 
     drop(checkpoint);
+    drop(alloc_half);
+    drop(b);
+}
+
+#[test]
+fn clone_empty() {
+    let b = BumpCar::new(256).unwrap();
+    let _half = Vec::<u8, _>::with_capacity_in(128, &b);
+
+    let c = b.try_clone_empty().unwrap();
+    assert_eq!(c.capacity(), b.capacity());
+    assert_eq!(c.remaining_capacity(), 256);
+
+    let _full = Vec::<u8, _>::with_capacity_in(256, &c);
+    assert_eq!(c.remaining_capacity(), 0);
+    assert_eq!(b.remaining_capacity(), 128);
+}
+
+#[test]
+fn clone_empty_keeps_buffer_align() {
+    let b = BumpCar::new_with_align(128, 64).unwrap();
+    let c = b.try_clone_empty().unwrap();
+    assert_eq!(c.buffer_align(), 64);
+    assert_eq!(c.capacity(), 128);
+}
+
+#[test]
+fn ownership_queries() {
+    let b = BumpCar::new(64).unwrap();
+    let byte = Layout::new::<u8>();
+
+    let first = b.allocate(byte).unwrap().cast::<u8>();
+    assert!(b.owns(first));
+    assert!(b.owns_allocation(first, byte));
+
+    let _rest = b.allocate(Layout::array::<u8>(63).unwrap()).unwrap();
+    let last = unsafe { first.add(63) };
+    let one_past_end = unsafe { first.add(64) };
+    assert!(b.owns(last));
+    assert!(!b.owns(one_past_end));
+    assert!(b.owns_allocation(last, byte));
+    assert!(!b.owns_allocation(last, Layout::new::<u16>()));
+    assert!(b.owns_allocation(one_past_end, Layout::new::<()>()));
+
+    let global = std::alloc::Global.allocate(byte).unwrap().cast::<u8>();
+    assert!(!b.owns(global));
+    assert!(!b.owns_allocation(global, byte));
+    assert!(!unsafe { b.try_deallocate(global, byte) });
+    unsafe { std::alloc::Global.deallocate(global, byte) };
+}
+
+#[test]
+fn try_deallocate_tip() {
+    let b = BumpCar::new(64).unwrap();
+    let layout = Layout::new::<u32>();
+
+    let first = b.allocate(layout).unwrap().cast::<u8>();
+    let second = b.allocate(layout).unwrap().cast::<u8>();
+    assert_eq!(b.remaining_capacity(), 56);
+
+    // not the last allocation: owned but not reclaimed
+    assert!(unsafe { b.try_deallocate(first, layout) });
+    assert_eq!(b.remaining_capacity(), 56);
+
+    assert!(unsafe { b.try_deallocate(second, layout) });
+    assert_eq!(b.remaining_capacity(), 60);
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn zeroize_on_reset() {
+    let mut b = BumpCar::new(64).unwrap();
+
+    let secret = Box::new_in([0xA5u8; 32], &b);
+    drop(secret);
+    b.reset();
+
+    let region = b.allocate(Layout::new::<[u8; 32]>()).unwrap();
+    assert!(unsafe { region.as_ref() }.iter().all(|&x| x == 0));
     drop(b);
+
+    let mut b = BumpCar::new(64).unwrap();
+    let _secret = Box::new_in([0xA5u8; 64], &b);
+    drop(_secret);
+    zeroize::Zeroize::zeroize(&mut b);
+    assert_eq!(b.remaining_capacity(), 64);
+    let region = b.allocate(Layout::new::<[u8; 64]>()).unwrap();
+    assert!(unsafe { region.as_ref() }.iter().all(|&x| x == 0));
+}
+
+#[test]
+#[cfg(all(feature = "debug-poison", debug_assertions, not(feature = "zeroize")))]
+fn poison_on_reset() {
+    let mut b = BumpCar::new(64).unwrap();
+    b.alloc([0xA5u8; 32]);
+    b.reset();
+
+    let region = b.allocate(Layout::new::<[u8; 48]>()).unwrap();
+    let bytes = unsafe { region.as_ref() };
+    assert!(bytes[..32].iter().all(|&x| x == 0xDD));
+}
+
+#[test]
+fn shrink_increase_alignment() {
+    let b = BumpCar::new(64).unwrap();
+    let old = Layout::from_size_align(8, 1).unwrap();
+    let new = Layout::from_size_align(4, 4).unwrap();
+
+    // already aligned: no reallocation, and the tail is reclaimed
+    let ptr = b.allocate(old).unwrap().cast::<u8>();
+    let shrunk = unsafe { b.shrink(ptr, old, new) }.unwrap();
+    assert_eq!(shrunk.cast::<u8>(), ptr);
+    assert_eq!(shrunk.len(), 4);
+    assert_eq!(b.remaining_capacity(), 60);
+
+    // misaligned: reallocate and copy
+    let ptr = b.allocate(Layout::from_size_align(9, 1).unwrap()).unwrap();
+    let ptr = unsafe { ptr.cast::<u8>().add(1) };
+    unsafe { ptr.as_ptr().copy_from_nonoverlapping([1u8, 2, 3, 4].as_ptr(), 4) };
+    let shrunk = unsafe { b.shrink(ptr, old, new) }.unwrap();
+    assert_ne!(shrunk.cast::<u8>(), ptr);
+    assert_eq!(shrunk.cast::<u8>().addr().get() % 4, 0);
+    assert_eq!(unsafe { shrunk.as_ref() }, [1, 2, 3, 4]);
+    assert_eq!(b.remaining_capacity(), 44);
+
+    // exhausted arena
+    let _fill = b.allocate(Layout::from_size_align(44, 1).unwrap()).unwrap();
+    assert!(unsafe { b.shrink(ptr, old, new) }.is_err());
+}
+
+#[test]
+fn freeze_prefix() {
+    let mut b = BumpCar::new(256).unwrap();
+    let table = b.alloc_handle([7u64; 8]).unwrap();
+    b.freeze();
+    assert_eq!(b.frozen_len(), 64);
+
+    let scratch = Vec::<u8, _>::with_capacity_in(128, &b);
+    assert_eq!(b.remaining_capacity(), 64);
+    drop(scratch);
+    b.reset();
+    assert_eq!(b.capacity(), 256);
+    assert_eq!(b.remaining_capacity(), 192);
+    assert_eq!(unsafe { b.get(table) }, &[7; 8]);
+
+    b.unfreeze();
+    assert_eq!(b.remaining_capacity(), 192);
+    b.reset();
+    assert_eq!(b.remaining_capacity(), 256);
+
+    b.alloc_handle(0u64).unwrap();
+    b.freeze();
+    b.reset_all();
+    assert_eq!(b.frozen_len(), 0);
+    assert_eq!(b.remaining_capacity(), 256);
+}
+
+#[test]
+fn typed_capacity_queries() {
+    #[repr(align(16))]
+    struct Aligned {
+        _data: [u8; 48],
+    }
+
+    fn check<T>(b: &BumpCar, offset: usize) {
+        let b_ref = &b;
+        let _offset = b_ref
+            .allocate(Layout::from_size_align(offset, 1).unwrap())
+            .unwrap();
+        let n = b.remaining_for::<T>();
+        assert!(b.can_allocate_value::<T>() == (n > 0));
+        if n == usize::MAX {
+            return;
+        }
+        assert!(b.can_allocate_array::<T>(n));
+        assert!(!b.can_allocate_array::<T>(n + 1));
+        assert!(b.can_allocate_slice::<T>(n));
+        assert!(!b.can_allocate_slice::<T>(n + 1));
+        assert!(b_ref.allocate(Layout::array::<T>(n + 1).unwrap()).is_err());
+        assert!(b_ref.allocate(Layout::array::<T>(n).unwrap()).is_ok());
+        assert!(!b.can_allocate_value::<T>());
+        assert_eq!(b.remaining_for::<T>(), 0);
+    }
+
+    for offset in [0, 1, 3, 7, 15, 100] {
+        check::<u8>(&BumpCar::new(256).unwrap(), offset);
+        check::<u16>(&BumpCar::new(256).unwrap(), offset);
+        check::<u64>(&BumpCar::new(256).unwrap(), offset);
+        check::<[u8; 3]>(&BumpCar::new(256).unwrap(), offset);
+        check::<Aligned>(&BumpCar::new(256).unwrap(), offset);
+        check::<()>(&BumpCar::new(256).unwrap(), offset);
+    }
+
+    let b = BumpCar::new(256).unwrap();
+    assert_eq!(b.remaining_for::<()>(), usize::MAX);
+    assert!(b.can_allocate_array::<()>(usize::MAX));
+    assert!(!b.can_allocate_array::<u64>(usize::MAX));
+    assert!(b.can_allocate_slice::<()>(usize::MAX));
+    assert!(!b.can_allocate_slice::<u64>(usize::MAX / 4));
+}
+
+#[test]
+fn carve_children() {
+    let parent = BumpCar::new(256).unwrap();
+    let _offset = Box::new_in(1u8, &parent);
+
+    let mut first = parent.carve(64).unwrap();
+    let second = parent.carve(64).unwrap();
+    assert_eq!(first.capacity(), 64);
+    assert_eq!(parent.remaining_capacity(), 256 - 8 - 128);
+
+    for child in [&first, &second] {
+        let ptr = (&child).allocate(Layout::new::<u8>()).unwrap().cast::<u8>();
+        assert_eq!(ptr.addr().get() % size_of::<usize>(), 0);
+        assert!(parent.owns(ptr));
+    }
+
+    let fill = Vec::<u8, _>::with_capacity_in(63, &first);
+    assert!(first.allocate(Layout::new::<u8>()).is_err());
+    assert_eq!(second.remaining_capacity(), 63);
+    assert_eq!(parent.remaining_capacity(), 256 - 8 - 128);
+
+    let from_second = Vec::<u8, _>::with_capacity_in(63, &second);
+    let from_parent = Vec::<u8, _>::with_capacity_in(120, &parent);
+    assert!(!first.owns(NonNull::new(from_second.as_ptr().cast_mut()).unwrap()));
+    drop((fill, from_second, from_parent));
+
+    first.reset();
+    assert_eq!(first.remaining_capacity(), 64);
+    // dropping the last allocation of second reclaimed its space
+    assert_eq!(second.remaining_capacity(), 63);
+}
+
+#[test]
+fn carve_drop_returns_memory() {
+    let mut parent = BumpCar::new(256).unwrap();
+    {
+        let child = parent.carve(128).unwrap();
+        let grandchild = child.carve(64).unwrap();
+        let v = Vec::<u64, _>::with_capacity_in(8, &grandchild);
+        assert_eq!(child.remaining_capacity(), 64);
+        assert_eq!(parent.remaining_capacity(), 128);
+        // The allocations are dropped before the arenas they borrow.
+        drop(v);
+        drop(grandchild);
+        assert_eq!(child.remaining_capacity(), 128);
+    }
+    assert_eq!(parent.remaining_capacity(), 256);
+
+    let child = parent.carve(64).unwrap();
+    parent.alloc(0u8);
+    drop(child);
+    // The child was not the last allocation anymore: its memory returns on reset.
+    assert_eq!(parent.remaining_capacity(), 256 - 65);
+    parent.reset();
+    assert_eq!(parent.remaining_capacity(), 256);
+}
+
+#[test]
+fn owned_allocator() {
+    fn build(n: u32) -> Box<[u32], BumpCar> {
+        let mut v = Vec::with_capacity_in(2, BumpCar::new(256).unwrap());
+        v.extend(0..n);
+        v.into_boxed_slice()
+    }
+
+    let b = build(10);
+    assert_eq!(&b[..], &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    assert!(Box::allocator(&b).owns(NonNull::from(&b[0]).cast()));
+
+    assert!(Vec::<u8, _>::try_with_capacity_in(257, BumpCar::new(256).unwrap()).is_err());
+}
+
+#[test]
+fn shared_allocator() {
+    use std::rc::Rc;
+
+    let bumpcar = Rc::new(BumpCar::new(256).unwrap());
+    let a = Box::new_in(1u64, bumpcar.clone());
+    let mut b = Vec::new_in(bumpcar.clone());
+    b.extend([2u64, 3]);
+    assert_eq!(Rc::strong_count(&bumpcar), 3);
+    assert_eq!(*a + b.iter().sum::<u64>(), 6);
+
+    drop(bumpcar);
+    assert_eq!(Box::allocator(&a).remaining_capacity(), 256 - 8 - 32);
+}
+
+#[test]
+fn grow_in_place() {
+    let b = BumpCar::new(512).unwrap();
+    let mut v = Vec::<u8, _>::with_capacity_in(16, &b);
+    let ptr = v.as_ptr();
+    v.extend(0..128);
+    assert_eq!(v.as_ptr(), ptr);
+    assert_eq!(b.remaining_capacity(), 512 - v.capacity());
+
+    // Another allocation prevents growing in place
+    let other = Box::new_in(0u8, &b);
+    let remaining = b.remaining_capacity();
+    v.reserve_exact(v.capacity() - v.len() + 1);
+    assert_ne!(v.as_ptr(), ptr);
+    assert_eq!(b.remaining_capacity(), remaining - v.capacity());
+    assert!(v.iter().copied().eq(0..128));
+    drop(other);
+}
+
+#[test]
+fn grow_zeroed_in_place() {
+    let b = BumpCar::new(64).unwrap();
+    let old = Layout::new::<[u8; 8]>();
+    let new = Layout::new::<[u8; 32]>();
+    let ptr = b.allocate(old).unwrap().cast::<u8>();
+    unsafe {
+        ptr.write_bytes(0xff, 8);
+        let grown = b.grow_zeroed(ptr, old, new).unwrap();
+        assert_eq!(grown.cast::<u8>(), ptr);
+        assert_eq!(&grown.as_ref()[..8], &[0xff; 8]);
+        assert_eq!(&grown.as_ref()[8..], &[0; 24]);
+    }
+    assert_eq!(b.remaining_capacity(), 32);
+}
+
+#[test]
+fn deallocate_last() {
+    let b = BumpCar::new(64).unwrap();
+    let first = Box::new_in(1u64, &b);
+    for _ in 0..100 {
+        let scratch = Box::new_in([0u8; 32], &b);
+        drop(scratch);
+    }
+    assert_eq!(b.remaining_capacity(), 56);
+
+    // Only the last allocation is reclaimed
+    let second = Box::new_in(2u64, &b);
+    drop(first);
+    assert_eq!(b.remaining_capacity(), 48);
+    drop(second);
+    assert_eq!(b.remaining_capacity(), 56);
+}
+
+#[test]
+fn allocate_high_alignment() {
+    #[repr(align(64))]
+    struct CacheLine([u8; 64]);
+
+    let b = BumpCar::new(4 * 4096).unwrap();
+    for align in [16, 32, 64, 4096] {
+        let _byte = Box::new_in(0u8, &b);
+        let ptr = b
+            .allocate(Layout::from_size_align(align / 2, align).unwrap())
+            .unwrap();
+        assert_eq!(ptr.cast::<u8>().addr().get() % align, 0);
+        assert_eq!(ptr.len(), align / 2);
+    }
+
+    let line = Box::new_in(CacheLine([1; 64]), &b);
+    assert!(std::ptr::from_ref(&*line).is_aligned());
+    assert_eq!(line.0[63], 1);
+
+    // growing to a greater alignment moves a misaligned allocation
+    let ptr = b.allocate(Layout::new::<[u8; 1]>()).unwrap().cast::<u8>();
+    let grown = unsafe {
+        b.grow(
+            ptr,
+            Layout::new::<[u8; 1]>(),
+            Layout::from_size_align(32, 32).unwrap(),
+        )
+    }
+    .unwrap();
+    assert_eq!(grown.cast::<u8>().addr().get() % 32, 0);
+}
+
+#[test]
+fn allocate_zeroed_after_reset() {
+    let mut b = BumpCar::new(64).unwrap();
+    let dirty = b.allocate(Layout::new::<[u8; 64]>()).unwrap();
+    unsafe { dirty.cast::<u8>().write_bytes(0xff, 64) };
+    b.reset();
+
+    let _byte = Box::new_in(1u8, &b);
+    let zeroed = b.allocate_zeroed(Layout::new::<[u64; 4]>()).unwrap();
+    assert_eq!(zeroed.cast::<u8>().addr().get() % 8, 0);
+    assert!(unsafe { zeroed.as_ref() }.iter().all(|&x| x == 0));
+    // the alignment padding is left untouched
+    #[cfg(not(feature = "zeroize"))]
+    assert_eq!(unsafe { zeroed.cast::<u8>().sub(1).read() }, 0xff);
+}
+
+#[test]
+fn alloc_layout_raw_pointers() {
+    let b = BumpCar::new(32).unwrap();
+    let byte = b.alloc_layout(Layout::new::<u8>()).unwrap();
+    let word = b.alloc_layout(Layout::new::<u64>()).unwrap();
+    assert_eq!(word.addr().get() % 8, 0);
+    assert_eq!(word.addr().get() - byte.addr().get(), 8);
+    assert!(b.owns(byte) && b.owns(word));
+
+    unsafe { b.deallocate(word, Layout::new::<u64>()) };
+    assert_eq!(b.remaining_capacity(), 24);
+    assert!(b.alloc_layout(Layout::new::<[u8; 32]>()).is_err());
+}
+
+#[test]
+fn allocate_at_least_padding() {
+    let b = BumpCar::new(11).unwrap();
+    let layout = Layout::from_size_align(3, 4).unwrap();
+
+    let first = b.allocate_at_least(layout).unwrap();
+    assert_eq!(first.len(), 4);
+    let second = b.allocate_at_least(layout).unwrap();
+    assert_eq!(second.len(), 4);
+    assert_eq!(second.cast::<u8>(), unsafe { first.cast::<u8>().add(4) });
+
+    // not enough room for the padding: exact size
+    let third = b.allocate_at_least(layout).unwrap();
+    assert_eq!(third.len(), 3);
+    assert_eq!(b.remaining_capacity(), 0);
+
+    unsafe { b.deallocate(third.cast(), layout) };
+    assert_eq!(b.remaining_capacity(), 3);
+}
+
+#[test]
+fn marker_rewind() {
+    let mut b = BumpCar::new(256).unwrap();
+    b.alloc(0u64);
+    let outer = b.marker();
+    assert_eq!(outer.offset(), 8);
+
+    b.alloc([0u8; 32]);
+    let inner = b.marker();
+    assert!(outer < inner);
+    b.alloc([0u8; 64]);
+    assert_eq!(b.remaining_capacity(), 152);
+
+    unsafe { b.rewind_to(inner) };
+    assert_eq!(b.remaining_capacity(), 216);
+    b.rewind(outer);
+    assert_eq!(b.remaining_capacity(), 248);
+}
+
+#[test]
+#[should_panic(expected = "invalid BumpCar marker")]
+fn marker_ahead() {
+    let mut b = BumpCar::new(256).unwrap();
+    b.alloc(0u64);
+    let marker = b.marker();
+    b.reset();
+    b.rewind(marker);
+}
+
+#[test]
+fn bytes_allocated_since_marker() {
+    let mut b = BumpCar::new(256).unwrap();
+    b.alloc(0u8);
+    let marker = b.marker();
+    assert_eq!(b.bytes_allocated_since(marker), 0);
+    b.alloc(0u32);
+    b.alloc([0u8; 10]);
+    assert_eq!(b.bytes_allocated_since(marker), 17);
+    b.reset();
+    assert_eq!(b.bytes_allocated_since(marker), 0);
+}
+
+#[test]
+fn scope_rewinds() {
+    let mut b = BumpCar::new(256).unwrap();
+    b.alloc(0u64);
+    let len = b.scope(|s| {
+        let mut v = Vec::new_in(&**s);
+        v.extend_from_slice(&[1u8; 100]);
+        assert_eq!(s.remaining_capacity(), 148);
+        v.len()
+    });
+    assert_eq!(len, 100);
+    assert_eq!(b.remaining_capacity(), 248);
+}
+
+#[test]
+fn scope_rewinds_on_panic() {
+    let mut b = BumpCar::new(256).unwrap();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        b.scope(|s| {
+            s.alloc([0u8; 64]);
+            panic!("oops");
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(b.remaining_capacity(), 256);
+}
+
+#[test]
+fn branded_scope_nested() {
+    let mut b = BumpCar::new(256).unwrap();
+    b.alloc(0u64);
+    let name = b.branded_scope(|outer| {
+        let name = outer.alloc_str("outer");
+        let len = outer.scope(|inner| {
+            let copy = inner.alloc_str(name);
+            copy.make_ascii_uppercase();
+            assert_eq!(inner.remaining_capacity(), 238);
+            copy.len()
+        });
+        assert_eq!(outer.remaining_capacity(), 243);
+        assert!(outer.try_alloc([0u8; 512]).is_err());
+        format!("{name}{len}")
+    });
+    assert_eq!(name, "outer5");
+    assert_eq!(b.remaining_capacity(), 248);
+}
+
+#[test]
+fn nested_checkpoints_return_capacity() {
+    let b = BumpCar::new(256).unwrap();
+    {
+        let first = b.checkpoint();
+        first.alloc([0u8; 8]);
+        {
+            let second = first.checkpoint();
+            second.alloc([0u8; 8]);
+            let third = second.checkpoint();
+            third.alloc([0u8; 8]);
+            assert_eq!(third.remaining_capacity(), 232);
+        }
+        assert_eq!(first.remaining_capacity(), 248);
+    }
+    assert_eq!(b.remaining_capacity(), 256);
+
+    let odd = BumpCar::new(11).unwrap();
+    odd.alloc(0u8);
+    let checkpoint = odd.checkpoint();
+    assert_eq!(checkpoint.capacity(), 0);
+    drop(checkpoint);
+    assert_eq!(odd.remaining_capacity(), odd.capacity() - 8);
+}
+
+#[test]
+fn raw_parts_round_trip() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    let value = bumpcar.alloc_handle(7u32).unwrap();
+    let (buffer, used, allocator) = bumpcar.into_raw_parts();
+    assert_eq!((buffer.len(), used), (64, 4));
+
+    let erased: (*mut u8, usize, usize) = (buffer.cast::<u8>().as_ptr(), buffer.len(), used);
+    let buffer = std::ptr::NonNull::slice_from_raw_parts(
+        std::ptr::NonNull::new(erased.0).unwrap(),
+        erased.1,
+    );
+    // SAFETY: the parts come from into_raw_parts.
+    let mut bumpcar = unsafe { BumpCar::from_raw_parts(buffer, erased.2, allocator) };
+    // SAFETY: the handle was allocated in the same buffer, which was not reset.
+    assert_eq!(unsafe { *bumpcar.get(value) }, 7);
+    assert_eq!(bumpcar.remaining_capacity(), 60);
+    bumpcar.reset();
+    assert_eq!(bumpcar.remaining_capacity(), 64);
+}
+
+#[test]
+fn dyn_allocator() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    let allocator: &dyn Allocator = bumpcar.as_dyn();
+    let v = Box::new_in(42u64, allocator);
+    assert_eq!(*v, 42);
+    assert!(bumpcar.owns(NonNull::from(&*v).cast()));
+    assert_eq!(bumpcar.remaining_capacity(), 56);
+    drop(v);
+    assert_eq!(bumpcar.remaining_capacity(), 64);
+}
+
+#[test]
+fn recover_allocator() {
+    let outer = BumpCar::new(256).unwrap();
+    let inner = BumpCar::new_in(64, &outer).unwrap();
+    assert!(std::ptr::eq(*inner.allocator(), &outer));
+    assert_eq!(outer.remaining_capacity(), 192);
+
+    let allocator = inner.into_allocator();
+    assert!(std::ptr::eq(allocator, &outer));
+    assert_eq!(outer.remaining_capacity(), 256);
+}
+
+#[test]
+fn leaked_bumpcar() {
+    // Keeps the leaked arena reachable, so that Miri does not report it.
+    static LEAKED: AtomicPtr<BumpCar> = AtomicPtr::new(std::ptr::null_mut());
+
+    fn intern(bumpcar: &'static BumpCar, s: &str) -> &'static str {
+        bumpcar.alloc_str(s)
+    }
+
+    let capacity = 256 + size_of::<BumpCar>();
+    let bumpcar = BumpCar::new(capacity).unwrap().leak();
+    LEAKED.store(std::ptr::from_ref(bumpcar).cast_mut(), Ordering::Relaxed);
+
+    let names: Vec<&'static str> = ["a", "bc", "def"]
+        .into_iter()
+        .map(|s| intern(bumpcar, s))
+        .collect();
+    assert_eq!(names, ["a", "bc", "def"]);
+    assert_eq!(
+        bumpcar.remaining_capacity(),
+        capacity - size_of::<BumpCar>().next_multiple_of(size_of::<usize>()) - 6
+    );
+    assert!(bumpcar.owns(NonNull::from(names[2]).cast()));
+}
+
+#[test]
+#[should_panic = "not enough capacity to leak"]
+fn leak_too_small() {
+    BumpCar::new(8).unwrap().leak();
+}
+
+#[test]
+fn reserve_grows_buffer() {
+    let mut bumpcar = BumpCar::new(64).unwrap();
+    let frozen = bumpcar.alloc_handle([1u32, 2, 3]).unwrap();
+    bumpcar.freeze();
+    bumpcar.alloc(0u64);
+
+    bumpcar.try_reserve(8).unwrap();
+    assert_eq!(bumpcar.capacity(), 64);
+
+    bumpcar.try_reserve(100).unwrap();
+    assert_eq!(bumpcar.capacity(), 128);
+    assert_eq!(bumpcar.remaining_capacity(), 128 - 24);
+    bumpcar.try_reserve(1000).unwrap();
+    assert_eq!(bumpcar.capacity(), 1024);
+    // SAFETY: the handle lies in the frozen prefix.
+    assert_eq!(unsafe { *bumpcar.get(frozen) }, [1, 2, 3]);
+
+    bumpcar.reset();
+    assert_eq!(bumpcar.remaining_capacity(), 1024 - 12);
+    assert!(bumpcar.try_reserve(usize::MAX).is_err());
+    assert!(bumpcar.try_reserve(isize::MAX as usize).is_err());
+    assert_eq!(bumpcar.capacity(), 1024);
+}
+
+#[test]
+fn reset_and_shrink() {
+    let mut bumpcar = BumpCar::new(4096).unwrap();
+    let frozen = bumpcar.alloc_handle(7u64).unwrap();
+    bumpcar.freeze();
+    bumpcar.alloc([0u8; 2048]);
+
+    bumpcar.reset_and_shrink_to(8192);
+    assert_eq!(bumpcar.capacity(), 4096);
+    assert_eq!(bumpcar.remaining_capacity(), 4088);
+
+    bumpcar.alloc([0u8; 2048]);
+    bumpcar.reset_and_shrink_to(256);
+    assert_eq!(bumpcar.capacity(), 256);
+    assert_eq!(bumpcar.remaining_capacity(), 248);
+
+    bumpcar.reset_and_shrink_to(0);
+    assert_eq!(bumpcar.capacity(), 8);
+    assert_eq!(bumpcar.remaining_capacity(), 0);
+    // SAFETY: the handle lies in the frozen prefix.
+    assert_eq!(unsafe { *bumpcar.get(frozen) }, 7);
+}
+
+#[test]
+fn debug_usage_summary() {
+    let mut bumpcar = BumpCar::new(64).unwrap();
+    bumpcar.alloc(1u32);
+    bumpcar.freeze();
+    bumpcar.alloc(2u32);
+    assert_eq!(
+        format!("{bumpcar:?}"),
+        "BumpCar { capacity: 64, used: 8, remaining: 56, frozen: 4, allocator: Global }"
+    );
+
+    let child = bumpcar.carve(16).unwrap();
+    let debug = format!("{child:?}");
+    assert!(debug.starts_with("BumpCar { capacity: 16, used: 0, remaining: 16, frozen: 0, allocator: BumpCar { capacity: 64, used: 24,"));
+}
+
+#[test]
+fn usage_getters() {
+    let mut bumpcar = BumpCar::new(64).unwrap();
+    assert!(bumpcar.is_empty());
+    assert_eq!((bumpcar.used(), bumpcar.utilization()), (0, 0.0));
+
+    bumpcar.alloc(1u8);
+    bumpcar.alloc(2u32);
+    assert!(!bumpcar.is_empty());
+    assert_eq!(bumpcar.used(), 8);
+    assert_eq!(bumpcar.utilization(), 0.125);
+
+    bumpcar.alloc([0u8; 56]);
+    assert_eq!(bumpcar.utilization(), 1.0);
+    bumpcar.reset();
+    assert!(bumpcar.is_empty());
+
+    let empty = BumpCar::new(0).unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(empty.utilization(), 0.0);
+}
+
+#[cfg(feature = "unchecked")]
+#[test]
+fn allocate_unchecked_matches_allocate() {
+    let checked = BumpCar::new_with_align(256, 64).unwrap();
+    let unchecked = BumpCar::new_with_align(256, 64).unwrap();
+    for layout in [
+        Layout::new::<u8>(),
+        Layout::new::<u64>(),
+        Layout::from_size_align(3, 32).unwrap(),
+        Layout::new::<[u16; 7]>(),
+    ] {
+        let a = checked.allocate(layout).unwrap().cast::<u8>();
+        assert!(unchecked.can_allocate(layout));
+        let b = unsafe { unchecked.allocate_unchecked(layout) };
+        assert_eq!(a.addr().get() % layout.align(), 0);
+        assert_eq!(b.addr().get() % layout.align(), 0);
+        unsafe { b.write_bytes(0xAB, layout.size()) };
+    }
+    assert_eq!(checked.remaining_capacity(), unchecked.remaining_capacity());
+}
+
+#[test]
+fn try_clone_copies_contents() {
+    let mut bumpcar = BumpCar::new_with_align(128, 32).unwrap();
+    let config = bumpcar.alloc_handle(*b"config").unwrap();
+    bumpcar.freeze();
+    let state = bumpcar.alloc_handle([1u32, 2, 3]).unwrap();
+
+    let mut clone = bumpcar.try_clone().unwrap();
+    assert_eq!(clone.capacity(), bumpcar.capacity());
+    assert_eq!(clone.buffer_align(), 32);
+    assert_eq!(clone.used(), bumpcar.used());
+    assert_eq!(clone.frozen_len(), bumpcar.frozen_len());
+    unsafe {
+        clone.get_mut(state)[0] = 10;
+        assert_eq!(clone.get(state), &[10, 2, 3]);
+        assert_eq!(bumpcar.get(state), &[1, 2, 3]);
+    }
+
+    clone.reset();
+    assert_eq!(unsafe { clone.get(config) }, b"config");
+    assert_eq!(clone.used(), bumpcar.frozen_len());
+}
+
+#[test]
+fn snapshot_restores_contents_and_cursor() {
+    let mut bumpcar = BumpCar::new(128).unwrap();
+    let config = bumpcar.alloc_handle(*b"config").unwrap();
+    bumpcar.freeze();
+    let state = bumpcar.alloc_handle([1u32, 2, 3]).unwrap();
+    let snapshot = bumpcar.snapshot();
+    assert_eq!(snapshot.len(), bumpcar.used());
+
+    for step in 0..3 {
+        unsafe { bumpcar.get_mut(state)[0] += 10 };
+        bumpcar.alloc([step as u8; 32]);
+        bumpcar.restore(&snapshot).unwrap();
+        assert_eq!(unsafe { bumpcar.get(state) }, &[1, 2, 3]);
+        assert_eq!(bumpcar.used(), snapshot.len());
+    }
+
+    let mut other = BumpCar::new(64).unwrap();
+    other.restore(&snapshot).unwrap();
+    assert_eq!(unsafe { other.get(config) }, b"config");
+    other.reset();
+    assert_eq!(other.used(), bumpcar.frozen_len());
+
+    let mut small = BumpCar::new(8).unwrap();
+    small.alloc(7u8);
+    assert!(small.restore(&snapshot).is_err());
+    assert_eq!(small.used(), 1);
+}
+
+#[test]
+fn snapshot_copies_uninitialized_bytes() {
+    let mut bumpcar = BumpCar::new(64).unwrap();
+    bumpcar.alloc(1u8);
+    // Padding, then an allocation that is never written.
+    bumpcar.allocate(Layout::new::<[u64; 2]>()).unwrap();
+    let snapshot = bumpcar.snapshot();
+    assert_eq!(snapshot.len(), 24);
+    bumpcar.restore(&snapshot).unwrap();
+    assert_eq!(bumpcar.used(), 24);
+
+    let bumpcar = BumpCar::new(64).unwrap();
+    bumpcar.alloc(*b"abcd");
+    // SAFETY: the only allocation was written.
+    assert_eq!(unsafe { bumpcar.snapshot().as_bytes() }, b"abcd");
+}
+
+#[test]
+fn new_prefaulted_is_empty() {
+    let bumpcar = BumpCar::new_prefaulted(10_000).unwrap();
+    assert_eq!(bumpcar.remaining_capacity(), 10_000);
+    assert_eq!(bumpcar.peak_usage(), 0);
+    assert_eq!(*bumpcar.alloc(7u64), 7);
+
+    let empty = BumpCar::new_prefaulted(0).unwrap();
+    assert_eq!(empty.capacity(), 0);
+}
+
+#[test]
+fn new_zeroed_skips_pristine_memset() {
+    let mut bumpcar = BumpCar::new_zeroed(256).unwrap();
+    let region = bumpcar.allocate_zeroed(Layout::new::<[u8; 64]>()).unwrap();
+    assert!(unsafe { region.as_ref() }.iter().all(|&b| b == 0));
+    unsafe { region.cast::<u8>().write_bytes(0xAB, 64) };
+    unsafe { bumpcar.deallocate(region.cast(), Layout::new::<[u8; 64]>()) };
+
+    let region = bumpcar.allocate_zeroed(Layout::new::<[u8; 128]>()).unwrap();
+    assert!(unsafe { region.as_ref() }.iter().all(|&b| b == 0));
+    unsafe { region.cast::<u8>().write_bytes(0xCD, 128) };
+    bumpcar.reset();
+
+    let region = bumpcar.allocate_zeroed(Layout::new::<[u8; 256]>()).unwrap();
+    assert!(unsafe { region.as_ref() }.iter().all(|&b| b == 0));
+    bumpcar.reset();
+
+    bumpcar.alloc([0xEFu8; 256]);
+    bumpcar.reset();
+    bumpcar.try_reserve(1024).unwrap();
+    bumpcar.alloc([0u8; 256]);
+    let region = bumpcar.allocate_zeroed(Layout::new::<[u8; 256]>()).unwrap();
+    assert!(unsafe { region.as_ref() }.iter().all(|&b| b == 0));
+}
+
+#[test]
+fn new_rounded_reports_actual_capacity() {
+    assert_eq!(BumpCar::new_rounded(1000, 4096).unwrap().capacity(), 4096);
+    assert_eq!(BumpCar::new_rounded(8192, 4096).unwrap().capacity(), 8192);
+    assert_eq!(BumpCar::new_rounded(0, 64).unwrap().capacity(), 0);
+    let err = BumpCar::new_rounded(100, 48).unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::InvalidAlignment);
+    let err = BumpCar::new_rounded(usize::MAX, 4096).unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::Overflow);
+}
+
+#[test]
+fn report_summarizes_usage() {
+    let mut b = BumpCar::new(200).unwrap();
+    b.alloc([0u8; 40]);
+    b.freeze();
+    b.alloc([0u8; 60]);
+    b.reset();
+    b.alloc([0u8; 10]);
+
+    let report = b.report().to_string();
+    assert!(
+        report.starts_with("50 bytes used of 200 (25.0%), peak 100 bytes, 40 bytes frozen"),
+        "{report}"
+    );
+    #[cfg(feature = "stats")]
+    assert!(report.ends_with("3 allocations (0 failed), 0 bytes of alignment waste, 1 resets"));
+}
+
+#[test]
+fn capacity_for_typed_values() {
+    let b = BumpCar::with_capacity_for::<u32>(10).unwrap();
+    assert_eq!(b.capacity(), 40);
+    b.alloc_slice_fill_default::<u32>(10);
+    assert!(b.try_alloc(0u8).is_err());
+    assert_eq!(
+        BumpCar::with_capacity_for::<u64>(usize::MAX)
+            .unwrap_err()
+            .kind(),
+        BumpErrorKind::Overflow
+    );
+
+    let builder = CapacityBuilder::new()
+        .value::<u8>()
+        .array::<u64>(3)
+        .layout(Layout::from_size_align(5, 32).unwrap());
+    // allocated in the least favorable order
+    let b = builder.build().unwrap();
+    b.alloc(0u8);
+    b.alloc_layout(Layout::from_size_align(5, 32).unwrap())
+        .unwrap();
+    b.alloc([0u64; 3]);
+    assert_eq!(builder.capacity().unwrap(), 1 + 24 + 7 + 5 + 31);
+
+    let overflow = builder.array::<u64>(usize::MAX / 4);
+    assert_eq!(
+        overflow.capacity().unwrap_err().kind(),
+        BumpErrorKind::Overflow
+    );
+    assert!(overflow.value::<u8>().build().is_err());
+}
+
+#[test]
+fn peak_usage_tracks_high_water_mark() {
+    let mut bumpcar = BumpCar::new(256).unwrap();
+    let v = Vec::<u8, _>::with_capacity_in(100, &bumpcar);
+    drop(v);
+    assert_eq!((bumpcar.used(), bumpcar.peak_usage()), (0, 100));
+
+    let mut v = Vec::<u8, _>::with_capacity_in(150, &bumpcar);
+    v.push(1);
+    v.shrink_to_fit();
+    assert_eq!((bumpcar.used(), bumpcar.peak_usage()), (1, 150));
+    drop(v);
+
+    let marker = bumpcar.marker();
+    bumpcar.alloc([0u8; 200]);
+    bumpcar.rewind(marker);
+    assert_eq!(bumpcar.peak_usage(), 200);
+
+    bumpcar.reset_peak();
+    assert_eq!(bumpcar.peak_usage(), 0);
+    bumpcar.alloc(0u64);
+    bumpcar.reset();
+    assert_eq!(bumpcar.peak_usage(), 8);
+}
+
+#[test]
+fn align_cursor_once() {
+    let bumpcar = BumpCar::new(60).unwrap();
+    bumpcar.alloc(1u8);
+    bumpcar.align_cursor(8).unwrap();
+    assert_eq!(bumpcar.used(), 8);
+    bumpcar.align_cursor(8).unwrap();
+    assert_eq!(bumpcar.used(), 8);
+
+    bumpcar.alloc([0u8; 49]);
+    assert!(bumpcar.align_cursor(8).is_err());
+    assert_eq!(bumpcar.used(), 57);
+    bumpcar.align_cursor(4).unwrap();
+    assert_eq!(bumpcar.used(), 60);
+}
+
+#[test]
+#[should_panic = "not a power of two"]
+fn align_cursor_invalid() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    let _ = bumpcar.align_cursor(3);
+}
+
+#[test]
+fn aligned_buffer() {
+    for align in [1, 8, 64, 4096] {
+        let bumpcar = BumpCar::new_with_align(256, align).unwrap();
+        assert_eq!(bumpcar.buffer_align(), align.max(size_of::<usize>()));
+        assert_eq!(bumpcar.alloc(0u8) as *mut u8 as usize % align, 0);
+    }
+    assert!(BumpCar::new_with_align(256, 3).is_err());
+    assert!(BumpCar::new_with_align(256, 0).is_err());
+
+    let mut bumpcar = BumpCar::new_with_align(64, 4096).unwrap();
+    bumpcar.alloc(1u32);
+    bumpcar.try_reserve(1024).unwrap();
+    assert_eq!(bumpcar.alloc(0u8) as *mut u8 as usize % 4096, 4);
+
+    let align = bumpcar.buffer_align();
+    let (buffer, used, allocator) = bumpcar.into_raw_parts();
+    assert_eq!(buffer.cast::<u8>().as_ptr().addr() % 4096, 0);
+    // SAFETY: the parts and alignment come from the same BumpCar.
+    let bumpcar = unsafe { BumpCar::from_raw_parts_with_align(buffer, used, align, allocator) };
+    assert_eq!(bumpcar.used(), 5);
 }
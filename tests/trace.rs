@@ -0,0 +1,160 @@
+#![cfg(feature = "trace")]
+#![feature(allocator_api)]
+
+use std::alloc::{Global, Layout};
+
+use dodgems::{BumpCar, ReplayReport, Trace, TraceCar, TraceError, TraceOp};
+
+fn record(recorder: &mut TraceCar<BumpCar>) {
+    let mut v = Vec::new_in(&*recorder);
+    for i in 0..100u64 {
+        v.push(i);
+    }
+    let b = Box::new_in([7u8; 3], &*recorder);
+    v.shrink_to_fit();
+    drop(b);
+    drop(v);
+    recorder.record_reset();
+    recorder.inner_mut().reset();
+    let _ = Box::new_in(1u32, &*recorder);
+}
+
+#[test]
+fn records_operations() {
+    let mut recorder = TraceCar::new(BumpCar::new(4096).unwrap());
+    record(&mut recorder);
+    let trace = recorder.take_trace();
+    let ops: Vec<_> = trace.events().iter().map(|event| event.op).collect();
+    assert_eq!(ops.first(), Some(&TraceOp::Allocate));
+    assert!(ops.contains(&TraceOp::Grow));
+    assert!(ops.contains(&TraceOp::Shrink));
+    assert!(ops.contains(&TraceOp::Deallocate));
+    assert_eq!(
+        ops[ops.len() - 3..],
+        [TraceOp::Reset, TraceOp::Allocate, TraceOp::Deallocate]
+    );
+    assert!(trace.events().iter().all(|event| event.ok));
+
+    // The box is the second allocation.
+    let dealloc = trace
+        .events()
+        .iter()
+        .find(|event| event.op == TraceOp::Deallocate)
+        .unwrap();
+    assert_eq!(dealloc.id, 1);
+    assert_eq!(dealloc.layout, Layout::new::<[u8; 3]>());
+    assert_eq!(recorder.events(), 0);
+}
+
+#[test]
+fn records_failures() {
+    let recorder = TraceCar::new(BumpCar::new(16).unwrap());
+    assert!(Box::try_new_in([0u8; 32], &recorder).is_err());
+    let (_, trace) = recorder.into_parts();
+    assert_eq!(trace.events().len(), 1);
+    assert!(!trace.events()[0].ok);
+}
+
+#[test]
+fn pausing() {
+    let recorder = TraceCar::new(BumpCar::new(64).unwrap());
+    recorder.set_recording(false);
+    let a = Box::new_in(1u8, &recorder);
+    recorder.set_recording(true);
+    drop(a);
+    assert_eq!(recorder.events(), 0);
+}
+
+#[test]
+fn bytes_round_trip() {
+    let mut recorder = TraceCar::new(BumpCar::new(4096).unwrap());
+    record(&mut recorder);
+    let trace = recorder.take_trace();
+    let bytes = trace.to_bytes();
+    assert!(bytes.len() < trace.events().len() * 6);
+    assert_eq!(Trace::from_bytes(&bytes), Ok(trace));
+    assert_eq!(Trace::from_bytes(b"DGT\x01"), Ok(Trace::new()));
+}
+
+#[test]
+fn invalid_bytes() {
+    assert_eq!(Trace::from_bytes(b"").unwrap_err().offset(), 0);
+    assert_eq!(Trace::from_bytes(b"DGT\x02").unwrap_err().offset(), 0);
+    // Unknown operation.
+    assert_eq!(Trace::from_bytes(b"DGT\x01\x0f").unwrap_err().offset(), 4);
+    // Truncated size.
+    assert_eq!(
+        Trace::from_bytes(b"DGT\x01\x80\x80").unwrap_err().offset(),
+        6
+    );
+    // Alignment out of range.
+    let error: TraceError = Trace::from_bytes(b"DGT\x01\x80\x08\x7f").unwrap_err();
+    assert_eq!(error.offset(), 6);
+    assert_eq!(error.to_string(), "invalid allocation trace at byte 6");
+}
+
+#[test]
+fn replay() {
+    let mut recorder = TraceCar::new(BumpCar::new(4096).unwrap());
+    record(&mut recorder);
+    let trace = recorder.take_trace();
+    let events = trace.events().len();
+
+    let mut bumpcar = BumpCar::new(4096).unwrap();
+    let mut resets = 0;
+    let report = trace.replay(&mut bumpcar, |bumpcar| {
+        resets += 1;
+        bumpcar.reset();
+    });
+    assert_eq!(resets, 1);
+    assert_eq!(
+        report,
+        ReplayReport {
+            operations: events,
+            failures: 0,
+            mismatches: 0,
+        }
+    );
+    assert_eq!(bumpcar.remaining_capacity(), 4096);
+
+    let report = trace.replay(&mut Global, |_| {});
+    assert_eq!(report.mismatches, 0);
+}
+
+#[test]
+fn replay_skips_failed_allocations() {
+    let recorder = TraceCar::new(BumpCar::new(4096).unwrap());
+    let mut v = Vec::new_in(&recorder);
+    v.extend(0..100u64);
+    drop(v);
+    let trace = recorder.take_trace();
+
+    let mut bumpcar = BumpCar::new(64).unwrap();
+    let report = trace.replay(&mut bumpcar, BumpCar::reset);
+    assert!(report.failures > 0);
+    assert_eq!(report.failures, report.mismatches);
+    assert!(report.operations < trace.events().len());
+    assert_eq!(bumpcar.remaining_capacity(), 64);
+}
+
+#[test]
+fn replay_skips_invalid_resizes() {
+    // Allocates 4096 bytes, then "grows" them to 8, and "shrinks" them to 8192.
+    let bytes = b"DGT\x01\x80\x80\x20\x00\x83\x00\x08\x00\x85\x00\x80\x40\x00";
+    let trace = Trace::from_bytes(bytes).unwrap();
+    assert_eq!(trace.events()[1].op, TraceOp::Grow);
+    assert_eq!(trace.events()[2].op, TraceOp::Shrink);
+
+    let report = trace.replay(&mut Global, |_| {});
+    assert_eq!(
+        report,
+        ReplayReport {
+            operations: 1,
+            failures: 0,
+            mismatches: 2,
+        }
+    );
+    let mut bumpcar = BumpCar::new(8192).unwrap();
+    assert_eq!(trace.replay(&mut bumpcar, BumpCar::reset).mismatches, 2);
+    assert_eq!(bumpcar.remaining_capacity(), 8192);
+}
@@ -0,0 +1,62 @@
+#![cfg(all(feature = "alloc", feature = "tracing"))]
+#![feature(allocator_api)]
+
+use std::sync::{Arc, Mutex};
+
+use dodgems::TracingCar;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Subscriber recording the messages of the events.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+struct Message<'a>(&'a mut String);
+
+impl Visit for Message<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        use std::fmt::Write;
+        write!(self.0, "{}={:?} ", field.name(), value).unwrap();
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+    fn event(&self, event: &Event<'_>) {
+        let mut message = String::new();
+        event.record(&mut Message(&mut message));
+        self.0.lock().unwrap().push(message);
+    }
+    fn enter(&self, _: &Id) {}
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn tracing_events() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let mut bumpcar = TracingCar::new(64).unwrap();
+        let mut v = Vec::<u8, _>::with_capacity_in(8, &bumpcar);
+        v.reserve(24);
+        assert!(Box::try_new_in([0u8; 64], &bumpcar).is_err());
+        drop(v);
+        bumpcar.reset();
+    });
+
+    let events = recorder.0.lock().unwrap();
+    assert_eq!(events.len(), 4);
+    assert!(events[0].contains("operation=\"allocate\""));
+    assert!(events[0].contains("remaining=56"));
+    assert!(events[1].contains("operation=\"grow\""));
+    assert!(events[1].contains("old_size=8"));
+    assert!(events[2].contains("BumpCar allocation failed"));
+    assert!(events[3].contains("BumpCar reset"));
+}
@@ -0,0 +1,505 @@
+#![cfg(feature = "alloc")]
+
+use std::any::Any;
+use std::future::Future;
+use std::marker::PhantomPinned;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use dodgems::{bump_format, bump_vec, slice_assume_init_mut, BumpBox, BumpCar};
+
+#[test]
+fn alloc_values() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    let a = bumpcar.alloc(1u8);
+    let b = bumpcar.alloc(2u64);
+    *a += 1;
+    *b += 1;
+    assert_eq!((*a, *b), (2, 3));
+    assert_eq!(std::ptr::from_mut(b).addr() % 8, 0);
+    assert_eq!(bumpcar.remaining_capacity(), 48);
+
+    let s = bumpcar.alloc_slice_copy(&[1u16, 2, 3]);
+    s.reverse();
+    assert_eq!(s, [3, 2, 1]);
+    assert_eq!(bumpcar.alloc_slice_copy::<u32>(&[]), &[] as &[u32]);
+}
+
+#[test]
+#[should_panic = "BumpCar capacity exceeded"]
+fn alloc_exceeded() {
+    let bumpcar = BumpCar::new(16).unwrap();
+    bumpcar.alloc([0u8; 17]);
+}
+
+#[test]
+fn alloc_in_place() {
+    let bumpcar = BumpCar::new(4096 + 128).unwrap();
+    let frame = bumpcar.alloc_with(|| [7u32; 1024]);
+    assert!(frame.iter().all(|&x| x == 7));
+
+    let strings = bumpcar.alloc_slice_fill_with(3, |i| i.to_string());
+    assert_eq!(strings, ["0", "1", "2"]);
+    // SAFETY: the strings are never used again, and the arena does not drop them.
+    unsafe { std::ptr::drop_in_place(strings) };
+    assert!(bumpcar.alloc_slice_fill_with(0, |_| 0u64).is_empty());
+}
+
+#[test]
+fn alloc_try_with_rolls_back() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    bumpcar.alloc(1u8);
+    let used = bumpcar.used();
+    let err = bumpcar.alloc_try_with(|| Err::<[u64; 4], _>("bad input"));
+    assert_eq!(err, Err("bad input"));
+    assert_eq!(bumpcar.used(), used);
+
+    let ok = bumpcar.alloc_try_with(|| Ok::<_, ()>([3u64; 4])).unwrap();
+    assert_eq!(*ok, [3; 4]);
+
+    let mut called = false;
+    let full = bumpcar.try_alloc_try_with(|| {
+        called = true;
+        Ok::<_, ()>([0u64; 8])
+    });
+    assert!(full.is_err());
+    assert!(!called);
+}
+
+#[test]
+#[should_panic = "BumpCar capacity exceeded"]
+fn alloc_slice_overflow() {
+    let bumpcar = BumpCar::new(16).unwrap();
+    bumpcar.alloc_slice_fill_with(usize::MAX, |_| 0u64);
+}
+
+#[test]
+fn try_alloc_full() {
+    let bumpcar = BumpCar::new(16).unwrap();
+    assert_eq!(*bumpcar.try_alloc(1u64).unwrap(), 1);
+    assert!(bumpcar.try_alloc([0u8; 9]).is_err());
+
+    let mut called = false;
+    assert!(bumpcar
+        .try_alloc_with(|| {
+            called = true;
+            [0u64; 2]
+        })
+        .is_err());
+    assert!(!called);
+
+    assert!(bumpcar.try_alloc_slice_copy(&[1u32, 2, 3]).is_err());
+    assert!(bumpcar
+        .try_alloc_slice_fill_with(usize::MAX, |_| 0u8)
+        .is_err());
+    assert_eq!(bumpcar.try_alloc_slice_copy(&[1u32, 2]).unwrap(), [1, 2]);
+    assert_eq!(bumpcar.remaining_capacity(), 0);
+}
+
+#[test]
+fn alloc_slice_helpers() {
+    let bumpcar = BumpCar::new(256).unwrap();
+    let source = vec![vec![1u8], vec![2, 3]];
+    let cloned = bumpcar.alloc_slice_clone(&source);
+    cloned[0].push(4);
+    assert_eq!(cloned, [vec![1, 4], vec![2, 3]]);
+    assert_eq!(source, [vec![1], vec![2, 3]]);
+    cloned.iter_mut().for_each(|v| drop(std::mem::take(v)));
+
+    let defaults = bumpcar.alloc_slice_fill_default::<Option<u16>>(3);
+    assert_eq!(defaults, [None, None, None]);
+    assert!(bumpcar.try_alloc_slice_fill_default::<u64>(64).is_err());
+}
+
+#[test]
+fn alloc_strings() {
+    let bumpcar = BumpCar::new(32).unwrap();
+    let s = bumpcar.alloc_str("abc");
+    s.make_ascii_uppercase();
+    assert_eq!(s, "ABC");
+
+    let name = "world";
+    let greeting = bumpcar.alloc_fmt(format_args!("hello {name} {}", 1 + 1));
+    assert_eq!(greeting, "hello world 2");
+    assert_eq!(bumpcar.remaining_capacity(), 32 - 3 - 13);
+
+    assert!(bumpcar.try_alloc_fmt(format_args!("{:020}", 1)).is_err());
+    assert!(bumpcar.try_alloc_str("a very long string").is_err());
+}
+
+#[test]
+fn macros() {
+    let bumpcar = BumpCar::new(256).unwrap();
+    let empty: &mut [u8] = bump_vec![in &bumpcar;];
+    assert!(empty.is_empty());
+
+    let v = bump_vec![in &bumpcar; 1u32, 2, 3,];
+    v[0] = 4;
+    assert_eq!(v, [4, 2, 3]);
+    let zeros = bump_vec![in &bumpcar; 0u64; 4];
+    assert_eq!(zeros, [0; 4]);
+
+    let s = bump_format!(in &bumpcar, "{}-{:?}", v.len(), zeros.first());
+    assert_eq!(s, "3-Some(0)");
+}
+
+#[test]
+fn alloc_uninit_slice_fill() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    let slice = bumpcar.alloc_uninit_slice::<u32>(4);
+    assert_eq!(slice.len(), 4);
+    assert_eq!(slice.as_ptr().addr() % 4, 0);
+    for (i, x) in slice.iter_mut().enumerate() {
+        x.write(i as u32 * 10);
+    }
+    // SAFETY: every element has been written.
+    let slice = unsafe { slice_assume_init_mut(slice) };
+    assert_eq!(slice, [0, 10, 20, 30]);
+    assert_eq!(bumpcar.remaining_capacity(), 48);
+
+    assert!(bumpcar.try_alloc_uninit_slice::<u64>(7).is_err());
+    assert!(bumpcar.try_alloc_uninit_slice::<u64>(usize::MAX).is_err());
+    assert_eq!(bumpcar.alloc_uninit_slice::<u64>(6).len(), 6);
+}
+
+#[test]
+fn alloc_aligned_slices() {
+    use dodgems::{BumpErrorKind, SIMD_ALIGN};
+
+    let bumpcar = BumpCar::new(1024).unwrap();
+    for align in [1, 16, 32, 128] {
+        bumpcar.alloc(1u8);
+        let slice = bumpcar.alloc_slice_aligned::<f32>(10, align);
+        assert_eq!(slice.len(), 10);
+        assert_eq!(slice.as_ptr().addr() % align.max(4), 0);
+        assert!(slice.iter().all(|&x| x == 0.0));
+    }
+    let simd = bumpcar.alloc_simd::<f64>(8);
+    assert_eq!(simd.as_ptr().addr() % SIMD_ALIGN, 0);
+
+    let err = bumpcar.try_alloc_slice_aligned::<u8>(4, 3).unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::InvalidAlignment);
+    let err = bumpcar.try_alloc_simd::<u64>(1024).unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::CapacityExceeded);
+}
+
+#[test]
+fn extend_slices() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    let mut words = bumpcar.alloc_slice_copy(&[1u32, 2]);
+    bumpcar.extend_slice_copy(&mut words, &[3, 4]);
+    let start = words.as_ptr();
+    assert_eq!(words, [1, 2, 3, 4]);
+
+    let other = bumpcar.alloc(5u32);
+    bumpcar.extend_slice_copy(&mut words, &[5]);
+    assert_eq!(words, [1, 2, 3, 4, 5]);
+    assert_ne!(words.as_ptr(), start);
+    assert_eq!(*other, 5);
+
+    let mut empty: &mut [u32] = &mut [];
+    bumpcar.extend_slice_copy(&mut empty, &[6, 7]);
+    assert_eq!(empty, [6, 7]);
+
+    let mut outside = vec![1u32];
+    let mut outside_slice = outside.as_mut_slice();
+    bumpcar.extend_slice_copy(&mut outside_slice, &[2]);
+    assert_eq!(outside_slice, [1, 2]);
+
+    let before = bumpcar.remaining_capacity();
+    assert!(bumpcar.try_extend_slice_copy(&mut words, &[0; 64]).is_err());
+    assert_eq!(words, [1, 2, 3, 4, 5]);
+    assert_eq!(bumpcar.remaining_capacity(), before);
+}
+
+#[test]
+fn alloc_c_strings() {
+    use dodgems::BumpErrorKind;
+
+    let bumpcar = BumpCar::new(16).unwrap();
+    let path = bumpcar.alloc_cstr("/tmp");
+    assert_eq!(path.to_bytes_with_nul(), b"/tmp\0");
+    let empty = bumpcar.alloc_cstr_from_bytes(b"");
+    assert_eq!(empty.to_bytes_with_nul(), b"\0");
+    assert_eq!(bumpcar.remaining_capacity(), 10);
+
+    let err = bumpcar.try_alloc_cstr_from_bytes(b"a\0b").unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::InteriorNul);
+    assert_eq!(bumpcar.remaining_capacity(), 10);
+    let err = bumpcar.try_alloc_cstr("0123456789").unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::CapacityExceeded);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn alloc_os_strings_and_paths() {
+    use std::ffi::OsStr;
+    use std::path::{Path, PathBuf};
+
+    let bumpcar = BumpCar::new(64).unwrap();
+    let name = bumpcar.alloc_os_str(OsStr::new("héllo"));
+    assert_eq!(name, "héllo");
+
+    let mut buf = PathBuf::from("/var");
+    buf.push("log");
+    let path = bumpcar.alloc_path(&buf);
+    drop(buf);
+    assert_eq!(path, Path::new("/var/log"));
+    assert_eq!(path.file_name(), Some(OsStr::new("log")));
+    assert!(bumpcar.try_alloc_path(Path::new(&"x".repeat(64))).is_err());
+}
+
+#[test]
+fn box_drops_its_value() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    let rc = Rc::new(());
+
+    let b = bumpcar.alloc_box(Rc::clone(&rc));
+    assert_eq!(Rc::strong_count(&rc), 2);
+    drop(b);
+    assert_eq!(Rc::strong_count(&rc), 1);
+    let used = bumpcar.remaining_capacity();
+
+    let b = bumpcar.alloc_box(Rc::clone(&rc));
+    let inner = BumpBox::into_inner(b);
+    assert_eq!(Rc::strong_count(&rc), 2);
+    drop(inner);
+
+    let b = bumpcar.alloc_box(Rc::clone(&rc));
+    let leaked = BumpBox::leak(b);
+    assert_eq!(Rc::strong_count(&rc), 2);
+    assert!(bumpcar.remaining_capacity() < used);
+    assert!(Rc::ptr_eq(leaked, &rc));
+    drop(unsafe { BumpBox::from_mut(leaked) });
+    assert_eq!(Rc::strong_count(&rc), 1);
+
+    let mut n = bumpcar.alloc_box(1u32);
+    *n += 1;
+    assert_eq!(n, bumpcar.alloc_box(2));
+    assert!(bumpcar.try_alloc_box([0u8; 64]).is_err());
+}
+
+#[test]
+fn pinned_values_are_dropped_in_place() {
+    let mut bumpcar = BumpCar::new(256).unwrap();
+    let rc = Rc::new(());
+    {
+        let mut future = bumpcar.alloc_pinned(async { 42 });
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(42));
+
+        let node = bumpcar.alloc_pinned_with(|| (Rc::clone(&rc), PhantomPinned));
+        assert_eq!(Rc::strong_count(&node.0), 2);
+        assert!(bumpcar.try_alloc_pinned([0u8; 256]).is_err());
+    }
+    assert_eq!(Rc::strong_count(&rc), 1);
+
+    bumpcar.reset();
+    assert_eq!(bumpcar.remaining_capacity(), 256);
+    bumpcar.try_reserve(1024).unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore = "the leaked buffer fails the leak checker")]
+fn leaked_pinned_values_are_never_reclaimed() {
+    let mut bumpcar = BumpCar::new(64).unwrap();
+    bumpcar.alloc(0u64);
+    core::mem::forget(bumpcar.alloc_pinned([1u64; 2]));
+    assert_eq!(bumpcar.remaining_capacity(), 40);
+    bumpcar.alloc(0u64);
+
+    bumpcar.reset();
+    assert_eq!(bumpcar.remaining_capacity(), 40);
+    assert!(bumpcar.try_reserve(1024).is_err());
+    bumpcar.reset_all();
+    assert_eq!(bumpcar.remaining_capacity(), 40);
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+#[cfg_attr(miri, ignore = "the leaked buffer fails the leak checker")]
+fn leaked_pinned_values_survive_rewinds() {
+    let mut bumpcar = BumpCar::new(64).unwrap();
+    let marker = bumpcar.marker();
+    let pinned = bumpcar.alloc_pinned([7u64; 2]);
+    let first = std::ptr::NonNull::from(&*pinned);
+    core::mem::forget(pinned);
+    bumpcar.rewind(marker);
+
+    let second = bumpcar.scope(|scope| {
+        let pinned = scope.alloc_pinned([9u64; 2]);
+        let second = std::ptr::NonNull::from(&*pinned);
+        core::mem::forget(pinned);
+        second
+    });
+    // SAFETY: the values of leaked pinned boxes are never reclaimed.
+    unsafe {
+        assert_eq!(first.read(), [7; 2]);
+        assert_eq!(second.read(), [9; 2]);
+    }
+    assert_eq!(bumpcar.remaining_capacity(), 32);
+}
+
+#[test]
+fn unsized_values() {
+    trait Handler {
+        fn handle(&self, x: u32) -> u32;
+    }
+    struct Add(u32);
+    impl Handler for Add {
+        fn handle(&self, x: u32) -> u32 {
+            x + self.0
+        }
+    }
+    struct Double;
+    impl Handler for Double {
+        fn handle(&self, x: u32) -> u32 {
+            x * 2
+        }
+    }
+
+    let bumpcar = BumpCar::new(256).unwrap();
+    let handlers: [&dyn Handler; 2] = [
+        bumpcar.alloc_unsize::<dyn Handler>(Add(3)),
+        bumpcar.alloc_unsize::<dyn Handler>(Double),
+    ];
+    assert_eq!(handlers.iter().fold(1, |x, h| h.handle(x)), 8);
+
+    let slice = bumpcar.alloc_unsize::<[u8]>([1, 2, 3]);
+    slice[0] = 4;
+    assert_eq!(slice, [4, 2, 3]);
+    assert!(bumpcar.try_alloc_unsize::<[u8]>([0; 256]).is_err());
+
+    let rc = Rc::new(());
+    let b: BumpBox<dyn Any> = bumpcar.alloc_box(Rc::clone(&rc));
+    assert!(b.is::<Rc<()>>());
+    drop(b);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn slice_from_lying_iterator() {
+    /// Iterator reporting a length of `len`, but yielding `actual` elements.
+    struct Lying {
+        len: usize,
+        actual: usize,
+        next: usize,
+    }
+
+    impl Iterator for Lying {
+        type Item = u32;
+
+        fn next(&mut self) -> Option<u32> {
+            (self.next < self.actual).then(|| {
+                self.next += 1;
+                self.next as u32
+            })
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.len, Some(self.len))
+        }
+    }
+
+    impl ExactSizeIterator for Lying {}
+
+    let bumpcar = BumpCar::new(256).unwrap();
+    let exact = bumpcar.alloc_slice_from_iter_exact((0..4u32).map(|i| i * 10));
+    assert_eq!(exact, [0, 10, 20, 30]);
+
+    let short = bumpcar.alloc_slice_from_iter_exact(Lying {
+        len: 10,
+        actual: 3,
+        next: 0,
+    });
+    assert_eq!(short, [1, 2, 3]);
+    assert_eq!(bumpcar.used(), 16 + 12);
+
+    let long = bumpcar.alloc_slice_from_iter_exact(Lying {
+        len: 2,
+        actual: 5,
+        next: 0,
+    });
+    assert_eq!(long, [1, 2]);
+
+    let empty = bumpcar.alloc_slice_from_iter_exact(std::iter::empty::<String>());
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn typed_errors_describe_the_request() {
+    use dodgems::BumpErrorKind;
+    use std::alloc::Layout;
+
+    let bumpcar = BumpCar::new(16).unwrap();
+    bumpcar.alloc(1u8);
+    let err = bumpcar.try_alloc_slice_copy(&[0u32; 8]).unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::CapacityExceeded);
+    assert_eq!(err.layout(), Some(Layout::new::<[u32; 8]>()));
+    assert_eq!(err.remaining_capacity(), 15);
+
+    let err = bumpcar
+        .try_alloc_uninit_slice::<u64>(usize::MAX)
+        .unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::Overflow);
+    assert_eq!(err.layout(), None);
+
+    let err = bumpcar
+        .try_alloc_fmt(format_args!("{}", "x".repeat(20)))
+        .unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::CapacityExceeded);
+    assert_eq!(err.layout(), Some(Layout::new::<[u8; 20]>()));
+
+    struct Failing;
+    impl std::fmt::Display for Failing {
+        fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            Err(std::fmt::Error)
+        }
+    }
+    let err = bumpcar
+        .try_alloc_fmt(format_args!("{Failing}"))
+        .unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::Format);
+
+    let err = BumpCar::new_with_align(16, 3).unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::InvalidAlignment);
+    let err = BumpCar::new(usize::MAX).unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::Overflow);
+    let Err(err) = BumpCar::new_in(64, dodgems::FailCar::fail_nth(std::alloc::System, 0)) else {
+        panic!("the backing allocator should fail");
+    };
+    assert_eq!(err.kind(), BumpErrorKind::AllocatorFailed);
+    assert_eq!(err.layout(), Some(Layout::new::<[u64; 8]>()));
+}
+
+#[test]
+fn arena_errors_describe_the_request() {
+    use dodgems::{BumpErrorKind, BumpInterner, CompactBumpCar, SlotCar, StackCar, SyncBumpCar};
+    use std::alloc::{Layout, System};
+    use std::mem::MaybeUninit;
+
+    let err = SyncBumpCar::new(usize::MAX).unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::Overflow);
+    let err = SlotCar::new(Layout::new::<u64>(), usize::MAX).unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::Overflow);
+    let Err(err) = StackCar::new_in(64, dodgems::FailCar::fail_nth(System, 0)) else {
+        panic!("the backing allocator should fail");
+    };
+    assert_eq!(err.kind(), BumpErrorKind::AllocatorFailed);
+    assert_eq!(err.layout(), Some(Layout::new::<[u64; 8]>()));
+
+    let mut buffer = vec![MaybeUninit::uninit(); 1 << 16];
+    let err = CompactBumpCar::<u16>::from_buffer(&mut buffer).unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::Overflow);
+
+    let bumpcar = BumpCar::new(16).unwrap();
+    bumpcar.alloc(1u8);
+    let err = bumpcar.alloc_handle([0u64; 2]).unwrap_err();
+    assert_eq!(err.kind(), BumpErrorKind::CapacityExceeded);
+    assert_eq!(err.layout(), Some(Layout::new::<[u64; 2]>()));
+    assert_eq!(err.remaining_capacity(), 15);
+    let err = BumpInterner::new(&bumpcar)
+        .intern_symbol(&"x".repeat(20))
+        .unwrap_err();
+    assert_eq!(err.layout(), Some(Layout::new::<[u8; 20]>()));
+}
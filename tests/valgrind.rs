@@ -0,0 +1,29 @@
+#![cfg(all(feature = "alloc", feature = "valgrind"))]
+#![feature(allocator_api)]
+
+use std::mem::MaybeUninit;
+
+use dodgems::BumpCar;
+
+/// Outside of Valgrind, the client requests must not change the behavior of the arena.
+#[test]
+fn valgrind_requests_are_transparent() {
+    let mut bumpcar = BumpCar::new(256).unwrap();
+    let mut v = Vec::new_in(&bumpcar);
+    v.extend(0..16u64);
+    v.shrink_to(4);
+    drop(v);
+    let a = bumpcar.alloc(1u32);
+    *a += 1;
+    bumpcar.freeze();
+    bumpcar.alloc([0u8; 0]);
+    bumpcar.alloc(2u64);
+    bumpcar.reset();
+    assert_eq!(bumpcar.remaining_capacity(), 252);
+
+    let mut buffer = [MaybeUninit::uninit(); 64];
+    let bumpcar = BumpCar::from_buffer(&mut buffer);
+    assert_eq!(*bumpcar.alloc(3u8), 3);
+    drop(bumpcar);
+    buffer[0].write(1);
+}
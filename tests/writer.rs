@@ -0,0 +1,95 @@
+#![cfg(feature = "alloc")]
+#![feature(allocator_api)]
+
+use dodgems::{BumpCar, BumpWriter};
+
+#[test]
+fn writer_grows_in_place() {
+    let bumpcar = BumpCar::new(64).unwrap();
+    let mut writer = BumpWriter::new(&bumpcar);
+    for chunk in [&b"abc"[..], b"def", b"ghij"] {
+        writer.try_extend(chunk).unwrap();
+    }
+    assert_eq!(writer.as_bytes(), b"abcdefghij");
+    assert_eq!(bumpcar.remaining_capacity(), 54);
+
+    assert!(writer.try_extend(&[0; 55]).is_err());
+    assert_eq!(writer.len(), 10);
+    assert_eq!(writer.finish(), b"abcdefghij");
+    assert_eq!(bumpcar.remaining_capacity(), 54);
+}
+
+#[test]
+fn writer_interleaved_allocations() {
+    let bumpcar = BumpCar::new(256).unwrap();
+    let mut writer = BumpWriter::new(&bumpcar);
+    writer.try_extend(b"hello").unwrap();
+
+    let other = Box::new_in(*b"xyz", &bumpcar);
+    writer.try_extend(b" world").unwrap();
+    assert_eq!(&*other, b"xyz");
+
+    let bytes = writer.finish();
+    assert_eq!(bytes, b"hello world");
+    assert!(bumpcar.owns(std::ptr::NonNull::from(bytes).cast()));
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[cfg_attr(miri, ignore = "too slow under Miri")]
+fn io_write_megabytes() {
+    use std::io::Write;
+
+    let bumpcar = BumpCar::new(4 << 20).unwrap();
+    let mut writer = BumpWriter::new(&bumpcar);
+    for i in 0..(3 << 18) {
+        writer.write_all(&u32::to_le_bytes(i)).unwrap();
+    }
+    writer.flush().unwrap();
+
+    let bytes = writer.finish();
+    assert_eq!(bytes.len(), 3 << 20);
+    for (i, chunk) in bytes.chunks_exact(4).enumerate() {
+        assert_eq!(chunk, u32::to_le_bytes(i as u32));
+    }
+
+    let mut writer = BumpWriter::new(&bumpcar);
+    let err = writer.write_all(&[0; 2 << 20]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::OutOfMemory);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn read_to_bump_from_reader() {
+    use dodgems::read_to_bump;
+
+    let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    let bumpcar = BumpCar::new(2048).unwrap();
+    let bytes = read_to_bump(&bumpcar, &mut &data[..], usize::MAX).unwrap();
+    assert_eq!(bytes, &data[..]);
+    bytes[0] = 42;
+    assert_eq!(bumpcar.remaining_capacity(), 1048);
+
+    let truncated = read_to_bump(&bumpcar, &mut &data[..], 100).unwrap();
+    assert_eq!(truncated, &data[..100]);
+    assert_eq!(bumpcar.remaining_capacity(), 948);
+
+    let err = read_to_bump(&bumpcar, &mut &data[..], usize::MAX).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::OutOfMemory);
+    assert_eq!(bumpcar.remaining_capacity(), 948);
+}
+
+#[test]
+fn fmt_write_grows_in_place() {
+    use core::fmt::Write;
+
+    let bumpcar = BumpCar::new(64).unwrap();
+    let mut writer = BumpWriter::new(&bumpcar);
+    for i in 0..10 {
+        write!(writer, "{i},").unwrap();
+    }
+    assert_eq!(bumpcar.remaining_capacity(), 44);
+    assert!(write!(writer, "{}", "x".repeat(50)).is_err());
+    assert_eq!(writer.finish(), b"0,1,2,3,4,5,6,7,8,9,");
+    assert_eq!(bumpcar.remaining_capacity(), 44);
+}